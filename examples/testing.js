@@ -231,3 +231,19 @@ bench("benchmarked func", () => {
 		Math.random() + Math.random() ** 2;
 	}
 });
+
+bench("benchmarked async func", async () => {
+	await new Promise((resolve) => setTimeout(resolve, 1));
+});
+
+bench("Array.prototype.map", { group: "mapping", baseline: true }, () => {
+	[1, 2, 3, 4, 5].map((x) => x * 2);
+});
+
+bench("for loop", { group: "mapping" }, () => {
+	const array = [1, 2, 3, 4, 5];
+	const result = new Array(array.length);
+	for (let i = 0; i < array.length; ++i) {
+		result[i] = array[i] * 2;
+	}
+});
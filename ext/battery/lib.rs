@@ -1,5 +1,6 @@
 use battery::units::time::second;
-use deno_core::{error::AnyError, op2};
+use deno_core::{error::AnyError, op2, OpState};
+use sable_ext_permissions::PermissionsContainer;
 
 const DEFAULT_BATTERY_CHARGING: bool = true;
 const DEFAULT_BATTERY_CHARGING_TIME: f32 = 0.0;
@@ -9,8 +10,15 @@ const DEFAULT_BATTERY_LEVEL: f32 = 1.0;
 // If there's an error, we return the default values for the BatteryManager object.
 // https://w3c.github.io/battery/#internal-slots-0
 
+fn check_sys(state: &OpState) -> Result<(), AnyError> {
+    state
+        .borrow::<PermissionsContainer>()
+        .check_sys("battery", "navigator.getBattery()")
+}
+
 #[op2(fast)]
-pub fn op_battery_charging() -> Result<bool, AnyError> {
+pub fn op_battery_charging(state: &OpState) -> Result<bool, AnyError> {
+    check_sys(state)?;
     let manager = battery::Manager::new()?;
     let mut batteries = manager.batteries()?;
 
@@ -21,7 +29,8 @@ pub fn op_battery_charging() -> Result<bool, AnyError> {
 }
 
 #[op2(fast)]
-pub fn op_battery_charging_time() -> Result<f32, AnyError> {
+pub fn op_battery_charging_time(state: &OpState) -> Result<f32, AnyError> {
+    check_sys(state)?;
     let manager = battery::Manager::new()?;
     let mut batteries = manager.batteries()?;
 
@@ -38,7 +47,8 @@ pub fn op_battery_charging_time() -> Result<f32, AnyError> {
 }
 
 #[op2(fast)]
-pub fn op_battery_discharging_time() -> Result<f32, AnyError> {
+pub fn op_battery_discharging_time(state: &OpState) -> Result<f32, AnyError> {
+    check_sys(state)?;
     let manager = battery::Manager::new()?;
     let mut batteries = manager.batteries()?;
 
@@ -58,7 +68,8 @@ pub fn op_battery_discharging_time() -> Result<f32, AnyError> {
 }
 
 #[op2(fast)]
-pub fn op_battery_level() -> Result<f32, AnyError> {
+pub fn op_battery_level(state: &OpState) -> Result<f32, AnyError> {
+    check_sys(state)?;
     let manager = battery::Manager::new()?;
     let mut batteries = manager.batteries()?;
 
@@ -1,2 +1,8 @@
+import { op_set_global_event_callback } from "ext:core/ops";
+import { fireGlobalEvent } from "ext:sable/web/events.js";
+
+// Op state isn't part of the snapshot, so the runtime gets the callback once it starts
+op_set_global_event_callback(fireGlobalEvent);
+
 delete globalThis.Sable.core;
 delete globalThis.Deno;
@@ -28,13 +28,10 @@ export function createTable(data, columns, printer) {
 		data = data.map((x) => [x]);
 	}
 
-	const maxWidth = [7];
 	const tableData = [
 		["(index)"],
 	];
 
-	let tableString = "";
-
 	let row = 1;
 	for (const key in data) {
 		const obj = data[key];
@@ -50,17 +47,29 @@ export function createTable(data, columns, printer) {
 			tableData[row][0] ??= String(row);
 			tableData[row][column] = value;
 
-			maxWidth[column] = Math.max(
-				textWidth(value),
-				maxWidth[column] ?? textWidth(key),
-			);
-
 			++column;
 		}
 
 		++row;
 	}
 
+	return renderTable(tableData);
+}
+
+/**
+ * @param {string[][]} tableData Rows of cells, first row is the header
+ * @returns {string} table
+ */
+export function renderTable(tableData) {
+	const maxWidth = [];
+	for (const rowData of tableData) {
+		for (const [column, value] of rowData.entries()) {
+			maxWidth[column] = Math.max(textWidth(value), maxWidth[column] ?? 0);
+		}
+	}
+
+	let tableString = "";
+
 	let topBar = tableCharacters.topLeft;
 	let headerSeparator = tableCharacters.leftHorizontal;
 	let bottomBar = tableCharacters.bottomLeft;
@@ -1,8 +1,13 @@
-use deno_core::op2;
+use deno_core::{error::AnyError, op2, OpState};
 use device_query::{DeviceQuery, DeviceState, MouseState};
+use sable_ext_permissions::PermissionsContainer;
 
 #[op2(fast)]
-pub fn op_device_mouse(#[buffer] buf: &mut [u32]) -> () {
+pub fn op_device_mouse(state: &OpState, #[buffer] buf: &mut [u32]) -> Result<(), AnyError> {
+    state
+        .borrow::<PermissionsContainer>()
+        .check_sys("mouse", "addEventListener()")?;
+
     let device_state = DeviceState::new();
     let mouse: MouseState = device_state.get_mouse();
     buf[0] = mouse.coords.0 as u32;
@@ -10,4 +15,5 @@ pub fn op_device_mouse(#[buffer] buf: &mut [u32]) -> () {
     buf[2] = mouse.button_pressed[1] as u32;
     buf[3] = mouse.button_pressed[2] as u32;
     buf[4] = mouse.button_pressed[3] as u32;
+    Ok(())
 }
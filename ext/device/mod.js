@@ -29,10 +29,15 @@ let button3 = false;
 
 let initialized = false;
 
+const MOUSE_EVENTS = ["mousemove", "mousedown", "mouseup"];
+
 const originalAddEventListener = globalThis.addEventListener;
 
 globalThis.addEventListener = (...args) => {
-	if (!initialized) {
+	// Mouse is polled only once it's listened to, as it needs the sys permission and keeps the runtime alive
+	if (!initialized && MOUSE_EVENTS.includes(args[0])) {
+		// Throws right away when the permission has been denied
+		op_device_mouse(new Uint32Array(5));
 		initialized = true;
 		setInterval(() => {
 			let buffer = new Uint32Array(5); // [mouseX, mouseY, button1, button2, button3]
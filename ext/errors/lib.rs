@@ -0,0 +1,158 @@
+use std::{cell::RefCell, collections::HashMap, fmt, io};
+
+use deno_core::{
+    error::{custom_error, get_custom_error_class, AnyError},
+    op2,
+};
+use serde::Serialize;
+
+/// Amount of sys errors, whose details are kept until their JS error gets built
+const MAX_PENDING_SYS_ERRORS: usize = 64;
+
+thread_local! {
+    /// Details of sys errors keyed by their message, until `op_error_sys_details` takes them
+    ///
+    /// Errors of async ops get built in JS after the whole batch of finished ops got serialized,
+    /// so details can't be stored only for the latest error
+    static PENDING_SYS_ERRORS: RefCell<HashMap<String, SysErrorDetails>> = RefCell::default();
+}
+
+/// Name of the `Sable.errors` class, which errors of given kind get thrown as
+fn io_error_class(kind: io::ErrorKind) -> &'static str {
+    match kind {
+        io::ErrorKind::NotFound => "NotFound",
+        io::ErrorKind::PermissionDenied => "PermissionDenied",
+        io::ErrorKind::AlreadyExists => "AlreadyExists",
+        io::ErrorKind::ConnectionRefused => "ConnectionRefused",
+        io::ErrorKind::ConnectionReset => "ConnectionReset",
+        io::ErrorKind::ConnectionAborted => "ConnectionAborted",
+        io::ErrorKind::NotConnected => "NotConnected",
+        io::ErrorKind::AddrInUse => "AddrInUse",
+        io::ErrorKind::AddrNotAvailable => "AddrNotAvailable",
+        io::ErrorKind::BrokenPipe => "BrokenPipe",
+        io::ErrorKind::WouldBlock => "WouldBlock",
+        io::ErrorKind::InvalidInput => "InvalidInput",
+        io::ErrorKind::InvalidData => "InvalidData",
+        io::ErrorKind::TimedOut => "TimedOut",
+        io::ErrorKind::Interrupted => "Interrupted",
+        io::ErrorKind::WriteZero => "WriteZero",
+        io::ErrorKind::UnexpectedEof => "UnexpectedEof",
+        io::ErrorKind::NotADirectory => "NotADirectory",
+        io::ErrorKind::IsADirectory => "IsADirectory",
+        io::ErrorKind::DirectoryNotEmpty => "DirectoryNotEmpty",
+        _ => "Error",
+    }
+}
+
+/// Name of the JS class used for errors thrown by ops, classes other than builtin ones are registered in `errors/mod.js`
+///
+/// It gets called right before the error is sent to JS, so details of sys errors get stored for the builder there
+pub fn get_error_class_name(error: &AnyError) -> &'static str {
+    if let Some(context) = error.downcast_ref::<SysErrorContext>() {
+        let details = SysErrorDetails {
+            syscall: context.syscall.clone(),
+            path: context.path.clone(),
+            errno: error
+                .downcast_ref::<io::Error>()
+                .and_then(io::Error::raw_os_error),
+        };
+        PENDING_SYS_ERRORS.with_borrow_mut(|pending| {
+            // Errors which never got built in JS mustn't pile up
+            if pending.len() >= MAX_PENDING_SYS_ERRORS {
+                pending.clear();
+            }
+            pending.insert(format!("{error:#}"), details);
+        });
+    }
+
+    get_custom_error_class(error)
+        .or_else(|| {
+            error
+                .downcast_ref::<io::Error>()
+                .map(|error| io_error_class(error.kind()))
+        })
+        .unwrap_or("Error")
+}
+
+/// System call which failed with an IO error
+#[derive(Debug)]
+struct SysErrorContext {
+    syscall: String,
+    path: Option<String>,
+}
+
+impl fmt::Display for SysErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{} '{}'", self.syscall, path),
+            None => f.write_str(&self.syscall),
+        }
+    }
+}
+
+/// Properties of errors thrown by failed system calls, see `errors/mod.js`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SysErrorDetails {
+    syscall: String,
+    path: Option<String>,
+    errno: Option<i32>,
+}
+
+/// Adds name of the failed system call and path it got called with to the IO error
+///
+/// They're shown in the message and set as `syscall`, `path` and `errno` properties of the thrown error
+pub fn sys_error(error: io::Error, syscall: &str, path: Option<&str>) -> AnyError {
+    AnyError::from(error).context(SysErrorContext {
+        syscall: syscall.to_string(),
+        path: path.map(str::to_string),
+    })
+}
+
+/** Takes details of the sys error with given message, which has just been thrown by an op */
+#[op2]
+#[serde]
+pub fn op_error_sys_details(#[string] message: &str) -> Option<SysErrorDetails> {
+    take_sys_error_details(message)
+}
+
+fn take_sys_error_details(message: &str) -> Option<SysErrorDetails> {
+    PENDING_SYS_ERRORS.with_borrow_mut(|pending| pending.remove(message))
+}
+
+/// Error which gets thrown as `DOMException` with given name, e.g. "QuotaExceededError"
+///
+/// Message then has the `{name}: {message}` format, which `web/exception.js` parses
+pub fn dom_exception(name: &str, message: &str) -> AnyError {
+    custom_error("DOMException", format!("{name}: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sys_error_details_are_passed_to_js() {
+        let error = sys_error(io::Error::from_raw_os_error(2), "open", Some("/missing"));
+        assert_eq!(get_error_class_name(&error), "NotFound");
+
+        let message = format!("{error:#}");
+        assert!(message.starts_with("open '/missing': "));
+        assert_eq!(
+            take_sys_error_details(&message),
+            Some(SysErrorDetails {
+                syscall: "open".to_string(),
+                path: Some("/missing".to_string()),
+                errno: Some(2),
+            })
+        );
+        // Details are only taken once
+        assert_eq!(take_sys_error_details(&message), None);
+    }
+
+    #[test]
+    fn other_errors_have_no_details() {
+        let error = AnyError::from(io::Error::from_raw_os_error(2));
+        assert_eq!(get_error_class_name(&error), "NotFound");
+        assert_eq!(take_sys_error_details(&format!("{error:#}")), None);
+    }
+}
@@ -0,0 +1,69 @@
+import { core } from "ext:core/mod.js";
+import { op_error_sys_details } from "ext:core/ops";
+
+/**
+ * Creates error class, which ops can throw by returning a custom error with the same class name
+ * @param {string} name
+ * @returns {typeof Error}
+ */
+function createErrorClass(name) {
+	const ErrorClass = class extends Error {
+		/** @type {string | undefined} Name of the failed system call, e.g. "open" */
+		syscall;
+		/** @type {string | undefined} Path the system call failed on */
+		path;
+		/** @type {number | undefined} OS error number */
+		errno;
+
+		/**
+		 * @param {string} message
+		 * @param {ErrorOptions} [options]
+		 */
+		constructor(message, options) {
+			super(message, options);
+			this.name = name;
+		}
+	};
+	Object.defineProperty(ErrorClass, "name", { value: name });
+
+	// Errors created by `sys_error` in `errors/lib.rs` come with details of the failed system call
+	core.registerErrorBuilder(name, (message) => {
+		const error = new ErrorClass(message);
+		const details = op_error_sys_details(message);
+		if (details) {
+			error.syscall = details.syscall;
+			// Path is missing for operations on open resources
+			error.path = details.path ?? undefined;
+			error.errno = details.errno ?? undefined;
+		}
+		return error;
+	});
+	return ErrorClass;
+}
+
+// Ops throw these when `io::ErrorKind` of the error matches the name, `code` is set to the OS error code, e.g. "ENOENT"
+Sable.errors = {
+	/** Operation requires a permission which hasn't been granted */
+	PermissionDenied: createErrorClass("PermissionDenied"),
+	NotFound: createErrorClass("NotFound"),
+	AlreadyExists: createErrorClass("AlreadyExists"),
+	ConnectionRefused: createErrorClass("ConnectionRefused"),
+	ConnectionReset: createErrorClass("ConnectionReset"),
+	ConnectionAborted: createErrorClass("ConnectionAborted"),
+	NotConnected: createErrorClass("NotConnected"),
+	AddrInUse: createErrorClass("AddrInUse"),
+	AddrNotAvailable: createErrorClass("AddrNotAvailable"),
+	BrokenPipe: createErrorClass("BrokenPipe"),
+	WouldBlock: createErrorClass("WouldBlock"),
+	InvalidInput: createErrorClass("InvalidInput"),
+	InvalidData: createErrorClass("InvalidData"),
+	TimedOut: createErrorClass("TimedOut"),
+	WriteZero: createErrorClass("WriteZero"),
+	UnexpectedEof: createErrorClass("UnexpectedEof"),
+	// Registered by deno_core, which throws them from resource ops
+	Interrupted: core.Interrupted,
+	BadResource: core.BadResource,
+	NotADirectory: createErrorClass("NotADirectory"),
+	IsADirectory: createErrorClass("IsADirectory"),
+	DirectoryNotEmpty: createErrorClass("DirectoryNotEmpty"),
+};
@@ -0,0 +1,320 @@
+import {
+	encodeMultipart,
+	FormData,
+	parseMultipart,
+} from "ext:sable/fetch/formdata.js";
+import { URLSearchParams } from "ext:sable/url/url.js";
+import { Blob, readBlob } from "ext:sable/web/blob.js";
+import { TextDecoder, TextEncoder } from "ext:sable/web/encoding.js";
+import {
+	isReadableStreamDisturbed,
+	isReadableStreamLocked,
+	ReadableStream,
+} from "ext:sable/web/streams.js";
+
+// https://fetch.spec.whatwg.org/#body-mixin
+
+/**
+ * @typedef {string | ArrayBuffer | ArrayBufferView | Blob | FormData | URLSearchParams | ReadableStream<Uint8Array>} BodyInit
+ */
+
+/**
+ * @typedef {{
+ *   stream: ReadableStream<Uint8Array>;
+ *   source: Uint8Array | null;
+ *   length: number | null;
+ * }} InnerBody
+ *
+ * `source` holds the whole body when it's known upfront,
+ * which lets it be sent at once and resent after redirects
+ */
+
+export const _body = Symbol("[[body]]");
+
+const encoder = new TextEncoder();
+
+/**
+ * @param {Uint8Array} bytes
+ * @returns {ReadableStream<Uint8Array>}
+ */
+function streamFromBytes(bytes) {
+	return new ReadableStream({
+		type: "bytes",
+		start(controller) {
+			if (bytes.byteLength > 0) {
+				controller.enqueue(bytes.slice());
+			}
+			controller.close();
+		},
+	});
+}
+
+/**
+ * @param {Uint8Array} source
+ * @returns {InnerBody}
+ */
+function bodyFromBytes(source) {
+	return {
+		stream: streamFromBytes(source),
+		source,
+		length: source.byteLength,
+	};
+}
+
+/**
+ * Converts `BodyInit` into a body and its default `Content-Type`
+ * @param {BodyInit} object
+ * @returns {{ body: InnerBody; contentType: string | null }}
+ */
+export function extractBody(object) {
+	if (object instanceof ReadableStream) {
+		if (isReadableStreamDisturbed(object) || isReadableStreamLocked(object)) {
+			throw new TypeError("ReadableStream has already been used");
+		}
+		return {
+			body: { stream: object, source: null, length: null },
+			contentType: null,
+		};
+	}
+
+	if (object instanceof ArrayBuffer) {
+		return {
+			body: bodyFromBytes(new Uint8Array(object.slice(0))),
+			contentType: null,
+		};
+	}
+
+	if (ArrayBuffer.isView(object)) {
+		const bytes = new Uint8Array(
+			object.buffer,
+			object.byteOffset,
+			object.byteLength,
+		);
+		return { body: bodyFromBytes(bytes.slice()), contentType: null };
+	}
+
+	if (object instanceof Blob) {
+		return {
+			body: bodyFromBytes(readBlob(object)),
+			contentType: object.type === "" ? null : object.type,
+		};
+	}
+
+	if (object instanceof FormData) {
+		const { blob, boundary } = encodeMultipart(object);
+		return {
+			body: bodyFromBytes(readBlob(blob)),
+			contentType: `multipart/form-data; boundary=${boundary}`,
+		};
+	}
+
+	if (object instanceof URLSearchParams) {
+		return {
+			body: bodyFromBytes(encoder.encode(object.toString())),
+			contentType: "application/x-www-form-urlencoded;charset=UTF-8",
+		};
+	}
+
+	return {
+		body: bodyFromBytes(encoder.encode(String(object))),
+		contentType: "text/plain;charset=UTF-8",
+	};
+}
+
+/**
+ * Splits the stream into two, one of which stays with the original body
+ * @param {InnerBody} body
+ * @returns {InnerBody}
+ */
+export function cloneBody(body) {
+	const [stream1, stream2] = body.stream.tee();
+	body.stream = stream1;
+	return { stream: stream2, source: body.source, length: body.length };
+}
+
+/**
+ * @param {InnerBody | null} body
+ * @returns {boolean}
+ */
+export function isBodyUnusable(body) {
+	return body !== null &&
+		(isReadableStreamDisturbed(body.stream) ||
+			isReadableStreamLocked(body.stream));
+}
+
+/**
+ * Reads the whole body
+ * @param {InnerBody | null} body
+ * @returns {Promise<Uint8Array>}
+ */
+async function consumeBody(body) {
+	if (isBodyUnusable(body)) {
+		throw new TypeError("Body has already been used");
+	}
+	if (body === null) {
+		return new Uint8Array(0);
+	}
+
+	const reader = body.stream.getReader();
+	/** @type {Uint8Array[]} */
+	const chunks = [];
+	let length = 0;
+	while (true) {
+		const { value, done } = await reader.read();
+		if (done) break;
+		if (!(value instanceof Uint8Array)) {
+			throw new TypeError("Body chunks have to be Uint8Arrays");
+		}
+		chunks.push(value);
+		length += value.byteLength;
+	}
+
+	if (chunks.length === 1) {
+		return chunks[0];
+	}
+	const bytes = new Uint8Array(length);
+	let offset = 0;
+	for (const chunk of chunks) {
+		bytes.set(chunk, offset);
+		offset += chunk.byteLength;
+	}
+	return bytes;
+}
+
+/** Matches `boundary` parameter of `multipart/form-data` Content-Type */
+const BOUNDARY_PARAMETER = /;\s*boundary=(?:"([^"]+)"|([^;\s]+))/i;
+
+/**
+ * Parses `multipart/form-data` or `application/x-www-form-urlencoded` body
+ * @param {Uint8Array} bytes
+ * @param {string | null} contentType
+ * @returns {FormData}
+ */
+function parseFormData(bytes, contentType) {
+	const essence = contentType?.split(";")[0].trim().toLowerCase();
+	if (essence === "multipart/form-data") {
+		const boundary = BOUNDARY_PARAMETER.exec(contentType);
+		if (boundary === null) {
+			throw new TypeError("Multipart Content-Type is missing the boundary");
+		}
+		return parseMultipart(bytes, boundary[1] ?? boundary[2]);
+	}
+
+	if (essence === "application/x-www-form-urlencoded") {
+		const formData = new FormData();
+		const params = new URLSearchParams(new TextDecoder().decode(bytes));
+		for (const [name, value] of params) {
+			formData.append(name, value);
+		}
+		return formData;
+	}
+
+	throw new TypeError(
+		`Body with Content-Type '${contentType}' is not form data`,
+	);
+}
+
+/**
+ * Adds body mixin methods to prototype of `Request` or `Response`
+ * @param {Function} Class
+ */
+export function mixinBody(Class) {
+	/**
+	 * @param {any} object
+	 * @returns {InnerBody | null}
+	 */
+	const bodyOf = (object) => {
+		if (!(object instanceof Class)) {
+			throw new TypeError("Illegal invocation");
+		}
+		return object[_body];
+	};
+
+	Object.defineProperties(Class.prototype, {
+		body: {
+			get() {
+				return bodyOf(this)?.stream ?? null;
+			},
+			configurable: true,
+			enumerable: true,
+		},
+		bodyUsed: {
+			get() {
+				const body = bodyOf(this);
+				return body !== null && isReadableStreamDisturbed(body.stream);
+			},
+			configurable: true,
+			enumerable: true,
+		},
+		arrayBuffer: {
+			/** @returns {Promise<ArrayBuffer>} */
+			async value() {
+				const bytes = await consumeBody(bodyOf(this));
+				if (
+					bytes.byteOffset === 0 &&
+					bytes.byteLength === bytes.buffer.byteLength
+				) {
+					return bytes.buffer;
+				}
+				return bytes.buffer.slice(
+					bytes.byteOffset,
+					bytes.byteOffset + bytes.byteLength,
+				);
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+		bytes: {
+			/** @returns {Promise<Uint8Array>} */
+			value() {
+				return consumeBody(bodyOf(this));
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+		text: {
+			/** @returns {Promise<string>} */
+			async value() {
+				const bytes = await consumeBody(bodyOf(this));
+				return new TextDecoder().decode(bytes);
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+		json: {
+			/** @returns {Promise<any>} */
+			async value() {
+				const bytes = await consumeBody(bodyOf(this));
+				return JSON.parse(new TextDecoder().decode(bytes));
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+		blob: {
+			/** @returns {Promise<Blob>} */
+			async value() {
+				const bytes = await consumeBody(bodyOf(this));
+				return new Blob([bytes], {
+					type: this.headers.get("content-type") ?? "",
+				});
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+		formData: {
+			/** @returns {Promise<FormData>} */
+			async value() {
+				const bytes = await consumeBody(bodyOf(this));
+				return parseFormData(bytes, this.headers.get("content-type"));
+			},
+			configurable: true,
+			enumerable: true,
+			writable: true,
+		},
+	});
+}
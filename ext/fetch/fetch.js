@@ -0,0 +1,251 @@
+import { op_close, op_fetch, op_fetch_send } from "ext:core/ops";
+import { _body, isBodyUnusable } from "ext:sable/fetch/body.js";
+import { headerList, headersFromList } from "ext:sable/fetch/headers.js";
+import {
+	_headers,
+	_method,
+	_redirect,
+	_signal,
+	_url,
+	Request,
+} from "ext:sable/fetch/request.js";
+import {
+	createFetchResponse,
+	NULL_BODY_STATUSES,
+	REDIRECT_STATUSES,
+} from "ext:sable/fetch/response.js";
+import { URL } from "ext:sable/url/url.js";
+import { blobFromObjectURL } from "ext:sable/web/blob.js";
+import {
+	errorReadableStream,
+	readableStreamForRid,
+	writableStreamForRid,
+} from "ext:sable/web/streams.js";
+
+// https://fetch.spec.whatwg.org/#fetch-method
+
+/** Limit of followed redirects, after which fetch fails */
+const MAX_REDIRECTS = 20;
+
+/** Headers describing the body, which get dropped when a redirect changes the method to GET */
+const BODY_HEADERS = [
+	"content-encoding",
+	"content-language",
+	"content-length",
+	"content-location",
+	"content-type",
+];
+
+/** Headers with credentials, which don't get sent to other origins after redirects */
+const CREDENTIAL_HEADERS = ["authorization", "cookie", "proxy-authorization"];
+
+/**
+ * Closes the resource, unless it has already been closed
+ * @param {number} rid
+ */
+function tryClose(rid) {
+	try {
+		op_close(rid);
+	} catch {
+		// Already closed
+	}
+}
+
+/**
+ * @param {[string, string][]} list
+ * @param {string} name
+ * @returns {string | null}
+ */
+function findHeader(list, name) {
+	const header = list.find(([headerName]) =>
+		headerName.toLowerCase() === name
+	);
+	return header === undefined ? null : header[1];
+}
+
+/**
+ * @param {[string, string][]} list
+ * @param {string[]} names
+ * @returns {[string, string][]}
+ */
+function removeHeaders(list, names) {
+	return list.filter(([name]) => !names.includes(name.toLowerCase()));
+}
+
+/**
+ * Sends a single request, aborting it closes its resources
+ * @param {string} method
+ * @param {string} url
+ * @param {[string, string][]} headers
+ * @param {import("ext:sable/fetch/body.js").InnerBody | null} body
+ * @param {AbortSignal} signal
+ */
+async function send(method, url, headers, body, signal) {
+	const { requestRid, requestBodyRid } = op_fetch(
+		method,
+		url,
+		headers,
+		body?.source ?? null,
+		body !== null && body.source === null,
+	);
+
+	const abort = () => tryClose(requestRid);
+	signal.addEventListener("abort", abort, { once: true });
+
+	if (requestBodyRid !== null) {
+		body.stream
+			.pipeTo(writableStreamForRid(requestBodyRid))
+			.catch(() => tryClose(requestRid));
+	}
+
+	try {
+		return await op_fetch_send(requestRid);
+	} catch (error) {
+		if (signal.aborted) {
+			throw signal.reason;
+		}
+		throw error;
+	} finally {
+		signal.removeEventListener("abort", abort);
+	}
+}
+
+/**
+ * Fetches the resource, following redirects unless the request says otherwise
+ * @param {Request | string} input
+ * @param {import("ext:sable/fetch/request.js").RequestInit} [init]
+ * @returns {Promise<Response>}
+ */
+export async function fetch(input, init = undefined) {
+	const request = new Request(input, init);
+	const signal = request[_signal];
+	signal.throwIfAborted();
+	if (isBodyUnusable(request[_body])) {
+		throw new TypeError("Request body has already been used");
+	}
+
+	if (request[_url].startsWith("blob:")) {
+		return fetchObjectURL(request[_method], request[_url]);
+	}
+
+	let method = request[_method];
+	let url = request[_url];
+	let headers = headerList(request[_headers]);
+	let body = request[_body];
+	const urlList = [url];
+
+	while (true) {
+		const info = await send(method, url, headers, body, signal);
+		const location = findHeader(info.headers, "location");
+
+		if (
+			!REDIRECT_STATUSES.includes(info.status) ||
+			location === null ||
+			request[_redirect] === "manual"
+		) {
+			return createResponse(info, method, urlList, signal);
+		}
+
+		tryClose(info.responseRid);
+		if (request[_redirect] === "error") {
+			throw new TypeError(`Request to '${url}' has been redirected`);
+		}
+		if (urlList.length > MAX_REDIRECTS) {
+			throw new TypeError(`Request to '${url}' exceeded redirect limit`);
+		}
+
+		const nextUrl = new URL(location, url);
+		if (nextUrl.protocol !== "http:" && nextUrl.protocol !== "https:") {
+			throw new TypeError(`Request has been redirected to '${nextUrl}'`);
+		}
+
+		if (
+			((info.status === 301 || info.status === 302) && method === "POST") ||
+			(info.status === 303 && method !== "GET" && method !== "HEAD")
+		) {
+			method = "GET";
+			body = null;
+			headers = removeHeaders(headers, BODY_HEADERS);
+		} else if (body !== null && body.source === null) {
+			throw new TypeError("Request with a streamed body cannot be redirected");
+		}
+
+		if (nextUrl.origin !== new URL(url).origin) {
+			headers = removeHeaders(headers, CREDENTIAL_HEADERS);
+		}
+
+		url = nextUrl.href;
+		urlList.push(url);
+	}
+}
+
+/**
+ * Responds with the blob which the `blob:` URL references
+ * @param {string} method
+ * @param {string} url
+ * @returns {Response}
+ */
+function fetchObjectURL(method, url) {
+	if (method !== "GET") {
+		throw new TypeError("Object URLs can only be fetched using GET method");
+	}
+	// Fragment doesn't identify the blob
+	const blob = blobFromObjectURL(url.split("#")[0]);
+	if (blob === null) {
+		throw new TypeError(`Object URL '${url}' has been revoked`);
+	}
+
+	/** @type {[string, string][]} */
+	const headers = [["content-length", String(blob.size)]];
+	if (blob.type !== "") {
+		headers.push(["content-type", blob.type]);
+	}
+	return createFetchResponse({
+		status: 200,
+		statusText: "OK",
+		headers: headersFromList(headers, "immutable"),
+		body: { stream: blob.stream(), source: null, length: blob.size },
+		urlList: [url],
+	});
+}
+
+/**
+ * @param {{
+ *   status: number;
+ *   statusText: string;
+ *   headers: [string, string][];
+ *   responseRid: number;
+ * }} info
+ * @param {string} method
+ * @param {string[]} urlList
+ * @param {AbortSignal} signal
+ * @returns {Response}
+ */
+function createResponse(info, method, urlList, signal) {
+	let body = null;
+	if (method === "HEAD" || NULL_BODY_STATUSES.includes(info.status)) {
+		tryClose(info.responseRid);
+	} else {
+		// Reading the body fails once the request gets aborted
+		const abort = () => {
+			errorReadableStream(stream, signal.reason);
+			tryClose(info.responseRid);
+		};
+		// Listener mustn't keep the finished body alive for as long as the signal lives
+		const stream = readableStreamForRid(
+			info.responseRid,
+			true,
+			() => signal.removeEventListener("abort", abort),
+		);
+		body = { stream, source: null, length: null };
+		signal.addEventListener("abort", abort, { once: true });
+	}
+
+	return createFetchResponse({
+		status: info.status,
+		statusText: info.statusText,
+		headers: headersFromList(info.headers, "immutable"),
+		body,
+		urlList,
+	});
+}
@@ -0,0 +1,387 @@
+import { op_crypto_new_uuidv4 } from "ext:core/ops";
+import { Blob, File } from "ext:sable/web/blob.js";
+import { TextDecoder, TextEncoder } from "ext:sable/web/encoding.js";
+
+// https://xhr.spec.whatwg.org/#interface-formdata
+
+/**
+ * @typedef {string | File} FormDataEntryValue
+ */
+
+const _entries = Symbol("[[entries]]");
+
+/**
+ * Converts the value into an entry value, blobs become files named "blob" unless a file name is given
+ * @param {string | Blob} value
+ * @param {string} [fileName]
+ * @returns {FormDataEntryValue}
+ */
+function toEntryValue(value, fileName) {
+	if (!(value instanceof Blob)) {
+		return String(value).toWellFormed();
+	}
+	if (value instanceof File && fileName === undefined) {
+		return value;
+	}
+	fileName ??= value instanceof File ? value.name : "blob";
+	return new File([value], fileName, {
+		type: value.type,
+		lastModified: value instanceof File ? value.lastModified : undefined,
+	});
+}
+
+/**
+ * @param {IArguments} args
+ * @param {number} required
+ * @param {string} method
+ */
+function requireArguments(args, required, method) {
+	if (args.length < required) {
+		throw new TypeError(
+			`FormData.${method} requires ${required} argument${required === 1 ? "" : "s"}`,
+		);
+	}
+}
+
+export class FormData {
+	/** @type {[string, FormDataEntryValue][]} Names and values in the order they were added */
+	[_entries] = [];
+
+	/**
+	 * HTML forms don't exist outside of browsers, so the form argument can't be given
+	 * @param {undefined} [form]
+	 */
+	constructor(form = undefined) {
+		if (form !== undefined) {
+			throw new TypeError("FormData can't be created from an HTML form");
+		}
+	}
+
+	/**
+	 * @param {string} name
+	 * @param {string | Blob} value
+	 * @param {string} [fileName]
+	 */
+	append(name, value, fileName = undefined) {
+		assertFormData(this);
+		requireArguments(arguments, 2, "append");
+		this[_entries].push([
+			String(name).toWellFormed(),
+			toEntryValue(value, fileName === undefined ? undefined : String(fileName)),
+		]);
+	}
+
+	/**
+	 * Removes all entries with the name
+	 * @param {string} name
+	 */
+	delete(name) {
+		assertFormData(this);
+		requireArguments(arguments, 1, "delete");
+		name = String(name).toWellFormed();
+		this[_entries] = this[_entries].filter(([entryName]) => entryName !== name);
+	}
+
+	/**
+	 * Returns value of the first entry with the name
+	 * @param {string} name
+	 * @returns {FormDataEntryValue | null}
+	 */
+	get(name) {
+		assertFormData(this);
+		requireArguments(arguments, 1, "get");
+		name = String(name).toWellFormed();
+		return this[_entries].find(([entryName]) => entryName === name)?.[1] ??
+			null;
+	}
+
+	/**
+	 * @param {string} name
+	 * @returns {FormDataEntryValue[]}
+	 */
+	getAll(name) {
+		assertFormData(this);
+		requireArguments(arguments, 1, "getAll");
+		name = String(name).toWellFormed();
+		return this[_entries]
+			.filter(([entryName]) => entryName === name)
+			.map(([, value]) => value);
+	}
+
+	/**
+	 * @param {string} name
+	 * @returns {boolean}
+	 */
+	has(name) {
+		assertFormData(this);
+		requireArguments(arguments, 1, "has");
+		name = String(name).toWellFormed();
+		return this[_entries].some(([entryName]) => entryName === name);
+	}
+
+	/**
+	 * Replaces the first entry with the name and removes the others, appends the entry if there are none
+	 * @param {string} name
+	 * @param {string | Blob} value
+	 * @param {string} [fileName]
+	 */
+	set(name, value, fileName = undefined) {
+		assertFormData(this);
+		requireArguments(arguments, 2, "set");
+		name = String(name).toWellFormed();
+		value = toEntryValue(
+			value,
+			fileName === undefined ? undefined : String(fileName),
+		);
+
+		const index = this[_entries].findIndex(([entryName]) => entryName === name);
+		if (index === -1) {
+			this[_entries].push([name, value]);
+			return;
+		}
+		this[_entries][index] = [name, value];
+		this[_entries] = this[_entries].filter(([entryName], i) =>
+			i <= index || entryName !== name
+		);
+	}
+
+	/**
+	 * @param {(value: FormDataEntryValue, name: string, formData: FormData) => void} callback
+	 * @param {any} [thisArg]
+	 */
+	forEach(callback, thisArg = undefined) {
+		assertFormData(this);
+		for (const [name, value] of this) {
+			callback.call(thisArg, value, name, this);
+		}
+	}
+
+	/** @returns {IterableIterator<[string, FormDataEntryValue]>} */
+	*entries() {
+		assertFormData(this);
+		for (const [name, value] of this[_entries]) {
+			yield [name, value];
+		}
+	}
+
+	/** @returns {IterableIterator<string>} */
+	*keys() {
+		for (const [name] of this.entries()) {
+			yield name;
+		}
+	}
+
+	/** @returns {IterableIterator<FormDataEntryValue>} */
+	*values() {
+		for (const [, value] of this.entries()) {
+			yield value;
+		}
+	}
+
+	[Symbol.iterator]() {
+		return this.entries();
+	}
+
+	get [Symbol.toStringTag]() {
+		return "FormData";
+	}
+}
+
+/**
+ * @param {any} formData
+ */
+function assertFormData(formData) {
+	if (!(formData instanceof FormData)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart/form-data-encoding-algorithm
+
+const encoder = new TextEncoder();
+
+/** Line breaks in names and string values, which get normalized to CRLF */
+const LINE_BREAKS = /\r\n|\r|\n/g;
+
+/**
+ * Escapes name or file name, so that it can be put in a quoted header parameter
+ * @param {string} name
+ * @returns {string}
+ */
+function escapeName(name) {
+	return name
+		.replaceAll("\n", "%0A")
+		.replaceAll("\r", "%0D")
+		.replaceAll('"', "%22");
+}
+
+/**
+ * Encodes the entries as `multipart/form-data`, file contents stay in the blob store
+ * @param {FormData} formData
+ * @returns {{ blob: Blob; boundary: string }}
+ */
+export function encodeMultipart(formData) {
+	const boundary = `----SableFormBoundary${
+		op_crypto_new_uuidv4().replaceAll("-", "")
+	}`;
+
+	/** @type {(Uint8Array | Blob)[]} */
+	const parts = [];
+	for (const [name, value] of formData[_entries]) {
+		let disposition = `form-data; name="${
+			escapeName(name.replace(LINE_BREAKS, "\r\n"))
+		}"`;
+		if (typeof value === "string") {
+			parts.push(encoder.encode(
+				`--${boundary}\r\nContent-Disposition: ${disposition}\r\n\r\n` +
+					`${value.replace(LINE_BREAKS, "\r\n")}\r\n`,
+			));
+			continue;
+		}
+
+		disposition += `; filename="${escapeName(value.name)}"`;
+		const type = value.type || "application/octet-stream";
+		parts.push(
+			encoder.encode(
+				`--${boundary}\r\nContent-Disposition: ${disposition}\r\n` +
+					`Content-Type: ${type}\r\n\r\n`,
+			),
+			value,
+			encoder.encode("\r\n"),
+		);
+	}
+	parts.push(encoder.encode(`--${boundary}--\r\n`));
+
+	return { blob: new Blob(parts), boundary };
+}
+
+/**
+ * @param {Uint8Array} bytes
+ * @param {Uint8Array} pattern
+ * @param {number} from
+ * @returns {number}
+ */
+function indexOfBytes(bytes, pattern, from) {
+	const last = bytes.byteLength - pattern.byteLength;
+	let index = bytes.indexOf(pattern[0], from);
+	while (index !== -1 && index <= last) {
+		let matches = true;
+		for (let i = 1; i < pattern.byteLength && matches; i++) {
+			matches = bytes[index + i] === pattern[i];
+		}
+		if (matches) {
+			return index;
+		}
+		index = bytes.indexOf(pattern[0], index + 1);
+	}
+	return -1;
+}
+
+/** Matches `name` and `filename` parameters of Content-Disposition header */
+const DISPOSITION_PARAMETER = /;\s*(name|filename)="([^"]*)"/gi;
+
+/**
+ * Reverts `escapeName`
+ * @param {string} name
+ * @returns {string}
+ */
+function unescapeName(name) {
+	return name
+		.replaceAll("%0A", "\n")
+		.replaceAll("%0D", "\r")
+		.replaceAll("%22", '"');
+}
+
+/**
+ * Parses headers of a part into lowercased names and values
+ * @param {string} text
+ * @returns {Map<string, string>}
+ */
+function parsePartHeaders(text) {
+	/** @type {Map<string, string>} */
+	const headers = new Map();
+	for (const line of text.split("\r\n")) {
+		const colon = line.indexOf(":");
+		if (colon === -1) {
+			throw new TypeError(`Invalid multipart header: '${line}'`);
+		}
+		headers.set(
+			line.slice(0, colon).trim().toLowerCase(),
+			line.slice(colon + 1).trim(),
+		);
+	}
+	return headers;
+}
+
+/**
+ * Parses `multipart/form-data` body
+ * @param {Uint8Array} bytes
+ * @param {string} boundary
+ * @returns {FormData}
+ */
+export function parseMultipart(bytes, boundary) {
+	const decoder = new TextDecoder();
+	const delimiter = encoder.encode(`--${boundary}`);
+	const partEnd = encoder.encode(`\r\n--${boundary}`);
+	const headersEnd = encoder.encode("\r\n\r\n");
+	const formData = new FormData();
+
+	let offset = indexOfBytes(bytes, delimiter, 0);
+	if (offset === -1) {
+		throw new TypeError("Multipart body doesn't contain the boundary");
+	}
+	offset += delimiter.byteLength;
+
+	while (true) {
+		// Closing delimiter has two dashes after the boundary
+		if (bytes[offset] === 0x2d && bytes[offset + 1] === 0x2d) {
+			return formData;
+		}
+		if (bytes[offset] !== 0x0d || bytes[offset + 1] !== 0x0a) {
+			throw new TypeError("Invalid multipart body delimiter");
+		}
+		offset += 2;
+
+		const headersEndIndex = indexOfBytes(bytes, headersEnd, offset);
+		if (headersEndIndex === -1) {
+			throw new TypeError("Multipart body part is missing headers");
+		}
+		const headers = parsePartHeaders(
+			decoder.decode(bytes.subarray(offset, headersEndIndex)),
+		);
+		offset = headersEndIndex + headersEnd.byteLength;
+
+		const bodyEnd = indexOfBytes(bytes, partEnd, offset);
+		if (bodyEnd === -1) {
+			throw new TypeError("Multipart body is missing the closing boundary");
+		}
+		const body = bytes.subarray(offset, bodyEnd);
+		offset = bodyEnd + partEnd.byteLength;
+
+		const disposition = headers.get("content-disposition") ?? "";
+		if (!/^form-data\s*(;|$)/i.test(disposition)) {
+			throw new TypeError(
+				`Invalid multipart Content-Disposition: '${disposition}'`,
+			);
+		}
+		/** @type {Record<string, string>} */
+		const parameters = {};
+		for (const [, key, value] of disposition.matchAll(DISPOSITION_PARAMETER)) {
+			parameters[key.toLowerCase()] = unescapeName(value);
+		}
+		if (parameters.name === undefined) {
+			throw new TypeError("Multipart body part is missing a name");
+		}
+
+		if (parameters.filename === undefined) {
+			formData.append(parameters.name, decoder.decode(body));
+		} else {
+			formData.append(
+				parameters.name,
+				new File([body], parameters.filename, {
+					type: headers.get("content-type") ?? "text/plain",
+				}),
+			);
+		}
+	}
+}
@@ -0,0 +1,354 @@
+// https://fetch.spec.whatwg.org/#headers-class
+
+/**
+ * Restricts how headers can be modified:
+ * - "immutable" - headers of responses returned by `fetch` and `Response.error/redirect`, which can't be changed
+ * - "request" - headers of a `Request`
+ * - "response" - headers of a `Response`
+ * - "none" - headers created using the `Headers` constructor
+ *
+ * Forbidden header names aren't filtered, they only guard browser users from scripts, which doesn't apply here
+ * @typedef {"immutable" | "request" | "response" | "none"} HeadersGuard
+ */
+
+/**
+ * @typedef {Headers | [string, string][] | Record<string, string>} HeadersInit
+ */
+
+/** Matches RFC 7230 token, which header names consist of */
+const HEADER_NAME = /^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$/;
+
+/** Matches bytes which aren't allowed in header values */
+const INVALID_HEADER_VALUE = /[\0\r\n]|[^\x00-\xff]/;
+
+/** Leading and trailing tabs, spaces and newlines, which get stripped from header values */
+const HTTP_WHITESPACE = /^[\t\n\r ]+|[\t\n\r ]+$/g;
+
+const _list = Symbol("[[headerList]]");
+const _guard = Symbol("[[guard]]");
+
+/**
+ * @param {string} name
+ * @returns {string}
+ */
+function validateName(name) {
+	name = String(name);
+	if (!HEADER_NAME.test(name)) {
+		throw new TypeError(`Invalid header name: '${name}'`);
+	}
+	return name;
+}
+
+/**
+ * @param {string} value
+ * @returns {string}
+ */
+function normalizeValue(value) {
+	value = String(value).replace(HTTP_WHITESPACE, "");
+	if (INVALID_HEADER_VALUE.test(value)) {
+		throw new TypeError(`Invalid header value: '${value}'`);
+	}
+	return value;
+}
+
+export class Headers {
+	/** @type {[string, string][]} Names and values in the order they were added */
+	[_list] = [];
+	/** @type {HeadersGuard} */
+	[_guard] = "none";
+
+	/**
+	 * @param {HeadersInit} [init]
+	 */
+	constructor(init = undefined) {
+		if (init !== undefined) {
+			fillHeaders(this, init);
+		}
+	}
+
+	/**
+	 * Adds the value, which gets combined with the existing ones
+	 * @param {string} name
+	 * @param {string} value
+	 */
+	append(name, value) {
+		assertHeaders(this);
+		appendHeader(this, name, value);
+	}
+
+	/**
+	 * @param {string} name
+	 */
+	delete(name) {
+		assertHeaders(this);
+		name = validateName(name);
+		assertMutable(this);
+
+		const lowerName = name.toLowerCase();
+		this[_list] = this[_list].filter(([headerName]) =>
+			headerName.toLowerCase() !== lowerName
+		);
+	}
+
+	/**
+	 * Returns values of the header combined with ", " or `null` when there are none
+	 * @param {string} name
+	 * @returns {string | null}
+	 */
+	get(name) {
+		assertHeaders(this);
+		name = validateName(name);
+		return getHeader(this, name);
+	}
+
+	/**
+	 * Returns values of `Set-Cookie` headers separately, since they can't be combined
+	 * @returns {string[]}
+	 */
+	getSetCookie() {
+		assertHeaders(this);
+		return this[_list]
+			.filter(([name]) => name.toLowerCase() === "set-cookie")
+			.map(([, value]) => value);
+	}
+
+	/**
+	 * @param {string} name
+	 * @returns {boolean}
+	 */
+	has(name) {
+		assertHeaders(this);
+		name = validateName(name);
+		const lowerName = name.toLowerCase();
+		return this[_list].some(([headerName]) =>
+			headerName.toLowerCase() === lowerName
+		);
+	}
+
+	/**
+	 * Replaces all values of the header
+	 * @param {string} name
+	 * @param {string} value
+	 */
+	set(name, value) {
+		assertHeaders(this);
+		name = validateName(name);
+		value = normalizeValue(value);
+		assertMutable(this);
+
+		const lowerName = name.toLowerCase();
+		const index = this[_list].findIndex(([headerName]) =>
+			headerName.toLowerCase() === lowerName
+		);
+		if (index === -1) {
+			this[_list].push([name, value]);
+			return;
+		}
+
+		this[_list][index][1] = value;
+		this[_list] = this[_list].filter(([headerName], i) =>
+			i <= index || headerName.toLowerCase() !== lowerName
+		);
+	}
+
+	/**
+	 * @param {(value: string, name: string, headers: Headers) => void} callback
+	 * @param {any} [thisArg]
+	 */
+	forEach(callback, thisArg = undefined) {
+		assertHeaders(this);
+		for (const [name, value] of this) {
+			callback.call(thisArg, value, name, this);
+		}
+	}
+
+	/**
+	 * Iterates over lowercased names sorted alphabetically and their combined values
+	 * @returns {IterableIterator<[string, string]>}
+	 */
+	*entries() {
+		assertHeaders(this);
+		yield* sortAndCombine(this);
+	}
+
+	/** @returns {IterableIterator<string>} */
+	*keys() {
+		for (const [name] of this.entries()) {
+			yield name;
+		}
+	}
+
+	/** @returns {IterableIterator<string>} */
+	*values() {
+		for (const [, value] of this.entries()) {
+			yield value;
+		}
+	}
+
+	[Symbol.iterator]() {
+		return this.entries();
+	}
+
+	get [Symbol.toStringTag]() {
+		return "Headers";
+	}
+}
+
+/**
+ * @param {any} headers
+ */
+function assertHeaders(headers) {
+	if (!(headers instanceof Headers)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {Headers} headers
+ */
+function assertMutable(headers) {
+	if (headers[_guard] === "immutable") {
+		throw new TypeError("Headers are immutable");
+	}
+}
+
+/**
+ * @param {Headers} headers
+ * @param {string} name
+ * @param {string} value
+ */
+function appendHeader(headers, name, value) {
+	name = validateName(name);
+	value = normalizeValue(value);
+	assertMutable(headers);
+	headers[_list].push([name, value]);
+}
+
+/**
+ * @param {Headers} headers
+ * @param {string} name
+ * @returns {string | null}
+ */
+export function getHeader(headers, name) {
+	const lowerName = name.toLowerCase();
+	const values = headers[_list]
+		.filter(([headerName]) => headerName.toLowerCase() === lowerName)
+		.map(([, value]) => value);
+	return values.length === 0 ? null : values.join(", ");
+}
+
+/**
+ * @param {Headers} headers
+ * @returns {[string, string][]}
+ */
+function sortAndCombine(headers) {
+	/** @type {Map<string, string[]>} */
+	const combined = new Map();
+	/** @type {[string, string][]} */
+	const cookies = [];
+	for (const [name, value] of headers[_list]) {
+		const lowerName = name.toLowerCase();
+		if (lowerName === "set-cookie") {
+			cookies.push([lowerName, value]);
+		} else if (combined.has(lowerName)) {
+			combined.get(lowerName).push(value);
+		} else {
+			combined.set(lowerName, [value]);
+		}
+	}
+
+	const entries = [...combined].map(([name, values]) => [
+		name,
+		values.join(", "),
+	]);
+	entries.push(...cookies);
+	// Sort is stable, so Set-Cookie headers keep their order
+	return entries.sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+}
+
+/**
+ * @param {Headers} headers
+ * @param {HeadersInit} init
+ */
+export function fillHeaders(headers, init) {
+	if (typeof init !== "object" || init === null) {
+		throw new TypeError("Headers must be an object or an iterable of pairs");
+	}
+
+	if (Symbol.iterator in init) {
+		for (const pair of init) {
+			const [name, value, ...rest] = pair;
+			if (rest.length > 0 || pair.length !== 2) {
+				throw new TypeError("Header pairs must have exactly two items");
+			}
+			appendHeader(headers, name, value);
+		}
+		return;
+	}
+
+	for (const name of Object.keys(init)) {
+		appendHeader(headers, name, init[name]);
+	}
+}
+
+/**
+ * @param {HeadersInit | undefined} init
+ * @param {HeadersGuard} guard
+ * @returns {Headers}
+ */
+export function createHeaders(init, guard) {
+	const headers = new Headers(init);
+	headers[_guard] = guard;
+	return headers;
+}
+
+/**
+ * Creates headers out of the header list received from Rust
+ * @param {[string, string][]} list
+ * @param {HeadersGuard} guard
+ * @returns {Headers}
+ */
+export function headersFromList(list, guard) {
+	const headers = new Headers();
+	headers[_list] = list;
+	headers[_guard] = guard;
+	return headers;
+}
+
+/**
+ * @param {Headers} headers
+ * @returns {[string, string][]}
+ */
+export function headerList(headers) {
+	return headers[_list];
+}
+
+/**
+ * Copies the headers, the copy has given guard
+ * @param {Headers} headers
+ * @param {HeadersGuard} guard
+ * @returns {Headers}
+ */
+export function cloneHeaders(headers, guard) {
+	return headersFromList(
+		headers[_list].map(([name, value]) => [name, value]),
+		guard,
+	);
+}
+
+/**
+ * Changes the guard, e.g. to make headers immutable after they've been filled
+ * @param {Headers} headers
+ * @param {HeadersGuard} guard
+ */
+export function setHeadersGuard(headers, guard) {
+	headers[_guard] = guard;
+}
+
+/**
+ * @param {Headers} headers
+ * @returns {HeadersGuard}
+ */
+export function headersGuard(headers) {
+	return headers[_guard];
+}
@@ -0,0 +1,363 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    error::Error,
+    io,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use deno_core::{
+    error::{type_error, AnyError},
+    futures::{future::LocalBoxFuture, FutureExt, Stream},
+    op2,
+    url::Url,
+    AsyncRefCell, AsyncResult, BufMutView, BufView, ByteString, CancelFuture, CancelHandle,
+    JsBuffer, OpState, RcRef, Resource, ResourceId, WriteOutcome,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    redirect::Policy,
+    Body, Client, Method,
+};
+use sable_ext_errors::sys_error;
+use sable_ext_permissions::PermissionsContainer;
+use serde::Serialize;
+use tokio::{io::AsyncReadExt, sync::mpsc};
+
+const USER_AGENT: &str = concat!("Sable/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP client shared by all requests, created on first use
+///
+/// Proxies get configured by reqwest from `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`.
+/// Redirects aren't followed, `fetch` does that itself so that redirect modes and permissions apply
+fn http_client(state: &mut OpState) -> Result<Client, AnyError> {
+    if let Some(client) = state.try_borrow::<Client>() {
+        return Ok(client.clone());
+    }
+
+    let client = Client::builder()
+        .user_agent(USER_AGENT)
+        .redirect(Policy::none())
+        .build()?;
+    state.put(client.clone());
+    Ok(client)
+}
+
+/// Network errors are reported as `TypeError`, like in browsers
+fn request_error(error: reqwest::Error) -> AnyError {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    type_error(message)
+}
+
+enum ResponseBody {
+    Http {
+        response: reqwest::Response,
+        /// Part of the last chunk which didn't fit into the read buffer
+        pending: Bytes,
+    },
+    File(tokio::fs::File),
+}
+
+struct FetchResponse {
+    status: u16,
+    status_text: String,
+    headers: Vec<(ByteString, ByteString)>,
+    body: ResponseBody,
+}
+
+impl FetchResponse {
+    fn http(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().as_bytes().to_vec().into(),
+                    value.as_bytes().to_vec().into(),
+                )
+            })
+            .collect();
+
+        Self {
+            status: status.as_u16(),
+            status_text: status.canonical_reason().unwrap_or_default().to_string(),
+            headers,
+            body: ResponseBody::Http {
+                response,
+                pending: Bytes::new(),
+            },
+        }
+    }
+
+    fn file(file: tokio::fs::File) -> Self {
+        Self {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: Vec::new(),
+            body: ResponseBody::File(file),
+        }
+    }
+}
+
+/// Request which waits to be sent by `op_fetch_send`
+pub struct FetchRequestResource {
+    response: RefCell<Option<LocalBoxFuture<'static, Result<FetchResponse, AnyError>>>>,
+    /// Aborts the request when it gets closed
+    cancel: CancelHandle,
+}
+
+impl Resource for FetchRequestResource {
+    fn name(&self) -> Cow<'_, str> {
+        "fetchRequest".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+}
+
+/// Streamed request body, the request ends once it gets closed
+pub struct FetchRequestBodyResource {
+    sender: RefCell<Option<mpsc::Sender<Bytes>>>,
+}
+
+impl Resource for FetchRequestBodyResource {
+    fn name(&self) -> Cow<'_, str> {
+        "fetchRequestBody".into()
+    }
+
+    fn write(self: Rc<Self>, buf: BufView) -> AsyncResult<WriteOutcome> {
+        let sender = self.sender.borrow().clone();
+        Box::pin(async move {
+            let broken_pipe = || sys_error(io::ErrorKind::BrokenPipe.into(), "write", None);
+            let sender = sender.ok_or_else(broken_pipe)?;
+            let nwritten = buf.len();
+            sender
+                .send(Bytes::copy_from_slice(&buf))
+                .await
+                .map_err(|_| broken_pipe())?;
+            Ok(WriteOutcome::Full { nwritten })
+        })
+    }
+
+    fn close(self: Rc<Self>) {
+        self.sender.borrow_mut().take();
+    }
+}
+
+/// Chunks written to `FetchRequestBodyResource`
+struct RequestBodyStream(mpsc::Receiver<Bytes>);
+
+impl Stream for RequestBodyStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_recv(cx).map(|chunk| chunk.map(Ok))
+    }
+}
+
+/// Body of a received response
+pub struct FetchResponseResource {
+    body: AsyncRefCell<ResponseBody>,
+    /// Interrupts pending reads when the body gets closed
+    cancel: CancelHandle,
+}
+
+async fn read_body(body: &mut ResponseBody, buf: &mut [u8]) -> Result<usize, AnyError> {
+    match body {
+        ResponseBody::Http { response, pending } => {
+            while pending.is_empty() {
+                match response.chunk().await.map_err(request_error)? {
+                    Some(chunk) => *pending = chunk,
+                    None => return Ok(0),
+                }
+            }
+            let read = pending.len().min(buf.len());
+            buf[..read].copy_from_slice(&pending.split_to(read));
+            Ok(read)
+        }
+        ResponseBody::File(file) => file
+            .read(buf)
+            .await
+            .map_err(|error| sys_error(error, "read", None)),
+    }
+}
+
+impl Resource for FetchResponseResource {
+    fn name(&self) -> Cow<'_, str> {
+        "fetchResponse".into()
+    }
+
+    fn read_byob(self: Rc<Self>, mut buf: BufMutView) -> AsyncResult<(usize, BufMutView)> {
+        Box::pin(async move {
+            let mut body = RcRef::map(&self, |resource| &resource.body)
+                .borrow_mut()
+                .await;
+            let read = read_body(&mut body, &mut buf)
+                .or_cancel(RcRef::map(&self, |resource| &resource.cancel))
+                .await
+                .map_err(|canceled| sys_error(canceled.into(), "read", None))??;
+            Ok((read, buf))
+        })
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchReturn {
+    request_rid: ResourceId,
+    /// Resource which the body has to be written to, when it's streamed
+    request_body_rid: Option<ResourceId>,
+}
+
+/// Prepares a request, which gets sent by `op_fetch_send`
+#[op2]
+#[serde]
+pub fn op_fetch(
+    state: &mut OpState,
+    #[serde] method: ByteString,
+    #[string] url: String,
+    #[serde] headers: Vec<(ByteString, ByteString)>,
+    #[buffer] body: Option<JsBuffer>,
+    has_body_stream: bool,
+) -> Result<FetchReturn, AnyError> {
+    let url = Url::parse(&url).map_err(|_| type_error(format!("Invalid URL: '{url}'")))?;
+    let mut request_body_rid = None;
+
+    let response = match url.scheme() {
+        "http" | "https" => {
+            state
+                .borrow::<PermissionsContainer>()
+                .check_net_url(&url, "fetch()")?;
+
+            let method =
+                Method::from_bytes(&method).map_err(|_| type_error("Invalid request method"))?;
+            let mut header_map = HeaderMap::with_capacity(headers.len());
+            for (name, value) in headers {
+                let name = HeaderName::from_bytes(&name)
+                    .map_err(|_| type_error("Invalid request header name"))?;
+                let value = HeaderValue::from_bytes(&value)
+                    .map_err(|_| type_error("Invalid request header value"))?;
+                header_map.append(name, value);
+            }
+
+            let mut request = http_client(state)?.request(method, url).headers(header_map);
+            if has_body_stream {
+                // Writes wait until the previous chunk has been sent
+                let (sender, receiver) = mpsc::channel(1);
+                request = request.body(Body::wrap_stream(RequestBodyStream(receiver)));
+                request_body_rid = Some(state.resource_table.add(FetchRequestBodyResource {
+                    sender: RefCell::new(Some(sender)),
+                }));
+            } else if let Some(body) = body {
+                request = request.body(body.to_vec());
+            }
+
+            async move {
+                let response = request.send().await.map_err(request_error)?;
+                Ok(FetchResponse::http(response))
+            }
+            .boxed_local()
+        }
+        "file" => {
+            if method.as_slice() != b"GET" {
+                return Err(type_error("Files can only be fetched using the GET method"));
+            }
+            let path = url
+                .to_file_path()
+                .map_err(|_| type_error(format!("Invalid file URL: '{url}'")))?;
+            let path_str = path.to_string_lossy().into_owned();
+            state
+                .borrow::<PermissionsContainer>()
+                .check_read(&path_str, "fetch()")?;
+
+            async move {
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .map_err(|error| sys_error(error, "open", Some(&path_str)))?;
+                Ok(FetchResponse::file(file))
+            }
+            .boxed_local()
+        }
+        scheme => {
+            return Err(type_error(format!(
+                "URL scheme '{scheme}' is not supported by fetch"
+            )))
+        }
+    };
+
+    let request_rid = state.resource_table.add(FetchRequestResource {
+        response: RefCell::new(Some(response)),
+        cancel: Default::default(),
+    });
+    Ok(FetchReturn {
+        request_rid,
+        request_body_rid,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchResponseInfo {
+    status: u16,
+    status_text: String,
+    headers: Vec<(ByteString, ByteString)>,
+    /// Resource the body can be read from
+    response_rid: ResourceId,
+}
+
+/// Sends the request and waits for the response headers, fails once the request resource gets closed
+#[op2(async)]
+#[serde]
+pub async fn op_fetch_send(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<FetchResponseInfo, AnyError> {
+    let resource = state
+        .borrow()
+        .resource_table
+        .get::<FetchRequestResource>(rid)?;
+    let response = resource
+        .response
+        .borrow_mut()
+        .take()
+        .ok_or_else(|| type_error("Request has already been sent"))?;
+
+    let result = response
+        .or_cancel(RcRef::map(&resource, |resource| &resource.cancel))
+        .await;
+    // Resource could have been closed already by aborting the request
+    let _ = state
+        .borrow_mut()
+        .resource_table
+        .take::<FetchRequestResource>(rid);
+    let response = result.map_err(|_| type_error("Request has been aborted"))??;
+
+    let response_rid = state
+        .borrow_mut()
+        .resource_table
+        .add(FetchResponseResource {
+            body: AsyncRefCell::new(response.body),
+            cancel: Default::default(),
+        });
+    Ok(FetchResponseInfo {
+        status: response.status,
+        status_text: response.status_text,
+        headers: response.headers,
+        response_rid,
+    })
+}
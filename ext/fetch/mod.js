@@ -0,0 +1,11 @@
+import { fetch } from "ext:sable/fetch/fetch.js";
+import { FormData } from "ext:sable/fetch/formdata.js";
+import { Headers } from "ext:sable/fetch/headers.js";
+import { Request } from "ext:sable/fetch/request.js";
+import { Response } from "ext:sable/fetch/response.js";
+
+globalThis.fetch = fetch;
+globalThis.FormData = FormData;
+globalThis.Headers = Headers;
+globalThis.Request = Request;
+globalThis.Response = Response;
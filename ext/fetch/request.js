@@ -0,0 +1,295 @@
+import {
+	_body,
+	cloneBody,
+	extractBody,
+	isBodyUnusable,
+	mixinBody,
+} from "ext:sable/fetch/body.js";
+import { cloneHeaders, createHeaders } from "ext:sable/fetch/headers.js";
+import { URL } from "ext:sable/url/url.js";
+import {
+	AbortSignal,
+	createDependentAbortSignal,
+} from "ext:sable/web/events.js";
+import { ReadableStream } from "ext:sable/web/streams.js";
+
+// https://fetch.spec.whatwg.org/#request-class
+
+/**
+ * @typedef {"follow" | "error" | "manual"} RequestRedirect
+ */
+
+/**
+ * @typedef {{
+ *   method?: string;
+ *   headers?: import("ext:sable/fetch/headers.js").HeadersInit;
+ *   body?: import("ext:sable/fetch/body.js").BodyInit | null;
+ *   redirect?: RequestRedirect;
+ *   signal?: AbortSignal | null;
+ *   duplex?: "half";
+ * }} RequestInit
+ */
+
+const REDIRECT_MODES = ["follow", "error", "manual"];
+
+/** Methods which get uppercased, other methods are case-sensitive */
+const NORMALIZED_METHODS = ["DELETE", "GET", "HEAD", "OPTIONS", "POST", "PUT"];
+
+const FORBIDDEN_METHODS = ["CONNECT", "TRACE", "TRACK"];
+
+const METHOD = /^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$/;
+
+export const _method = Symbol("[[method]]");
+export const _url = Symbol("[[url]]");
+export const _headers = Symbol("[[headers]]");
+export const _redirect = Symbol("[[redirect]]");
+export const _signal = Symbol("[[signal]]");
+
+/**
+ * @param {string} method
+ * @returns {string}
+ */
+function normalizeMethod(method) {
+	method = String(method);
+	if (!METHOD.test(method)) {
+		throw new TypeError(`Invalid request method: '${method}'`);
+	}
+
+	const upperMethod = method.toUpperCase();
+	if (FORBIDDEN_METHODS.includes(upperMethod)) {
+		throw new TypeError(`Request method '${method}' is forbidden`);
+	}
+	return NORMALIZED_METHODS.includes(upperMethod) ? upperMethod : method;
+}
+
+/**
+ * @param {any} signal
+ * @returns {AbortSignal | null}
+ */
+function validateSignal(signal) {
+	if (signal === undefined || signal === null) {
+		return null;
+	}
+	if (!(signal instanceof AbortSignal)) {
+		throw new TypeError("signal must be an AbortSignal");
+	}
+	return signal;
+}
+
+export class Request {
+	/** @type {string} */
+	[_method];
+	/** @type {string} */
+	[_url];
+	/** @type {import("ext:sable/fetch/headers.js").Headers} */
+	[_headers];
+	/** @type {RequestRedirect} */
+	[_redirect];
+	/** @type {AbortSignal} */
+	[_signal];
+	/** @type {import("ext:sable/fetch/body.js").InnerBody | null} */
+	[_body] = null;
+
+	/**
+	 * @param {Request | string} input
+	 * @param {RequestInit} [init]
+	 */
+	constructor(input, init = {}) {
+		if (init === null || typeof init !== "object") {
+			init = {};
+		}
+
+		/** @type {Request | null} */
+		const inputRequest = input instanceof Request ? input : null;
+		/** @type {AbortSignal | null} */
+		let signal = null;
+		if (inputRequest !== null) {
+			this[_url] = inputRequest[_url];
+			this[_method] = inputRequest[_method];
+			this[_redirect] = inputRequest[_redirect];
+			signal = inputRequest[_signal];
+		} else {
+			const url = new URL(input);
+			if (url.username !== "" || url.password !== "") {
+				throw new TypeError("Request URL cannot include credentials");
+			}
+			this[_url] = url.href;
+			this[_method] = "GET";
+			this[_redirect] = "follow";
+		}
+
+		if (init.method !== undefined) {
+			this[_method] = normalizeMethod(init.method);
+		}
+
+		if (init.redirect !== undefined) {
+			const redirect = String(init.redirect);
+			if (!REDIRECT_MODES.includes(redirect)) {
+				throw new TypeError(`Invalid redirect mode: '${redirect}'`);
+			}
+			this[_redirect] = redirect;
+		}
+
+		if (init.signal !== undefined) {
+			signal = validateSignal(init.signal);
+		}
+		// Request has its own signal, which gets aborted along with the given one
+		this[_signal] = createDependentAbortSignal(
+			signal === null ? [] : [signal],
+		);
+
+		this[_headers] = init.headers !== undefined
+			? createHeaders(init.headers, "request")
+			: inputRequest !== null
+			? cloneHeaders(inputRequest[_headers], "request")
+			: createHeaders(undefined, "request");
+
+		const inputBody = inputRequest?.[_body] ?? null;
+		const hasBody = (init.body !== undefined && init.body !== null) ||
+			inputBody !== null;
+		if (hasBody && (this[_method] === "GET" || this[_method] === "HEAD")) {
+			throw new TypeError("Request with GET/HEAD method cannot have body");
+		}
+
+		if (init.body !== undefined && init.body !== null) {
+			const { body, contentType } = extractBody(init.body);
+			if (init.body instanceof ReadableStream && init.duplex !== "half") {
+				throw new TypeError(
+					"Requests with a ReadableStream body require duplex: 'half'",
+				);
+			}
+			if (contentType !== null && !this[_headers].has("content-type")) {
+				this[_headers].append("content-type", contentType);
+			}
+			this[_body] = body;
+		} else if (inputBody !== null) {
+			if (isBodyUnusable(inputBody)) {
+				throw new TypeError("Body of the input request has already been used");
+			}
+			// Body moves over to the new request, which leaves the input request used once it's read
+			this[_body] = inputBody;
+		}
+	}
+
+	/** @returns {string} */
+	get method() {
+		assertRequest(this);
+		return this[_method];
+	}
+
+	/** @returns {string} */
+	get url() {
+		assertRequest(this);
+		return this[_url];
+	}
+
+	/** @returns {import("ext:sable/fetch/headers.js").Headers} */
+	get headers() {
+		assertRequest(this);
+		return this[_headers];
+	}
+
+	/** @returns {RequestRedirect} */
+	get redirect() {
+		assertRequest(this);
+		return this[_redirect];
+	}
+
+	/**
+	 * Signal which follows the one given in the constructor
+	 * @returns {AbortSignal}
+	 */
+	get signal() {
+		assertRequest(this);
+		return this[_signal];
+	}
+
+	get destination() {
+		assertRequest(this);
+		return "";
+	}
+
+	get referrer() {
+		assertRequest(this);
+		return "about:client";
+	}
+
+	get referrerPolicy() {
+		assertRequest(this);
+		return "";
+	}
+
+	get mode() {
+		assertRequest(this);
+		return "cors";
+	}
+
+	get credentials() {
+		assertRequest(this);
+		return "same-origin";
+	}
+
+	get cache() {
+		assertRequest(this);
+		return "default";
+	}
+
+	get integrity() {
+		assertRequest(this);
+		return "";
+	}
+
+	get keepalive() {
+		assertRequest(this);
+		return false;
+	}
+
+	get isReloadNavigation() {
+		assertRequest(this);
+		return false;
+	}
+
+	get isHistoryNavigation() {
+		assertRequest(this);
+		return false;
+	}
+
+	get duplex() {
+		assertRequest(this);
+		return "half";
+	}
+
+	/** @returns {Request} */
+	clone() {
+		assertRequest(this);
+		if (isBodyUnusable(this[_body])) {
+			throw new TypeError("Body has already been used");
+		}
+
+		const request = new Request(this[_url], {
+			method: this[_method],
+			redirect: this[_redirect],
+			signal: this[_signal],
+		});
+		request[_headers] = cloneHeaders(this[_headers], "request");
+		if (this[_body] !== null) {
+			request[_body] = cloneBody(this[_body]);
+		}
+		return request;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "Request";
+	}
+}
+
+mixinBody(Request);
+
+/**
+ * @param {any} request
+ */
+function assertRequest(request) {
+	if (!(request instanceof Request)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
@@ -0,0 +1,258 @@
+import {
+	_body,
+	cloneBody,
+	extractBody,
+	isBodyUnusable,
+	mixinBody,
+} from "ext:sable/fetch/body.js";
+import {
+	cloneHeaders,
+	createHeaders,
+	headersGuard,
+	setHeadersGuard,
+} from "ext:sable/fetch/headers.js";
+import { URL } from "ext:sable/url/url.js";
+
+// https://fetch.spec.whatwg.org/#response-class
+
+/**
+ * @typedef {{
+ *   status?: number;
+ *   statusText?: string;
+ *   headers?: import("ext:sable/fetch/headers.js").HeadersInit;
+ * }} ResponseInit
+ */
+
+/**
+ * @typedef {"basic" | "default" | "error"} ResponseType
+ */
+
+/** Statuses of responses which can't have a body */
+export const NULL_BODY_STATUSES = [101, 103, 204, 205, 304];
+
+export const REDIRECT_STATUSES = [301, 302, 303, 307, 308];
+
+/** Matches RFC 7230 reason-phrase */
+const REASON_PHRASE = /^[\t\x20-\x7e\x80-\xff]*$/;
+
+export const _type = Symbol("[[type]]");
+export const _status = Symbol("[[status]]");
+export const _statusText = Symbol("[[statusText]]");
+export const _headers = Symbol("[[headers]]");
+export const _urlList = Symbol("[[urlList]]");
+
+export class Response {
+	/** @type {ResponseType} */
+	[_type] = "default";
+	/** @type {number} */
+	[_status] = 200;
+	/** @type {string} */
+	[_statusText] = "";
+	/** @type {import("ext:sable/fetch/headers.js").Headers} */
+	[_headers];
+	/** @type {string[]} URLs the response has been fetched from, the last one being the final URL */
+	[_urlList] = [];
+	/** @type {import("ext:sable/fetch/body.js").InnerBody | null} */
+	[_body] = null;
+
+	/**
+	 * @param {import("ext:sable/fetch/body.js").BodyInit | null} [body]
+	 * @param {ResponseInit} [init]
+	 */
+	constructor(body = null, init = {}) {
+		if (init === null || typeof init !== "object") {
+			init = {};
+		}
+
+		const status = init.status === undefined ? 200 : Number(init.status);
+		if (!Number.isInteger(status) || status < 200 || status > 599) {
+			throw new RangeError(
+				`Response status has to be between 200 and 599, received ${init.status}`,
+			);
+		}
+
+		const statusText = init.statusText === undefined
+			? ""
+			: String(init.statusText);
+		if (!REASON_PHRASE.test(statusText)) {
+			throw new TypeError(`Invalid response status text: '${statusText}'`);
+		}
+
+		this[_status] = status;
+		this[_statusText] = statusText;
+		this[_headers] = createHeaders(init.headers, "response");
+
+		if (body !== null && body !== undefined) {
+			if (NULL_BODY_STATUSES.includes(status)) {
+				throw new TypeError(
+					`Response with status ${status} cannot have body`,
+				);
+			}
+
+			const extracted = extractBody(body);
+			if (
+				extracted.contentType !== null &&
+				!this[_headers].has("content-type")
+			) {
+				this[_headers].append("content-type", extracted.contentType);
+			}
+			this[_body] = extracted.body;
+		}
+	}
+
+	/**
+	 * Creates network error response
+	 * @returns {Response}
+	 */
+	static error() {
+		const response = new Response();
+		response[_type] = "error";
+		response[_status] = 0;
+		setHeadersGuard(response[_headers], "immutable");
+		return response;
+	}
+
+	/**
+	 * @param {string} url
+	 * @param {number} [status=302]
+	 * @returns {Response}
+	 */
+	static redirect(url, status = 302) {
+		const location = new URL(url).href;
+		status = Number(status);
+		if (!REDIRECT_STATUSES.includes(status)) {
+			throw new RangeError(`Invalid redirect status: ${status}`);
+		}
+
+		const response = new Response(null, { headers: { location } });
+		response[_status] = status;
+		setHeadersGuard(response[_headers], "immutable");
+		return response;
+	}
+
+	/**
+	 * Creates response with data serialized as JSON
+	 * @param {any} data
+	 * @param {ResponseInit} [init]
+	 * @returns {Response}
+	 */
+	static json(data, init = {}) {
+		const json = JSON.stringify(data);
+		if (json === undefined) {
+			throw new TypeError("Data cannot be serialized as JSON");
+		}
+
+		const response = new Response(json, init);
+		response[_headers].set("content-type", "application/json");
+		return response;
+	}
+
+	/** @returns {ResponseType} */
+	get type() {
+		assertResponse(this);
+		return this[_type];
+	}
+
+	/**
+	 * Final URL of the response, empty for responses created using the constructor
+	 * @returns {string}
+	 */
+	get url() {
+		assertResponse(this);
+		const url = this[_urlList].at(-1);
+		return url === undefined ? "" : url.replace(/#.*$/, "");
+	}
+
+	/**
+	 * Whether the response has been fetched after following a redirect
+	 * @returns {boolean}
+	 */
+	get redirected() {
+		assertResponse(this);
+		return this[_urlList].length > 1;
+	}
+
+	/** @returns {number} */
+	get status() {
+		assertResponse(this);
+		return this[_status];
+	}
+
+	/** @returns {boolean} */
+	get ok() {
+		assertResponse(this);
+		return this[_status] >= 200 && this[_status] <= 299;
+	}
+
+	/** @returns {string} */
+	get statusText() {
+		assertResponse(this);
+		return this[_statusText];
+	}
+
+	/** @returns {import("ext:sable/fetch/headers.js").Headers} */
+	get headers() {
+		assertResponse(this);
+		return this[_headers];
+	}
+
+	/** @returns {Response} */
+	clone() {
+		assertResponse(this);
+		if (isBodyUnusable(this[_body])) {
+			throw new TypeError("Body has already been used");
+		}
+
+		const response = new Response();
+		response[_type] = this[_type];
+		response[_status] = this[_status];
+		response[_statusText] = this[_statusText];
+		response[_headers] = cloneHeaders(
+			this[_headers],
+			headersGuard(this[_headers]),
+		);
+		response[_urlList] = [...this[_urlList]];
+		if (this[_body] !== null) {
+			response[_body] = cloneBody(this[_body]);
+		}
+		return response;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "Response";
+	}
+}
+
+mixinBody(Response);
+
+/**
+ * @param {any} response
+ */
+function assertResponse(response) {
+	if (!(response instanceof Response)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Creates response received by `fetch`, which has immutable headers
+ * @param {{
+ *   status: number;
+ *   statusText: string;
+ *   headers: import("ext:sable/fetch/headers.js").Headers;
+ *   body: import("ext:sable/fetch/body.js").InnerBody | null;
+ *   urlList: string[];
+ * }} parts
+ * @returns {Response}
+ */
+export function createFetchResponse(parts) {
+	const response = new Response();
+	response[_type] = "basic";
+	response[_status] = parts.status;
+	response[_statusText] = parts.statusText;
+	response[_headers] = parts.headers;
+	setHeadersGuard(response[_headers], "immutable");
+	response[_urlList] = parts.urlList;
+	response[_body] = parts.body;
+	return response;
+}
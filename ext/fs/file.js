@@ -0,0 +1,157 @@
+import {
+	op_close,
+	op_fstat,
+	op_fsync,
+	op_ftruncate,
+	op_open,
+	op_read,
+	op_seek,
+	op_write,
+} from "ext:core/ops";
+import { pathFromURL } from "ext:sable/url/url.js";
+import {
+	readableStreamForRid,
+	writableStreamForRid,
+} from "ext:sable/web/streams.js";
+
+/**
+ * @typedef {{
+ *   read?: boolean;
+ *   write?: boolean;
+ *   append?: boolean;
+ *   truncate?: boolean;
+ *   create?: boolean;
+ *   createNew?: boolean;
+ *   mode?: number;
+ * }} OpenOptions
+ */
+
+/** Position from which `FsFile.seek` offset is counted */
+export const SeekMode = Object.freeze({
+	Start: 0,
+	Current: 1,
+	End: 2,
+});
+
+export class FsFile {
+	/** @type {number} */
+	#rid;
+	/** @type {ReadableStream<Uint8Array> | undefined} */
+	#readable;
+	/** @type {WritableStream<Uint8Array> | undefined} */
+	#writable;
+
+	/**
+	 * @param {number} rid - resource id of an open file
+	 */
+	constructor(rid) {
+		this.#rid = rid;
+	}
+
+	/** Resource id, which can be used with `Sable.io` functions */
+	get rid() {
+		return this.#rid;
+	}
+
+	/**
+	 * Reads bytes into the buffer from the current position
+	 * @param {Uint8Array} buffer
+	 * @returns {Promise<number | null>} amount of bytes read or `null` at the end of the file
+	 */
+	async read(buffer) {
+		if (buffer.length === 0) return 0;
+		const read = await op_read(this.#rid, buffer);
+		return read === 0 ? null : read;
+	}
+
+	/**
+	 * Writes bytes at the current position, not necessarily all of them
+	 * @param {Uint8Array} data
+	 * @returns {Promise<number>} amount of bytes written
+	 */
+	write(data) {
+		return op_write(this.#rid, data);
+	}
+
+	/**
+	 * Moves the current position
+	 * @param {number} offset
+	 * @param {typeof SeekMode[keyof typeof SeekMode]} [whence=SeekMode.Start]
+	 * @returns {Promise<number>} new position from the start of the file
+	 */
+	seek(offset, whence = SeekMode.Start) {
+		return op_seek(this.#rid, offset, whence);
+	}
+
+	/**
+	 * Truncates or extends the file to given length
+	 * @param {number} [len=0]
+	 * @returns {Promise<void>}
+	 */
+	truncate(len = 0) {
+		return op_ftruncate(this.#rid, len);
+	}
+
+	/**
+	 * Flushes data and metadata of the file to the disk
+	 * @returns {Promise<void>}
+	 */
+	sync() {
+		return op_fsync(this.#rid);
+	}
+
+	/**
+	 * @returns {Promise<import("ext:sable/fs/mod.js").FileInfo>}
+	 */
+	async stat() {
+		const info = await op_fstat(this.#rid);
+		return {
+			...info,
+			mtime: info.mtime === null ? null : new Date(info.mtime),
+			atime: info.atime === null ? null : new Date(info.atime),
+			birthtime: info.birthtime === null ? null : new Date(info.birthtime),
+		};
+	}
+
+	/** Closes the file, pending reads and writes get interrupted */
+	close() {
+		op_close(this.#rid);
+	}
+
+	/**
+	 * Stream of the file contents from the current position, file gets closed once it's read to the end
+	 * @returns {ReadableStream<Uint8Array>}
+	 */
+	get readable() {
+		this.#readable ??= readableStreamForRid(this.#rid);
+		return this.#readable;
+	}
+
+	/**
+	 * Stream writing to the file from the current position, file gets closed along with the stream
+	 * @returns {WritableStream<Uint8Array>}
+	 */
+	get writable() {
+		this.#writable ??= writableStreamForRid(this.#rid);
+		return this.#writable;
+	}
+}
+
+/**
+ * Opens a file, by default only for reading
+ * @param {string | URL} path
+ * @param {OpenOptions} [options]
+ * @returns {Promise<FsFile>}
+ */
+export async function open(path, options = { read: true }) {
+	const rid = await op_open(pathFromURL(path), {
+		read: Boolean(options.read),
+		write: Boolean(options.write),
+		append: Boolean(options.append),
+		truncate: Boolean(options.truncate),
+		create: Boolean(options.create),
+		createNew: Boolean(options.createNew),
+		mode: options.mode,
+	});
+	return new FsFile(rid);
+}
@@ -0,0 +1,214 @@
+use std::{borrow::Cow, cell::RefCell, io::SeekFrom, rc::Rc};
+
+use deno_core::{
+    error::{type_error, AnyError},
+    op2, AsyncRefCell, AsyncResult, BufMutView, BufView, CancelHandle, CancelTryFuture, OpState,
+    RcRef, Resource, ResourceId, WriteOutcome,
+};
+use sable_ext_errors::sys_error;
+use sable_ext_permissions::PermissionsContainer;
+use serde::Deserialize;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+
+use crate::FileInfo;
+
+/// Open file, which can be read and written using `Sable.io.read` and `Sable.io.write`
+pub struct FileResource {
+    file: AsyncRefCell<File>,
+    /// Cancels pending operations when the resource gets closed
+    cancel: CancelHandle,
+}
+
+impl FileResource {
+    fn new(file: File) -> Self {
+        Self {
+            file: AsyncRefCell::new(file),
+            cancel: Default::default(),
+        }
+    }
+}
+
+impl Resource for FileResource {
+    fn name(&self) -> Cow<'_, str> {
+        "fsFile".into()
+    }
+
+    fn read_byob(self: Rc<Self>, mut buf: BufMutView) -> AsyncResult<(usize, BufMutView)> {
+        Box::pin(async move {
+            let mut file = RcRef::map(&self, |resource| &resource.file)
+                .borrow_mut()
+                .await;
+            let read = file
+                .read(&mut buf)
+                .try_or_cancel(RcRef::map(&self, |resource| &resource.cancel))
+                .await
+                .map_err(|error| sys_error(error, "read", None))?;
+            Ok((read, buf))
+        })
+    }
+
+    fn write(self: Rc<Self>, buf: BufView) -> AsyncResult<WriteOutcome> {
+        Box::pin(async move {
+            let mut file = RcRef::map(&self, |resource| &resource.file)
+                .borrow_mut()
+                .await;
+            let written = async {
+                let written = file.write(&buf).await?;
+                // Tokio finishes writes in the background, data has to reach the file before the promise
+                // resolves, as closing the resource wouldn't wait for it
+                file.flush().await?;
+                Ok(written)
+            }
+            .try_or_cancel(RcRef::map(&self, |resource| &resource.cancel))
+            .await
+            .map_err(|error| sys_error(error, "write", None))?;
+            Ok(WriteOutcome::Partial {
+                nwritten: written,
+                view: buf,
+            })
+        })
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+}
+
+/// Options of `Sable.fs.open`, which mirror `std::fs::OpenOptions`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: bool,
+    create_new: bool,
+    mode: Option<u32>,
+}
+
+fn file_resource(
+    state: &Rc<RefCell<OpState>>,
+    rid: ResourceId,
+) -> Result<Rc<FileResource>, AnyError> {
+    state.borrow().resource_table.get::<FileResource>(rid)
+}
+
+#[op2(async)]
+#[smi]
+pub async fn op_open(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    #[serde] options: OpenOptions,
+) -> Result<ResourceId, AnyError> {
+    {
+        let state = state.borrow();
+        let permissions = state.borrow::<PermissionsContainer>();
+        if options.read {
+            permissions.check_read(&path, "Sable.fs.open()")?;
+        }
+        if options.write || options.append || options.create || options.create_new {
+            permissions.check_write(&path, "Sable.fs.open()")?;
+        }
+    }
+
+    let mut open_options = tokio::fs::OpenOptions::new();
+    open_options
+        .read(options.read)
+        .write(options.write)
+        .append(options.append)
+        .truncate(options.truncate)
+        .create(options.create)
+        .create_new(options.create_new);
+    if let Some(mode) = options.mode {
+        open_options.mode(mode);
+    }
+
+    let file = open_options
+        .open(&path)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
+    let rid = state
+        .borrow_mut()
+        .resource_table
+        .add(FileResource::new(file));
+    Ok(rid)
+}
+
+/// Moves the cursor by offset from the start (0), current position (1) or the end (2), returns the new position
+#[op2(async)]
+#[number]
+pub async fn op_seek(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+    #[number] offset: i64,
+    whence: u8,
+) -> Result<u64, AnyError> {
+    let seek_from = match whence {
+        0 => SeekFrom::Start(
+            u64::try_from(offset).map_err(|_| type_error("Can't seek before the start"))?,
+        ),
+        1 => SeekFrom::Current(offset),
+        2 => SeekFrom::End(offset),
+        whence => return Err(type_error(format!("Invalid seek mode: {}", whence))),
+    };
+
+    let resource = file_resource(&state, rid)?;
+    let mut file = RcRef::map(&resource, |resource| &resource.file)
+        .borrow_mut()
+        .await;
+    let position = file
+        .seek(seek_from)
+        .await
+        .map_err(|error| sys_error(error, "seek", None))?;
+    Ok(position)
+}
+
+#[op2(async)]
+pub async fn op_ftruncate(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+    #[number] len: u64,
+) -> Result<(), AnyError> {
+    let resource = file_resource(&state, rid)?;
+    let file = RcRef::map(&resource, |resource| &resource.file)
+        .borrow_mut()
+        .await;
+    file.set_len(len)
+        .await
+        .map_err(|error| sys_error(error, "ftruncate", None))?;
+    Ok(())
+}
+
+/// Flushes written data and metadata to the disk
+#[op2(async)]
+pub async fn op_fsync(state: Rc<RefCell<OpState>>, #[smi] rid: ResourceId) -> Result<(), AnyError> {
+    let resource = file_resource(&state, rid)?;
+    let file = RcRef::map(&resource, |resource| &resource.file)
+        .borrow_mut()
+        .await;
+    file.sync_all()
+        .await
+        .map_err(|error| sys_error(error, "fsync", None))?;
+    Ok(())
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_fstat(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<FileInfo, AnyError> {
+    let resource = file_resource(&state, rid)?;
+    let file = RcRef::map(&resource, |resource| &resource.file)
+        .borrow_mut()
+        .await;
+    let metadata = file
+        .metadata()
+        .await
+        .map_err(|error| sys_error(error, "fstat", None))?;
+    Ok(metadata.into())
+}
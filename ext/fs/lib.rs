@@ -1,53 +1,510 @@
-use deno_core::{error::AnyError, op2};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::CString,
+    io,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use deno_core::{
+    error::{type_error, AnyError},
+    op2, OpState,
+};
+use sable_ext_errors::sys_error;
+use sable_ext_permissions::PermissionsContainer;
+use serde::Serialize;
+
+mod file;
+pub use file::{op_fstat, op_fsync, op_ftruncate, op_open, op_seek, FileResource};
+mod watch;
+pub use watch::{op_watch_next, op_watch_start, FsEvent, FsEventKind, FsWatcherResource, Watcher};
+
+/// Attempts at generating an unused name for a temporary file or directory
+const TEMP_NAME_ATTEMPTS: usize = 100;
+
+fn check_read(state: &Rc<RefCell<OpState>>, path: &str, api_name: &str) -> Result<(), AnyError> {
+    state
+        .borrow()
+        .borrow::<PermissionsContainer>()
+        .check_read(path, api_name)
+}
+
+fn check_write(state: &Rc<RefCell<OpState>>, path: &str, api_name: &str) -> Result<(), AnyError> {
+    state
+        .borrow()
+        .borrow::<PermissionsContainer>()
+        .check_write(path, api_name)
+}
 
 // Read files
 #[op2(async)]
 #[buffer]
-pub async fn op_read_file(#[string] path: String) -> Result<Vec<u8>, AnyError> {
-    let contents = tokio::fs::read(path).await?;
+pub async fn op_read_file(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<Vec<u8>, AnyError> {
+    check_read(&state, &path, "Sable.fs.readFile()")?;
+    let contents = tokio::fs::read(&path)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
     Ok(contents)
 }
 
 #[op2(async)]
 #[string]
-pub async fn op_read_text_file(#[string] path: String) -> Result<String, AnyError> {
-    let contents = tokio::fs::read_to_string(path).await?;
+pub async fn op_read_text_file(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<String, AnyError> {
+    check_read(&state, &path, "Sable.fs.readTextFile()")?;
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
     Ok(contents)
 }
 
 // Write files
 #[op2(async)]
 pub async fn op_write_file(
+    state: Rc<RefCell<OpState>>,
     #[string] path: String,
     #[buffer(copy)] contents: Vec<u8>,
 ) -> Result<(), AnyError> {
-    tokio::fs::write(path, contents).await?;
+    check_write(&state, &path, "Sable.fs.writeFile()")?;
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
     Ok(())
 }
 
 #[op2(async)]
 pub async fn op_write_text_file(
+    state: Rc<RefCell<OpState>>,
     #[string] path: String,
     #[string] contents: String,
 ) -> Result<(), AnyError> {
-    tokio::fs::write(path, contents).await?;
+    check_write(&state, &path, "Sable.fs.writeTextFile()")?;
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
     Ok(())
 }
 
 // Remove files
 #[op2(async)]
-pub async fn op_remove_file(#[string] path: String) -> Result<(), AnyError> {
-    tokio::fs::remove_file(path).await?;
+pub async fn op_remove_file(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.removeFile()")?;
+    tokio::fs::remove_file(&path)
+        .await
+        .map_err(|error| sys_error(error, "unlink", Some(&path)))?;
     Ok(())
 }
 
 #[op2(async)]
-pub async fn op_remove_dir(#[string] path: String, recursive: bool) -> Result<(), AnyError> {
+pub async fn op_remove_dir(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    recursive: bool,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.removeDirectory()")?;
     if recursive {
-        tokio::fs::remove_dir_all(path).await?;
+        tokio::fs::remove_dir_all(&path)
+            .await
+            .map_err(|error| sys_error(error, "rmdir", Some(&path)))?;
     } else {
-        tokio::fs::remove_dir(path).await?;
+        tokio::fs::remove_dir(&path)
+            .await
+            .map_err(|error| sys_error(error, "rmdir", Some(&path)))?;
+    }
+
+    Ok(())
+}
+
+// File information
+/// Times are in milliseconds since the UNIX epoch, `None` if the platform doesn't support them
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileInfo {
+    is_file: bool,
+    is_directory: bool,
+    is_symlink: bool,
+    size: u64,
+    mtime: Option<f64>,
+    atime: Option<f64>,
+    birthtime: Option<f64>,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    dev: u64,
+    ino: u64,
+    nlink: u64,
+}
+
+fn to_msec(time: io::Result<SystemTime>) -> Option<f64> {
+    let duration = time.ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some(duration.as_secs_f64() * 1000.0)
+}
+
+impl From<std::fs::Metadata> for FileInfo {
+    fn from(metadata: std::fs::Metadata) -> Self {
+        let file_type = metadata.file_type();
+        Self {
+            is_file: file_type.is_file(),
+            is_directory: file_type.is_dir(),
+            is_symlink: file_type.is_symlink(),
+            size: metadata.len(),
+            mtime: to_msec(metadata.modified()),
+            atime: to_msec(metadata.accessed()),
+            birthtime: to_msec(metadata.created()),
+            mode: metadata.mode(),
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+            nlink: metadata.nlink(),
+        }
+    }
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_stat(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<FileInfo, AnyError> {
+    check_read(&state, &path, "Sable.fs.stat()")?;
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|error| sys_error(error, "stat", Some(&path)))?;
+    Ok(metadata.into())
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_lstat(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<FileInfo, AnyError> {
+    check_read(&state, &path, "Sable.fs.lstat()")?;
+    let metadata = tokio::fs::symlink_metadata(&path)
+        .await
+        .map_err(|error| sys_error(error, "lstat", Some(&path)))?;
+    Ok(metadata.into())
+}
+
+// Directories
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirEntry {
+    name: String,
+    is_file: bool,
+    is_directory: bool,
+    is_symlink: bool,
+}
+
+#[op2(async)]
+#[serde]
+pub async fn op_read_dir(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<Vec<DirEntry>, AnyError> {
+    check_read(&state, &path, "Sable.fs.readDir()")?;
+
+    let mut entries = vec![];
+    let read_dir_error = |error| sys_error(error, "scandir", Some(&path));
+    let mut read_dir = tokio::fs::read_dir(&path).await.map_err(read_dir_error)?;
+    while let Some(entry) = read_dir.next_entry().await.map_err(read_dir_error)? {
+        let file_type = entry.file_type().await.map_err(read_dir_error)?;
+        entries.push(DirEntry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_file: file_type.is_file(),
+            is_directory: file_type.is_dir(),
+            is_symlink: file_type.is_symlink(),
+        });
+    }
+
+    Ok(entries)
+}
+
+#[op2(async)]
+pub async fn op_mkdir(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    recursive: bool,
+    mode: Option<u32>,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.mkdir()")?;
+
+    let mut builder = tokio::fs::DirBuilder::new();
+    builder.recursive(recursive);
+    if let Some(mode) = mode {
+        builder.mode(mode);
+    }
+    builder
+        .create(&path)
+        .await
+        .map_err(|error| sys_error(error, "mkdir", Some(&path)))?;
+
+    Ok(())
+}
+
+// Moving and copying
+#[op2(async)]
+pub async fn op_rename(
+    state: Rc<RefCell<OpState>>,
+    #[string] from: String,
+    #[string] to: String,
+) -> Result<(), AnyError> {
+    check_read(&state, &from, "Sable.fs.rename()")?;
+    check_write(&state, &from, "Sable.fs.rename()")?;
+    check_write(&state, &to, "Sable.fs.rename()")?;
+    tokio::fs::rename(&from, to)
+        .await
+        .map_err(|error| sys_error(error, "rename", Some(&from)))?;
+    Ok(())
+}
+
+#[op2(async)]
+pub async fn op_copy_file(
+    state: Rc<RefCell<OpState>>,
+    #[string] from: String,
+    #[string] to: String,
+) -> Result<(), AnyError> {
+    check_read(&state, &from, "Sable.fs.copyFile()")?;
+    check_write(&state, &to, "Sable.fs.copyFile()")?;
+    tokio::fs::copy(&from, to)
+        .await
+        .map_err(|error| sys_error(error, "copyfile", Some(&from)))?;
+    Ok(())
+}
+
+// Links
+#[op2(async)]
+pub async fn op_symlink(
+    state: Rc<RefCell<OpState>>,
+    #[string] target: String,
+    #[string] path: String,
+) -> Result<(), AnyError> {
+    // Link can point anywhere, so it requires full access to the file system
+    check_read(&state, "/", "Sable.fs.symlink()")?;
+    check_write(&state, "/", "Sable.fs.symlink()")?;
+    tokio::fs::symlink(target, &path)
+        .await
+        .map_err(|error| sys_error(error, "symlink", Some(&path)))?;
+    Ok(())
+}
+
+#[op2(async)]
+#[string]
+pub async fn op_read_link(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<String, AnyError> {
+    check_read(&state, &path, "Sable.fs.readLink()")?;
+    let target = tokio::fs::read_link(&path)
+        .await
+        .map_err(|error| sys_error(error, "readlink", Some(&path)))?;
+    Ok(target.to_string_lossy().into_owned())
+}
+
+#[op2(async)]
+#[string]
+pub async fn op_real_path(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+) -> Result<String, AnyError> {
+    check_read(&state, &path, "Sable.fs.realPath()")?;
+    let real_path = tokio::fs::canonicalize(&path)
+        .await
+        .map_err(|error| sys_error(error, "realpath", Some(&path)))?;
+    Ok(real_path.to_string_lossy().into_owned())
+}
+
+// File attributes
+#[op2(async)]
+pub async fn op_chmod(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    mode: u32,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.chmod()")?;
+    let permissions = std::fs::Permissions::from_mode(mode);
+    tokio::fs::set_permissions(&path, permissions)
+        .await
+        .map_err(|error| sys_error(error, "chmod", Some(&path)))?;
+    Ok(())
+}
+
+#[op2(async)]
+pub async fn op_truncate(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    #[number] len: u64,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.truncate()")?;
+    let file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .await
+        .map_err(|error| sys_error(error, "open", Some(&path)))?;
+    file.set_len(len)
+        .await
+        .map_err(|error| sys_error(error, "ftruncate", Some(&path)))?;
+    Ok(())
+}
+
+fn to_timespec(msec: f64) -> libc::timespec {
+    let secs = (msec / 1000.0).floor();
+    libc::timespec {
+        tv_sec: secs as libc::time_t,
+        tv_nsec: ((msec / 1000.0 - secs) * 1e9) as libc::c_long,
     }
+}
+
+/// Changes access and modification times, which are in milliseconds since the UNIX epoch
+#[op2(async)]
+pub async fn op_utime(
+    state: Rc<RefCell<OpState>>,
+    #[string] path: String,
+    atime: f64,
+    mtime: f64,
+) -> Result<(), AnyError> {
+    check_write(&state, &path, "Sable.fs.utime()")?;
+
+    let c_path = CString::new(path.as_str())?;
+    let times = [to_timespec(atime), to_timespec(mtime)];
+    tokio::task::spawn_blocking(move || {
+        // SAFETY: path is a valid null terminated string and times has two elements
+        let result = unsafe { libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    })
+    .await?
+    .map_err(|error| sys_error(error, "utime", Some(&path)))?;
 
     Ok(())
 }
+
+// Temporary files
+/// Returns path of a random entry in given or the default temporary directory
+fn temp_path(dir: Option<String>, prefix: &str, suffix: &str) -> PathBuf {
+    let dir = dir.map_or_else(env::temp_dir, PathBuf::from);
+    dir.join(format!("{}{:08x}{}", prefix, fastrand::u32(..), suffix))
+}
+
+/// Rejects prefix or suffix which could place the entry outside of the temporary directory
+fn check_temp_affix(kind: &str, affix: &str) -> Result<(), AnyError> {
+    if affix.contains(std::path::is_separator) || affix.contains("..") {
+        return Err(type_error(format!(
+            "Invalid {} \"{}\", it can't contain path separators or \"..\"",
+            kind, affix
+        )));
+    }
+    Ok(())
+}
+
+fn check_temp_path(
+    state: &Rc<RefCell<OpState>>,
+    dir: &Option<String>,
+    prefix: &str,
+    suffix: &str,
+    api_name: &str,
+) -> Result<(), AnyError> {
+    check_temp_affix("prefix", prefix)?;
+    check_temp_affix("suffix", suffix)?;
+
+    let default_dir = env::temp_dir();
+    let dir = match dir {
+        Some(dir) => Path::new(dir),
+        None => &default_dir,
+    };
+    check_write(state, &dir.to_string_lossy(), api_name)
+}
+
+#[op2(async)]
+#[string]
+pub async fn op_make_temp_file(
+    state: Rc<RefCell<OpState>>,
+    #[string] dir: Option<String>,
+    #[string] prefix: String,
+    #[string] suffix: String,
+) -> Result<String, AnyError> {
+    check_temp_path(&state, &dir, &prefix, &suffix, "Sable.fs.makeTempFile()")?;
+
+    for _ in 0..TEMP_NAME_ATTEMPTS {
+        let path = temp_path(dir.clone(), &prefix, &suffix);
+        let created = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)
+            .await;
+
+        match created {
+            Ok(_) => return Ok(path.to_string_lossy().into_owned()),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(sys_error(error, "open", path.to_str())),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "Failed to find an unused name for the temporary file",
+    )
+    .into())
+}
+
+#[op2(async)]
+#[string]
+pub async fn op_make_temp_dir(
+    state: Rc<RefCell<OpState>>,
+    #[string] dir: Option<String>,
+    #[string] prefix: String,
+    #[string] suffix: String,
+) -> Result<String, AnyError> {
+    check_temp_path(&state, &dir, &prefix, &suffix, "Sable.fs.makeTempDir()")?;
+
+    for _ in 0..TEMP_NAME_ATTEMPTS {
+        let path = temp_path(dir.clone(), &prefix, &suffix);
+        let created = tokio::fs::DirBuilder::new().mode(0o700).create(&path).await;
+
+        match created {
+            Ok(_) => return Ok(path.to_string_lossy().into_owned()),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(sys_error(error, "mkdir", path.to_str())),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "Failed to find an unused name for the temporary directory",
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_affixes_cant_leave_directory() {
+        assert!(check_temp_affix("prefix", "sable_test_").is_ok());
+        assert!(check_temp_affix("suffix", ".txt").is_ok());
+
+        for affix in ["../", "..", "a/b", "/etc/"] {
+            assert!(
+                check_temp_affix("prefix", affix).is_err(),
+                "{affix:?} is valid"
+            );
+        }
+    }
+}
@@ -1,67 +1,282 @@
 import {
+	op_chmod,
+	op_copy_file,
+	op_lstat,
+	op_make_temp_dir,
+	op_make_temp_file,
+	op_mkdir,
+	op_read_dir,
 	op_read_file,
+	op_read_link,
 	op_read_text_file,
+	op_real_path,
 	op_remove_dir,
 	op_remove_file,
+	op_rename,
+	op_stat,
+	op_symlink,
+	op_truncate,
+	op_utime,
 	op_write_file,
 	op_write_text_file,
 } from "ext:core/ops";
+import { FsFile, open, SeekMode } from "ext:sable/fs/file.js";
+import { FsWatcher, watch } from "ext:sable/fs/watch.js";
+import { pathFromURL } from "ext:sable/url/url.js";
+
+/**
+ * @typedef {{
+ *   isFile: boolean;
+ *   isDirectory: boolean;
+ *   isSymlink: boolean;
+ *   size: number;
+ *   mtime: Date | null;
+ *   atime: Date | null;
+ *   birthtime: Date | null;
+ *   mode: number;
+ *   uid: number;
+ *   gid: number;
+ *   dev: number;
+ *   ino: number;
+ *   nlink: number;
+ * }} FileInfo
+ */
+
+/**
+ * @typedef {{
+ *   name: string;
+ *   isFile: boolean;
+ *   isDirectory: boolean;
+ *   isSymlink: boolean;
+ * }} DirEntry
+ */
+
+/**
+ * @typedef {{
+ *   dir?: string;
+ *   prefix?: string;
+ *   suffix?: string;
+ * }} MakeTempOptions
+ */
 
 /**
  * Reads a file asynchronously
- * @param {string} path
+ * @param {string | URL} path
  * @returns {Promise<Uint8Array>}
  */
 function readFile(path) {
-	return op_read_file(path);
+	return op_read_file(pathFromURL(path));
 }
 
 /**
  * Reads a text file synchronously
- * @param {string} path
+ * @param {string | URL} path
  * @returns {Promise<string>}
  */
 function readTextFile(path) {
-	return op_read_text_file(path);
+	return op_read_text_file(pathFromURL(path));
 }
 
 /**
  * Writes a file asynchronously
- * @param {string} path
+ * @param {string | URL} path
  * @param {Uint8Array} contents
  * @returns {Promise<void>}
  */
 function writeFile(path, contents) {
-	return op_write_file(path, contents);
+	return op_write_file(pathFromURL(path), contents);
 }
 
 /**
  * Writes a text file asynchronously
- * @param {string} path
+ * @param {string | URL} path
  * @param {string} contents
  * @returns {Promise<void>}
  */
 function writeTextFile(path, contents) {
-	return op_write_text_file(path, contents);
+	return op_write_text_file(pathFromURL(path), contents);
 }
 
 /**
  * Deletes file asynchronously
- * @param {string} path
+ * @param {string | URL} path
  * @returns {Promise<void>}
  */
 function removeFile(path) {
-	return op_remove_file(path);
+	return op_remove_file(pathFromURL(path));
 }
 
 /**
  * Deletes directory asynchronously
- * @param {string} path
+ * @param {string | URL} path
  * @param {boolean} recursive
  * @returns {Promise<void>}
  */
 function removeDirectory(path, recursive) {
-	return op_remove_dir(path, recursive);
+	return op_remove_dir(pathFromURL(path), recursive);
+}
+
+/**
+ * @param {Awaited<ReturnType<typeof op_stat>>} info
+ * @returns {FileInfo}
+ */
+function toFileInfo(info) {
+	return {
+		...info,
+		mtime: info.mtime === null ? null : new Date(info.mtime),
+		atime: info.atime === null ? null : new Date(info.atime),
+		birthtime: info.birthtime === null ? null : new Date(info.birthtime),
+	};
+}
+
+/**
+ * @param {Date | number} time - date or milliseconds since the UNIX epoch
+ * @returns {number}
+ */
+function toMilliseconds(time) {
+	return time instanceof Date ? time.getTime() : Number(time);
+}
+
+/**
+ * Returns information about a file, following symlinks
+ * @param {string | URL} path
+ * @returns {Promise<FileInfo>}
+ */
+async function stat(path) {
+	return toFileInfo(await op_stat(pathFromURL(path)));
+}
+
+/**
+ * Returns information about a file, without following symlinks
+ * @param {string | URL} path
+ * @returns {Promise<FileInfo>}
+ */
+async function lstat(path) {
+	return toFileInfo(await op_lstat(pathFromURL(path)));
+}
+
+/**
+ * Iterates over entries of a directory
+ * @param {string | URL} path
+ * @returns {AsyncIterableIterator<DirEntry>}
+ */
+async function* readDir(path) {
+	yield* await op_read_dir(pathFromURL(path));
+}
+
+/**
+ * Creates a directory
+ * @param {string | URL} path
+ * @param {{ recursive?: boolean, mode?: number }} [options] - recursive also creates missing parents
+ * @returns {Promise<void>}
+ */
+function mkdir(path, options) {
+	return op_mkdir(pathFromURL(path), Boolean(options?.recursive), options?.mode);
+}
+
+/**
+ * Moves file or directory, replacing the target if it exists
+ * @param {string | URL} from
+ * @param {string | URL} to
+ * @returns {Promise<void>}
+ */
+function rename(from, to) {
+	return op_rename(pathFromURL(from), pathFromURL(to));
+}
+
+/**
+ * Copies contents and permissions of a file, replacing the target if it exists
+ * @param {string | URL} from
+ * @param {string | URL} to
+ * @returns {Promise<void>}
+ */
+function copyFile(from, to) {
+	return op_copy_file(pathFromURL(from), pathFromURL(to));
+}
+
+/**
+ * Creates symbolic link at path pointing to target
+ * @param {string | URL} target
+ * @param {string | URL} path
+ * @returns {Promise<void>}
+ */
+function symlink(target, path) {
+	return op_symlink(pathFromURL(target), pathFromURL(path));
+}
+
+/**
+ * Returns target of a symbolic link
+ * @param {string | URL} path
+ * @returns {Promise<string>}
+ */
+function readLink(path) {
+	return op_read_link(pathFromURL(path));
+}
+
+/**
+ * Returns absolute path with symlinks resolved
+ * @param {string | URL} path
+ * @returns {Promise<string>}
+ */
+function realPath(path) {
+	return op_real_path(pathFromURL(path));
+}
+
+/**
+ * Changes permissions of a file
+ * @param {string | URL} path
+ * @param {number} mode - e.g. 0o755
+ * @returns {Promise<void>}
+ */
+function chmod(path, mode) {
+	return op_chmod(pathFromURL(path), mode);
+}
+
+/**
+ * Truncates or extends file to given length
+ * @param {string | URL} path
+ * @param {number} [len=0]
+ * @returns {Promise<void>}
+ */
+function truncate(path, len = 0) {
+	return op_truncate(pathFromURL(path), len);
+}
+
+/**
+ * Changes access and modification times of a file
+ * @param {string | URL} path
+ * @param {Date | number} atime - date or milliseconds since the UNIX epoch
+ * @param {Date | number} mtime - date or milliseconds since the UNIX epoch
+ * @returns {Promise<void>}
+ */
+function utime(path, atime, mtime) {
+	return op_utime(pathFromURL(path), toMilliseconds(atime), toMilliseconds(mtime));
+}
+
+/**
+ * Creates a new empty file with an unique name and returns its path
+ * @param {MakeTempOptions} [options]
+ * @returns {Promise<string>}
+ */
+function makeTempFile(options) {
+	return op_make_temp_file(
+		options?.dir,
+		options?.prefix ?? "",
+		options?.suffix ?? "",
+	);
+}
+
+/**
+ * Creates a new empty directory with an unique name and returns its path
+ * @param {MakeTempOptions} [options]
+ * @returns {Promise<string>}
+ */
+function makeTempDir(options) {
+	return op_make_temp_dir(
+		options?.dir,
+		options?.prefix ?? "",
+		options?.suffix ?? "",
+	);
 }
 
 Sable.fs = {
@@ -71,4 +286,23 @@ Sable.fs = {
 	writeTextFile,
 	removeFile,
 	removeDirectory,
+	stat,
+	lstat,
+	readDir,
+	mkdir,
+	rename,
+	copyFile,
+	symlink,
+	readLink,
+	realPath,
+	chmod,
+	truncate,
+	utime,
+	makeTempFile,
+	makeTempDir,
+	open,
+	FsFile,
+	SeekMode,
+	watch,
+	FsWatcher,
 };
@@ -0,0 +1,105 @@
+import { op_close, op_watch_next, op_watch_start } from "ext:core/ops";
+import { pathFromURL } from "ext:sable/url/url.js";
+
+/**
+ * @typedef {{
+ *   kind: "create" | "modify" | "remove" | "rename";
+ *   paths: string[];
+ * }} FsEvent
+ *
+ * Rename events have the old and the new path
+ */
+
+/**
+ * Async iterable of file system changes, which are reported once no more have happened for a short while
+ * @implements {AsyncIterableIterator<FsEvent>}
+ */
+export class FsWatcher {
+	/** @type {number | undefined} */
+	#rid;
+	/** @type {Promise<void>} */
+	#ready;
+	/** @type {FsEvent[]} Changes which have been received, but not yet iterated over */
+	#pending = [];
+	#closed = false;
+
+	/**
+	 * @param {Promise<number>} rid - resource id of the watcher, which resolves once its paths are watched
+	 */
+	constructor(rid) {
+		this.#ready = rid.then((rid) => {
+			this.#rid = rid;
+			// Watcher got closed while its paths were being watched
+			if (this.#closed) op_close(rid);
+		});
+		// Failure is thrown by `ready` and `next()`, a watcher which only gets closed mustn't report it as unhandled
+		this.#ready.catch(() => {});
+	}
+
+	/** Resource id of the watcher, `undefined` until it's ready */
+	get rid() {
+		return this.#rid;
+	}
+
+	/**
+	 * Resolves once the paths are watched, changes made before that might not be reported
+	 *
+	 * Rejects when some of the paths can't be watched
+	 * @returns {Promise<void>}
+	 */
+	get ready() {
+		return this.#ready;
+	}
+
+	/**
+	 * @returns {Promise<IteratorResult<FsEvent>>}
+	 */
+	async next() {
+		await this.#ready;
+		while (this.#pending.length === 0) {
+			if (this.#closed) return { value: undefined, done: true };
+
+			const events = await op_watch_next(this.#rid);
+			if (events === null) {
+				this.#closed = true;
+			} else {
+				this.#pending.push(...events);
+			}
+		}
+		return { value: this.#pending.shift(), done: false };
+	}
+
+	/**
+	 * Called when iteration ends early, e.g. by `break` in a `for await` loop
+	 * @returns {Promise<IteratorResult<FsEvent>>}
+	 */
+	return() {
+		this.close();
+		return Promise.resolve({ value: undefined, done: true });
+	}
+
+	/** Stops watching, pending iteration ends */
+	close() {
+		if (this.#closed) return;
+		this.#closed = true;
+		if (this.#rid !== undefined) op_close(this.#rid);
+	}
+
+	[Symbol.asyncIterator]() {
+		return this;
+	}
+}
+
+/**
+ * Watches files and directories for changes
+ * @param {string | URL | (string | URL)[]} paths
+ * @param {{ recursive?: boolean }} [options] - whether changes inside subdirectories are reported, defaults to true
+ * @returns {FsWatcher}
+ */
+export function watch(paths, options) {
+	const rid = op_watch_start(
+		(Array.isArray(paths) ? paths : [paths]).map(pathFromURL),
+		options?.recursive ?? true,
+	);
+	return new FsWatcher(rid);
+}
@@ -0,0 +1,394 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    io, mem,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use deno_core::{
+    error::AnyError, op2, AsyncRefCell, CancelFuture, CancelHandle, OpState, RcRef, Resource,
+    ResourceId,
+};
+use sable_ext_errors::sys_error;
+use sable_ext_permissions::PermissionsContainer;
+use serde::Serialize;
+use tokio::{io::unix::AsyncFd, time::Instant};
+
+/// Changes reported by inotify
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_MODIFY
+    | libc::IN_ATTRIB
+    | libc::IN_DELETE
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+/// How long to wait for more changes after the first one, before reporting them together
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(50);
+
+/// Longest time changes get held back for, so that constant changes can't postpone them forever
+const MAX_DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
+
+/// Size of the buffer inotify events are read into, fits at least one event with the longest name
+const EVENTS_BUFFER_SIZE: usize = 4096;
+
+const EVENT_HEADER_SIZE: usize = mem::size_of::<libc::inotify_event>();
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FsEventKind {
+    Create,
+    Modify,
+    Remove,
+    Rename,
+}
+
+/// Change of the file system, rename has the old and the new path
+#[derive(Serialize, PartialEq)]
+pub struct FsEvent {
+    pub kind: FsEventKind,
+    pub paths: Vec<String>,
+}
+
+impl FsEvent {
+    fn new(kind: FsEventKind, paths: &[&Path]) -> Self {
+        Self {
+            kind,
+            paths: paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+}
+
+/// Event read from inotify, path is missing when the watch has already been removed
+struct RawEvent {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    path: Option<PathBuf>,
+}
+
+/// Inotify instance, which reports changes of watched paths
+pub struct Watcher {
+    fd: AsyncFd<OwnedFd>,
+    /// Watched paths by their watch descriptors
+    watches: HashMap<i32, PathBuf>,
+    recursive: bool,
+    /// Events which have been read, but not yet reported
+    pending: Vec<RawEvent>,
+}
+
+impl Watcher {
+    pub fn new(recursive: bool) -> io::Result<Self> {
+        // SAFETY: inotify_init1 has no preconditions
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: fd is a newly created file descriptor, which nothing else owns
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        Ok(Self {
+            fd: AsyncFd::new(fd)?,
+            watches: HashMap::new(),
+            recursive,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Watches the path and when recursive, all directories inside it
+    pub fn add_watch(&mut self, path: &Path) -> io::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        // SAFETY: fd is an inotify instance and c_path is a valid null terminated string
+        let wd =
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.watches.insert(wd, path.to_path_buf());
+
+        if self.recursive && path.is_dir() {
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?;
+                // Symlinks aren't followed, so that cycles can't happen
+                if entry.file_type()?.is_dir() {
+                    self.add_watch(&entry.path())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits for at least one event and returns all which are available
+    async fn read(&self) -> io::Result<Vec<RawEvent>> {
+        let mut buffer = [0u8; EVENTS_BUFFER_SIZE];
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                // SAFETY: buffer is valid for writes of its length
+                let read = unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        buffer.as_mut_ptr().cast(),
+                        EVENTS_BUFFER_SIZE,
+                    )
+                };
+                if read < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(read as usize)
+                }
+            });
+
+            if let Ok(read) = result {
+                return Ok(self.parse(&buffer[..read?]));
+            }
+        }
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Vec<RawEvent> {
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while offset + EVENT_HEADER_SIZE <= bytes.len() {
+            // SAFETY: kernel only writes whole events, so the header is within the buffer
+            let event = unsafe {
+                std::ptr::read_unaligned(bytes[offset..].as_ptr().cast::<libc::inotify_event>())
+            };
+            let name_start = offset + EVENT_HEADER_SIZE;
+            offset = name_start + event.len as usize;
+
+            // Name is padded with null bytes and missing for events of the watched path itself
+            let name = &bytes[name_start..offset.min(bytes.len())];
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+            let path = self.watches.get(&event.wd).map(|path| {
+                if name.is_empty() {
+                    path.clone()
+                } else {
+                    path.join(OsStr::from_bytes(name))
+                }
+            });
+
+            events.push(RawEvent {
+                wd: event.wd,
+                mask: event.mask,
+                cookie: event.cookie,
+                path,
+            });
+        }
+        events
+    }
+
+    /// Turns inotify events into file system events, pairing renames and dropping duplicates
+    fn process(&mut self, raw_events: Vec<RawEvent>) -> Vec<FsEvent> {
+        let mut events = Vec::new();
+        let mut moved_from: Vec<(u32, PathBuf)> = Vec::new();
+
+        for raw_event in raw_events {
+            if raw_event.mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&raw_event.wd);
+                continue;
+            }
+            let Some(path) = raw_event.path else {
+                continue;
+            };
+
+            let is_new = raw_event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+            if self.recursive && is_new && raw_event.mask & libc::IN_ISDIR != 0 {
+                // Directory could have been removed right away, which isn't worth failing for
+                let _ = self.add_watch(&path);
+            }
+
+            let event = if raw_event.mask & libc::IN_MOVED_FROM != 0 {
+                moved_from.push((raw_event.cookie, path));
+                continue;
+            } else if raw_event.mask & libc::IN_MOVED_TO != 0 {
+                let from = moved_from
+                    .iter()
+                    .position(|(cookie, _)| *cookie == raw_event.cookie)
+                    .map(|index| moved_from.remove(index).1);
+                match from {
+                    Some(from) => FsEvent::new(FsEventKind::Rename, &[&from, &path]),
+                    // Moved in from outside of the watched paths
+                    None => FsEvent::new(FsEventKind::Create, &[&path]),
+                }
+            } else if raw_event.mask & libc::IN_CREATE != 0 {
+                FsEvent::new(FsEventKind::Create, &[&path])
+            } else if raw_event.mask & (libc::IN_MODIFY | libc::IN_ATTRIB) != 0 {
+                FsEvent::new(FsEventKind::Modify, &[&path])
+            } else if raw_event.mask & (libc::IN_DELETE | libc::IN_DELETE_SELF) != 0 {
+                FsEvent::new(FsEventKind::Remove, &[&path])
+            } else {
+                continue;
+            };
+
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+
+        // Moved out of the watched paths
+        for (_, path) in moved_from {
+            let event = FsEvent::new(FsEventKind::Remove, &[&path]);
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Waits for changes and returns them once no more have happened for `DEBOUNCE_DURATION`,
+    /// or `MAX_DEBOUNCE_DURATION` after the first one
+    ///
+    /// Cancel safe, changes read before the future got dropped are returned by the next call
+    pub async fn next_events(&mut self) -> io::Result<Vec<FsEvent>> {
+        loop {
+            if self.pending.is_empty() {
+                let raw_events = self.read().await?;
+                self.pending.extend(raw_events);
+            }
+
+            let deadline = Instant::now() + MAX_DEBOUNCE_DURATION;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                match tokio::time::timeout(DEBOUNCE_DURATION.min(remaining), self.read()).await {
+                    Ok(more) => self.pending.extend(more?),
+                    Err(_) => break,
+                }
+            }
+
+            let raw_events = mem::take(&mut self.pending);
+            let events = self.process(raw_events);
+            if !events.is_empty() {
+                return Ok(events);
+            }
+        }
+    }
+}
+
+/// Watcher of file system changes created by `Sable.fs.watch`
+pub struct FsWatcherResource {
+    watcher: AsyncRefCell<Watcher>,
+    /// Ends the pending `op_watch_next` when the watcher gets closed
+    cancel: CancelHandle,
+}
+
+impl Resource for FsWatcherResource {
+    fn name(&self) -> Cow<'_, str> {
+        "fsWatcher".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+}
+
+/// Creates a watcher of given paths, recursive one walks through all of their subdirectories
+#[op2(async)]
+#[smi]
+pub async fn op_watch_start(
+    state: Rc<RefCell<OpState>>,
+    #[serde] paths: Vec<String>,
+    recursive: bool,
+) -> Result<ResourceId, AnyError> {
+    for path in &paths {
+        state
+            .borrow()
+            .borrow::<PermissionsContainer>()
+            .check_read(path, "Sable.fs.watch()")?;
+    }
+
+    let mut watcher =
+        Watcher::new(recursive).map_err(|error| sys_error(error, "inotify_init", None))?;
+    // Walking through large directory trees would block the event loop
+    let watcher = tokio::task::spawn_blocking(move || {
+        for path in &paths {
+            let real_path = std::fs::canonicalize(path)
+                .map_err(|error| sys_error(error, "watch", Some(path)))?;
+            watcher
+                .add_watch(&real_path)
+                .map_err(|error| sys_error(error, "watch", Some(path)))?;
+        }
+        Ok::<_, AnyError>(watcher)
+    })
+    .await??;
+
+    let rid = state.borrow_mut().resource_table.add(FsWatcherResource {
+        watcher: AsyncRefCell::new(watcher),
+        cancel: Default::default(),
+    });
+    Ok(rid)
+}
+
+/// Waits for the next debounced batch of changes, returns `None` once the watcher gets closed
+#[op2(async)]
+#[serde]
+pub async fn op_watch_next(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<Option<Vec<FsEvent>>, AnyError> {
+    let resource = state
+        .borrow()
+        .resource_table
+        .get::<FsWatcherResource>(rid)?;
+    let mut watcher = RcRef::map(&resource, |resource| &resource.watcher)
+        .borrow_mut()
+        .await;
+
+    match watcher
+        .next_events()
+        .or_cancel(RcRef::map(&resource, |resource| &resource.cancel))
+        .await
+    {
+        Ok(events) => Ok(Some(
+            events.map_err(|error| sys_error(error, "read", None))?,
+        )),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn constant_changes_are_reported_after_max_debounce() {
+        let dir = env::temp_dir().join(format!("sable_watch_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut watcher = Watcher::new(false).unwrap();
+        watcher.add_watch(&dir).unwrap();
+
+        // Changes keep happening more often than the debounce duration
+        let path = dir.join("changing.txt");
+        let writer = tokio::spawn(async move {
+            for index in 0..200 {
+                tokio::fs::write(&path, index.to_string()).await.unwrap();
+                tokio::time::sleep(DEBOUNCE_DURATION / 5).await;
+            }
+        });
+
+        let start = Instant::now();
+        let events = watcher.next_events().await.unwrap();
+        let elapsed = start.elapsed();
+        writer.abort();
+        let _ = writer.await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!events.is_empty());
+        assert!(elapsed < MAX_DEBOUNCE_DURATION * 2, "took {elapsed:?}");
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    io::{self, Read},
+    os::fd::{AsRawFd, RawFd},
+    rc::Rc,
+    sync::mpsc as std_mpsc,
+    thread,
+};
+
+use deno_core::{
+    error::{not_supported, AnyError},
+    op2, AsyncRefCell, AsyncResult, BufMutView, BufView, CancelHandle, CancelTryFuture, OpState,
+    RcRef, Resource, ResourceHandle, ResourceId, WriteOutcome,
+};
+use sable_ext_errors::sys_error;
+use serde::Serialize;
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::{mpsc, Mutex},
+};
+
+enum StdioStream {
+    /// Read through `STDIN_READER`
+    Stdin,
+    Stdout(tokio::io::Stdout),
+    Stderr(tokio::io::Stderr),
+}
+
+/// Reader of the process' stdin, shared by the stdin resources of all runtimes
+static STDIN_READER: Mutex<Option<StdinReader>> = Mutex::const_new(None);
+
+/// Reads from a source on a dedicated thread
+///
+/// Blocking reads can't be canceled, on tokio's blocking pool they would keep the runtime from shutting down.
+/// The thread gets detached instead, so a read which never finishes doesn't hold up anything
+struct StdinReader {
+    /// Sizes of the reads the thread should make
+    requests: std_mpsc::Sender<usize>,
+    results: mpsc::UnboundedReceiver<io::Result<Vec<u8>>>,
+    /// Whether the thread is reading for a request, whose result hasn't been received yet
+    requested: bool,
+    /// Bytes which didn't fit into the buffer of the read which received them
+    pending: Vec<u8>,
+}
+
+impl StdinReader {
+    fn spawn(mut source: impl Read + Send + 'static) -> io::Result<Self> {
+        let (requests, request_receiver) = std_mpsc::channel::<usize>();
+        let (result_sender, results) = mpsc::unbounded_channel();
+        thread::Builder::new()
+            .name("sable-stdin".to_owned())
+            .spawn(move || {
+                for size in request_receiver {
+                    let mut buf = vec![0; size];
+                    let result = source.read(&mut buf).map(|read| {
+                        buf.truncate(read);
+                        buf
+                    });
+                    if result_sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self {
+            requests,
+            results,
+            requested: false,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Cancel safe, bytes read for a canceled call are returned by the next one
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            if !self.requested {
+                self.requests
+                    .send(buf.len())
+                    .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+                self.requested = true;
+            }
+            let result = self
+                .results
+                .recv()
+                .await
+                .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe));
+            self.requested = false;
+            self.pending = result??;
+        }
+
+        let read = buf.len().min(self.pending.len());
+        buf[..read].copy_from_slice(&self.pending[..read]);
+        self.pending.drain(..read);
+        Ok(read)
+    }
+}
+
+/// Reads from stdin, the reading thread gets started by the first read
+async fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+    let mut reader = STDIN_READER.lock().await;
+    if reader.is_none() {
+        *reader = Some(StdinReader::spawn(io::stdin())?);
+    }
+    reader.as_mut().unwrap().read(buf).await
+}
+
+/// Standard input, output or error of the process
+pub struct StdioResource {
+    stream: AsyncRefCell<StdioStream>,
+    fd: RawFd,
+    /// Cancels pending operations when the resource gets closed
+    cancel: CancelHandle,
+    /// Terminal settings from before raw mode got enabled
+    original_termios: RefCell<Option<libc::termios>>,
+}
+
+impl StdioResource {
+    fn new(stream: StdioStream, fd: RawFd) -> Self {
+        Self {
+            stream: AsyncRefCell::new(stream),
+            fd,
+            cancel: Default::default(),
+            original_termios: RefCell::new(None),
+        }
+    }
+
+    fn set_raw(&self, enabled: bool) -> io::Result<()> {
+        let mut original_termios = self.original_termios.borrow_mut();
+
+        if !enabled {
+            if let Some(termios) = original_termios.take() {
+                set_termios(self.fd, &termios)?;
+            }
+            return Ok(());
+        }
+
+        if original_termios.is_some() {
+            return Ok(());
+        }
+
+        // SAFETY: termios is plain data, which tcgetattr overwrites
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: termios is a valid pointer
+        if unsafe { libc::tcgetattr(self.fd, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+
+        // SAFETY: termios has been initialized by tcgetattr
+        unsafe { libc::cfmakeraw(&mut termios) };
+        // Ctrl+C should still be able to stop the process
+        termios.c_lflag |= libc::ISIG;
+        set_termios(self.fd, &termios)?;
+
+        *original_termios = Some(original);
+        Ok(())
+    }
+}
+
+impl Drop for StdioResource {
+    // Terminal would stay in raw mode after the process exits otherwise
+    fn drop(&mut self) {
+        let _ = self.set_raw(false);
+    }
+}
+
+fn set_termios(fd: RawFd, termios: &libc::termios) -> io::Result<()> {
+    // SAFETY: termios is a valid pointer
+    if unsafe { libc::tcsetattr(fd, libc::TCSADRAIN, termios) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Tokio buffers writes, they would show up out of order with `console` output if they weren't flushed
+async fn write_and_flush(writer: &mut (impl AsyncWrite + Unpin), buf: &[u8]) -> io::Result<usize> {
+    let written = writer.write(buf).await?;
+    writer.flush().await?;
+    Ok(written)
+}
+
+impl Resource for StdioResource {
+    fn name(&self) -> Cow<'_, str> {
+        match self.fd {
+            libc::STDIN_FILENO => "stdin".into(),
+            libc::STDOUT_FILENO => "stdout".into(),
+            _ => "stderr".into(),
+        }
+    }
+
+    fn read_byob(self: Rc<Self>, mut buf: BufMutView) -> AsyncResult<(usize, BufMutView)> {
+        Box::pin(async move {
+            let stream = RcRef::map(&self, |resource| &resource.stream)
+                .borrow_mut()
+                .await;
+            let StdioStream::Stdin = &*stream else {
+                return Err(not_supported());
+            };
+            let read = read_stdin(&mut buf)
+                .try_or_cancel(RcRef::map(&self, |resource| &resource.cancel))
+                .await
+                .map_err(|error| sys_error(error, "read", None))?;
+            Ok((read, buf))
+        })
+    }
+
+    fn write(self: Rc<Self>, buf: BufView) -> AsyncResult<WriteOutcome> {
+        Box::pin(async move {
+            let mut stream = RcRef::map(&self, |resource| &resource.stream)
+                .borrow_mut()
+                .await;
+            let written = match &mut *stream {
+                StdioStream::Stdin => return Err(not_supported()),
+                StdioStream::Stdout(stdout) => write_and_flush(stdout, &buf).await,
+                StdioStream::Stderr(stderr) => write_and_flush(stderr, &buf).await,
+            }
+            .map_err(|error| sys_error(error, "write", None))?;
+            Ok(WriteOutcome::Partial {
+                nwritten: written,
+                view: buf,
+            })
+        })
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+
+    fn backing_handle(self: Rc<Self>) -> Option<ResourceHandle> {
+        Some(ResourceHandle::Fd(self.fd))
+    }
+}
+
+/// Adds stdin, stdout and stderr to the resource table, under rids matching their file descriptors
+pub fn init_stdio(state: &mut OpState) {
+    let stdin = std::io::stdin().as_raw_fd();
+    let stdout = std::io::stdout().as_raw_fd();
+    let stderr = std::io::stderr().as_raw_fd();
+
+    let resources = [
+        StdioResource::new(StdioStream::Stdin, stdin),
+        StdioResource::new(StdioStream::Stdout(tokio::io::stdout()), stdout),
+        StdioResource::new(StdioStream::Stderr(tokio::io::stderr()), stderr),
+    ];
+    for resource in resources {
+        let fd = resource.fd;
+        let rid = state.resource_table.add(resource);
+        // Sable.stdin and friends use the file descriptors as rids
+        assert_eq!(
+            rid as RawFd, fd,
+            "stdio has to be the first added resources"
+        );
+    }
+}
+
+fn resource_fd(state: &OpState, rid: ResourceId) -> Result<RawFd, AnyError> {
+    match state.resource_table.get_any(rid)?.backing_handle() {
+        Some(ResourceHandle::Fd(fd)) => Ok(fd),
+        _ => Err(not_supported()),
+    }
+}
+
+/// Whether resource is connected to a terminal
+#[op2(fast)]
+pub fn op_is_terminal(state: &mut OpState, #[smi] rid: ResourceId) -> Result<bool, AnyError> {
+    let Ok(fd) = resource_fd(state, rid) else {
+        return Ok(false);
+    };
+    // SAFETY: isatty accepts any file descriptor
+    Ok(unsafe { libc::isatty(fd) } == 1)
+}
+
+/// Enables or disables raw mode, in which input is available right away and isn't echoed
+#[op2(fast)]
+pub fn op_set_raw(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+    enabled: bool,
+) -> Result<(), AnyError> {
+    let resource = state.resource_table.get::<StdioResource>(rid)?;
+    resource
+        .set_raw(enabled)
+        .map_err(|error| sys_error(error, "tcsetattr", None))
+}
+
+#[derive(Serialize)]
+pub struct ConsoleSize {
+    columns: u16,
+    rows: u16,
+}
+
+#[op2]
+#[serde]
+pub fn op_console_size(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+) -> Result<ConsoleSize, AnyError> {
+    let fd = resource_fd(state, rid)?;
+    // SAFETY: winsize is plain data, which ioctl overwrites
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: TIOCGWINSZ expects a pointer to winsize
+    if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } != 0 {
+        return Err(sys_error(io::Error::last_os_error(), "ioctl", None));
+    }
+    Ok(ConsoleSize {
+        columns: size.ws_col,
+        rows: size.ws_row,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs::File,
+        io::Write,
+        os::fd::FromRawFd,
+        time::{Duration, Instant},
+    };
+
+    fn pipe() -> (File, File) {
+        let mut fds = [0; 2];
+        // SAFETY: fds has room for both ends of the pipe
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        // SAFETY: pipe has just opened the file descriptors, nothing else owns them
+        unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+    }
+
+    #[tokio::test]
+    async fn canceled_reads_lose_no_input() {
+        let (read_end, mut write_end) = pipe();
+        let mut reader = StdinReader::spawn(read_end).unwrap();
+        let mut buf = [0; 3];
+
+        let timeout = tokio::time::timeout(Duration::from_millis(50), reader.read(&mut buf));
+        assert!(timeout.await.is_err());
+
+        write_end.write_all(b"hello").unwrap();
+        assert_eq!(reader.read(&mut buf).await.unwrap(), 3);
+        assert_eq!(&buf, b"hel");
+        assert_eq!(reader.read(&mut buf).await.unwrap(), 2);
+        assert_eq!(&buf[..2], b"lo");
+
+        drop(write_end);
+        assert_eq!(reader.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn pending_reads_dont_block_runtime_shutdown() {
+        let (read_end, _write_end) = pipe();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut reader = StdinReader::spawn(read_end).unwrap();
+        runtime.spawn(async move {
+            let mut buf = [0; 8];
+            let _ = reader.read(&mut buf).await;
+        });
+        runtime.block_on(async { tokio::time::sleep(Duration::from_millis(10)).await });
+
+        let start = Instant::now();
+        drop(runtime);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -1,5 +1,10 @@
-import { op_close, op_read, op_write } from "ext:core/ops";
-import { Stderr, Stdin, Stdout } from "ext:sable/io/stdio.js";
+import {
+	op_close,
+	op_console_size,
+	op_read,
+	op_write,
+} from "ext:core/ops";
+import { isTerminal, Stderr, Stdin, Stdout } from "ext:sable/io/stdio.js";
 
 /**
  * Read resource id asynchronously
@@ -10,7 +15,8 @@ import { Stderr, Stdin, Stdout } from "ext:sable/io/stdio.js";
 async function read(rid, buffer) {
 	// Can't read into nothing
 	if (buffer.length === 0) return 0;
-	return (await op_read(rid)) || null;
+	const read = await op_read(rid, buffer);
+	return read === 0 ? null : read;
 }
 
 /**
@@ -23,6 +29,18 @@ function write(rid, data) {
 	return op_write(rid, data);
 }
 
+/**
+ * Returns size of the terminal, which stdout, stderr or stdin is connected to
+ * @returns {{ columns: number, rows: number }}
+ * @throws when none of them is a terminal
+ */
+function consoleSize() {
+	for (const rid of [1, 2]) {
+		if (isTerminal(rid)) return op_console_size(rid);
+	}
+	return op_console_size(0);
+}
+
 /**
  * Closes resource id
  * @param {number} rid resource id
@@ -35,6 +53,8 @@ Sable.io = {
 	read,
 	write,
 	close,
+	isTerminal,
+	consoleSize,
 
 	stdin: new Stdin(),
 	stdout: new Stdout(),
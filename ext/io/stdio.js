@@ -1,3 +1,18 @@
+import { op_is_terminal, op_set_raw } from "ext:core/ops";
+import {
+	readableStreamForRid,
+	writableStreamForRid,
+} from "ext:sable/web/streams.js";
+
+/**
+ * Whether resource is connected to a terminal
+ * @param {number} rid resource id
+ * @returns {boolean}
+ */
+export function isTerminal(rid) {
+	return op_is_terminal(rid);
+}
+
 class SharedStdio {
 	rid;
 
@@ -12,16 +27,65 @@ class SharedStdio {
 	close() {
 		Sable.io.close(this.rid);
 	}
+
+	/** Whether the stream is connected to a terminal */
+	isTerminal() {
+		return isTerminal(this.rid);
+	}
 }
 
 export class Stdin extends SharedStdio {
 	rid = 0;
+	/** @type {ReadableStream<Uint8Array> | undefined} */
+	#readable;
+
+	/**
+	 * Stream of the input, stdin stays open once the stream ends or gets canceled
+	 * @returns {ReadableStream<Uint8Array>}
+	 */
+	get readable() {
+		this.#readable ??= readableStreamForRid(this.rid, false);
+		return this.#readable;
+	}
+
+	/**
+	 * Enables or disables raw mode, in which input isn't echoed and can be read as soon as a key is pressed
+	 *
+	 * Ctrl+C still stops the process, terminal gets restored once the process exits
+	 * @param {boolean} enabled
+	 * @throws when stdin isn't a terminal
+	 */
+	setRaw(enabled) {
+		op_set_raw(this.rid, Boolean(enabled));
+	}
 }
 
 export class Stdout extends SharedStdio {
 	rid = 1;
+	/** @type {WritableStream<Uint8Array> | undefined} */
+	#writable;
+
+	/**
+	 * Stream writing to stdout, which stays open once the stream gets closed
+	 * @returns {WritableStream<Uint8Array>}
+	 */
+	get writable() {
+		this.#writable ??= writableStreamForRid(this.rid, false);
+		return this.#writable;
+	}
 }
 
 export class Stderr extends SharedStdio {
 	rid = 2;
+	/** @type {WritableStream<Uint8Array> | undefined} */
+	#writable;
+
+	/**
+	 * Stream writing to stderr, which stays open once the stream gets closed
+	 * @returns {WritableStream<Uint8Array>}
+	 */
+	get writable() {
+		this.#writable ??= writableStreamForRid(this.rid, false);
+		return this.#writable;
+	}
 }
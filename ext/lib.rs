@@ -2,13 +2,19 @@ pub mod extensions {
     pub use sable_ext_battery as battery;
     pub use sable_ext_crypto as crypto;
     pub use sable_ext_device as device;
+    pub use sable_ext_errors as errors;
+    pub use sable_ext_fetch as fetch;
     pub use sable_ext_fs as fs;
+    pub use sable_ext_io as io;
     pub use sable_ext_performance as performance;
+    pub use sable_ext_permissions as permissions;
     pub use sable_ext_runtime as runtime;
     pub use sable_ext_storage as storage;
     pub use sable_ext_testing as testing;
     pub use sable_ext_timers as timers;
+    pub use sable_ext_url as url;
     pub use sable_ext_web as web;
+    pub use sable_ext_worker as worker;
 
     deno_core::extension!(
         sable,
@@ -19,23 +25,83 @@ pub mod extensions {
             battery::op_battery_level,
             crypto::op_crypto_new_uuidv4,
             device::op_device_mouse,
+            errors::op_error_sys_details,
             runtime::op_runtime_state,
+            fetch::op_fetch,
+            fetch::op_fetch_send,
             fs::op_read_file,
             fs::op_read_text_file,
             fs::op_write_file,
             fs::op_write_text_file,
             fs::op_remove_file,
             fs::op_remove_dir,
+            fs::op_stat,
+            fs::op_lstat,
+            fs::op_read_dir,
+            fs::op_mkdir,
+            fs::op_rename,
+            fs::op_copy_file,
+            fs::op_symlink,
+            fs::op_read_link,
+            fs::op_real_path,
+            fs::op_chmod,
+            fs::op_truncate,
+            fs::op_utime,
+            fs::op_make_temp_file,
+            fs::op_make_temp_dir,
+            fs::op_open,
+            fs::op_seek,
+            fs::op_ftruncate,
+            fs::op_fsync,
+            fs::op_fstat,
+            fs::op_watch_start,
+            fs::op_watch_next,
+            io::op_is_terminal,
+            io::op_set_raw,
+            io::op_console_size,
             performance::op_high_res_time,
             performance::op_time_origin,
+            permissions::op_permissions_query,
+            permissions::op_permissions_request,
+            permissions::op_permissions_revoke,
             timers::op_timers_sleep,
             timers::op_create_timer,
-            testing::op_bench_fn,
+            testing::op_bench_report,
             testing::op_diff_str,
             testing::op_test_async_ops_sanitization,
+            testing::op_test_default_timeout,
+            testing::op_test_ignore_pending_async_ops,
+            testing::op_test_open_resources,
+            testing::op_test_pending_async_ops,
+            testing::op_test_pending_timers,
+            testing::op_test_report_failure,
+            testing::op_set_module_finish_callback,
+            testing::op_test_timeout,
+            testing::op_test_timeout_create,
             web::op_encoding_normalize_label,
+            url::op_url_parse,
+            url::op_url_reparse,
+            url::op_url_parse_search_params,
+            url::op_url_stringify_search_params,
+            url::op_url_to_file_path,
             web::op_encoding_decode_utf8,
             web::op_encoding_decode_single,
+            web::op_encoding_new_decoder,
+            web::op_encoding_decode,
+            web::op_blob_create_part,
+            web::op_blob_read_part,
+            web::op_blob_remove_part,
+            web::op_blob_create_object_url,
+            web::op_blob_revoke_object_url,
+            web::op_blob_from_object_url,
+            web::op_message_port_create_entangled,
+            web::op_message_port_post_message,
+            web::op_message_port_recv_message,
+            web::op_broadcast_subscribe,
+            web::op_broadcast_send,
+            web::op_broadcast_recv,
+            web::op_report_unhandled_rejection,
+            web::op_set_global_event_callback,
             storage::op_webstorage_length,
             storage::op_webstorage_get_item,
             storage::op_webstorage_set_item,
@@ -43,18 +109,46 @@ pub mod extensions {
             storage::op_webstorage_clear,
             storage::op_webstorage_key,
             storage::op_webstorage_keys,
+            worker::op_worker_create,
+            worker::op_worker_post_message,
+            worker::op_worker_recv_event,
+            worker::op_worker_scope_name,
+            worker::op_worker_scope_post_message,
+            worker::op_worker_scope_recv_message,
+            worker::op_worker_scope_close,
         ],
         esm_entry_point = "ext:sable/runtime.js",
         esm = [
             "sable.js",
             "runtime.js",
+            "errors/mod.js",
+            "fetch/mod.js",
+            "fetch/headers.js",
+            "fetch/body.js",
+            "fetch/formdata.js",
+            "fetch/request.js",
+            "fetch/response.js",
+            "fetch/fetch.js",
             "io/mod.js",
             "io/stdio.js",
             "fs/mod.js",
+            "fs/file.js",
+            "fs/watch.js",
             "battery/mod.ts",
             "web/mod.js",
             "web/events.js",
+            "web/errors.js",
+            "web/exception.js",
+            "web/base64.js",
+            "web/serialization.js",
             "web/encoding.js",
+            "web/streams.js",
+            "web/blob.js",
+            "web/message_port.js",
+            "web/broadcast_channel.js",
+            "url/mod.js",
+            "url/url.js",
+            "url/urlpattern.js",
             "console/mod.js",
             "console/printer.js",
             "console/formatter.js",
@@ -63,6 +157,7 @@ pub mod extensions {
             "crypto/mod.ts",
             "device/mod.js",
             "performance/mod.js",
+            "permissions/mod.js",
             "timers/mod.js",
             "webidl/mod.ts",
             "webidl/numbers.js",
@@ -76,9 +171,15 @@ pub mod extensions {
             "utils/loop_ansi.js",
             "utils/text_width.js",
             "testing/mod.js",
-            "storage/mod.ts"
+            "testing/bench.js",
+            "storage/mod.ts",
+            "worker/mod.js",
+            "worker/worker.js"
         ],
         state = |state| {
+            // sable_ext_io
+            io::init_stdio(state);
+
             // sable_ext_runtime
             state.put(runtime::RuntimeState::Default);
 
@@ -86,17 +187,41 @@ pub mod extensions {
             state.put(std::time::Instant::now());
             state.put(std::time::SystemTime::now());
 
+            // sable_ext_permissions
+            state.put(permissions::PermissionsContainer::default());
+
             // sable_ext_timers
             state.put(timers::TimerQueue::new());
 
             // sable_ext_testing
             state.put::<Option<std::rc::Rc<deno_core::OpMetricsSummaryTracker>>>(None);
+            state.put(testing::TestOptions::default());
+            state.put(testing::TestFailures::default());
+            state.put(testing::BenchOptions::default());
+            state.put(testing::BenchReport::default());
 
             // sable_ext_storage
             state.put::<Option<storage::LocalStoragePath>>(None);
+
+            // sable_ext_web
+            state.put(web::BlobStore::default());
+            state.put(web::BroadcastHub::default());
         }
     );
 
+    // Added only to runtimes of workers, after the snapshotted `sable` extension
+    deno_core::extension!(
+        sable_worker,
+        esm_entry_point = "ext:sable_worker/worker/scope.js",
+        esm = ["worker/scope.js",],
+        options = {
+            scope: worker::WorkerScope,
+        },
+        state = |state, options| {
+            state.put(options.scope);
+        },
+    );
+
     deno_core::extension!(
         sable_cleanup,
         esm_entry_point = "ext:sable_cleanup/cleanup.js",
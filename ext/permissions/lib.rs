@@ -0,0 +1,596 @@
+use deno_core::{
+    error::{custom_error, AnyError},
+    op2,
+    url::Url,
+    OpState,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    env, fmt,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionName {
+    Read,
+    Write,
+    Net,
+    Env,
+    Run,
+    Sys,
+}
+
+impl PermissionName {
+    /// Flag which grants the permission
+    fn flag(self) -> String {
+        format!("--allow-{}", self)
+    }
+}
+
+impl fmt::Display for PermissionName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PermissionName::Read => "read",
+            PermissionName::Write => "write",
+            PermissionName::Net => "net",
+            PermissionName::Env => "env",
+            PermissionName::Run => "run",
+            PermissionName::Sys => "sys",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    Granted,
+    Prompt,
+    Denied,
+}
+
+/// Resources which have been allowed, `None` means the flag wasn't passed and empty list allows everything
+#[derive(Default, Clone)]
+pub struct PermissionsOptions {
+    pub allow_read: Option<Vec<String>>,
+    pub allow_write: Option<Vec<String>>,
+    pub allow_net: Option<Vec<String>>,
+    pub allow_env: Option<Vec<String>>,
+    pub allow_run: Option<Vec<String>>,
+    pub allow_sys: Option<Vec<String>>,
+    /// Ask in the terminal for permissions which haven't been granted, instead of denying them
+    pub prompt: bool,
+}
+
+impl PermissionsOptions {
+    pub fn allow_all() -> Self {
+        Self {
+            allow_read: Some(vec![]),
+            allow_write: Some(vec![]),
+            allow_net: Some(vec![]),
+            allow_env: Some(vec![]),
+            allow_run: Some(vec![]),
+            allow_sys: Some(vec![]),
+            prompt: false,
+        }
+    }
+}
+
+/// Makes path absolute and removes `.` and `..` components, without touching the file system
+fn resolve_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().unwrap_or_default().join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Converts resource to the form in which it gets compared
+fn normalize_resource(name: PermissionName, resource: &str) -> String {
+    match name {
+        PermissionName::Read | PermissionName::Write => {
+            resolve_path(resource).to_string_lossy().into_owned()
+        }
+        PermissionName::Net => resource.to_lowercase(),
+        PermissionName::Env | PermissionName::Run | PermissionName::Sys => resource.to_string(),
+    }
+}
+
+/// Whether access to `granted` resource implies access to `resource`
+fn covers(name: PermissionName, granted: &str, resource: &str) -> bool {
+    match name {
+        PermissionName::Read | PermissionName::Write => Path::new(resource).starts_with(granted),
+        // Host without a port allows every port
+        PermissionName::Net => {
+            granted == resource
+                || resource
+                    .rsplit_once(':')
+                    .is_some_and(|(host, _)| host == granted)
+        }
+        PermissionName::Env | PermissionName::Run | PermissionName::Sys => granted == resource,
+    }
+}
+
+/// State of a single kind of permission
+#[derive(Default, Clone)]
+struct Permission {
+    granted_all: bool,
+    granted: HashSet<String>,
+    /// Resources which got denied in a prompt, so user doesn't get asked again
+    denied: HashSet<String>,
+}
+
+impl Permission {
+    fn new(allowed: &Option<Vec<String>>, name: PermissionName) -> Self {
+        match allowed {
+            None => Self::default(),
+            Some(allowed) if allowed.is_empty() => Self {
+                granted_all: true,
+                ..Default::default()
+            },
+            Some(allowed) => Self {
+                granted: allowed
+                    .iter()
+                    .map(|resource| normalize_resource(name, resource))
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+enum PromptResponse {
+    Allow,
+    AllowAll,
+    Deny,
+}
+
+/// Asks user in the terminal whether to grant the permission, blocking until they answer
+fn prompt(name: PermissionName, resource: Option<&str>, api_name: Option<&str>) -> PromptResponse {
+    let mut stderr = io::stderr();
+    let target = match resource {
+        Some(resource) => format!("{} access to \"{}\"", name, resource),
+        None => format!("{} access", name),
+    };
+    let _ = writeln!(stderr, "\u{26a0}\u{fe0f}  Sable requests {}.", target);
+    if let Some(api_name) = api_name {
+        let _ = writeln!(stderr, "   Requested by `{}` API.", api_name);
+    }
+
+    let mut input = String::new();
+    loop {
+        let _ = write!(
+            stderr,
+            "   Allow? [y/n/A] (y = yes, allow; n = no, deny; A = allow all {} permissions) > ",
+            name
+        );
+        let _ = stderr.flush();
+
+        input.clear();
+        match io::stdin().lock().read_line(&mut input) {
+            Ok(0) | Err(_) => return PromptResponse::Deny,
+            Ok(_) => {}
+        }
+
+        match input.trim() {
+            "y" | "Y" => return PromptResponse::Allow,
+            "A" => return PromptResponse::AllowAll,
+            "n" | "N" => return PromptResponse::Deny,
+            _ => {}
+        }
+    }
+}
+
+/// Permission of a worker, `true` inherits it from the parent, `false` takes it away
+/// and a list of resources narrows it down to them
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ChildPermissionArg {
+    Inherit(bool),
+    Resources(Vec<String>),
+}
+
+/// Kinds of permissions of a worker, ones which aren't given get inherited
+#[derive(Deserialize)]
+pub struct ChildPermissionKinds {
+    read: Option<ChildPermissionArg>,
+    write: Option<ChildPermissionArg>,
+    net: Option<ChildPermissionArg>,
+    env: Option<ChildPermissionArg>,
+    run: Option<ChildPermissionArg>,
+    sys: Option<ChildPermissionArg>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildPermissionsPreset {
+    Inherit,
+    None,
+}
+
+/// Permissions of a worker, which can't exceed permissions of its parent
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ChildPermissionsArg {
+    Preset(ChildPermissionsPreset),
+    Kinds(ChildPermissionKinds),
+}
+
+#[derive(Clone)]
+pub struct Permissions {
+    read: Permission,
+    write: Permission,
+    net: Permission,
+    env: Permission,
+    run: Permission,
+    sys: Permission,
+    prompt: bool,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self::new(&PermissionsOptions::default())
+    }
+}
+
+impl Permissions {
+    pub fn new(options: &PermissionsOptions) -> Self {
+        Self {
+            read: Permission::new(&options.allow_read, PermissionName::Read),
+            write: Permission::new(&options.allow_write, PermissionName::Write),
+            net: Permission::new(&options.allow_net, PermissionName::Net),
+            env: Permission::new(&options.allow_env, PermissionName::Env),
+            run: Permission::new(&options.allow_run, PermissionName::Run),
+            sys: Permission::new(&options.allow_sys, PermissionName::Sys),
+            // Prompt can't be answered without a terminal
+            prompt: options.prompt && io::stdin().is_terminal() && io::stderr().is_terminal(),
+        }
+    }
+
+    fn permission(&self, name: PermissionName) -> &Permission {
+        match name {
+            PermissionName::Read => &self.read,
+            PermissionName::Write => &self.write,
+            PermissionName::Net => &self.net,
+            PermissionName::Env => &self.env,
+            PermissionName::Run => &self.run,
+            PermissionName::Sys => &self.sys,
+        }
+    }
+
+    fn permission_mut(&mut self, name: PermissionName) -> &mut Permission {
+        match name {
+            PermissionName::Read => &mut self.read,
+            PermissionName::Write => &mut self.write,
+            PermissionName::Net => &mut self.net,
+            PermissionName::Env => &mut self.env,
+            PermissionName::Run => &mut self.run,
+            PermissionName::Sys => &mut self.sys,
+        }
+    }
+
+    /// Returns state of the permission to access given resource, or all resources if it's `None`
+    pub fn query(&self, name: PermissionName, resource: Option<&str>) -> PermissionState {
+        let permission = self.permission(name);
+        let resource = resource.map(|resource| normalize_resource(name, resource));
+
+        if permission.granted_all {
+            return PermissionState::Granted;
+        }
+
+        match resource {
+            Some(resource) => {
+                if permission
+                    .denied
+                    .iter()
+                    .any(|denied| covers(name, denied, &resource))
+                {
+                    PermissionState::Denied
+                } else if permission
+                    .granted
+                    .iter()
+                    .any(|granted| covers(name, granted, &resource))
+                {
+                    PermissionState::Granted
+                } else {
+                    PermissionState::Prompt
+                }
+            }
+            None => PermissionState::Prompt,
+        }
+    }
+
+    /// Prompts user for the permission if it hasn't been granted or denied yet
+    pub fn request(
+        &mut self,
+        name: PermissionName,
+        resource: Option<&str>,
+        api_name: Option<&str>,
+    ) -> PermissionState {
+        let state = self.query(name, resource);
+        if state != PermissionState::Prompt {
+            return state;
+        }
+
+        if !self.prompt {
+            return PermissionState::Denied;
+        }
+
+        let resource = resource.map(|resource| normalize_resource(name, resource));
+        let response = prompt(name, resource.as_deref(), api_name);
+
+        let permission = self.permission_mut(name);
+        match (response, resource) {
+            (PromptResponse::AllowAll, _) | (PromptResponse::Allow, None) => {
+                permission.granted_all = true;
+                PermissionState::Granted
+            }
+            (PromptResponse::Allow, Some(resource)) => {
+                permission.granted.insert(resource);
+                PermissionState::Granted
+            }
+            (PromptResponse::Deny, Some(resource)) => {
+                permission.denied.insert(resource);
+                PermissionState::Denied
+            }
+            (PromptResponse::Deny, None) => PermissionState::Denied,
+        }
+    }
+
+    /// Takes away permission to access given resource, or all resources if it's `None`
+    ///
+    /// Revoking a single resource also revokes broader grants which include it
+    pub fn revoke(&mut self, name: PermissionName, resource: Option<&str>) -> PermissionState {
+        let normalized = resource.map(|resource| normalize_resource(name, resource));
+
+        let permission = self.permission_mut(name);
+        permission.granted_all = false;
+        match &normalized {
+            Some(resource) => permission
+                .granted
+                .retain(|granted| !covers(name, granted, resource)),
+            None => permission.granted.clear(),
+        }
+
+        self.query(name, resource)
+    }
+
+    /// Returns `PermissionDenied` error, unless the permission has been granted already or in a prompt
+    pub fn check(
+        &mut self,
+        name: PermissionName,
+        resource: &str,
+        api_name: &str,
+    ) -> Result<(), AnyError> {
+        match self.request(name, Some(resource), Some(api_name)) {
+            PermissionState::Granted => Ok(()),
+            _ => Err(custom_error(
+                "PermissionDenied",
+                format!(
+                    "Requires {} access to \"{}\", run again with the {} flag",
+                    name,
+                    resource,
+                    name.flag()
+                ),
+            )),
+        }
+    }
+
+    /// Creates permissions of a worker, narrowed down from these permissions
+    pub fn create_child(&self, arg: &ChildPermissionsArg) -> Result<Permissions, AnyError> {
+        let kinds = match arg {
+            ChildPermissionsArg::Preset(ChildPermissionsPreset::Inherit) => return Ok(self.clone()),
+            ChildPermissionsArg::Preset(ChildPermissionsPreset::None) => {
+                return Ok(Permissions::default())
+            }
+            ChildPermissionsArg::Kinds(kinds) => kinds,
+        };
+
+        Ok(Self {
+            read: self.child_permission(PermissionName::Read, &kinds.read)?,
+            write: self.child_permission(PermissionName::Write, &kinds.write)?,
+            net: self.child_permission(PermissionName::Net, &kinds.net)?,
+            env: self.child_permission(PermissionName::Env, &kinds.env)?,
+            run: self.child_permission(PermissionName::Run, &kinds.run)?,
+            sys: self.child_permission(PermissionName::Sys, &kinds.sys)?,
+            // Worker would otherwise be able to ask for permissions it has been denied
+            prompt: false,
+        })
+    }
+
+    fn child_permission(
+        &self,
+        name: PermissionName,
+        arg: &Option<ChildPermissionArg>,
+    ) -> Result<Permission, AnyError> {
+        let resources = match arg {
+            None | Some(ChildPermissionArg::Inherit(true)) => {
+                return Ok(self.permission(name).clone())
+            }
+            Some(ChildPermissionArg::Inherit(false)) => return Ok(Permission::default()),
+            Some(ChildPermissionArg::Resources(resources)) => resources,
+        };
+
+        for resource in resources {
+            if self.query(name, Some(resource)) != PermissionState::Granted {
+                return Err(custom_error(
+                    "PermissionDenied",
+                    format!(
+                        "Worker can't be granted {} access to \"{}\", which its parent doesn't have",
+                        name, resource
+                    ),
+                ));
+            }
+        }
+        // Unlike an empty flag, an empty list doesn't allow everything
+        if resources.is_empty() {
+            return Ok(Permission::default());
+        }
+        Ok(Permission::new(&Some(resources.clone()), name))
+    }
+}
+
+/// Permissions shared between the `OpState` and the module loader
+#[derive(Clone, Default)]
+pub struct PermissionsContainer(pub Rc<RefCell<Permissions>>);
+
+impl PermissionsContainer {
+    pub fn new(permissions: Permissions) -> Self {
+        Self(Rc::new(RefCell::new(permissions)))
+    }
+
+    pub fn check_read(&self, path: &str, api_name: &str) -> Result<(), AnyError> {
+        self.0
+            .borrow_mut()
+            .check(PermissionName::Read, path, api_name)
+    }
+
+    pub fn check_write(&self, path: &str, api_name: &str) -> Result<(), AnyError> {
+        self.0
+            .borrow_mut()
+            .check(PermissionName::Write, path, api_name)
+    }
+
+    /// Checks access to host and port of given url
+    pub fn check_net_url(&self, url: &Url, api_name: &str) -> Result<(), AnyError> {
+        let host = url.host_str().unwrap_or_default();
+        let resource = match url.port_or_known_default() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        self.0
+            .borrow_mut()
+            .check(PermissionName::Net, &resource, api_name)
+    }
+
+    pub fn check_env(&self, variable: &str, api_name: &str) -> Result<(), AnyError> {
+        self.0
+            .borrow_mut()
+            .check(PermissionName::Env, variable, api_name)
+    }
+
+    /// Checks access to given kind of system information, e.g. "battery"
+    pub fn check_sys(&self, kind: &str, api_name: &str) -> Result<(), AnyError> {
+        self.0
+            .borrow_mut()
+            .check(PermissionName::Sys, kind, api_name)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PermissionDescriptor {
+    name: PermissionName,
+    path: Option<String>,
+    host: Option<String>,
+    variable: Option<String>,
+    command: Option<String>,
+    kind: Option<String>,
+}
+
+impl PermissionDescriptor {
+    /// Resource which is relevant for the permission
+    fn resource(&self) -> Option<&str> {
+        match self.name {
+            PermissionName::Read | PermissionName::Write => self.path.as_deref(),
+            PermissionName::Net => self.host.as_deref(),
+            PermissionName::Env => self.variable.as_deref(),
+            PermissionName::Run => self.command.as_deref(),
+            PermissionName::Sys => self.kind.as_deref(),
+        }
+    }
+}
+
+#[op2]
+#[serde]
+pub fn op_permissions_query(
+    state: &OpState,
+    #[serde] descriptor: PermissionDescriptor,
+) -> PermissionState {
+    let permissions = state.borrow::<PermissionsContainer>().0.borrow();
+    permissions.query(descriptor.name, descriptor.resource())
+}
+
+#[op2]
+#[serde]
+pub fn op_permissions_request(
+    state: &OpState,
+    #[serde] descriptor: PermissionDescriptor,
+) -> PermissionState {
+    let mut permissions = state.borrow::<PermissionsContainer>().0.borrow_mut();
+    permissions.request(descriptor.name, descriptor.resource(), None)
+}
+
+#[op2]
+#[serde]
+pub fn op_permissions_revoke(
+    state: &OpState,
+    #[serde] descriptor: PermissionDescriptor,
+) -> PermissionState {
+    let mut permissions = state.borrow::<PermissionsContainer>().0.borrow_mut();
+    permissions.revoke(descriptor.name, descriptor.resource())
+}
+
+#[cfg(test)]
+mod tests {
+    use deno_core::error::get_custom_error_class;
+
+    use super::*;
+
+    fn container(options: PermissionsOptions) -> PermissionsContainer {
+        PermissionsContainer::new(Permissions::new(&options))
+    }
+
+    #[test]
+    fn denied_checks_throw_permission_denied() {
+        let permissions = container(PermissionsOptions::default());
+
+        let checks = [
+            permissions.check_env("HOME", "test"),
+            permissions.check_sys("battery", "test"),
+            permissions.check_read("/tmp", "test"),
+        ];
+        for check in checks {
+            let error = check.unwrap_err();
+            assert_eq!(get_custom_error_class(&error), Some("PermissionDenied"));
+        }
+    }
+
+    #[test]
+    fn listed_resources_are_allowed() {
+        let permissions = container(PermissionsOptions {
+            allow_env: Some(vec!["HOME".to_string()]),
+            allow_sys: Some(vec!["battery".to_string()]),
+            ..Default::default()
+        });
+
+        assert!(permissions.check_env("HOME", "test").is_ok());
+        assert!(permissions.check_env("PATH", "test").is_err());
+        assert!(permissions.check_sys("battery", "test").is_ok());
+        assert!(permissions.check_sys("mouse", "test").is_err());
+    }
+
+    #[test]
+    fn empty_flag_allows_everything() {
+        let permissions = container(PermissionsOptions::allow_all());
+
+        assert!(permissions.check_env("PATH", "test").is_ok());
+        assert!(permissions.check_sys("mouse", "test").is_ok());
+    }
+}
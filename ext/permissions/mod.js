@@ -0,0 +1,92 @@
+import {
+	op_permissions_query,
+	op_permissions_request,
+	op_permissions_revoke,
+} from "ext:core/ops";
+
+/**
+ * @typedef {"granted" | "prompt" | "denied"} PermissionState
+ */
+
+/**
+ * @typedef {(
+ *   | { name: "read" | "write"; path?: string }
+ *   | { name: "net"; host?: string }
+ *   | { name: "env"; variable?: string }
+ *   | { name: "run"; command?: string }
+ *   | { name: "sys"; kind?: string }
+ * )} PermissionDescriptor
+ */
+
+const PERMISSION_NAMES = ["read", "write", "net", "env", "run", "sys"];
+
+class PermissionStatus {
+	/** @type {PermissionState} */
+	#state;
+
+	/**
+	 * @param {PermissionState} state
+	 */
+	constructor(state) {
+		this.#state = state;
+	}
+
+	/** @returns {PermissionState} */
+	get state() {
+		return this.#state;
+	}
+}
+
+/**
+ * @param {PermissionDescriptor} descriptor
+ * @returns {PermissionDescriptor}
+ */
+function validateDescriptor(descriptor) {
+	if (typeof descriptor !== "object" || descriptor === null) {
+		throw new TypeError("Permission descriptor has to be an object");
+	}
+	if (!PERMISSION_NAMES.includes(descriptor.name)) {
+		throw new TypeError(
+			`Invalid permission name '${descriptor.name}', expected one of: ${
+				PERMISSION_NAMES.join(", ")
+			}`,
+		);
+	}
+	return descriptor;
+}
+
+/**
+ * Returns current state of given permission
+ * @param {PermissionDescriptor} descriptor
+ * @returns {Promise<PermissionStatus>}
+ */
+async function query(descriptor) {
+	const state = op_permissions_query(validateDescriptor(descriptor));
+	return new PermissionStatus(state);
+}
+
+/**
+ * Prompts user for given permission, unless it has already been granted or denied
+ * @param {PermissionDescriptor} descriptor
+ * @returns {Promise<PermissionStatus>}
+ */
+async function request(descriptor) {
+	const state = op_permissions_request(validateDescriptor(descriptor));
+	return new PermissionStatus(state);
+}
+
+/**
+ * Revokes given permission, which has been granted using a flag or prompt
+ * @param {PermissionDescriptor} descriptor
+ * @returns {Promise<PermissionStatus>}
+ */
+async function revoke(descriptor) {
+	const state = op_permissions_revoke(validateDescriptor(descriptor));
+	return new PermissionStatus(state);
+}
+
+Sable.permissions = {
+	query,
+	request,
+	revoke,
+};
@@ -1,13 +1,18 @@
 import "ext:sable/sable.js";
+import "ext:sable/errors/mod.js";
 
 import "ext:sable/web/mod.js";
+import "ext:sable/url/mod.js";
 import "ext:sable/io/mod.js";
 import "ext:sable/fs/mod.js";
+import "ext:sable/fetch/mod.js";
 import "ext:sable/battery/mod.ts";
 import "ext:sable/console/mod.js";
 import "ext:sable/crypto/mod.ts";
 import "ext:sable/performance/mod.js";
+import "ext:sable/permissions/mod.js";
 import "ext:sable/timers/mod.js";
 import "ext:sable/testing/mod.js";
 import "ext:sable/storage/mod.ts";
 import "ext:sable/device/mod.js";
+import "ext:sable/worker/mod.js";
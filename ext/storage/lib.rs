@@ -3,6 +3,7 @@ use deno_core::{
     op2, OpState,
 };
 use rusqlite::{Connection, OptionalExtension};
+use sable_ext_errors::dom_exception;
 use std::path::PathBuf;
 
 #[derive(Debug)]
@@ -113,8 +114,10 @@ pub fn op_webstorage_set_item(
     let predicted_payload_size = key.len() + value.len();
 
     if size + predicted_payload_size >= STORAGE_SIZE_LIMIT {
-        // TODO(Im-Beast): Replace with DOMException
-        return Err(anyhow!("QuotaExceededError"));
+        return Err(dom_exception(
+            "QuotaExceededError",
+            "Storage size limit has been exceeded",
+        ));
     }
 
     let mut statement =
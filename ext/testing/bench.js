@@ -0,0 +1,369 @@
+import {
+	op_bench_report,
+	op_high_res_time,
+	op_runtime_state,
+	op_set_module_finish_callback,
+} from "ext:core/ops";
+
+import { renderTable } from "ext:sable/console/table.js";
+import { styles } from "ext:sable/utils/ansi.js";
+
+/** Time in milliseconds for which benchmark runs before collecting samples */
+const WARMUP_TIME = 100;
+/** Time in milliseconds spent collecting samples */
+const SAMPLING_TIME = 500;
+/** Minimal amount of collected samples, even if it exceeds the sampling time */
+const MIN_SAMPLES = 10;
+/** Minimal duration of a single sample, fast synchronous functions get called multiple times per sample */
+const MIN_SAMPLE_TIME = 0.01;
+
+/**
+ * Times are in milliseconds with nanosecond precision
+ * @typedef {{
+ *   name: string;
+ *   async: boolean;
+ *   samples: number;
+ *   outliers: number;
+ *   mean: number;
+ *   min: number;
+ *   max: number;
+ *   p75: number;
+ *   p99: number;
+ *   stdDev: number;
+ *   iterPerSec: number;
+ * }} BenchResult
+ */
+
+/**
+ * @typedef {{
+ *   group?: string;
+ *   baseline?: boolean;
+ * }} BenchOptions
+ */
+
+/**
+ * Nearest-rank percentile
+ * @param {number[]} sorted - samples sorted in ascending order
+ * @param {number} percent - value between 0 and 1
+ * @returns {number}
+ */
+function percentile(sorted, percent) {
+	const index = Math.ceil(percent * sorted.length) - 1;
+	return sorted[Math.min(Math.max(index, 0), sorted.length - 1)];
+}
+
+/**
+ * Computes statistics of samples after removing outliers using Tukey's fences
+ * @param {string} name
+ * @param {boolean} async
+ * @param {number[]} samples
+ * @returns {BenchResult}
+ */
+function computeResult(name, async, samples) {
+	samples.sort((a, b) => a - b);
+
+	const q1 = percentile(samples, 0.25);
+	const q3 = percentile(samples, 0.75);
+	const iqr = q3 - q1;
+	const low = q1 - 1.5 * iqr;
+	const high = q3 + 1.5 * iqr;
+
+	const filtered = samples.filter((sample) => sample >= low && sample <= high);
+
+	let sum = 0;
+	for (const sample of filtered) {
+		sum += sample;
+	}
+	const mean = sum / filtered.length;
+
+	let squaredDeviations = 0;
+	for (const sample of filtered) {
+		squaredDeviations += (sample - mean) ** 2;
+	}
+	const stdDev = filtered.length > 1
+		? Math.sqrt(squaredDeviations / (filtered.length - 1))
+		: 0;
+
+	return {
+		name,
+		async,
+		samples: filtered.length,
+		outliers: samples.length - filtered.length,
+		mean,
+		min: filtered[0],
+		max: filtered[filtered.length - 1],
+		p75: percentile(filtered, 0.75),
+		p99: percentile(filtered, 0.99),
+		stdDev,
+		iterPerSec: 1000 / mean,
+	};
+}
+
+/**
+ * @param {() => unknown} callback
+ * @param {number} batchSize
+ * @returns {number} average time of a single call
+ */
+function measureSync(callback, batchSize) {
+	const start = op_high_res_time();
+	for (let i = 0; i < batchSize; ++i) {
+		callback();
+	}
+	return (op_high_res_time() - start) / batchSize;
+}
+
+/**
+ * @param {() => Promise<unknown>} callback
+ * @returns {Promise<number>} time of the call
+ */
+async function measureAsync(callback) {
+	const start = op_high_res_time();
+	await callback();
+	return op_high_res_time() - start;
+}
+
+/**
+ * @param {string} name
+ * @param {() => unknown} callback
+ * @returns {Promise<BenchResult>}
+ */
+async function runBenchmark(name, callback) {
+	// Warmup lets JIT optimize the function and figures out whether it is asynchronous
+	let async = false;
+	let iterations = 0;
+	const warmupStart = op_high_res_time();
+	do {
+		const response = callback();
+		if (response instanceof Promise) {
+			async = true;
+			await response;
+		}
+		++iterations;
+	} while (op_high_res_time() - warmupStart < WARMUP_TIME);
+	const iterationTime = (op_high_res_time() - warmupStart) / iterations;
+
+	// Timer resolution and its overhead would dominate samples of very fast functions
+	const batchSize = async
+		? 1
+		: Math.max(1, Math.ceil(MIN_SAMPLE_TIME / iterationTime));
+
+	const samples = [];
+	const samplingStart = op_high_res_time();
+	while (
+		samples.length < MIN_SAMPLES ||
+		op_high_res_time() - samplingStart < SAMPLING_TIME
+	) {
+		samples.push(
+			async
+				? await measureAsync(callback)
+				: measureSync(callback, batchSize),
+		);
+	}
+
+	return computeResult(name, async, samples);
+}
+
+/**
+ * @param {number} time - time in milliseconds
+ * @returns {string}
+ */
+function formatTime(time) {
+	if (time < 1e-3) {
+		return `${(time * 1e6).toFixed(2)}ns`;
+	} else if (time < 1) {
+		return `${(time * 1e3).toFixed(2)}\u00b5s`;
+	} else if (time < 1e3) {
+		return `${time.toFixed(2)}ms`;
+	}
+	return `${(time / 1e3).toFixed(2)}s`;
+}
+
+/**
+ * @param {number} number
+ * @returns {string}
+ */
+function formatInteger(number) {
+	return Math.round(number).toString().replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+}
+
+/**
+ * @param {BenchResult} result
+ */
+function printResult(result) {
+	const { bold, lightBlack, reset } = styles;
+
+	const name = result.async
+		? `${bold}${result.name}${reset} ${lightBlack}(async)${reset}`
+		: `${bold}${result.name}${reset}`;
+	const time = `${bold}${formatTime(result.mean)}${reset}/iter \u00b1 ${
+		formatTime(result.stdDev)
+	}`;
+	const speed = `${formatInteger(result.iterPerSec)} iter/s`;
+	const range = `min ${formatTime(result.min)}, max ${
+		formatTime(result.max)
+	}, p75 ${formatTime(result.p75)}, p99 ${formatTime(result.p99)}`;
+	const samples = `${formatInteger(result.samples)} samples, ${
+		formatInteger(result.outliers)
+	} outliers removed`;
+
+	console.log(
+		`${name}\n  ${time}, ${speed}\n  ${range}\n  ${lightBlack}${samples}${reset}`,
+	);
+}
+
+/**
+ * @param {NonNullable<ReturnType<typeof op_bench_report>>} comparison
+ */
+function printComparison(comparison) {
+	const { green, yellow, red, lightBlack, reset } = styles;
+
+	const change = Math.abs(comparison.change).toFixed(2);
+	const direction = comparison.change > 0 ? "slower" : "faster";
+
+	let color = lightBlack;
+	if (comparison.regression) {
+		color = red;
+	} else if (comparison.significant) {
+		color = comparison.change > 0 ? yellow : green;
+	}
+
+	const significance = comparison.significant
+		? "significant"
+		: "not significant";
+
+	console.log(
+		`  ${color}${change}% ${direction} than baseline (${
+			formatTime(comparison.baseline.mean)
+		}/iter), ${significance}${
+			comparison.regression ? ", regression" : ""
+		}${reset}`,
+	);
+}
+
+/**
+ * @param {number} number
+ * @returns {string}
+ */
+function formatRatio(number) {
+	return `${number.toFixed(2)}\u00d7`;
+}
+
+/**
+ * Results of grouped benchmarks, in order in which they finished
+ * @type {Map<string, { baseline?: string, results: BenchResult[] }>}
+ */
+const groups = new Map();
+
+/** Prints table comparing benchmarks to the baseline for every group */
+function printGroups() {
+	const { bold, lightBlack, reset } = styles;
+
+	for (const [name, group] of groups) {
+		if (group.results.length === 0) continue;
+
+		const baseline =
+			group.results.find((result) => result.name === group.baseline) ??
+				group.results[0];
+
+		const tableData = [["Benchmark", "Time/iter", "Iter/s", "Comparison"]];
+		for (const result of group.results) {
+			let comparison;
+			if (result === baseline) {
+				comparison = `${lightBlack}baseline${reset}`;
+			} else if (result.mean >= baseline.mean) {
+				comparison = `${result.name} is ${
+					formatRatio(result.mean / baseline.mean)
+				} slower than ${baseline.name}`;
+			} else {
+				comparison = `${result.name} is ${
+					formatRatio(baseline.mean / result.mean)
+				} faster than ${baseline.name}`;
+			}
+
+			tableData.push([
+				result.name,
+				formatTime(result.mean),
+				formatInteger(result.iterPerSec),
+				comparison,
+			]);
+		}
+
+		console.log(`\n${bold}${name}${reset}\n${renderTable(tableData)}`);
+	}
+}
+
+/** @type {"default" | "test" | "bench" | undefined} */
+let runtimeState;
+
+/**
+ * Benchmarks run one after another, even if they're not awaited
+ * @type {Promise<unknown>}
+ */
+let queue = Promise.resolve();
+
+/**
+ * Benchmark given function
+ * @param {string} name - name of a benchmark
+ * @param {BenchOptions | (() => unknown | Promise<unknown>)} options
+ * @param {() => unknown | Promise<unknown>} [callback]
+ * @returns {Promise<BenchResult> | undefined}
+ */
+export function bench(name, options, callback) {
+	if (typeof options === "function") {
+		callback = options;
+		options = {};
+	}
+
+	if (!runtimeState) {
+		runtimeState = op_runtime_state();
+	}
+
+	if (runtimeState !== "bench") {
+		return;
+	}
+
+	const { group, baseline } = options;
+	if (group !== undefined) {
+		if (groups.size === 0) {
+			// Group tables get printed once every benchmark in the module has finished
+			op_set_module_finish_callback(() => queue.then(printGroups));
+		}
+
+		let benchGroup = groups.get(group);
+		if (!benchGroup) {
+			benchGroup = { results: [] };
+			groups.set(group, benchGroup);
+		}
+
+		if (baseline) {
+			if (benchGroup.baseline !== undefined) {
+				throw new TypeError(
+					`Group '${group}' already has a baseline benchmark '${benchGroup.baseline}'`,
+				);
+			}
+			benchGroup.baseline = name;
+		}
+	} else if (baseline) {
+		throw new TypeError(
+			`Benchmark '${name}' can't be a baseline, because it doesn't belong to a group`,
+		);
+	}
+
+	const result = queue.then(async () => {
+		const result = await runBenchmark(name, callback);
+		printResult(result);
+
+		const comparison = op_bench_report(result);
+		if (comparison) {
+			printComparison(comparison);
+		}
+
+		if (group !== undefined) {
+			groups.get(group).results.push(result);
+		}
+
+		return result;
+	});
+	queue = result.catch(() => {});
+	return result;
+}
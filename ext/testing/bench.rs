@@ -0,0 +1,173 @@
+use deno_core::{op2, OpState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default change of the mean in percent, past which a slower benchmark counts as a regression
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 10.0;
+
+/// Critical value of the two-tailed t-test at 95% confidence for large sample sizes
+const T_CRITICAL_VALUE: f64 = 1.96;
+
+/// Statistics of a single benchmark, times are in milliseconds
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchResult {
+    pub name: String,
+    pub r#async: bool,
+    pub samples: u64,
+    pub outliers: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p75: f64,
+    pub p99: f64,
+    pub std_dev: f64,
+    pub iter_per_sec: f64,
+}
+
+// Options which `sable bench` has been ran with
+#[derive(Clone)]
+pub struct BenchOptions {
+    /// Results of a previous run to compare against, keyed by benchmark name
+    pub baseline: Option<HashMap<String, BenchResult>>,
+    /// Change of the mean in percent, past which a slower benchmark counts as a regression
+    pub regression_threshold: f64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            baseline: None,
+            regression_threshold: DEFAULT_REGRESSION_THRESHOLD,
+        }
+    }
+}
+
+// Results of finished benchmarks and amount of them which regressed
+#[derive(Default)]
+pub struct BenchReport {
+    pub results: Vec<BenchResult>,
+    pub regressions: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchComparison {
+    baseline: BenchResult,
+    /// Change of the mean in percent, positive when benchmark got slower
+    change: f64,
+    /// Whether the change is statistically significant according to Welch's t-test
+    significant: bool,
+    regression: bool,
+}
+
+impl BenchComparison {
+    fn new(result: &BenchResult, baseline: &BenchResult, regression_threshold: f64) -> Self {
+        let change = (result.mean - baseline.mean) / baseline.mean * 100.0;
+        let significant = is_significant(result, baseline);
+
+        Self {
+            baseline: baseline.clone(),
+            change,
+            significant,
+            regression: significant && change > regression_threshold,
+        }
+    }
+}
+
+fn is_significant(result: &BenchResult, baseline: &BenchResult) -> bool {
+    let variance = |result: &BenchResult| result.std_dev.powi(2) / result.samples.max(1) as f64;
+    let standard_error = (variance(result) + variance(baseline)).sqrt();
+
+    if standard_error == 0.0 {
+        return result.mean != baseline.mean;
+    }
+
+    ((result.mean - baseline.mean) / standard_error).abs() > T_CRITICAL_VALUE
+}
+
+/** Records result of a finished benchmark, returns its comparison to the baseline if there's one */
+#[op2]
+#[serde]
+pub fn op_bench_report(
+    state: &mut OpState,
+    #[serde] result: BenchResult,
+) -> Option<BenchComparison> {
+    let options = state.borrow::<BenchOptions>();
+
+    let comparison = options
+        .baseline
+        .as_ref()
+        .and_then(|baseline| baseline.get(&result.name))
+        .map(|baseline| BenchComparison::new(&result, baseline, options.regression_threshold));
+
+    let report = state.borrow_mut::<BenchReport>();
+    if comparison
+        .as_ref()
+        .is_some_and(|comparison| comparison.regression)
+    {
+        report.regressions += 1;
+    }
+    report.results.push(result);
+
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(mean: f64, std_dev: f64) -> BenchResult {
+        BenchResult {
+            name: "bench".to_string(),
+            r#async: false,
+            samples: 100,
+            outliers: 0,
+            mean,
+            min: mean - std_dev,
+            max: mean + std_dev,
+            p75: mean,
+            p99: mean + std_dev,
+            std_dev,
+            iter_per_sec: 1000.0 / mean,
+        }
+    }
+
+    #[test]
+    fn identical_results_are_not_significant() {
+        let comparison = BenchComparison::new(&result(1.0, 0.1), &result(1.0, 0.1), 10.0);
+        assert_eq!(comparison.change, 0.0);
+        assert!(!comparison.significant);
+        assert!(!comparison.regression);
+    }
+
+    #[test]
+    fn significant_slowdown_past_threshold_is_regression() {
+        let comparison = BenchComparison::new(&result(1.5, 0.1), &result(1.0, 0.1), 10.0);
+        assert_eq!(comparison.change, 50.0);
+        assert!(comparison.significant);
+        assert!(comparison.regression);
+    }
+
+    #[test]
+    fn slowdown_within_threshold_is_not_regression() {
+        let comparison = BenchComparison::new(&result(1.05, 0.01), &result(1.0, 0.01), 10.0);
+        assert!(comparison.significant);
+        assert!(!comparison.regression);
+    }
+
+    #[test]
+    fn speedup_is_not_regression() {
+        let comparison = BenchComparison::new(&result(0.5, 0.1), &result(1.0, 0.1), 10.0);
+        assert!(comparison.change < 0.0);
+        assert!(comparison.significant);
+        assert!(!comparison.regression);
+    }
+
+    #[test]
+    fn noisy_slowdown_is_not_significant() {
+        let comparison = BenchComparison::new(&result(1.5, 5.0), &result(1.0, 5.0), 10.0);
+        assert!(!comparison.significant);
+        assert!(!comparison.regression);
+    }
+}
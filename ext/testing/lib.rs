@@ -1,11 +1,20 @@
-use deno_core::{op2, v8, OpMetricsSummaryTracker, OpState};
+use deno_core::{
+    error::AnyError, op2, v8, CancelFuture, CancelHandle, OpMetricsSummaryTracker, OpState,
+    Resource, ResourceId,
+};
 use diff::{PrettyDiffBuilder, PrettyDiffBuilderConfig};
-use std::{rc::Rc, time::Instant};
+use sable_ext_timers::TimerQueue;
+use std::{borrow::Cow, cell::RefCell, rc::Rc, time::Duration};
 
+mod bench;
 mod diff;
+pub use bench::{
+    op_bench_report, BenchOptions, BenchReport, BenchResult, DEFAULT_REGRESSION_THRESHOLD,
+};
 use imara_diff::{diff, intern::InternedInput, Algorithm};
 
-const NS_IN_MS: f64 = 1e+6;
+/** Default time in milliseconds after which an asynchronous test times out */
+pub const DEFAULT_TEST_TIMEOUT: u64 = 5000;
 
 const DIFF_CONFIG: PrettyDiffBuilderConfig = PrettyDiffBuilderConfig {
     lines_after_diff: 2,
@@ -13,58 +22,209 @@ const DIFF_CONFIG: PrettyDiffBuilderConfig = PrettyDiffBuilderConfig {
     print_first_and_last_lines: true,
 };
 
-/* Benchmark given function
+#[op2]
+#[string]
+pub fn op_diff_str(#[string] before: &str, #[string] after: &str) -> String {
+    let input = InternedInput::new(before, after);
+    let diff_builder = PrettyDiffBuilder::new(&input, DIFF_CONFIG);
+    diff(Algorithm::Histogram, &input, diff_builder)
+}
 
-It actually benchmarks that function twice:
- - first to warmup the function and break potential JIT bias
- - second run is the one that gets returned
+/** Returns whether there are no async ops running in the background */
+#[op2(fast)]
+pub fn op_test_async_ops_sanitization(state: &mut OpState) -> bool {
+    let metrics_tracker = state
+        .borrow::<Option<Rc<OpMetricsSummaryTracker>>>()
+        .clone();
+    match metrics_tracker {
+        None => true,
+        Some(tracker) => unignored_async_ops(state, &tracker)
+            .into_iter()
+            .all(|amount| amount == 0),
+    }
+}
 
-It confirms that results are stable by checking the difference
-between current function call and the average is smaller than 5e-6 (500NS)
+/** Options which `sable test` has been run with */
+#[derive(Clone, Copy)]
+pub struct TestOptions {
+    /** Time in milliseconds after which asynchronous tests time out, 0 disables timeouts */
+    pub timeout: u64,
+}
 
-Returns a number in milliseconds with nanosecond precision
-Which means how long one run of that function takes */
-#[op2]
-pub fn op_bench_fn(scope: &mut v8::HandleScope, func: &v8::Function) -> f64 {
-    let recv = v8::Integer::new(scope, 1).into();
-    let args = &[];
+impl Default for TestOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TEST_TIMEOUT,
+        }
+    }
+}
 
-    let mut avg: f64 = 0.0;
-    let mut time: f64;
+/** Names of ops registered in the runtime, ordered by their OpId */
+pub struct OpNames(pub Vec<&'static str>);
 
-    for _ in 0..2 {
-        avg = 0.0;
-        time = 1.0;
+/** Amount of tests which failed without throwing (e.g. because they timed out) */
+#[derive(Default)]
+pub struct TestFailures(pub u32);
 
-        while (time - avg).abs() > 5e-6 * time {
-            let now = Instant::now();
-            func.call(scope, recv, args);
-            time = now.elapsed().as_nanos() as f64;
+/** Callback which runs after the tested or benchmarked module has been evaluated,
+used by file level afterAll hooks in tests and by group tables in benchmarks */
+pub struct ModuleFinishCallback(pub v8::Global<v8::Function>);
 
-            avg = (avg + time) / 2.0;
-        }
+/** Amount of pending async ops per OpId, which have been left behind by timed out tests */
+#[derive(Default)]
+struct IgnoredAsyncOps(Vec<u64>);
+
+/** Returns amount of pending async ops per OpId */
+fn pending_async_ops(tracker: &OpMetricsSummaryTracker) -> Vec<u64> {
+    tracker
+        .per_op()
+        .iter()
+        .map(|metrics| metrics.ops_dispatched_async - metrics.ops_completed_async)
+        .collect()
+}
+
+/** Returns amount of pending async ops per OpId which haven't been ignored */
+fn unignored_async_ops(state: &mut OpState, tracker: &OpMetricsSummaryTracker) -> Vec<u64> {
+    let mut pending = pending_async_ops(tracker);
+
+    if state.try_borrow::<IgnoredAsyncOps>().is_none() {
+        state.put(IgnoredAsyncOps::default());
     }
 
-    avg as f64 / NS_IN_MS
+    let ignored = &mut state.borrow_mut::<IgnoredAsyncOps>().0;
+    ignored.resize(pending.len(), 0);
+
+    for (pending, ignored) in pending.iter_mut().zip(ignored.iter_mut()) {
+        // Ignored ops which have completed since shouldn't hide new ones
+        *ignored = (*ignored).min(*pending);
+        *pending -= *ignored;
+    }
+
+    pending
+}
+
+/** Returns the default timeout of asynchronous tests in milliseconds */
+#[op2(fast)]
+#[number]
+pub fn op_test_default_timeout(state: &OpState) -> u64 {
+    state.borrow::<TestOptions>().timeout
+}
+
+/** Counts a test which failed without throwing, e.g. because it timed out */
+#[op2(fast)]
+pub fn op_test_report_failure(state: &mut OpState) {
+    state.borrow_mut::<TestFailures>().0 += 1;
+}
+
+/** Sets the callback which runs once the tested or benchmarked module has been evaluated */
+#[op2]
+pub fn op_set_module_finish_callback(
+    state: &mut OpState,
+    #[global] callback: v8::Global<v8::Function>,
+) {
+    state.put(ModuleFinishCallback(callback));
 }
 
+/** Returns names and amounts of async ops which are still pending */
 #[op2]
-#[string]
-pub fn op_diff_str(#[string] before: &str, #[string] after: &str) -> String {
-    let input = InternedInput::new(before, after);
-    let diff_builder = PrettyDiffBuilder::new(&input, DIFF_CONFIG);
-    diff(Algorithm::Histogram, &input, diff_builder)
+#[serde]
+pub fn op_test_pending_async_ops(state: &mut OpState) -> Vec<(&'static str, u64)> {
+    let Some(tracker) = state
+        .borrow::<Option<Rc<OpMetricsSummaryTracker>>>()
+        .clone()
+    else {
+        return vec![];
+    };
+
+    let pending = unignored_async_ops(state, &tracker);
+    let op_names = &state.borrow::<OpNames>().0;
+
+    pending
+        .into_iter()
+        .enumerate()
+        .filter(|(_, amount)| *amount > 0)
+        .map(|(id, amount)| (op_names[id], amount))
+        .collect()
 }
 
-/** Returns whether there are no async ops running in the background */
+/** Makes currently pending async ops not count towards async ops sanitization */
 #[op2(fast)]
-pub fn op_test_async_ops_sanitization(state: &OpState) -> bool {
-    let metrics_tracker = state.borrow::<Option<Rc<OpMetricsSummaryTracker>>>();
-    match metrics_tracker {
-        None => true,
-        Some(tracker) => {
-            let summary = tracker.aggregate();
-            summary.ops_completed_async == summary.ops_dispatched_async
-        }
+pub fn op_test_ignore_pending_async_ops(state: &mut OpState) {
+    let Some(tracker) = state
+        .borrow::<Option<Rc<OpMetricsSummaryTracker>>>()
+        .clone()
+    else {
+        return;
+    };
+
+    let pending = pending_async_ops(&tracker);
+    state.put(IgnoredAsyncOps(pending));
+}
+
+/** Returns ids and names of resources which are currently open */
+#[op2]
+#[serde]
+pub fn op_test_open_resources(state: &OpState) -> Vec<(ResourceId, String)> {
+    state
+        .resource_table
+        .names()
+        .map(|(rid, name)| (rid, name.into_owned()))
+        .collect()
+}
+
+/** Returns ids of timers which are still waiting in the TimerQueue */
+#[op2]
+#[serde]
+pub fn op_test_pending_timers(state: &OpState) -> Vec<i32> {
+    state
+        .borrow::<TimerQueue>()
+        .iter()
+        .filter(|entry| !entry.0.is_canceled())
+        .map(|entry| entry.0.id())
+        .collect()
+}
+
+struct TestTimeout(Rc<CancelHandle>);
+impl Resource for TestTimeout {
+    fn name(&self) -> Cow<'_, str> {
+        "testTimeout".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.0.cancel();
     }
 }
+
+/** Creates a cancelable handle for `op_test_timeout` and returns its resource id */
+#[op2(fast)]
+#[smi]
+pub fn op_test_timeout_create(state: &mut OpState) -> ResourceId {
+    state
+        .resource_table
+        .add(TestTimeout(CancelHandle::new_rc()))
+}
+
+/** Waits given amount of milliseconds
+
+Returns true when it timed out or false when it got canceled by closing its resource
+
+It isn't tracked by the async ops sanitizer, as it gets canceled right before sanitization happens */
+#[op2(async)]
+pub async fn op_test_timeout(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+    #[number] timeout: u64,
+) -> Result<bool, AnyError> {
+    let cancel_handle = state
+        .borrow()
+        .resource_table
+        .get::<TestTimeout>(rid)?
+        .0
+        .clone();
+
+    let sleep_result = tokio::time::sleep(Duration::from_millis(timeout))
+        .or_cancel(cancel_handle)
+        .await;
+
+    Ok(sleep_result.is_ok())
+}
@@ -1,56 +1,129 @@
 import {
-	op_bench_fn,
+	op_close,
 	op_diff_str,
 	op_runtime_state,
+	op_set_module_finish_callback,
 	op_test_async_ops_sanitization,
+	op_test_default_timeout,
+	op_test_ignore_pending_async_ops,
+	op_test_open_resources,
+	op_test_pending_async_ops,
+	op_test_pending_timers,
+	op_test_report_failure,
+	op_test_timeout,
+	op_test_timeout_create,
 } from "ext:core/ops";
 
 import { Printer } from "ext:sable/console/printer.js";
+import { bench } from "ext:sable/testing/bench.js";
+import { getActiveTimer } from "ext:sable/timers/mod.js";
 import { styles } from "ext:sable/utils/ansi.js";
 import { textWidth } from "ext:sable/utils/text_width.js";
 
+/**
+ * @typedef {{
+ *   ops: [name: string, amount: number][];
+ *   resources: [rid: number, name: string][];
+ *   timers: { id: number, isInterval?: boolean, delay?: number }[];
+ * }} TestLeaks
+ */
+
+/**
+ * @param {TestLeaks["timers"][number]} timer
+ * @returns {string}
+ */
+function formatLeakedTimer(timer) {
+	if (timer.isInterval === undefined) {
+		return `timer #${timer.id}`;
+	} else if (timer.isInterval) {
+		return `setInterval #${timer.id} (every ${timer.delay}ms)`;
+	}
+	return `setTimeout #${timer.id} (after ${timer.delay}ms)`;
+}
+
+/**
+ * @param {TestLeaks["ops"]} ops
+ * @returns {string}
+ */
+function formatLeakedOps(ops) {
+	let formatted = "";
+	for (const [name, amount] of ops) {
+		formatted += `\n  ${name} (${amount})`;
+	}
+	return formatted;
+}
+
 /**
  * Error which gets thrown whenever:
- *  - TestContext is leaking async ops
+ *  - TestContext is leaking async ops, resources or timers
  *  - Test gets started when async ops are still pending
  */
-class TestContextLeakingAsyncOpsError extends Error {
+class TestContextLeakingError extends Error {
 	/**
+	 * @param {TestLeaks} leaks
 	 * @param {TestContext=} testContext
 	 * @param {boolean=} isAsync - whether given testContext callback returned a promise
 	 */
-	constructor(testContext, isAsync) {
+	constructor(leaks, testContext, isAsync) {
 		// TODO(Im-Beast): Replace this with pretty errors after they happen
 		let message = `
-You wanted to create a test, but there are still asynchronous ops running.
+You wanted to create a test, but there are still asynchronous ops running:${
+			formatLeakedOps(leaks.ops)
+		}
 Please make sure they've completed before you create the test.`;
 
 		if (testContext) {
-			message = `
-At least one asynchronous operation was started in ${testContext.title} but never completed!
-Please await all your promises or resolve test promise when every asynchronous operation has finished:`;
+			const kinds = [];
+			if (leaks.timers.length > 0) kinds.push("timers");
+			if (leaks.resources.length > 0) kinds.push("resources");
+			if (leaks.ops.length > 0) kinds.push("async ops");
+
+			message = `\n${testContext.title} is leaking ${kinds.join(", ")}!`;
 
-			if (isAsync) {
+			if (leaks.timers.length > 0) {
+				message += "\nTimers were created but never cleared:";
+				for (const timer of leaks.timers) {
+					message += `\n  ${formatLeakedTimer(timer)}`;
+				}
+			}
+
+			if (leaks.resources.length > 0) {
+				message += "\nResources were opened but never closed:";
+				for (const [rid, name] of leaks.resources) {
+					message += `\n  ${name} (rid ${rid})`;
+				}
+			}
+
+			if (leaks.ops.length > 0) {
 				message += `
+At least one asynchronous operation was started in ${testContext.title} but never completed:${
+					formatLeakedOps(leaks.ops)
+				}
+Please await all your promises or resolve test promise when every asynchronous operation has finished:`;
+
+				if (isAsync) {
+					message += `
 test('${testContext.title}', async (ctx) => {
   ...
 --^^^ ops leak somewhere around here, are you sure you awaited every promise?
 });`;
-			} else {
-				const ptd = "-".repeat(textWidth(testContext.title));
+				} else {
+					const ptd = "-".repeat(textWidth(testContext.title));
 
-				message += `
+					message += `
 test('${testContext.title}', (ctx) => {
 --------${ptd}^ this test is not asynchronous, but leaks asynchronous ops
   ...
 --^^^ ops leak somewhere around here, are you sure this test was meant to be synchronous?
 });`;
+				}
 			}
 		}
 
 		super(message);
 
-		this.name = "TestContextLeakingAsyncOpsError";
+		this.name = "TestContextLeakingError";
+		this.leaks = leaks;
 	}
 }
 
@@ -139,6 +212,264 @@ class ComparisonError extends Error {
 	}
 }
 
+/**
+ * @typedef {{
+ *   timeout?: number;
+ *   sanitizeOps?: boolean;
+ *   sanitizeResources?: boolean;
+ *   sanitizeTimers?: boolean;
+ * }} TestOptions
+ *
+ * timeout - time in milliseconds after which asynchronous test times out, 0 disables it
+ * sanitizeOps - whether test fails when it leaves async ops pending, defaults to true
+ * sanitizeResources - whether test fails when it leaves resources open, defaults to true
+ * sanitizeTimers - whether test fails when it leaves timers and intervals uncleared, defaults to true
+ */
+
+/** @type {number | undefined} */
+let defaultTimeout;
+
+/**
+ * Wait for `promise` to settle or for `timeout` to pass
+ * @param {Promise<void>} promise
+ * @param {number} timeout
+ * @returns {Promise<boolean>} whether `promise` timed out
+ */
+function raceTimeout(promise, timeout) {
+	if (timeout <= 0) {
+		return promise.then(() => false);
+	}
+
+	const rid = op_test_timeout_create();
+	return Promise.race([
+		promise.then(() => false),
+		op_test_timeout(rid, timeout),
+	]).finally(() => op_close(rid));
+}
+
+/**
+ * Call `callback` with `value`, after it resolves if it's a promise
+ * @template T, U
+ * @param {T | Promise<T>} value
+ * @param {(value: T) => U | Promise<U>} callback
+ * @returns {U | Promise<U>}
+ */
+function chain(value, callback) {
+	if (value instanceof Promise) {
+		return value.then(callback);
+	}
+	return callback(value);
+}
+
+/**
+ * Call `callback` and then `cleanup`, even if `callback` throws or rejects
+ *
+ * If both of them fail, error of `callback` takes precedence
+ * @template T
+ * @param {() => T | Promise<T>} callback
+ * @param {() => void | Promise<void>} cleanup
+ * @returns {T | Promise<T>}
+ */
+function tryFinally(callback, cleanup) {
+	const onError = (error) => {
+		let response;
+		try {
+			response = cleanup();
+		} catch {
+			throw error;
+		}
+
+		if (response instanceof Promise) {
+			return response.then(() => {
+				throw error;
+			}, () => {
+				throw error;
+			});
+		}
+		throw error;
+	};
+
+	let response;
+	try {
+		response = callback();
+	} catch (error) {
+		return onError(error);
+	}
+
+	if (response instanceof Promise) {
+		return response.then(
+			(value) => chain(cleanup(), () => value),
+			onError,
+		);
+	}
+
+	return chain(cleanup(), () => response);
+}
+
+/**
+ * Call `callback`, if it throws or rejects call `handler` with the error and rethrow it
+ * @template T
+ * @param {() => T | Promise<T>} callback
+ * @param {(error: unknown) => void} handler
+ * @returns {T | Promise<T>}
+ */
+function onError(callback, handler) {
+	let response;
+	try {
+		response = callback();
+	} catch (error) {
+		handler(error);
+		throw error;
+	}
+
+	if (response instanceof Promise) {
+		return response.catch((error) => {
+			handler(error);
+			throw error;
+		});
+	}
+	return response;
+}
+
+/**
+ * Error which gets thrown whenever lifecycle hook throws, rejects or times out
+ */
+class TestHookError extends Error {
+	/**
+	 * @param {HookKind} hook - kind of the hook which failed
+	 * @param {TestContext | undefined} testContext - test for which hook ran, undefined on the file level
+	 * @param {unknown} cause - reason why hook failed
+	 */
+	constructor(hook, testContext, cause) {
+		const where = testContext ? `for ${testContext.title}` : "on the file level";
+		const reason = cause instanceof Error ? cause.message : String(cause);
+
+		super(`${hook} hook failed ${where}: ${reason}`, { cause });
+
+		this.name = "TestHookError";
+		this.hook = hook;
+	}
+}
+
+/** @typedef {"beforeAll" | "afterAll" | "beforeEach" | "afterEach"} HookKind */
+
+/**
+ * Run given hooks one after another
+ * @param {((testContext?: TestContext) => void | Promise<void>)[]} hooks
+ * @param {HookKind} kind
+ * @param {TestContext | undefined} testContext - test for which hooks run, undefined on the file level
+ * @returns {void | Promise<void>}
+ * @throws {TestHookError} when any of the hooks fails
+ */
+function runHooks(hooks, kind, testContext) {
+	const fail = (error) => {
+		console.log(
+			`- ${styles.red}${styles.bold}${kind} hook failed${styles.reset}`,
+		);
+		throw new TestHookError(kind, testContext, error);
+	};
+
+	for (let i = 0; i < hooks.length; ++i) {
+		let response;
+		try {
+			response = hooks[i](testContext);
+		} catch (error) {
+			fail(error);
+		}
+
+		if (response instanceof Promise) {
+			defaultTimeout ??= op_test_default_timeout();
+			const timeout = defaultTimeout;
+
+			return raceTimeout(response, timeout).then((timedOut) => {
+				if (timedOut) {
+					fail(new Error(`timed out after ${timeout}ms`));
+				}
+				return runHooks(hooks.slice(i + 1), kind, testContext);
+			}, fail);
+		}
+	}
+}
+
+/**
+ * Lifecycle hooks for tests created on a single nesting level
+ *
+ * Hooks only run for tests which are created directly on their level:
+ *  - beforeAll hooks run before the first test, in order of registration
+ *  - beforeEach hooks run before every test, in order of registration
+ *  - afterEach hooks run after every test, in reverse order of registration
+ *  - afterAll hooks run after the last test, in reverse order of registration
+ *
+ * afterEach and afterAll hooks run even if tests fail
+ */
+class TestHooks {
+	/** @type {Record<HookKind, ((testContext?: TestContext) => void | Promise<void>)[]>} */
+	hooks = {
+		beforeAll: [],
+		afterAll: [],
+		beforeEach: [],
+		afterEach: [],
+	};
+
+	/** Whether beforeAll hooks already ran */
+	started = false;
+
+	/**
+	 * @param {TestContext | undefined} owner - test which owns these hooks, undefined on the file level
+	 */
+	constructor(owner) {
+		this.owner = owner;
+	}
+
+	/**
+	 * @param {HookKind} kind
+	 * @param {(testContext?: TestContext) => void | Promise<void>} callback
+	 */
+	add(kind, callback) {
+		if (typeof callback !== "function") {
+			throw new TypeError(`${kind} hook has to be a function`);
+		}
+		this.hooks[kind].push(callback);
+	}
+
+	/** Runs beforeAll hooks, unless they already ran */
+	runBeforeAll() {
+		if (this.started) return;
+		this.started = true;
+
+		const response = runHooks(this.hooks.beforeAll, "beforeAll", this.owner);
+		if (this.owner) return response;
+
+		// Async ops started by file level beforeAll hooks (e.g. servers) are
+		// meant to outlive tests, so they can't prevent further tests from starting
+		return chain(response, () => op_test_ignore_pending_async_ops());
+	}
+
+	/** Runs afterAll hooks, if beforeAll hooks ran */
+	runAfterAll() {
+		if (!this.started) return;
+		this.started = false;
+		return runHooks(this.hooks.afterAll.toReversed(), "afterAll", this.owner);
+	}
+
+	/** @param {TestContext} testContext */
+	runBeforeEach(testContext) {
+		return runHooks(this.hooks.beforeEach, "beforeEach", testContext);
+	}
+
+	/** @param {TestContext} testContext */
+	runAfterEach(testContext) {
+		return runHooks(
+			this.hooks.afterEach.toReversed(),
+			"afterEach",
+			testContext,
+		);
+	}
+}
+
+/** Hooks registered on the file level */
+const fileHooks = new TestHooks(undefined);
+
 /**
  * Value which gets returned whenever comparison passes
  */
@@ -357,18 +688,69 @@ class TestContext {
 	passedTests = 0;
 	failedTests = 0;
 
+	/** Whether this test didn't finish in time */
+	timedOut = false;
+
+	/** Whether callback of this test returned a promise */
+	isAsync = false;
+
+	/** Hooks for sub-tests of this test */
+	hooks = new TestHooks(this);
+
 	/**
 	 * Whether this test has sub-test which is currently running
 	 * If someone tries to create another subtest or comparison when test is locked it will throw
 	 */
 	locked = false;
 
+	/** @type {TestOptions} */
+	options;
+
+	/**
+	 * Amounts of async ops which have been pending before this test started
+	 * @type {Map<string, number> | undefined}
+	 */
+	pendingOps;
+
+	/**
+	 * Resources which have been open before this test started
+	 * @type {Set<number> | undefined}
+	 */
+	openResources;
+
+	/**
+	 * Timers which have been pending before this test started
+	 * @type {Set<number> | undefined}
+	 */
+	pendingTimers;
+
 	/**
 	 * @param {string} name - name for current test
+	 * @param {TestOptions} options - options for current test
 	 * @param {TestContext | undefined} parent - parent test
 	 */
-	constructor(name, parent) {
+	constructor(name, options, parent) {
 		this.name = name;
+		this.options = {
+			sanitizeOps: true,
+			sanitizeResources: true,
+			sanitizeTimers: true,
+			...options,
+		};
+
+		if (this.options.sanitizeOps) {
+			this.pendingOps = new Map(op_test_pending_async_ops());
+		}
+
+		if (this.options.sanitizeResources) {
+			this.openResources = new Set(
+				op_test_open_resources().map(([rid]) => rid),
+			);
+		}
+
+		if (this.options.sanitizeTimers) {
+			this.pendingTimers = new Set(op_test_pending_timers());
+		}
 
 		this.parent = parent;
 		this.start = performance.now();
@@ -378,13 +760,60 @@ class TestContext {
 	}
 
 	/**
-	 * @param {TestContext} testContext - currently evaluated TestContext
-	 * @param {boolean} async - whether TestContext returned a promise
 	 * @throws when async ops are still pending
 	 */
-	static sanitizeAsyncOps(testContext = undefined, async = false) {
+	static sanitizeAsyncOps() {
 		if (!op_test_async_ops_sanitization()) {
-			throw new TestContextLeakingAsyncOpsError(testContext, async);
+			const leaks = {
+				ops: op_test_pending_async_ops(),
+				resources: [],
+				timers: [],
+			};
+			throw new TestContextLeakingError(leaks);
+		}
+	}
+
+	/**
+	 * Make sure that given test hasn't left behind anything its enabled sanitizers check for
+	 * @param {TestContext} testContext - currently evaluated TestContext
+	 * @param {boolean} async - whether TestContext returned a promise
+	 * @throws when test leaks async ops, resources or timers
+	 */
+	static sanitize(testContext, async) {
+		const { options } = testContext;
+
+		/** @type {TestLeaks} */
+		const leaks = { ops: [], resources: [], timers: [] };
+
+		if (options.sanitizeOps) {
+			leaks.ops = op_test_pending_async_ops()
+				.map(([name, amount]) => [
+					name,
+					amount - (testContext.pendingOps.get(name) ?? 0),
+				])
+				.filter(([_, amount]) => amount > 0);
+		} else {
+			// Make sure ops which aren't sanitized don't fail tests which come after
+			op_test_ignore_pending_async_ops();
+		}
+
+		if (options.sanitizeResources) {
+			leaks.resources = op_test_open_resources().filter(
+				([rid]) => !testContext.openResources.has(rid),
+			);
+		}
+
+		if (options.sanitizeTimers) {
+			leaks.timers = op_test_pending_timers()
+				.filter((id) => !testContext.pendingTimers.has(id))
+				.map((id) => ({ id, ...getActiveTimer(id) }));
+		}
+
+		if (
+			leaks.ops.length > 0 || leaks.resources.length > 0 ||
+			leaks.timers.length > 0
+		) {
+			throw new TestContextLeakingError(leaks, testContext, async);
 		}
 	}
 
@@ -416,37 +845,119 @@ class TestContext {
 	/**
 	 * Create new test with given callback
 	 * @param {string} name
+	 * @param {TestOptions} options
 	 * @param {(context: TestContext) => void | Promise<void>} callback
 	 * @param {TestContext} [parent=undefined] parent
 	 * @returns {void | Promise<void>}
 	 */
-	static test(name, callback, parent) {
-		const testContext = new TestContext(name, parent);
+	static test(name, options, callback, parent) {
+		const hooks = parent?.hooks ?? fileHooks;
+
+		return chain(hooks.runBeforeAll(), () => {
+			const testContext = new TestContext(name, options, parent);
+			parent?.lock(testContext);
+
+			const response = onError(
+				() =>
+					tryFinally(
+						() =>
+							chain(
+								hooks.runBeforeEach(testContext),
+								() => testContext.run(callback),
+							),
+						() => hooks.runAfterEach(testContext),
+					),
+				(error) => {
+					testContext.abort(error);
+					parent?.unlock(testContext);
+				},
+			);
 
-		parent?.lock(testContext);
-		const response = callback(testContext);
+			return chain(response, () => {
+				if (testContext.timedOut) {
+					// Timed out tests already report what they left behind
+					parent?.unlock(testContext);
+					return;
+				}
 
-		if (response instanceof Promise) {
-			return response.then(() => {
 				testContext.finish();
 				parent?.unlock(testContext);
-				TestContext.sanitizeAsyncOps(testContext, true);
+				TestContext.sanitize(testContext, testContext.isAsync);
+			});
+		});
+	}
+
+	/**
+	 * Run callback of this test, followed by afterAll hooks of its sub-tests
+	 * @param {(context: TestContext) => void | Promise<void>} callback
+	 * @returns {void | Promise<void>}
+	 */
+	run(callback) {
+		const response = tryFinally(
+			() => callback(this),
+			() => this.hooks.runAfterAll(),
+		);
+
+		if (response instanceof Promise) {
+			this.isAsync = true;
+
+			defaultTimeout ??= op_test_default_timeout();
+			const timeout = this.options.timeout ?? defaultTimeout;
+
+			return raceTimeout(response, timeout).then((timedOut) => {
+				if (timedOut) {
+					this.timeOut(timeout);
+				}
 			});
-		} else {
-			testContext.finish();
-			parent?.unlock(testContext);
-			TestContext.sanitizeAsyncOps(testContext, false);
 		}
 	}
 
 	/**
 	 * Create new sub-test with given callback
 	 * @param {string} name
-	 * @param {(context: TestContext) => void | Promise<void>} callback
+	 * @param {TestOptions | ((context: TestContext) => void | Promise<void>)} options
+	 * @param {(context: TestContext) => void | Promise<void>} [callback]
 	 * @returns {void | Promise<void>}
 	 */
-	test(name, callback) {
-		return TestContext.test(name, callback, this);
+	test(name, options, callback) {
+		if (typeof options === "function") {
+			callback = options;
+			options = {};
+		}
+
+		return TestContext.test(name, options, callback, this);
+	}
+
+	/**
+	 * Register hook which runs before the first sub-test of this test
+	 * @param {() => void | Promise<void>} callback
+	 */
+	beforeAll(callback) {
+		this.hooks.add("beforeAll", callback);
+	}
+
+	/**
+	 * Register hook which runs after the last sub-test of this test
+	 * @param {() => void | Promise<void>} callback
+	 */
+	afterAll(callback) {
+		this.hooks.add("afterAll", callback);
+	}
+
+	/**
+	 * Register hook which runs before every sub-test of this test
+	 * @param {(context: TestContext) => void | Promise<void>} callback
+	 */
+	beforeEach(callback) {
+		this.hooks.add("beforeEach", callback);
+	}
+
+	/**
+	 * Register hook which runs after every sub-test of this test
+	 * @param {(context: TestContext) => void | Promise<void>} callback
+	 */
+	afterEach(callback) {
+		this.hooks.add("afterEach", callback);
 	}
 
 	/**
@@ -460,6 +971,54 @@ class TestContext {
 		console.groupEnd();
 	}
 
+	/**
+	 * Mark this test as failed after its callback or one of its hooks threw
+	 *
+	 * Failures get counted even if the error gets caught by the parent test
+	 * @param {unknown} error
+	 */
+	abort(error) {
+		// Timed out tests have already been reported
+		if (this.timedOut) return;
+
+		// Failed comparisons and hooks report themselves
+		if (this.failedTests === 0 && !(error instanceof TestHookError)) {
+			console.log(`- ${styles.red}${styles.bold}failed${styles.reset}`);
+		}
+
+		this.failedTests++;
+		op_test_report_failure();
+		console.groupEnd();
+	}
+
+	/**
+	 * Mark this test as timed out and report async ops it left behind
+	 *
+	 * Pending async ops get ignored by further sanitization,
+	 * so tests which come after this one can still run
+	 * @param {number} timeout - time in milliseconds after which this test timed out
+	 */
+	timeOut(timeout) {
+		this.timedOut = true;
+		this.failedTests++;
+		op_test_report_failure();
+
+		console.log(
+			`- ${styles.red}${styles.bold}timed out${styles.reset} after ${timeout}ms`,
+		);
+
+		const pendingOps = op_test_pending_async_ops();
+		if (pendingOps.length > 0) {
+			console.log("Pending async ops:");
+			for (const [name, amount] of pendingOps) {
+				console.log(`  ${styles.yellow}${name}${styles.reset} (${amount})`);
+			}
+		}
+
+		op_test_ignore_pending_async_ops();
+		console.groupEnd();
+	}
+
 	/**
 	 * Fail this test if `error` is a `ComparisonError`, otherwise pass
 	 *
@@ -469,6 +1028,9 @@ class TestContext {
 	 * @param {*} b
 	 */
 	assertComparisonError(error, a, b) {
+		// Results of timed out tests are discarded
+		if (this.timedOut) return;
+
 		if (this.locked) {
 			throw new TestContextInvalidUsageError(this);
 		}
@@ -667,10 +1229,16 @@ function noop() {}
 /**
  * Create new test
  * @param {string} name - name of a test
- * @param {(context: TestContext) => void | Promise<void>} callback
+ * @param {TestOptions | ((context: TestContext) => void | Promise<void>)} options
+ * @param {(context: TestContext) => void | Promise<void>} [callback]
  * @returns {void | Promise<void>}
  */
-function test(name, callback) {
+function test(name, options, callback) {
+	if (typeof options === "function") {
+		callback = options;
+		options = {};
+	}
+
 	if (!runtimeState) {
 		runtimeState = op_runtime_state();
 	}
@@ -681,27 +1249,69 @@ function test(name, callback) {
 	}
 
 	TestContext.sanitizeAsyncOps();
-	return TestContext.test(name, callback);
+	return TestContext.test(name, options, callback);
 }
 
-// TODO(Im-Beast): more advanced benchmarking
-function bench(name, callback) {
+/**
+ * Register lifecycle hook on the file level
+ * @param {HookKind} kind
+ * @param {(testContext?: TestContext) => void | Promise<void>} callback
+ */
+function registerFileHook(kind, callback) {
 	if (!runtimeState) {
 		runtimeState = op_runtime_state();
 	}
 
-	if (runtimeState !== "bench") {
-		Sable.bench = noop;
+	if (runtimeState !== "test") {
 		return;
 	}
 
-	console.log(`[ Benching '${name}' ]`);
-	const time = op_bench_fn(callback);
-	console.log(`${name} takes ${time}ms`);
-	return time;
+	if (kind === "afterAll" && fileHooks.hooks.afterAll.length === 0) {
+		// There's no way to know which test is the last one,
+		// so file level afterAll hooks run after the whole module has been evaluated
+		op_set_module_finish_callback(() => fileHooks.runAfterAll());
+	}
+
+	fileHooks.add(kind, callback);
+}
+
+/**
+ * Register hook which runs before the first test in this file
+ * @param {() => void | Promise<void>} callback
+ */
+function beforeAll(callback) {
+	registerFileHook("beforeAll", callback);
+}
+
+/**
+ * Register hook which runs after every test in this file has finished
+ * @param {() => void | Promise<void>} callback
+ */
+function afterAll(callback) {
+	registerFileHook("afterAll", callback);
+}
+
+/**
+ * Register hook which runs before every test in this file
+ * @param {(context: TestContext) => void | Promise<void>} callback
+ */
+function beforeEach(callback) {
+	registerFileHook("beforeEach", callback);
+}
+
+/**
+ * Register hook which runs after every test in this file
+ * @param {(context: TestContext) => void | Promise<void>} callback
+ */
+function afterEach(callback) {
+	registerFileHook("afterEach", callback);
 }
 
 Sable.testing = {
 	test,
 	bench,
+	beforeAll,
+	afterAll,
+	beforeEach,
+	afterEach,
 };
@@ -1,5 +1,7 @@
 use deno_core::{op2, CancelFuture, CancelHandle, OpState, Resource, ResourceId};
-use std::{cell::RefCell, cmp::Reverse, collections::BinaryHeap, rc::Rc, time::Duration};
+use std::{
+    borrow::Cow, cell::RefCell, cmp::Reverse, collections::BinaryHeap, rc::Rc, time::Duration,
+};
 use tokio::time::Instant;
 
 struct TimerHandle(RefCell<Rc<CancelHandle>>);
@@ -21,6 +23,10 @@ impl TimerHandle {
     }
 }
 impl Resource for TimerHandle {
+    fn name(&self) -> Cow<'_, str> {
+        "timer".into()
+    }
+
     fn close(self: Rc<Self>) {
         if !self.is_canceled() {
             self.0.borrow().cancel();
@@ -34,6 +40,14 @@ pub struct TimerEntry {
     enqueued: Instant,
     handle: Rc<TimerHandle>,
 }
+impl TimerEntry {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+    pub fn is_canceled(&self) -> bool {
+        self.handle.is_canceled()
+    }
+}
 impl PartialEq for TimerEntry {
     fn eq(&self, other: &Self) -> bool {
         // We ignore the id and the cancel handle for equality and comparison
@@ -1,7 +1,9 @@
+import { core } from "ext:core/mod.js";
 import { op_close, op_create_timer, op_timers_sleep } from "ext:core/ops";
 
 // https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timers
 import { toLong } from "ext:sable/webidl/mod.ts";
+import { reportError } from "ext:sable/web/errors.js";
 
 const activeTimers = new Map();
 
@@ -9,6 +11,10 @@ let nextId = 1;
 let nestingLevel = 0;
 let isTimerLoopRunning = false;
 
+// Pending `op_timers_sleep`, which keeps the runtime alive only while some timer is refed
+let sleepPromise = null;
+let refedTimers = 0;
+
 // Error which throws when someone tries to use setTimeout(code, ...) or setInterval(code, ...) syntax
 // It's not supported simply because its cursed
 class UnsupportedSetTimerCodeError extends Error {
@@ -22,6 +28,22 @@ class UnsupportedSetTimerCodeError extends Error {
 	}
 }
 
+/**
+ * Refs or unrefs the pending sleep, when the first refed timer gets created or the last one goes away
+ * @param {number} change
+ */
+function changeRefedTimers(change) {
+	const wasRefed = refedTimers > 0;
+	refedTimers += change;
+	if (sleepPromise !== null && wasRefed !== refedTimers > 0) {
+		if (refedTimers > 0) {
+			core.refOpPromise(sleepPromise);
+		} else {
+			core.unrefOpPromise(sleepPromise);
+		}
+	}
+}
+
 async function runTimerLoop() {
 	if (isTimerLoopRunning) {
 		throw new Error("WTF");
@@ -29,7 +51,12 @@ async function runTimerLoop() {
 	isTimerLoopRunning = true;
 
 	while (true) {
-		const timerId = await op_timers_sleep();
+		sleepPromise = op_timers_sleep();
+		if (refedTimers === 0) {
+			core.unrefOpPromise(sleepPromise);
+		}
+		const timerId = await sleepPromise;
+		sleepPromise = null;
 		if (timerId === null) {
 			// It's possible that there were no timers when the async op was
 			// resolved, but that some have been created in the microtasks that
@@ -49,8 +76,11 @@ async function runTimerLoop() {
 
 		const timer = activeTimers.get(timerId);
 		nestingLevel = timer.nestingLevel;
-		// TODO: Handle exceptions
-		timer.callback.apply(globalThis, timer.args);
+		try {
+			timer.callback.apply(globalThis, timer.args);
+		} catch (error) {
+			reportError(error);
+		}
 		nestingLevel = 0;
 
 		// Free the TimerHandle resource
@@ -62,6 +92,7 @@ async function runTimerLoop() {
 			timer.cancelRid = op_create_timer(delay, timerId);
 		} else {
 			activeTimers.delete(timerId);
+			if (timer.refed) changeRefedTimers(-1);
 		}
 	}
 
@@ -70,31 +101,32 @@ async function runTimerLoop() {
 
 /**
  * @param {(...args: any[]) => any} callback
- * @param {number} timeout
- * @param  {...any} args
- * @returns timeout id
+ * @param {number} delay
+ * @param {any[]} args
+ * @param {boolean} isInterval
+ * @param {boolean} refed whether the timer keeps the runtime alive
+ * @returns timer id
  */
-function setTimeout(callback, timeout = 0, ...args) {
-	if (typeof callback !== "function") {
-		throw new UnsupportedSetTimerCodeError("Timeout");
-	}
-
-	timeout = toLong(timeout);
-
+function startTimer(callback, delay, args, isInterval, refed) {
 	const id = nextId;
 	nextId++;
 
 	const currentNesting = nestingLevel + 1;
-	const delay = Math.max(timeout, currentNesting > 5 ? 4 : 0);
-	const cancelRid = op_create_timer(delay, id);
+	const cancelRid = op_create_timer(
+		Math.max(delay, currentNesting > 5 ? 4 : 0),
+		id,
+	);
 
 	activeTimers.set(id, {
 		nestingLevel: currentNesting,
 		callback,
 		args,
 		cancelRid,
-		isInterval: false,
+		isInterval,
+		delay,
+		refed,
 	});
+	if (refed) changeRefedTimers(1);
 
 	if (!isTimerLoopRunning) {
 		runTimerLoop();
@@ -105,38 +137,40 @@ function setTimeout(callback, timeout = 0, ...args) {
 
 /**
  * @param {(...args: any[]) => any} callback
- * @param {number} interval
+ * @param {number} timeout
  * @param  {...any} args
- * @returns interval id
+ * @returns timeout id
  */
-function setInterval(callback, interval = 0, ...args) {
+export function setTimeout(callback, timeout = 0, ...args) {
 	if (typeof callback !== "function") {
-		throw new UnsupportedSetTimerCodeError("Interval");
+		throw new UnsupportedSetTimerCodeError("Timeout");
 	}
 
-	interval = toLong(interval);
-
-	const id = nextId;
-	nextId++;
-
-	const currentNesting = nestingLevel + 1;
-	const delay = Math.max(interval, currentNesting > 5 ? 4 : 0);
-	const cancelRid = op_create_timer(delay, id);
+	return startTimer(callback, toLong(timeout), args, false, true);
+}
 
-	activeTimers.set(id, {
-		nestingLevel: currentNesting,
-		callback,
-		args,
-		cancelRid,
-		isInterval: true,
-		delay: interval,
-	});
+/**
+ * Like `setTimeout`, but the timer doesn't keep the runtime alive, e.g. for `AbortSignal.timeout()`
+ * @param {() => void} callback
+ * @param {number} timeout
+ * @returns timeout id, which `clearTimeout` accepts
+ */
+export function setUnrefTimeout(callback, timeout) {
+	return startTimer(callback, timeout, [], false, false);
+}
 
-	if (!isTimerLoopRunning) {
-		runTimerLoop();
+/**
+ * @param {(...args: any[]) => any} callback
+ * @param {number} interval
+ * @param  {...any} args
+ * @returns interval id
+ */
+function setInterval(callback, interval = 0, ...args) {
+	if (typeof callback !== "function") {
+		throw new UnsupportedSetTimerCodeError("Interval");
 	}
 
-	return id;
+	return startTimer(callback, toLong(interval), args, true, true);
 }
 
 function clearTimeout(id) {
@@ -144,6 +178,7 @@ function clearTimeout(id) {
 	if (timer) {
 		op_close(timer.cancelRid);
 		activeTimers.delete(id);
+		if (timer.refed) changeRefedTimers(-1);
 	}
 }
 
@@ -151,6 +186,18 @@ function clearInterval(id) {
 	clearTimeout(id);
 }
 
+/**
+ * Returns information about an active timer
+ * @param {number} id
+ * @returns {{ isInterval: boolean, delay: number } | undefined}
+ */
+export function getActiveTimer(id) {
+	const timer = activeTimers.get(id);
+	if (timer) {
+		return { isInterval: timer.isInterval, delay: timer.delay };
+	}
+}
+
 globalThis.setTimeout = setTimeout;
 globalThis.setInterval = setInterval;
 globalThis.clearTimeout = clearTimeout;
@@ -1,4 +1,4 @@
-// This declaration file is used to give type hints in ext:core/ops specifiers
+// This declaration file is used to give type hints in ext:core/ops and ext:core/mod.js specifiers
 // Whenever new op is added, it should be added here as well
 
 declare module "ext:core/ops" {
@@ -7,8 +7,8 @@ declare module "ext:core/ops" {
 	export function op_get_promise_details(
 		promise: Promise<any>,
 	): [state: 0 | 1 | 2, result: any];
-	export function op_read(rid: number): Promise<Uint8Array | null>;
-	export function op_write(rid: number, data: Uint8Array): Promise<void>;
+	export function op_read(rid: number, buffer: Uint8Array): Promise<number>;
+	export function op_write(rid: number, data: Uint8Array): Promise<number>;
 	export function op_close(rid: number): Promise<void>;
 	export function op_encode(input: string): Uint8Array;
 
@@ -24,6 +24,13 @@ declare module "ext:core/ops" {
 	// crypto
 	export function op_crypto_new_uuidv4(): `${string}-${string}-${string}-${string}-${string}`;
 
+	// errors
+	export function op_error_sys_details(message: string): {
+		syscall: string;
+		path: string | null;
+		errno: number | null;
+	} | null;
+
 	// fs
 	export function op_read_text_file(path: string): Promise<string>;
 	export function op_write_text_file(path: string, data: string): Promise<void>;
@@ -34,15 +41,163 @@ declare module "ext:core/ops" {
 		path: string,
 		recursive: boolean,
 	): Promise<void>;
+	type FileInfo = {
+		isFile: boolean;
+		isDirectory: boolean;
+		isSymlink: boolean;
+		size: number;
+		mtime: number | null;
+		atime: number | null;
+		birthtime: number | null;
+		mode: number;
+		uid: number;
+		gid: number;
+		dev: number;
+		ino: number;
+		nlink: number;
+	};
+	export function op_stat(path: string): Promise<FileInfo>;
+	export function op_lstat(path: string): Promise<FileInfo>;
+	export function op_read_dir(path: string): Promise<{
+		name: string;
+		isFile: boolean;
+		isDirectory: boolean;
+		isSymlink: boolean;
+	}[]>;
+	export function op_mkdir(
+		path: string,
+		recursive: boolean,
+		mode?: number,
+	): Promise<void>;
+	export function op_rename(from: string, to: string): Promise<void>;
+	export function op_copy_file(from: string, to: string): Promise<void>;
+	export function op_symlink(target: string, path: string): Promise<void>;
+	export function op_read_link(path: string): Promise<string>;
+	export function op_real_path(path: string): Promise<string>;
+	export function op_chmod(path: string, mode: number): Promise<void>;
+	export function op_truncate(path: string, len: number): Promise<void>;
+	export function op_utime(
+		path: string,
+		atime: number,
+		mtime: number,
+	): Promise<void>;
+	export function op_make_temp_file(
+		dir: string | undefined,
+		prefix: string,
+		suffix: string,
+	): Promise<string>;
+	export function op_make_temp_dir(
+		dir: string | undefined,
+		prefix: string,
+		suffix: string,
+	): Promise<string>;
+	export function op_open(path: string, options: {
+		read: boolean;
+		write: boolean;
+		append: boolean;
+		truncate: boolean;
+		create: boolean;
+		createNew: boolean;
+		mode?: number;
+	}): Promise<number>;
+	export function op_seek(
+		rid: number,
+		offset: number,
+		whence: number,
+	): Promise<number>;
+	export function op_ftruncate(rid: number, len: number): Promise<void>;
+	export function op_fsync(rid: number): Promise<void>;
+	export function op_fstat(rid: number): Promise<FileInfo>;
+	type FsEvent = {
+		kind: "create" | "modify" | "remove" | "rename";
+		paths: string[];
+	};
+	export function op_watch_start(
+		paths: string[],
+		recursive: boolean,
+	): Promise<number>;
+	export function op_watch_next(rid: number): Promise<FsEvent[] | null>;
+
+	// fetch
+	export function op_fetch(
+		method: string,
+		url: string,
+		headers: [string, string][],
+		body: Uint8Array | null,
+		hasBodyStream: boolean,
+	): { requestRid: number; requestBodyRid: number | null };
+	export function op_fetch_send(rid: number): Promise<{
+		status: number;
+		statusText: string;
+		headers: [string, string][];
+		responseRid: number;
+	}>;
+
+	// io
+	export function op_is_terminal(rid: number): boolean;
+	export function op_set_raw(rid: number, enabled: boolean): void;
+	export function op_console_size(rid: number): {
+		columns: number;
+		rows: number;
+	};
 
 	// performance
 	export function op_high_res_time(): number;
 	export function op_time_origin(): number;
 
+	// permissions
+	type PermissionState = "granted" | "prompt" | "denied";
+	export function op_permissions_query(descriptor: object): PermissionState;
+	export function op_permissions_request(descriptor: object): PermissionState;
+	export function op_permissions_revoke(descriptor: object): PermissionState;
+
 	// testing
 	export function op_test_async_ops_sanitization(): boolean;
+	export function op_test_default_timeout(): number;
+	export function op_test_report_failure(): void;
+	export function op_set_module_finish_callback(
+		callback: () => void | Promise<void>,
+	): void;
+	export function op_test_pending_async_ops(): [name: string, amount: number][];
+	export function op_test_ignore_pending_async_ops(): void;
+	export function op_test_open_resources(): [rid: number, name: string][];
+	export function op_test_pending_timers(): number[];
+	export function op_test_timeout_create(): number;
+	export function op_test_timeout(rid: number, timeout: number): Promise<boolean>;
+	type BenchResult = import("ext:sable/testing/bench.js").BenchResult;
+	export function op_bench_report(result: BenchResult): {
+		baseline: BenchResult;
+		change: number;
+		significant: boolean;
+		regression: boolean;
+	} | null;
 	export function op_diff_str(a: string, b: string): string;
-	export function op_bench_fn(callback: () => void): number;
+
+	// url
+	type UrlParts = {
+		href: string;
+		origin: string;
+		protocol: string;
+		username: string;
+		password: string;
+		host: string;
+		hostname: string;
+		port: string;
+		pathname: string;
+		search: string;
+		hash: string;
+	};
+	export function op_url_parse(href: string, base?: string): UrlParts | null;
+	export function op_url_reparse(
+		href: string,
+		setter: string,
+		value: string,
+	): UrlParts | null;
+	export function op_url_parse_search_params(query: string): [string, string][];
+	export function op_url_stringify_search_params(
+		pairs: [string, string][],
+	): string;
+	export function op_url_to_file_path(href: string): string;
 
 	// web
 	type BufferSource = import("ext:sable/web/encoding.js").BufferSource;
@@ -57,7 +212,51 @@ declare module "ext:core/ops" {
 		fatal: boolean,
 		ignoreBOM: boolean,
 	): void;
+	/** Opaque decoder, which keeps incomplete sequences between chunks */
+	export type TextDecoderResource = object;
+	export function op_encoding_new_decoder(
+		encoding: string,
+		fatal: boolean,
+		ignoreBOM: boolean,
+	): TextDecoderResource;
+	export function op_encoding_decode(
+		input: BufferSource,
+		decoder: TextDecoderResource,
+		stream: boolean,
+	): string;
 	export function op_encode(): void;
+	export function op_blob_create_part(data: Uint8Array): number;
+	export function op_blob_read_part(
+		id: number,
+		start: number,
+		end: number,
+	): Uint8Array;
+	export function op_blob_remove_part(id: number): void;
+	export function op_blob_create_object_url(
+		mediaType: string,
+		parts: [id: number, start: number, end: number][],
+	): string;
+	export function op_blob_revoke_object_url(url: string): void;
+	export function op_blob_from_object_url(url: string): {
+		mediaType: string;
+		parts: [id: number, size: number][];
+	} | null;
+	type SerializedMessage = import("ext:sable/web/message_port.js").SerializedMessage;
+	export function op_message_port_create_entangled(): [number, number];
+	export function op_message_port_post_message(
+		rid: number,
+		message: SerializedMessage,
+	): void;
+	export function op_message_port_recv_message(
+		rid: number,
+	): Promise<SerializedMessage | null>;
+	export function op_broadcast_subscribe(name: string): number;
+	export function op_broadcast_send(rid: number, data: Uint8Array): void;
+	export function op_broadcast_recv(rid: number): Promise<Uint8Array | null>;
+	export function op_report_unhandled_rejection(): void;
+	export function op_set_global_event_callback(
+		callback: (type: string, cancelable: boolean) => boolean,
+	): void;
 
 	// timers
 	export function op_timers_sleep(): Promise<number | null>;
@@ -85,4 +284,84 @@ declare module "ext:core/ops" {
 	): void;
 	export function op_webstorage_clear(session: boolean): string | null;
 	export function op_webstorage_keys(session: boolean): string[];
+
+	// worker
+	type WorkerPermissions = import("ext:sable/worker/worker.js").WorkerPermissions;
+	export function op_worker_create(
+		specifier: string,
+		name: string,
+		permissions: WorkerPermissions | undefined,
+	): number;
+	export function op_worker_post_message(
+		rid: number,
+		message: SerializedMessage,
+	): void;
+	export function op_worker_recv_event(rid: number): Promise<
+		| { message: SerializedMessage }
+		| {
+			error: {
+				message: string;
+				filename: string;
+				lineno: number;
+				colno: number;
+			};
+		}
+		| null
+	>;
+	export function op_worker_scope_name(): string;
+	export function op_worker_scope_post_message(message: SerializedMessage): void;
+	export function op_worker_scope_recv_message(): Promise<
+		SerializedMessage | null
+	>;
+	export function op_worker_scope_close(): void;
+}
+
+declare module "ext:core/mod.js" {
+	export const core: {
+		Interrupted: typeof Error;
+		BadResource: typeof Error;
+		destructureError(error: Error): {
+			frames?: Array<{
+				fileName?: string;
+				lineNumber?: number;
+				columnNumber?: number;
+			}>;
+		};
+		registerErrorClass(
+			className: string,
+			errorClass: new (message: string) => Error,
+		): void;
+		registerErrorBuilder(
+			className: string,
+			errorBuilder: (message: string) => any,
+		): void;
+		reportUnhandledException(error: any): void;
+		refOpPromise(promise: Promise<any>): void;
+		unrefOpPromise(promise: Promise<any>): void;
+		hostObjectBrand: symbol;
+		serialize(
+			value: any,
+			options?: {
+				hostObjects?: object[];
+				transferredArrayBuffers?: any[];
+				forStorage?: boolean;
+			},
+			errorCallback?: (message: string) => void,
+		): Uint8Array;
+		deserialize(
+			buffer: Uint8Array,
+			options?: {
+				hostObjects?: object[];
+				transferredArrayBuffers?: any[];
+				forStorage?: boolean;
+			},
+		): any;
+		setUnhandledPromiseRejectionHandler(
+			handler: (promise: Promise<any>, reason: any) => boolean,
+		): void;
+		setHandledPromiseRejectionHandler(
+			handler: (promise: Promise<any>, reason: any) => void,
+		): void;
+		setReportExceptionCallback(callback: (error: any) => void): void;
+	};
 }
@@ -0,0 +1,118 @@
+use deno_core::{
+    error::{type_error, AnyError},
+    op2,
+    url::{form_urlencoded, quirks, Url},
+};
+use serde::Serialize;
+
+/// Components of a parsed URL, as returned by getters of the `URL` class
+#[derive(Serialize)]
+pub struct UrlParts {
+    href: String,
+    origin: String,
+    protocol: String,
+    username: String,
+    password: String,
+    host: String,
+    hostname: String,
+    port: String,
+    pathname: String,
+    search: String,
+    hash: String,
+}
+
+impl From<&Url> for UrlParts {
+    fn from(url: &Url) -> Self {
+        Self {
+            href: quirks::href(url).to_string(),
+            origin: quirks::origin(url),
+            protocol: quirks::protocol(url).to_string(),
+            username: quirks::username(url).to_string(),
+            password: quirks::password(url).to_string(),
+            host: quirks::host(url).to_string(),
+            hostname: quirks::hostname(url).to_string(),
+            port: quirks::port(url).to_string(),
+            pathname: quirks::pathname(url).to_string(),
+            search: quirks::search(url).to_string(),
+            hash: quirks::hash(url).to_string(),
+        }
+    }
+}
+
+/// Parses the URL, relative to `base` when it's given, returns `null` when either of them is invalid
+#[op2]
+#[serde]
+pub fn op_url_parse(#[string] href: &str, #[string] base: Option<String>) -> Option<UrlParts> {
+    let base = match base {
+        Some(base) => Some(Url::parse(&base).ok()?),
+        None => None,
+    };
+    let url = Url::options().base_url(base.as_ref()).parse(href).ok()?;
+    Some(UrlParts::from(&url))
+}
+
+/// Changes a component of the URL using its setter, returns `null` when the value has been rejected
+///
+/// Some setters ignore invalid values instead of rejecting them, like the `URL` setters do
+#[op2]
+#[serde]
+pub fn op_url_reparse(
+    #[string] href: &str,
+    #[string] setter: &str,
+    #[string] value: &str,
+) -> Result<Option<UrlParts>, AnyError> {
+    let mut url = Url::parse(href).map_err(|_| type_error(format!("Invalid URL: '{href}'")))?;
+    let result = match setter {
+        "href" => quirks::set_href(&mut url, value).map_err(|_| ()),
+        "protocol" => quirks::set_protocol(&mut url, value),
+        "username" => quirks::set_username(&mut url, value),
+        "password" => quirks::set_password(&mut url, value),
+        "host" => quirks::set_host(&mut url, value),
+        "hostname" => quirks::set_hostname(&mut url, value),
+        "port" => quirks::set_port(&mut url, value),
+        "pathname" => {
+            quirks::set_pathname(&mut url, value);
+            Ok(())
+        }
+        "search" => {
+            quirks::set_search(&mut url, value);
+            Ok(())
+        }
+        "hash" => {
+            quirks::set_hash(&mut url, value);
+            Ok(())
+        }
+        _ => return Err(type_error(format!("Unknown URL setter: '{setter}'"))),
+    };
+    Ok(result.ok().map(|_| UrlParts::from(&url)))
+}
+
+/// Parses `application/x-www-form-urlencoded` query into name-value pairs
+#[op2]
+#[serde]
+pub fn op_url_parse_search_params(#[string] query: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+/// Serializes name-value pairs as `application/x-www-form-urlencoded` query
+#[op2]
+#[string]
+pub fn op_url_stringify_search_params(#[serde] pairs: Vec<(String, String)>) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish()
+}
+
+/// Converts `file:` URL into a path, percent-decoding it
+#[op2]
+#[string]
+pub fn op_url_to_file_path(#[string] href: &str) -> Result<String, AnyError> {
+    Url::parse(href)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+        .map(|path| path.to_string_lossy().into_owned())
+        .ok_or_else(|| type_error(format!("Invalid file URL: '{href}'")))
+}
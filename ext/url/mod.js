@@ -0,0 +1,6 @@
+import { URL, URLSearchParams } from "ext:sable/url/url.js";
+import { URLPattern } from "ext:sable/url/urlpattern.js";
+
+globalThis.URL = URL;
+globalThis.URLSearchParams = URLSearchParams;
+globalThis.URLPattern = URLPattern;
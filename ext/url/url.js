@@ -0,0 +1,550 @@
+import {
+	op_url_parse,
+	op_url_parse_search_params,
+	op_url_reparse,
+	op_url_stringify_search_params,
+	op_url_to_file_path,
+} from "ext:core/ops";
+import { createObjectURL, revokeObjectURL } from "ext:sable/web/blob.js";
+
+// https://url.spec.whatwg.org/#api
+
+/**
+ * @typedef {{
+ *   href: string;
+ *   origin: string;
+ *   protocol: string;
+ *   username: string;
+ *   password: string;
+ *   host: string;
+ *   hostname: string;
+ *   port: string;
+ *   pathname: string;
+ *   search: string;
+ *   hash: string;
+ * }} UrlParts
+ */
+
+const _parts = Symbol("[[url]]");
+const _searchParams = Symbol("[[searchParams]]");
+const _list = Symbol("[[list]]");
+const _url = Symbol("[[url object]]");
+
+/**
+ * Converts the value to a string, replacing lone surrogates with U+FFFD
+ * @param {any} value
+ * @returns {string}
+ */
+function toUSVString(value) {
+	return String(value).toWellFormed();
+}
+
+/**
+ * @param {any} url
+ * @param {any} [base]
+ * @returns {UrlParts | null}
+ */
+function parse(url, base) {
+	return op_url_parse(
+		toUSVString(url),
+		base === undefined ? undefined : toUSVString(base),
+	);
+}
+
+export class URL {
+	/** @type {UrlParts} */
+	[_parts];
+	/** @type {URLSearchParams | null} Created on first access */
+	[_searchParams] = null;
+
+	/**
+	 * @param {string | URL} url
+	 * @param {string | URL} [base]
+	 */
+	constructor(url, base = undefined) {
+		const parts = parse(url, base);
+		if (parts === null) {
+			throw new TypeError(
+				base === undefined
+					? `Invalid URL: '${url}'`
+					: `Invalid URL: '${url}' with base '${base}'`,
+			);
+		}
+		this[_parts] = parts;
+	}
+
+	/**
+	 * Same as the constructor, but returns `null` instead of throwing for invalid URLs
+	 * @param {string | URL} url
+	 * @param {string | URL} [base]
+	 * @returns {URL | null}
+	 */
+	static parse(url, base = undefined) {
+		try {
+			return new URL(url, base);
+		} catch {
+			return null;
+		}
+	}
+
+	/**
+	 * @param {string | URL} url
+	 * @param {string | URL} [base]
+	 * @returns {boolean}
+	 */
+	static canParse(url, base = undefined) {
+		return parse(url, base) !== null;
+	}
+
+	/**
+	 * Creates `blob:` URL, which can be fetched until it gets revoked
+	 * @param {Blob} blob
+	 * @returns {string}
+	 */
+	static createObjectURL(blob) {
+		return createObjectURL(blob);
+	}
+
+	/**
+	 * Releases contents of the blob referenced by the `blob:` URL
+	 * @param {string} url
+	 */
+	static revokeObjectURL(url) {
+		revokeObjectURL(url);
+	}
+
+	/** @returns {string} */
+	get href() {
+		assertURL(this);
+		return this[_parts].href;
+	}
+
+	/** @param {string} value */
+	set href(value) {
+		assertURL(this);
+		const parts = op_url_reparse(this[_parts].href, "href", toUSVString(value));
+		if (parts === null) {
+			throw new TypeError(`Invalid URL: '${value}'`);
+		}
+		this[_parts] = parts;
+		updateSearchParams(this);
+	}
+
+	/** @returns {string} */
+	get origin() {
+		assertURL(this);
+		return this[_parts].origin;
+	}
+
+	/** @returns {string} */
+	get protocol() {
+		assertURL(this);
+		return this[_parts].protocol;
+	}
+
+	/** @param {string} value */
+	set protocol(value) {
+		assertURL(this);
+		setComponent(this, "protocol", value);
+	}
+
+	/** @returns {string} */
+	get username() {
+		assertURL(this);
+		return this[_parts].username;
+	}
+
+	/** @param {string} value */
+	set username(value) {
+		assertURL(this);
+		setComponent(this, "username", value);
+	}
+
+	/** @returns {string} */
+	get password() {
+		assertURL(this);
+		return this[_parts].password;
+	}
+
+	/** @param {string} value */
+	set password(value) {
+		assertURL(this);
+		setComponent(this, "password", value);
+	}
+
+	/** @returns {string} */
+	get host() {
+		assertURL(this);
+		return this[_parts].host;
+	}
+
+	/** @param {string} value */
+	set host(value) {
+		assertURL(this);
+		setComponent(this, "host", value);
+	}
+
+	/** @returns {string} */
+	get hostname() {
+		assertURL(this);
+		return this[_parts].hostname;
+	}
+
+	/** @param {string} value */
+	set hostname(value) {
+		assertURL(this);
+		setComponent(this, "hostname", value);
+	}
+
+	/** @returns {string} */
+	get port() {
+		assertURL(this);
+		return this[_parts].port;
+	}
+
+	/** @param {string} value */
+	set port(value) {
+		assertURL(this);
+		setComponent(this, "port", value);
+	}
+
+	/** @returns {string} */
+	get pathname() {
+		assertURL(this);
+		return this[_parts].pathname;
+	}
+
+	/** @param {string} value */
+	set pathname(value) {
+		assertURL(this);
+		setComponent(this, "pathname", value);
+	}
+
+	/** @returns {string} */
+	get search() {
+		assertURL(this);
+		return this[_parts].search;
+	}
+
+	/** @param {string} value */
+	set search(value) {
+		assertURL(this);
+		setComponent(this, "search", value);
+		updateSearchParams(this);
+	}
+
+	/**
+	 * Query parameters, which stay in sync with `search`
+	 * @returns {URLSearchParams}
+	 */
+	get searchParams() {
+		assertURL(this);
+		if (this[_searchParams] === null) {
+			const searchParams = new URLSearchParams(this[_parts].search);
+			searchParams[_url] = this;
+			this[_searchParams] = searchParams;
+		}
+		return this[_searchParams];
+	}
+
+	/** @returns {string} */
+	get hash() {
+		assertURL(this);
+		return this[_parts].hash;
+	}
+
+	/** @param {string} value */
+	set hash(value) {
+		assertURL(this);
+		setComponent(this, "hash", value);
+	}
+
+	/** @returns {string} */
+	toString() {
+		assertURL(this);
+		return this[_parts].href;
+	}
+
+	/** @returns {string} */
+	toJSON() {
+		assertURL(this);
+		return this[_parts].href;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "URL";
+	}
+}
+
+/**
+ * @param {any} url
+ */
+function assertURL(url) {
+	if (!(url instanceof URL)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Invalid values get ignored, like in browsers
+ * @param {URL} url
+ * @param {"protocol" | "username" | "password" | "host" | "hostname" | "port" | "pathname" | "search" | "hash"} setter
+ * @param {string} value
+ */
+function setComponent(url, setter, value) {
+	const parts = op_url_reparse(url[_parts].href, setter, toUSVString(value));
+	if (parts !== null) {
+		url[_parts] = parts;
+	}
+}
+
+/**
+ * Replaces query parameters of the URL after its query changed
+ * @param {URL} url
+ */
+function updateSearchParams(url) {
+	const searchParams = url[_searchParams];
+	if (searchParams !== null) {
+		searchParams[_list] = op_url_parse_search_params(
+			url[_parts].search.slice(1),
+		);
+	}
+}
+
+/**
+ * @typedef {URLSearchParams | [string, string][] | Record<string, string> | string} URLSearchParamsInit
+ */
+
+export class URLSearchParams {
+	/** @type {[string, string][]} */
+	[_list] = [];
+	/** @type {URL | null} URL which query the parameters belong to */
+	[_url] = null;
+
+	/**
+	 * @param {URLSearchParamsInit} [init]
+	 */
+	constructor(init = "") {
+		if (init instanceof URLSearchParams) {
+			this[_list] = init[_list].map(([name, value]) => [name, value]);
+		} else if (typeof init === "object" && init !== null) {
+			if (Symbol.iterator in init) {
+				for (const pair of init) {
+					const entries = [...pair];
+					if (entries.length !== 2) {
+						throw new TypeError("Search param pairs must have exactly two items");
+					}
+					this[_list].push([
+						toUSVString(entries[0]),
+						toUSVString(entries[1]),
+					]);
+				}
+			} else {
+				for (const name of Object.keys(init)) {
+					this[_list].push([toUSVString(name), toUSVString(init[name])]);
+				}
+			}
+		} else {
+			let query = toUSVString(init);
+			if (query.startsWith("?")) {
+				query = query.slice(1);
+			}
+			this[_list] = op_url_parse_search_params(query);
+		}
+	}
+
+	/** @returns {number} */
+	get size() {
+		assertSearchParams(this);
+		return this[_list].length;
+	}
+
+	/**
+	 * @param {string} name
+	 * @param {string} value
+	 */
+	append(name, value) {
+		assertSearchParams(this);
+		this[_list].push([toUSVString(name), toUSVString(value)]);
+		updateURL(this);
+	}
+
+	/**
+	 * Removes all parameters with the name, or only those which also have the value
+	 * @param {string} name
+	 * @param {string} [value]
+	 */
+	delete(name, value = undefined) {
+		assertSearchParams(this);
+		name = toUSVString(name);
+		if (value === undefined) {
+			this[_list] = this[_list].filter(([paramName]) => paramName !== name);
+		} else {
+			value = toUSVString(value);
+			this[_list] = this[_list].filter(([paramName, paramValue]) =>
+				paramName !== name || paramValue !== value
+			);
+		}
+		updateURL(this);
+	}
+
+	/**
+	 * Returns value of the first parameter with the name
+	 * @param {string} name
+	 * @returns {string | null}
+	 */
+	get(name) {
+		assertSearchParams(this);
+		name = toUSVString(name);
+		return this[_list].find(([paramName]) => paramName === name)?.[1] ?? null;
+	}
+
+	/**
+	 * @param {string} name
+	 * @returns {string[]}
+	 */
+	getAll(name) {
+		assertSearchParams(this);
+		name = toUSVString(name);
+		return this[_list]
+			.filter(([paramName]) => paramName === name)
+			.map(([, value]) => value);
+	}
+
+	/**
+	 * Whether there is a parameter with the name, which also has the value when it's given
+	 * @param {string} name
+	 * @param {string} [value]
+	 * @returns {boolean}
+	 */
+	has(name, value = undefined) {
+		assertSearchParams(this);
+		name = toUSVString(name);
+		if (value === undefined) {
+			return this[_list].some(([paramName]) => paramName === name);
+		}
+		value = toUSVString(value);
+		return this[_list].some(([paramName, paramValue]) =>
+			paramName === name && paramValue === value
+		);
+	}
+
+	/**
+	 * Replaces value of the first parameter with the name and removes the others
+	 * @param {string} name
+	 * @param {string} value
+	 */
+	set(name, value) {
+		assertSearchParams(this);
+		name = toUSVString(name);
+		value = toUSVString(value);
+
+		const index = this[_list].findIndex(([paramName]) => paramName === name);
+		if (index === -1) {
+			this[_list].push([name, value]);
+		} else {
+			this[_list][index][1] = value;
+			this[_list] = this[_list].filter(([paramName], i) =>
+				i <= index || paramName !== name
+			);
+		}
+		updateURL(this);
+	}
+
+	/**
+	 * Sorts parameters by their names, keeping the order of parameters with the same name
+	 */
+	sort() {
+		assertSearchParams(this);
+		// Names are compared by their UTF-16 code units, which is what `<` does
+		this[_list].sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0));
+		updateURL(this);
+	}
+
+	/**
+	 * @param {(value: string, name: string, searchParams: URLSearchParams) => void} callback
+	 * @param {any} [thisArg]
+	 */
+	forEach(callback, thisArg = undefined) {
+		assertSearchParams(this);
+		for (let i = 0; i < this[_list].length; i++) {
+			const [name, value] = this[_list][i];
+			callback.call(thisArg, value, name, this);
+		}
+	}
+
+	/** @returns {IterableIterator<[string, string]>} */
+	*entries() {
+		assertSearchParams(this);
+		for (let i = 0; i < this[_list].length; i++) {
+			const [name, value] = this[_list][i];
+			yield [name, value];
+		}
+	}
+
+	/** @returns {IterableIterator<string>} */
+	*keys() {
+		for (const [name] of this.entries()) {
+			yield name;
+		}
+	}
+
+	/** @returns {IterableIterator<string>} */
+	*values() {
+		for (const [, value] of this.entries()) {
+			yield value;
+		}
+	}
+
+	[Symbol.iterator]() {
+		return this.entries();
+	}
+
+	/**
+	 * Serializes parameters as `application/x-www-form-urlencoded`
+	 * @returns {string}
+	 */
+	toString() {
+		assertSearchParams(this);
+		return op_url_stringify_search_params(this[_list]);
+	}
+
+	get [Symbol.toStringTag]() {
+		return "URLSearchParams";
+	}
+}
+
+/**
+ * @param {any} searchParams
+ */
+function assertSearchParams(searchParams) {
+	if (!(searchParams instanceof URLSearchParams)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Replaces query of the URL which the parameters belong to
+ * @param {URLSearchParams} searchParams
+ */
+function updateURL(searchParams) {
+	const url = searchParams[_url];
+	if (url === null) return;
+
+	// Empty query gets removed along with the "?"
+	const parts = op_url_reparse(
+		url[_parts].href,
+		"search",
+		op_url_stringify_search_params(searchParams[_list]),
+	);
+	url[_parts] = parts;
+}
+
+/**
+ * Converts `file:` URL into a path, so that APIs accepting paths can be given e.g. `new URL("./file.txt", import.meta.url)`
+ * @param {string | URL} path
+ * @returns {string}
+ */
+export function pathFromURL(path) {
+	return path instanceof URL ? op_url_to_file_path(path.href) : path;
+}
@@ -0,0 +1,1514 @@
+import { op_url_parse, op_url_reparse } from "ext:core/ops";
+
+// https://urlpattern.spec.whatwg.org/
+
+/**
+ * @typedef {{
+ *   protocol?: string;
+ *   username?: string;
+ *   password?: string;
+ *   hostname?: string;
+ *   port?: string;
+ *   pathname?: string;
+ *   search?: string;
+ *   hash?: string;
+ *   baseURL?: string;
+ * }} URLPatternInit
+ */
+
+/**
+ * @typedef {{
+ *   input: string;
+ *   groups: Record<string, string | undefined>;
+ * }} URLPatternComponentResult
+ */
+
+/**
+ * @typedef {{
+ *   inputs: (string | URLPatternInit)[];
+ *   protocol: URLPatternComponentResult;
+ *   username: URLPatternComponentResult;
+ *   password: URLPatternComponentResult;
+ *   hostname: URLPatternComponentResult;
+ *   port: URLPatternComponentResult;
+ *   pathname: URLPatternComponentResult;
+ *   search: URLPatternComponentResult;
+ *   hash: URLPatternComponentResult;
+ * }} URLPatternResult
+ */
+
+/**
+ * @typedef {"open" | "close" | "regexp" | "name" | "char" | "escaped-char" | "other-modifier" | "asterisk" | "end" | "invalid-char"} TokenType
+ * @typedef {{ type: TokenType; index: number; value: string }} Token
+ */
+
+/**
+ * @typedef {"none" | "optional" | "zero-or-more" | "one-or-more"} PartModifier
+ * @typedef {{
+ *   type: "fixed-text" | "regexp" | "segment-wildcard" | "full-wildcard";
+ *   value: string;
+ *   modifier: PartModifier;
+ *   name: string;
+ *   prefix: string;
+ *   suffix: string;
+ * }} Part
+ */
+
+/**
+ * @typedef {{ delimiterCodePoint: string; prefixCodePoint: string; ignoreCase: boolean }} PatternOptions
+ */
+
+/**
+ * @typedef {{
+ *   patternString: string;
+ *   regularExpression: RegExp;
+ *   groupNameList: string[];
+ *   hasRegExpGroups: boolean;
+ * }} Component
+ */
+
+/** @typedef {(value: string) => string} EncodingCallback */
+
+const COMPONENTS = [
+	"protocol",
+	"username",
+	"password",
+	"hostname",
+	"port",
+	"pathname",
+	"search",
+	"hash",
+];
+
+/** Special schemes and their default ports */
+const SPECIAL_SCHEMES = {
+	ftp: "21",
+	file: null,
+	http: "80",
+	https: "443",
+	ws: "80",
+	wss: "443",
+};
+
+const FULL_WILDCARD_REGEXP = ".*";
+
+/** @type {PatternOptions} */
+const DEFAULT_OPTIONS = {
+	delimiterCodePoint: "",
+	prefixCodePoint: "",
+	ignoreCase: false,
+};
+
+/** @type {PatternOptions} */
+const HOSTNAME_OPTIONS = {
+	delimiterCodePoint: ".",
+	prefixCodePoint: "",
+	ignoreCase: false,
+};
+
+/** @type {PatternOptions} */
+const PATHNAME_OPTIONS = {
+	delimiterCodePoint: "/",
+	prefixCodePoint: "/",
+	ignoreCase: false,
+};
+
+/**
+ * @param {string} scheme
+ * @returns {boolean}
+ */
+function isSpecialScheme(scheme) {
+	return Object.hasOwn(SPECIAL_SCHEMES, scheme);
+}
+
+/**
+ * @param {string} codePoint
+ * @param {boolean} first
+ * @returns {boolean}
+ */
+function isValidNameCodePoint(codePoint, first) {
+	return first
+		? /^[$_\p{ID_Start}]$/u.test(codePoint)
+		: /^[$\u200C\u200D\p{ID_Continue}]$/u.test(codePoint);
+}
+
+/**
+ * @param {string} value
+ * @returns {string}
+ */
+function escapeRegexpString(value) {
+	return value.replace(/[.+*?^${}()[\]|/\\]/g, "\\$&");
+}
+
+/**
+ * @param {string} value
+ * @returns {string}
+ */
+function escapePatternString(value) {
+	return value.replace(/[+*?:{}()\\]/g, "\\$&");
+}
+
+/**
+ * @param {PartModifier} modifier
+ * @returns {string}
+ */
+function modifierToString(modifier) {
+	switch (modifier) {
+		case "optional":
+			return "?";
+		case "zero-or-more":
+			return "*";
+		case "one-or-more":
+			return "+";
+		default:
+			return "";
+	}
+}
+
+/**
+ * @param {PatternOptions} options
+ * @returns {string}
+ */
+function segmentWildcardRegexp(options) {
+	return `[^${escapeRegexpString(options.delimiterCodePoint)}]+?`;
+}
+
+// https://urlpattern.spec.whatwg.org/#tokenizing
+
+/**
+ * @param {string} input
+ * @param {"strict" | "lenient"} policy whether invalid characters throw or become "invalid-char" tokens
+ * @returns {Token[]}
+ */
+function tokenize(input, policy) {
+	/** @type {Token[]} */
+	const tokens = [];
+	let index = 0;
+	let nextIndex = 0;
+	let codePoint = "";
+
+	const seekAndGetNextCodePoint = (position) => {
+		codePoint = String.fromCodePoint(input.codePointAt(position));
+		nextIndex = position + codePoint.length;
+	};
+	const addToken = (type, nextPosition, valuePosition, valueLength) => {
+		tokens.push({
+			type,
+			index,
+			value: input.substring(valuePosition, valuePosition + valueLength),
+		});
+		index = nextPosition;
+	};
+	const addTokenWithDefaultLength = (type, nextPosition, valuePosition) => {
+		addToken(type, nextPosition, valuePosition, nextPosition - valuePosition);
+	};
+	const addDefaultToken = (type) => {
+		addTokenWithDefaultLength(type, nextIndex, index);
+	};
+	const processError = (nextPosition, valuePosition) => {
+		if (policy === "strict") {
+			throw new TypeError(
+				`Invalid pattern '${input}' at position ${valuePosition}`,
+			);
+		}
+		addTokenWithDefaultLength("invalid-char", nextPosition, valuePosition);
+	};
+
+	while (index < input.length) {
+		seekAndGetNextCodePoint(index);
+
+		if (codePoint === "*") {
+			addDefaultToken("asterisk");
+			continue;
+		}
+		if (codePoint === "+" || codePoint === "?") {
+			addDefaultToken("other-modifier");
+			continue;
+		}
+		if (codePoint === "\\") {
+			if (index === input.length - 1) {
+				processError(nextIndex, index);
+				continue;
+			}
+			const escapedIndex = nextIndex;
+			seekAndGetNextCodePoint(nextIndex);
+			addToken(
+				"escaped-char",
+				nextIndex,
+				escapedIndex,
+				nextIndex - escapedIndex,
+			);
+			continue;
+		}
+		if (codePoint === "{") {
+			addDefaultToken("open");
+			continue;
+		}
+		if (codePoint === "}") {
+			addDefaultToken("close");
+			continue;
+		}
+
+		if (codePoint === ":") {
+			let namePosition = nextIndex;
+			const nameStart = namePosition;
+			while (namePosition < input.length) {
+				seekAndGetNextCodePoint(namePosition);
+				if (!isValidNameCodePoint(codePoint, namePosition === nameStart)) {
+					break;
+				}
+				namePosition = nextIndex;
+			}
+			if (namePosition <= nameStart) {
+				processError(nameStart, index);
+				continue;
+			}
+			addTokenWithDefaultLength("name", namePosition, nameStart);
+			continue;
+		}
+
+		if (codePoint === "(") {
+			let depth = 1;
+			let regexpPosition = nextIndex;
+			const regexpStart = regexpPosition;
+			let error = false;
+
+			while (regexpPosition < input.length) {
+				seekAndGetNextCodePoint(regexpPosition);
+				if (codePoint.codePointAt(0) > 0x7f) {
+					processError(regexpStart, index);
+					error = true;
+					break;
+				}
+				if (regexpPosition === regexpStart && codePoint === "?") {
+					processError(regexpStart, index);
+					error = true;
+					break;
+				}
+				if (codePoint === "\\") {
+					if (regexpPosition === input.length - 1) {
+						processError(regexpStart, index);
+						error = true;
+						break;
+					}
+					seekAndGetNextCodePoint(nextIndex);
+					if (codePoint.codePointAt(0) > 0x7f) {
+						processError(regexpStart, index);
+						error = true;
+						break;
+					}
+					regexpPosition = nextIndex;
+					continue;
+				}
+				if (codePoint === ")") {
+					depth--;
+					if (depth === 0) {
+						regexpPosition = nextIndex;
+						break;
+					}
+				} else if (codePoint === "(") {
+					depth++;
+					if (regexpPosition === input.length - 1) {
+						processError(regexpStart, index);
+						error = true;
+						break;
+					}
+					// Only non-capturing groups are allowed
+					const temporaryPosition = nextIndex;
+					seekAndGetNextCodePoint(nextIndex);
+					if (codePoint !== "?") {
+						processError(regexpStart, index);
+						error = true;
+						break;
+					}
+					nextIndex = temporaryPosition;
+				}
+				regexpPosition = nextIndex;
+			}
+
+			if (error) continue;
+			if (depth !== 0) {
+				processError(regexpStart, index);
+				continue;
+			}
+			const regexpLength = regexpPosition - regexpStart - 1;
+			if (regexpLength === 0) {
+				processError(regexpStart, index);
+				continue;
+			}
+			addToken("regexp", regexpPosition, regexpStart, regexpLength);
+			continue;
+		}
+
+		addDefaultToken("char");
+	}
+
+	addTokenWithDefaultLength("end", index, index);
+	return tokens;
+}
+
+// https://urlpattern.spec.whatwg.org/#parsing-patterns
+
+/**
+ * @param {string} input
+ * @param {PatternOptions} options
+ * @param {EncodingCallback} encodingCallback
+ * @returns {Part[]}
+ */
+function parsePatternString(input, options, encodingCallback) {
+	const tokens = tokenize(input, "strict");
+	const wildcardRegexp = segmentWildcardRegexp(options);
+	/** @type {Part[]} */
+	const parts = [];
+	let pendingFixedValue = "";
+	let index = 0;
+	let nextNumericName = 0;
+
+	/** @param {TokenType} type */
+	const tryConsume = (type) => {
+		if (tokens[index].type !== type) return null;
+		return tokens[index++];
+	};
+	const tryConsumeModifier = () =>
+		tryConsume("other-modifier") ?? tryConsume("asterisk");
+	const tryConsumeRegexpOrWildcard = (nameToken) => {
+		const token = tryConsume("regexp");
+		if (nameToken === null && token === null) {
+			return tryConsume("asterisk");
+		}
+		return token;
+	};
+	/** @param {TokenType} type */
+	const consumeRequired = (type) => {
+		const token = tryConsume(type);
+		if (token === null) {
+			throw new TypeError(
+				`Invalid pattern '${input}', expected ${type} at position ${
+					tokens[index].index
+				}`,
+			);
+		}
+		return token;
+	};
+	const consumeText = () => {
+		let result = "";
+		while (true) {
+			const token = tryConsume("char") ?? tryConsume("escaped-char");
+			if (token === null) break;
+			result += token.value;
+		}
+		return result;
+	};
+	const maybeAddPartFromPendingFixedValue = () => {
+		if (pendingFixedValue === "") return;
+		const value = encodingCallback(pendingFixedValue);
+		pendingFixedValue = "";
+		parts.push({
+			type: "fixed-text",
+			value,
+			modifier: "none",
+			name: "",
+			prefix: "",
+			suffix: "",
+		});
+	};
+	const addPart = (
+		prefix,
+		nameToken,
+		regexpOrWildcardToken,
+		suffix,
+		modifierToken,
+	) => {
+		/** @type {PartModifier} */
+		let modifier = "none";
+		if (modifierToken !== null) {
+			modifier = modifierToken.value === "?"
+				? "optional"
+				: modifierToken.value === "*"
+				? "zero-or-more"
+				: "one-or-more";
+		}
+
+		if (
+			nameToken === null &&
+			regexpOrWildcardToken === null &&
+			modifier === "none"
+		) {
+			pendingFixedValue += prefix;
+			return;
+		}
+		maybeAddPartFromPendingFixedValue();
+
+		if (nameToken === null && regexpOrWildcardToken === null) {
+			if (prefix === "") return;
+			parts.push({
+				type: "fixed-text",
+				value: encodingCallback(prefix),
+				modifier,
+				name: "",
+				prefix: "",
+				suffix: "",
+			});
+			return;
+		}
+
+		let regexpValue;
+		if (regexpOrWildcardToken === null) {
+			regexpValue = wildcardRegexp;
+		} else if (regexpOrWildcardToken.type === "asterisk") {
+			regexpValue = FULL_WILDCARD_REGEXP;
+		} else {
+			regexpValue = regexpOrWildcardToken.value;
+		}
+
+		/** @type {Part["type"]} */
+		let type = "regexp";
+		if (regexpValue === wildcardRegexp) {
+			type = "segment-wildcard";
+			regexpValue = "";
+		} else if (regexpValue === FULL_WILDCARD_REGEXP) {
+			type = "full-wildcard";
+			regexpValue = "";
+		}
+
+		let name = "";
+		if (nameToken !== null) {
+			name = nameToken.value;
+		} else if (regexpOrWildcardToken !== null) {
+			name = String(nextNumericName++);
+		}
+		if (parts.some((part) => part.name === name)) {
+			throw new TypeError(`Invalid pattern '${input}', duplicate name '${name}'`);
+		}
+
+		parts.push({
+			type,
+			value: regexpValue,
+			modifier,
+			name,
+			prefix: encodingCallback(prefix),
+			suffix: encodingCallback(suffix),
+		});
+	};
+
+	while (index < tokens.length) {
+		const charToken = tryConsume("char");
+		let nameToken = tryConsume("name");
+		let regexpOrWildcardToken = tryConsumeRegexpOrWildcard(nameToken);
+
+		if (nameToken !== null || regexpOrWildcardToken !== null) {
+			let prefix = charToken?.value ?? "";
+			if (prefix !== "" && prefix !== options.prefixCodePoint) {
+				pendingFixedValue += prefix;
+				prefix = "";
+			}
+			maybeAddPartFromPendingFixedValue();
+			const modifierToken = tryConsumeModifier();
+			addPart(prefix, nameToken, regexpOrWildcardToken, "", modifierToken);
+			continue;
+		}
+
+		const fixedToken = charToken ?? tryConsume("escaped-char");
+		if (fixedToken !== null) {
+			pendingFixedValue += fixedToken.value;
+			continue;
+		}
+
+		if (tryConsume("open") !== null) {
+			const prefix = consumeText();
+			nameToken = tryConsume("name");
+			regexpOrWildcardToken = tryConsumeRegexpOrWildcard(nameToken);
+			const suffix = consumeText();
+			consumeRequired("close");
+			const modifierToken = tryConsumeModifier();
+			addPart(prefix, nameToken, regexpOrWildcardToken, suffix, modifierToken);
+			continue;
+		}
+
+		maybeAddPartFromPendingFixedValue();
+		consumeRequired("end");
+	}
+
+	return parts;
+}
+
+/**
+ * @param {Part[]} parts
+ * @param {PatternOptions} options
+ * @returns {{ regexpString: string; nameList: string[] }}
+ */
+function generateRegularExpression(parts, options) {
+	let result = "^";
+	/** @type {string[]} */
+	const nameList = [];
+
+	for (const part of parts) {
+		const modifier = modifierToString(part.modifier);
+
+		if (part.type === "fixed-text") {
+			result += part.modifier === "none"
+				? escapeRegexpString(part.value)
+				: `(?:${escapeRegexpString(part.value)})${modifier}`;
+			continue;
+		}
+
+		nameList.push(part.name);
+		let regexpValue = part.value;
+		if (part.type === "segment-wildcard") {
+			regexpValue = segmentWildcardRegexp(options);
+		} else if (part.type === "full-wildcard") {
+			regexpValue = FULL_WILDCARD_REGEXP;
+		}
+
+		const optionalOrNone = part.modifier === "none" ||
+			part.modifier === "optional";
+		if (part.prefix === "" && part.suffix === "") {
+			result += optionalOrNone
+				? `(${regexpValue})${modifier}`
+				: `((?:${regexpValue})${modifier})`;
+			continue;
+		}
+
+		const prefix = escapeRegexpString(part.prefix);
+		const suffix = escapeRegexpString(part.suffix);
+		if (optionalOrNone) {
+			result += `(?:${prefix}(${regexpValue})${suffix})${modifier}`;
+			continue;
+		}
+
+		result +=
+			`(?:${prefix}((?:${regexpValue})(?:${suffix}${prefix}(?:${regexpValue}))*)${suffix})`;
+		if (part.modifier === "zero-or-more") {
+			result += "?";
+		}
+	}
+
+	result += "$";
+	return { regexpString: result, nameList };
+}
+
+/**
+ * Generates normalized pattern string, which is what the component getters return
+ * @param {Part[]} parts
+ * @param {PatternOptions} options
+ * @returns {string}
+ */
+function generatePatternString(parts, options) {
+	let result = "";
+
+	for (let index = 0; index < parts.length; index++) {
+		const part = parts[index];
+		const previousPart = index > 0 ? parts[index - 1] : null;
+		const nextPart = index < parts.length - 1 ? parts[index + 1] : null;
+		const modifier = modifierToString(part.modifier);
+
+		if (part.type === "fixed-text") {
+			result += part.modifier === "none"
+				? escapePatternString(part.value)
+				: `{${escapePatternString(part.value)}}${modifier}`;
+			continue;
+		}
+
+		const customName = !/^[0-9]/.test(part.name);
+		let needsGrouping = part.suffix !== "" ||
+			(part.prefix !== "" && part.prefix !== options.prefixCodePoint);
+
+		if (
+			!needsGrouping &&
+			customName &&
+			part.type === "segment-wildcard" &&
+			part.modifier === "none" &&
+			nextPart !== null &&
+			nextPart.prefix === "" &&
+			nextPart.suffix === ""
+		) {
+			if (nextPart.type === "fixed-text") {
+				const firstCodePoint = String.fromCodePoint(
+					nextPart.value.codePointAt(0),
+				);
+				needsGrouping = isValidNameCodePoint(firstCodePoint, false);
+			} else {
+				needsGrouping = /^[0-9]/.test(nextPart.name);
+			}
+		}
+
+		if (
+			!needsGrouping &&
+			part.prefix === "" &&
+			previousPart !== null &&
+			previousPart.type === "fixed-text" &&
+			previousPart.value.at(-1) === options.prefixCodePoint
+		) {
+			needsGrouping = true;
+		}
+
+		if (needsGrouping) {
+			result += "{";
+		}
+		result += escapePatternString(part.prefix);
+		if (customName) {
+			result += `:${part.name}`;
+		}
+
+		if (part.type === "regexp") {
+			result += `(${part.value})`;
+		} else if (part.type === "segment-wildcard" && !customName) {
+			result += `(${segmentWildcardRegexp(options)})`;
+		} else if (part.type === "full-wildcard") {
+			if (
+				!customName &&
+				(previousPart === null ||
+					previousPart.type === "fixed-text" ||
+					previousPart.modifier !== "none" ||
+					needsGrouping ||
+					part.prefix !== "")
+			) {
+				result += "*";
+			} else {
+				result += `(${FULL_WILDCARD_REGEXP})`;
+			}
+		}
+
+		if (
+			part.type === "segment-wildcard" &&
+			customName &&
+			part.suffix !== "" &&
+			isValidNameCodePoint(String.fromCodePoint(part.suffix.codePointAt(0)), false)
+		) {
+			result += "\\";
+		}
+		result += escapePatternString(part.suffix);
+		if (needsGrouping) {
+			result += "}";
+		}
+		result += modifier;
+	}
+
+	return result;
+}
+
+/**
+ * @param {string} input
+ * @param {EncodingCallback} encodingCallback
+ * @param {PatternOptions} options
+ * @returns {Component}
+ */
+function compileComponent(input, encodingCallback, options) {
+	const parts = parsePatternString(input, options, encodingCallback);
+	const { regexpString, nameList } = generateRegularExpression(parts, options);
+
+	let regularExpression;
+	try {
+		regularExpression = new RegExp(regexpString, options.ignoreCase ? "vi" : "v");
+	} catch (error) {
+		throw new TypeError(`Invalid pattern '${input}': ${error.message}`);
+	}
+
+	return {
+		patternString: generatePatternString(parts, options),
+		regularExpression,
+		groupNameList: nameList,
+		hasRegExpGroups: parts.some((part) => part.type === "regexp"),
+	};
+}
+
+/**
+ * @param {Component} protocol
+ * @returns {boolean}
+ */
+function protocolComponentMatchesSpecialScheme(protocol) {
+	return Object.keys(SPECIAL_SCHEMES).some((scheme) =>
+		protocol.regularExpression.test(scheme)
+	);
+}
+
+// https://urlpattern.spec.whatwg.org/#canon-encoding-callbacks
+
+/**
+ * Changes component of the dummy URL and returns its new components
+ * @param {string} href
+ * @param {string} setter
+ * @param {string} value
+ */
+function reparse(href, setter, value) {
+	const parts = op_url_reparse(href, setter, value);
+	if (parts === null) {
+		throw new TypeError(`Invalid ${setter}: '${value}'`);
+	}
+	return parts;
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeProtocol(value) {
+	if (value === "") return value;
+	const parts = op_url_parse(`${value}://dummy.test`, undefined);
+	if (parts === null) {
+		throw new TypeError(`Invalid protocol: '${value}'`);
+	}
+	return parts.protocol.slice(0, -1);
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeUsername(value) {
+	if (value === "") return value;
+	return reparse("fake://dummy.test", "username", value).username;
+}
+
+/** @type {EncodingCallback} */
+function canonicalizePassword(value) {
+	if (value === "") return value;
+	return reparse("fake://dummy.test", "password", value).password;
+}
+
+/**
+ * @param {string} value
+ * @param {string} [protocol]
+ * @returns {string}
+ */
+function canonicalizeHostname(value, protocol = undefined) {
+	if (value === "") return value;
+	// Hosts of special URLs get normalized, e.g. lowercased, others only get percent-encoded
+	const special = protocol === undefined || protocol === "" ||
+		isSpecialScheme(protocol);
+	const dummy = special ? "https://dummy.test" : "fake://dummy.test";
+	return reparse(dummy, "hostname", value).hostname;
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeIPv6Hostname(value) {
+	if (!/^[0-9a-fA-F[\]:]*$/.test(value)) {
+		throw new TypeError(`Invalid IPv6 hostname: '${value}'`);
+	}
+	return value.toLowerCase();
+}
+
+/**
+ * @param {string} value
+ * @param {string} [protocol]
+ * @returns {string}
+ */
+function canonicalizePort(value, protocol = undefined) {
+	if (value === "") return value;
+	if (!/^[0-9]*$/.test(value)) {
+		throw new TypeError(`Invalid port: '${value}'`);
+	}
+	let dummy = "fake://dummy.test";
+	if (protocol !== undefined && protocol !== "") {
+		dummy = `${protocol}://dummy.test`;
+	}
+	return reparse(dummy, "port", value).port;
+}
+
+/** @type {EncodingCallback} */
+function canonicalizePathname(value) {
+	if (value === "") return value;
+	const leadingSlash = value[0] === "/";
+	// Relative paths would get resolved against the dummy path otherwise
+	const modifiedValue = leadingSlash ? value : `/-${value}`;
+	const pathname =
+		reparse("fake://fake-url", "pathname", modifiedValue).pathname;
+	return leadingSlash ? pathname : pathname.slice(2);
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeOpaquePathname(value) {
+	if (value === "") return value;
+	const parts = op_url_parse(`fake:${value}`, undefined);
+	if (parts === null) {
+		throw new TypeError(`Invalid pathname: '${value}'`);
+	}
+	return parts.pathname;
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeSearch(value) {
+	if (value === "") return value;
+	return reparse("fake://dummy.test", "search", value).search.slice(1);
+}
+
+/** @type {EncodingCallback} */
+function canonicalizeHash(value) {
+	if (value === "") return value;
+	return reparse("fake://dummy.test", "hash", value).hash.slice(1);
+}
+
+// https://urlpattern.spec.whatwg.org/#constructor-string-parsing
+
+/**
+ * Splits pattern string like "https://:subdomain.example.com/*" into components
+ * @param {string} input
+ * @returns {URLPatternInit}
+ */
+function parseConstructorString(input) {
+	const tokens = tokenize(input, "lenient");
+	/** @type {URLPatternInit} */
+	const result = {};
+	let componentStart = 0;
+	let tokenIndex = 0;
+	let tokenIncrement = 1;
+	let groupDepth = 0;
+	let hostnameIPv6BracketDepth = 0;
+	let protocolMatchesSpecialScheme = false;
+	let state = "init";
+
+	const safeToken = (index) => tokens[Math.min(index, tokens.length - 1)];
+	const isNonSpecialPatternChar = (index, value) => {
+		const token = safeToken(index);
+		return token.value === value &&
+			(token.type === "char" ||
+				token.type === "escaped-char" ||
+				token.type === "invalid-char");
+	};
+	const isSearchPrefix = () => {
+		if (isNonSpecialPatternChar(tokenIndex, "?")) return true;
+		if (tokens[tokenIndex].value !== "?") return false;
+		if (tokenIndex === 0) return true;
+		// "?" after a name, regexp or group is a modifier
+		const previousType = safeToken(tokenIndex - 1).type;
+		return previousType !== "name" &&
+			previousType !== "regexp" &&
+			previousType !== "close" &&
+			previousType !== "asterisk";
+	};
+	const isHashPrefix = () => isNonSpecialPatternChar(tokenIndex, "#");
+	const isPathnameStart = () => isNonSpecialPatternChar(tokenIndex, "/");
+	const isProtocolSuffix = () => isNonSpecialPatternChar(tokenIndex, ":");
+	const isIdentityTerminator = () => isNonSpecialPatternChar(tokenIndex, "@");
+	const makeComponentString = () =>
+		input.substring(safeToken(componentStart).index, tokens[tokenIndex].index);
+
+	const rewind = () => {
+		tokenIndex = componentStart;
+		tokenIncrement = 0;
+	};
+	const rewindAndSetState = (newState) => {
+		rewind();
+		state = newState;
+	};
+	const changeState = (newState, skip) => {
+		if (state !== "init" && state !== "authority" && state !== "done") {
+			result[state] = makeComponentString();
+		}
+
+		if (state !== "init" && newState !== "done") {
+			const beforeHostname = ["protocol", "authority", "username", "password"]
+				.includes(state);
+			const beforePathname = beforeHostname ||
+				state === "hostname" ||
+				state === "port";
+
+			if (
+				beforeHostname &&
+				["port", "pathname", "search", "hash"].includes(newState) &&
+				result.hostname === undefined
+			) {
+				result.hostname = "";
+			}
+			if (
+				beforePathname &&
+				(newState === "search" || newState === "hash") &&
+				result.pathname === undefined
+			) {
+				result.pathname = protocolMatchesSpecialScheme ? "/" : "";
+			}
+			if (
+				(beforePathname || state === "pathname") &&
+				newState === "hash" &&
+				result.search === undefined
+			) {
+				result.search = "";
+			}
+		}
+
+		state = newState;
+		tokenIndex += skip;
+		componentStart = tokenIndex;
+		tokenIncrement = 0;
+	};
+
+	while (tokenIndex < tokens.length) {
+		tokenIncrement = 1;
+		const token = tokens[tokenIndex];
+
+		if (token.type === "end") {
+			if (state === "init") {
+				// Input without a protocol is a relative pattern
+				rewind();
+				if (isHashPrefix()) {
+					changeState("hash", 1);
+				} else if (isSearchPrefix()) {
+					changeState("search", 1);
+				} else {
+					changeState("pathname", 0);
+				}
+				tokenIndex += tokenIncrement;
+				continue;
+			}
+			if (state === "authority") {
+				rewindAndSetState("hostname");
+				tokenIndex += tokenIncrement;
+				continue;
+			}
+			changeState("done", 0);
+			break;
+		}
+
+		if (token.type === "open") {
+			groupDepth++;
+			tokenIndex += tokenIncrement;
+			continue;
+		}
+		if (groupDepth > 0) {
+			if (token.type === "close") {
+				groupDepth--;
+			} else {
+				tokenIndex += tokenIncrement;
+				continue;
+			}
+		}
+
+		switch (state) {
+			case "init":
+				if (isProtocolSuffix()) {
+					rewindAndSetState("protocol");
+				}
+				break;
+			case "protocol":
+				if (isProtocolSuffix()) {
+					const protocol = compileComponent(
+						makeComponentString(),
+						canonicalizeProtocol,
+						DEFAULT_OPTIONS,
+					);
+					protocolMatchesSpecialScheme =
+						protocolComponentMatchesSpecialScheme(protocol);
+
+					let nextState = "pathname";
+					let skip = 1;
+					if (
+						isNonSpecialPatternChar(tokenIndex + 1, "/") &&
+						isNonSpecialPatternChar(tokenIndex + 2, "/")
+					) {
+						nextState = "authority";
+						skip = 3;
+					} else if (protocolMatchesSpecialScheme) {
+						nextState = "authority";
+					}
+					changeState(nextState, skip);
+				}
+				break;
+			case "authority":
+				if (isIdentityTerminator()) {
+					rewindAndSetState("username");
+				} else if (isPathnameStart() || isSearchPrefix() || isHashPrefix()) {
+					rewindAndSetState("hostname");
+				}
+				break;
+			case "username":
+				if (isNonSpecialPatternChar(tokenIndex, ":")) {
+					changeState("password", 1);
+				} else if (isIdentityTerminator()) {
+					changeState("hostname", 1);
+				}
+				break;
+			case "password":
+				if (isIdentityTerminator()) {
+					changeState("hostname", 1);
+				}
+				break;
+			case "hostname":
+				if (isNonSpecialPatternChar(tokenIndex, "[")) {
+					hostnameIPv6BracketDepth++;
+				} else if (isNonSpecialPatternChar(tokenIndex, "]")) {
+					hostnameIPv6BracketDepth--;
+				} else if (
+					isNonSpecialPatternChar(tokenIndex, ":") &&
+					hostnameIPv6BracketDepth === 0
+				) {
+					changeState("port", 1);
+				} else if (isPathnameStart()) {
+					changeState("pathname", 0);
+				} else if (isSearchPrefix()) {
+					changeState("search", 1);
+				} else if (isHashPrefix()) {
+					changeState("hash", 1);
+				}
+				break;
+			case "port":
+				if (isPathnameStart()) {
+					changeState("pathname", 0);
+				} else if (isSearchPrefix()) {
+					changeState("search", 1);
+				} else if (isHashPrefix()) {
+					changeState("hash", 1);
+				}
+				break;
+			case "pathname":
+				if (isSearchPrefix()) {
+					changeState("search", 1);
+				} else if (isHashPrefix()) {
+					changeState("hash", 1);
+				}
+				break;
+			case "search":
+				if (isHashPrefix()) {
+					changeState("hash", 1);
+				}
+				break;
+		}
+
+		tokenIndex += tokenIncrement;
+	}
+
+	if (result.hostname !== undefined && result.port === undefined) {
+		result.port = "";
+	}
+	return result;
+}
+
+// https://urlpattern.spec.whatwg.org/#canon-processing-for-init
+
+/**
+ * @param {string} input
+ * @param {"pattern" | "url"} type
+ * @returns {boolean}
+ */
+function isAbsolutePathname(input, type) {
+	if (input === "") return false;
+	if (input[0] === "/") return true;
+	if (type === "url") return false;
+	return input[0] === "\\" && input[1] === "/" ||
+		input[0] === "{" && input[1] === "/";
+}
+
+/**
+ * Copies known members of the dictionary, converted to strings
+ * @param {any} init
+ * @returns {URLPatternInit}
+ */
+function toURLPatternInit(init) {
+	if (init === null || typeof init !== "object") {
+		throw new TypeError("URLPattern input must be a string or an object");
+	}
+
+	/** @type {URLPatternInit} */
+	const result = {};
+	for (const member of [...COMPONENTS, "baseURL"]) {
+		if (init[member] !== undefined) {
+			result[member] = String(init[member]).toWellFormed();
+		}
+	}
+	return result;
+}
+
+/**
+ * Fills in components missing from `init` using its `baseURL`, and canonicalizes components of URLs
+ * @param {URLPatternInit} init
+ * @param {"pattern" | "url"} type
+ * @param {string | undefined} defaults value of components which are neither in `init` nor in its `baseURL`
+ * @returns {URLPatternInit}
+ */
+function processURLPatternInit(init, type, defaults) {
+	/** @type {URLPatternInit} */
+	const result = {};
+	if (defaults !== undefined) {
+		for (const component of COMPONENTS) {
+			result[component] = defaults;
+		}
+	}
+
+	/** @param {string} value */
+	const processBaseURLString = (value) =>
+		type === "pattern" ? escapePatternString(value) : value;
+
+	let baseURL = null;
+	if (init.baseURL !== undefined) {
+		baseURL = op_url_parse(init.baseURL, undefined);
+		if (baseURL === null) {
+			throw new TypeError(`Invalid base URL: '${init.baseURL}'`);
+		}
+
+		// Components are taken from the base URL until the first given component
+		const given = COMPONENTS.map((component) => init[component] !== undefined);
+		const [
+			protocolGiven,
+			usernameGiven,
+			passwordGiven,
+			hostnameGiven,
+			portGiven,
+			pathnameGiven,
+			searchGiven,
+			hashGiven,
+		] = given;
+		const authorityGiven = protocolGiven || hostnameGiven || portGiven;
+
+		if (!protocolGiven) {
+			result.protocol = processBaseURLString(baseURL.protocol.slice(0, -1));
+		}
+		if (type !== "pattern" && !authorityGiven && !usernameGiven) {
+			result.username = processBaseURLString(baseURL.username);
+		}
+		if (
+			type !== "pattern" && !authorityGiven && !usernameGiven &&
+			!passwordGiven
+		) {
+			result.password = processBaseURLString(baseURL.password);
+		}
+		if (!protocolGiven && !hostnameGiven) {
+			result.hostname = processBaseURLString(baseURL.hostname);
+		}
+		if (!authorityGiven) {
+			result.port = processBaseURLString(baseURL.port);
+		}
+		if (!authorityGiven && !pathnameGiven) {
+			result.pathname = processBaseURLString(baseURL.pathname);
+		}
+		if (!authorityGiven && !pathnameGiven && !searchGiven) {
+			result.search = processBaseURLString(baseURL.search.slice(1));
+		}
+		if (!authorityGiven && !pathnameGiven && !searchGiven && !hashGiven) {
+			result.hash = processBaseURLString(baseURL.hash.slice(1));
+		}
+	}
+
+	if (init.protocol !== undefined) {
+		const protocol = init.protocol.replace(/:$/, "");
+		result.protocol = type === "pattern"
+			? protocol
+			: canonicalizeProtocol(protocol);
+	}
+	if (init.username !== undefined) {
+		result.username = type === "pattern"
+			? init.username
+			: canonicalizeUsername(init.username);
+	}
+	if (init.password !== undefined) {
+		result.password = type === "pattern"
+			? init.password
+			: canonicalizePassword(init.password);
+	}
+	if (init.hostname !== undefined) {
+		result.hostname = type === "pattern"
+			? init.hostname
+			: canonicalizeHostname(init.hostname, result.protocol);
+	}
+	if (init.port !== undefined) {
+		result.port = type === "pattern"
+			? init.port
+			: canonicalizePort(init.port, result.protocol);
+	}
+
+	if (init.pathname !== undefined) {
+		let pathname = init.pathname;
+		// Relative pathnames get resolved against the base URL
+		const opaquePath = baseURL !== null && baseURL.pathname[0] !== "/" &&
+			baseURL.host === "";
+		if (
+			baseURL !== null && !opaquePath && !isAbsolutePathname(pathname, type)
+		) {
+			const basePath = processBaseURLString(baseURL.pathname);
+			const slashIndex = basePath.lastIndexOf("/");
+			if (slashIndex !== -1) {
+				pathname = basePath.substring(0, slashIndex + 1) + pathname;
+			}
+		}
+
+		if (type === "pattern") {
+			result.pathname = pathname;
+		} else if (
+			result.protocol === undefined ||
+			result.protocol === "" ||
+			isSpecialScheme(result.protocol)
+		) {
+			result.pathname = canonicalizePathname(pathname);
+		} else {
+			result.pathname = canonicalizeOpaquePathname(pathname);
+		}
+	}
+
+	if (init.search !== undefined) {
+		const search = init.search.replace(/^\?/, "");
+		result.search = type === "pattern" ? search : canonicalizeSearch(search);
+	}
+	if (init.hash !== undefined) {
+		const hash = init.hash.replace(/^#/, "");
+		result.hash = type === "pattern" ? hash : canonicalizeHash(hash);
+	}
+
+	return result;
+}
+
+/**
+ * @param {string} input
+ * @returns {boolean}
+ */
+function hostnamePatternIsIPv6Address(input) {
+	if (input.length < 2) return false;
+	return input[0] === "[" ||
+		(input[0] === "{" && input[1] === "[") ||
+		(input[0] === "\\" && input[1] === "[");
+}
+
+const _components = Symbol("[[components]]");
+
+export class URLPattern {
+	/** @type {Record<string, Component>} */
+	[_components] = {};
+
+	/**
+	 * @param {string | URLPatternInit} [input]
+	 * @param {string | { ignoreCase?: boolean }} [baseURL]
+	 * @param {{ ignoreCase?: boolean }} [options]
+	 */
+	constructor(input = {}, baseURL = undefined, options = undefined) {
+		if (typeof baseURL !== "string") {
+			options = baseURL;
+			baseURL = undefined;
+		}
+		const ignoreCase = Boolean(options?.ignoreCase);
+
+		/** @type {URLPatternInit} */
+		let init;
+		if (typeof input === "string") {
+			init = parseConstructorString(input);
+			if (baseURL === undefined && init.protocol === undefined) {
+				throw new TypeError(
+					`Relative pattern '${input}' requires a base URL`,
+				);
+			}
+			if (baseURL !== undefined) {
+				init.baseURL = baseURL;
+			}
+		} else {
+			if (baseURL !== undefined) {
+				throw new TypeError(
+					"Base URL can only be given along with a pattern string",
+				);
+			}
+			init = toURLPatternInit(input);
+		}
+
+		const processed = processURLPatternInit(init, "pattern", undefined);
+		for (const component of COMPONENTS) {
+			processed[component] ??= "*";
+		}
+		if (
+			isSpecialScheme(processed.protocol) &&
+			processed.port === SPECIAL_SCHEMES[processed.protocol]
+		) {
+			processed.port = "";
+		}
+
+		const components = this[_components];
+		components.protocol = compileComponent(
+			processed.protocol,
+			canonicalizeProtocol,
+			DEFAULT_OPTIONS,
+		);
+		components.username = compileComponent(
+			processed.username,
+			canonicalizeUsername,
+			DEFAULT_OPTIONS,
+		);
+		components.password = compileComponent(
+			processed.password,
+			canonicalizePassword,
+			DEFAULT_OPTIONS,
+		);
+		components.hostname = hostnamePatternIsIPv6Address(processed.hostname)
+			? compileComponent(
+				processed.hostname,
+				canonicalizeIPv6Hostname,
+				HOSTNAME_OPTIONS,
+			)
+			: compileComponent(
+				processed.hostname,
+				(value) => canonicalizeHostname(value),
+				HOSTNAME_OPTIONS,
+			);
+		components.port = compileComponent(
+			processed.port,
+			(value) => canonicalizePort(value),
+			DEFAULT_OPTIONS,
+		);
+
+		const compileOptions = { ...DEFAULT_OPTIONS, ignoreCase };
+		components.pathname =
+			protocolComponentMatchesSpecialScheme(components.protocol)
+				? compileComponent(
+					processed.pathname,
+					canonicalizePathname,
+					{ ...PATHNAME_OPTIONS, ignoreCase },
+				)
+				: compileComponent(
+					processed.pathname,
+					canonicalizeOpaquePathname,
+					compileOptions,
+				);
+		components.search = compileComponent(
+			processed.search,
+			canonicalizeSearch,
+			compileOptions,
+		);
+		components.hash = compileComponent(
+			processed.hash,
+			canonicalizeHash,
+			compileOptions,
+		);
+	}
+
+	/**
+	 * @param {string | URLPatternInit} [input]
+	 * @param {string} [baseURL]
+	 * @returns {boolean}
+	 */
+	test(input = {}, baseURL = undefined) {
+		assertURLPattern(this);
+		return match(this, input, baseURL) !== null;
+	}
+
+	/**
+	 * Returns groups matched by each component, or `null` if the input doesn't match
+	 * @param {string | URLPatternInit} [input]
+	 * @param {string} [baseURL]
+	 * @returns {URLPatternResult | null}
+	 */
+	exec(input = {}, baseURL = undefined) {
+		assertURLPattern(this);
+		return match(this, input, baseURL);
+	}
+
+	/** @returns {string} */
+	get protocol() {
+		assertURLPattern(this);
+		return this[_components].protocol.patternString;
+	}
+
+	/** @returns {string} */
+	get username() {
+		assertURLPattern(this);
+		return this[_components].username.patternString;
+	}
+
+	/** @returns {string} */
+	get password() {
+		assertURLPattern(this);
+		return this[_components].password.patternString;
+	}
+
+	/** @returns {string} */
+	get hostname() {
+		assertURLPattern(this);
+		return this[_components].hostname.patternString;
+	}
+
+	/** @returns {string} */
+	get port() {
+		assertURLPattern(this);
+		return this[_components].port.patternString;
+	}
+
+	/** @returns {string} */
+	get pathname() {
+		assertURLPattern(this);
+		return this[_components].pathname.patternString;
+	}
+
+	/** @returns {string} */
+	get search() {
+		assertURLPattern(this);
+		return this[_components].search.patternString;
+	}
+
+	/** @returns {string} */
+	get hash() {
+		assertURLPattern(this);
+		return this[_components].hash.patternString;
+	}
+
+	/**
+	 * Whether any component uses custom regular expression groups
+	 * @returns {boolean}
+	 */
+	get hasRegExpGroups() {
+		assertURLPattern(this);
+		return COMPONENTS.some((component) =>
+			this[_components][component].hasRegExpGroups
+		);
+	}
+
+	get [Symbol.toStringTag]() {
+		return "URLPattern";
+	}
+}
+
+/**
+ * @param {any} pattern
+ */
+function assertURLPattern(pattern) {
+	if (!(pattern instanceof URLPattern)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {URLPattern} pattern
+ * @param {string | URLPatternInit} input
+ * @param {string | undefined} baseURL
+ * @returns {URLPatternResult | null}
+ */
+function match(pattern, input, baseURL) {
+	/** @type {Record<string, string>} */
+	let values;
+	/** @type {(string | URLPatternInit)[]} */
+	const inputs = [input];
+
+	if (typeof input === "string") {
+		if (baseURL !== undefined) {
+			baseURL = String(baseURL);
+			inputs.push(baseURL);
+		}
+		const url = op_url_parse(input, baseURL);
+		if (url === null) return null;
+
+		values = {
+			protocol: url.protocol.slice(0, -1),
+			username: url.username,
+			password: url.password,
+			hostname: url.hostname,
+			port: url.port,
+			pathname: url.pathname,
+			search: url.search.slice(1),
+			hash: url.hash.slice(1),
+		};
+	} else {
+		if (baseURL !== undefined) {
+			throw new TypeError(
+				"Base URL can only be given along with a URL string",
+			);
+		}
+		const init = toURLPatternInit(input);
+		try {
+			values = processURLPatternInit(init, "url", "");
+		} catch {
+			// Inputs which can't be canonicalized don't match
+			return null;
+		}
+	}
+
+	/** @type {URLPatternResult} */
+	const result = { inputs };
+	for (const name of COMPONENTS) {
+		const component = pattern[_components][name];
+		const execResult = component.regularExpression.exec(values[name]);
+		if (execResult === null) return null;
+
+		/** @type {Record<string, string | undefined>} */
+		const groups = {};
+		component.groupNameList.forEach((groupName, index) => {
+			groups[groupName] = execResult[index + 1];
+		});
+		result[name] = { input: values[name], groups };
+	}
+	return result;
+}
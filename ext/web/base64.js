@@ -0,0 +1,91 @@
+import { DOMException } from "ext:sable/web/exception.js";
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#atob
+
+const alphabet =
+	"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/** Maps char codes to their 6-bit values, characters outside of the alphabet map to -1 */
+const lookup = new Int8Array(128).fill(-1);
+for (let i = 0; i < alphabet.length; i++) {
+	lookup[alphabet.charCodeAt(i)] = i;
+}
+
+/**
+ * @param {string} message
+ * @returns {DOMException}
+ */
+function invalidCharacterError(message) {
+	return new DOMException(message, "InvalidCharacterError");
+}
+
+/**
+ * Encodes binary string, whose characters are bytes, into base64
+ * @param {string} data
+ * @returns {string}
+ */
+export function btoa(data) {
+	if (arguments.length === 0) {
+		throw new TypeError("btoa requires a string to encode");
+	}
+	data = String(data);
+
+	let output = "";
+	for (let i = 0; i < data.length; i += 3) {
+		const bytes = [];
+		for (let j = i; j < Math.min(i + 3, data.length); j++) {
+			const byte = data.charCodeAt(j);
+			if (byte > 0xff) {
+				throw invalidCharacterError(
+					"String to encode contains characters outside of the Latin1 range",
+				);
+			}
+			bytes.push(byte);
+		}
+
+		const bits = (bytes[0] << 16) | ((bytes[1] ?? 0) << 8) | (bytes[2] ?? 0);
+		output += alphabet[bits >> 18] + alphabet[(bits >> 12) & 0x3f];
+		output += bytes.length > 1 ? alphabet[(bits >> 6) & 0x3f] : "=";
+		output += bytes.length > 2 ? alphabet[bits & 0x3f] : "=";
+	}
+	return output;
+}
+
+/**
+ * Decodes base64 into binary string, whose characters are bytes
+ *
+ * https://infra.spec.whatwg.org/#forgiving-base64-decode
+ * @param {string} data
+ * @returns {string}
+ */
+export function atob(data) {
+	if (arguments.length === 0) {
+		throw new TypeError("atob requires a string to decode");
+	}
+	data = String(data).replace(/[\t\n\f\r ]/g, "");
+	if (data.length % 4 === 0) {
+		data = data.replace(/==?$/, "");
+	}
+	if (data.length % 4 === 1) {
+		throw invalidCharacterError("String to decode is not correctly encoded");
+	}
+
+	let output = "";
+	let bits = 0;
+	let bitCount = 0;
+	for (let i = 0; i < data.length; i++) {
+		const code = data.charCodeAt(i);
+		const value = code < 128 ? lookup[code] : -1;
+		if (value === -1) {
+			throw invalidCharacterError("String to decode is not correctly encoded");
+		}
+
+		bits = (bits << 6) | value;
+		bitCount += 6;
+		if (bitCount >= 8) {
+			bitCount -= 8;
+			output += String.fromCharCode((bits >> bitCount) & 0xff);
+		}
+	}
+	return output;
+}
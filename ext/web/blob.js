@@ -0,0 +1,426 @@
+import {
+	op_blob_create_object_url,
+	op_blob_create_part,
+	op_blob_from_object_url,
+	op_blob_read_part,
+	op_blob_remove_part,
+	op_blob_revoke_object_url,
+} from "ext:core/ops";
+import { core } from "ext:core/mod.js";
+import { TextDecoder, TextEncoder } from "ext:sable/web/encoding.js";
+import { ReadableStream } from "ext:sable/web/streams.js";
+
+// https://w3c.github.io/FileAPI/
+
+/**
+ * @typedef {ArrayBuffer | ArrayBufferView | Blob | string} BlobPart
+ */
+
+/**
+ * @typedef {{
+ *   type?: string;
+ *   endings?: "transparent" | "native";
+ * }} BlobPropertyBag
+ */
+
+/**
+ * @typedef {{
+ *   handle: PartHandle;
+ *   start: number;
+ *   end: number;
+ * }} PartRange
+ *
+ * Range of bytes in a part, slices of a blob share parts with it
+ */
+
+/** Size of chunks which `Blob.stream()` reads at once */
+const STREAM_CHUNK_SIZE = 64 * 1024;
+
+const _parts = Symbol("[[parts]]");
+const _type = Symbol("[[type]]");
+const _size = Symbol("[[size]]");
+
+/** Parts get removed from the blob store once no blob references them */
+const partRegistry = new FinalizationRegistry((id) => op_blob_remove_part(id));
+
+/**
+ * Reference to bytes kept in the blob store
+ */
+class PartHandle {
+	/**
+	 * @param {number} id
+	 * @param {number} size
+	 */
+	constructor(id, size) {
+		this.id = id;
+		this.size = size;
+		partRegistry.register(this, id);
+	}
+}
+
+/**
+ * @param {Uint8Array} bytes
+ * @returns {PartRange}
+ */
+function createPart(bytes) {
+	const handle = new PartHandle(op_blob_create_part(bytes), bytes.byteLength);
+	return { handle, start: 0, end: handle.size };
+}
+
+/**
+ * Lowercases the type, or replaces it with an empty string when it contains characters outside of U+0020-U+007E
+ * @param {any} type
+ * @returns {string}
+ */
+function normalizeType(type) {
+	type = String(type);
+	return /^[\x20-\x7E]*$/.test(type) ? type.toLowerCase() : "";
+}
+
+const encoder = new TextEncoder();
+
+/**
+ * @param {Iterable<BlobPart>} blobParts
+ * @param {"transparent" | "native"} endings
+ * @returns {PartRange[]}
+ */
+function processBlobParts(blobParts, endings) {
+	/** @type {PartRange[]} */
+	const parts = [];
+	/** @type {Uint8Array[]} Consecutive bytes get stored as a single part */
+	let pending = [];
+
+	const flush = () => {
+		if (pending.length === 0) return;
+		const length = pending.reduce((sum, bytes) => sum + bytes.byteLength, 0);
+		if (length > 0) {
+			const bytes = new Uint8Array(length);
+			let offset = 0;
+			for (const chunk of pending) {
+				bytes.set(chunk, offset);
+				offset += chunk.byteLength;
+			}
+			parts.push(createPart(bytes));
+		}
+		pending = [];
+	};
+
+	for (const element of blobParts) {
+		if (element instanceof Blob) {
+			flush();
+			parts.push(...element[_parts]);
+		} else if (element instanceof ArrayBuffer) {
+			pending.push(new Uint8Array(element.slice(0)));
+		} else if (ArrayBuffer.isView(element)) {
+			pending.push(
+				new Uint8Array(element.buffer, element.byteOffset, element.byteLength)
+					.slice(),
+			);
+		} else {
+			let string = String(element).toWellFormed();
+			if (endings === "native") {
+				// Native line ending is "\n" on the supported platforms
+				string = string.replace(/\r\n?/g, "\n");
+			}
+			pending.push(encoder.encode(string));
+		}
+	}
+	flush();
+	return parts;
+}
+
+/**
+ * Returns ranges of parts within `start..end` range of the blob
+ * @param {PartRange[]} parts
+ * @param {number} start
+ * @param {number} end
+ * @returns {PartRange[]}
+ */
+function sliceParts(parts, start, end) {
+	/** @type {PartRange[]} */
+	const sliced = [];
+	let offset = 0;
+	for (const part of parts) {
+		const size = part.end - part.start;
+		const partStart = Math.max(start - offset, 0);
+		const partEnd = Math.min(end - offset, size);
+		if (partStart < partEnd) {
+			sliced.push({
+				handle: part.handle,
+				start: part.start + partStart,
+				end: part.start + partEnd,
+			});
+		}
+		offset += size;
+		if (offset >= end) break;
+	}
+	return sliced;
+}
+
+/**
+ * Reads the whole blob
+ * @param {Blob} blob
+ * @returns {Uint8Array}
+ */
+export function readBlob(blob) {
+	const bytes = new Uint8Array(blob[_size]);
+	let offset = 0;
+	for (const { handle, start, end } of blob[_parts]) {
+		bytes.set(op_blob_read_part(handle.id, start, end), offset);
+		offset += end - start;
+	}
+	return bytes;
+}
+
+export class Blob {
+	/** @type {PartRange[]} */
+	[_parts];
+	/** @type {string} */
+	[_type];
+	/** @type {number} */
+	[_size];
+	/**
+	 * Parts are freed once the blob gets garbage collected, so the serializer mustn't copy it as a plain object.
+	 * Blobs are never passed as host objects, which makes serializing them throw DataCloneError
+	 */
+	[core.hostObjectBrand] = null;
+
+	/**
+	 * @param {Iterable<BlobPart>} [blobParts]
+	 * @param {BlobPropertyBag} [options]
+	 */
+	constructor(blobParts = [], options = {}) {
+		if (typeof blobParts !== "object" || blobParts === null) {
+			throw new TypeError("Blob parts must be a sequence");
+		}
+		if (options === null || typeof options !== "object") {
+			options = {};
+		}
+
+		const endings = options.endings === undefined
+			? "transparent"
+			: String(options.endings);
+		if (endings !== "transparent" && endings !== "native") {
+			throw new TypeError(`Invalid line endings: '${endings}'`);
+		}
+
+		this[_parts] = processBlobParts(blobParts, endings);
+		this[_type] = options.type === undefined ? "" : normalizeType(options.type);
+		this[_size] = this[_parts].reduce(
+			(size, part) => size + part.end - part.start,
+			0,
+		);
+	}
+
+	/** @returns {number} */
+	get size() {
+		assertBlob(this);
+		return this[_size];
+	}
+
+	/** @returns {string} */
+	get type() {
+		assertBlob(this);
+		return this[_type];
+	}
+
+	/**
+	 * Returns blob with bytes in the range, negative indices are counted from the end
+	 * @param {number} [start]
+	 * @param {number} [end]
+	 * @param {string} [contentType]
+	 * @returns {Blob}
+	 */
+	slice(start = undefined, end = undefined, contentType = undefined) {
+		assertBlob(this);
+		const size = this[_size];
+		const relativeStart = relativeIndex(start, 0, size);
+		const relativeEnd = relativeIndex(end, size, size);
+
+		const blob = new Blob();
+		blob[_parts] = sliceParts(this[_parts], relativeStart, relativeEnd);
+		blob[_size] = Math.max(relativeEnd - relativeStart, 0);
+		blob[_type] = contentType === undefined ? "" : normalizeType(contentType);
+		return blob;
+	}
+
+	/**
+	 * Stream which reads the blob in chunks
+	 * @returns {ReadableStream<Uint8Array>}
+	 */
+	stream() {
+		assertBlob(this);
+		const parts = this[_parts];
+		let index = 0;
+		let offset = parts[0]?.start ?? 0;
+
+		return new ReadableStream({
+			type: "bytes",
+			pull(controller) {
+				while (index < parts.length) {
+					const { handle, end } = parts[index];
+					if (offset >= end) {
+						index++;
+						offset = parts[index]?.start ?? 0;
+						continue;
+					}
+
+					const chunkEnd = Math.min(end, offset + STREAM_CHUNK_SIZE);
+					controller.enqueue(op_blob_read_part(handle.id, offset, chunkEnd));
+					offset = chunkEnd;
+					return;
+				}
+				controller.close();
+				controller.byobRequest?.respond(0);
+			},
+		});
+	}
+
+	/** @returns {Promise<string>} */
+	async text() {
+		assertBlob(this);
+		return new TextDecoder().decode(readBlob(this));
+	}
+
+	/** @returns {Promise<ArrayBuffer>} */
+	async arrayBuffer() {
+		assertBlob(this);
+		return readBlob(this).buffer;
+	}
+
+	/** @returns {Promise<Uint8Array>} */
+	async bytes() {
+		assertBlob(this);
+		return readBlob(this);
+	}
+
+	get [Symbol.toStringTag]() {
+		return "Blob";
+	}
+}
+
+/**
+ * @param {any} blob
+ */
+function assertBlob(blob) {
+	if (!(blob instanceof Blob)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {number | undefined} index
+ * @param {number} defaultIndex
+ * @param {number} size
+ * @returns {number}
+ */
+function relativeIndex(index, defaultIndex, size) {
+	if (index === undefined) {
+		return defaultIndex;
+	}
+	index = Math.trunc(Number(index)) || 0;
+	return index < 0 ? Math.max(size + index, 0) : Math.min(index, size);
+}
+
+/**
+ * @typedef {BlobPropertyBag & { lastModified?: number }} FilePropertyBag
+ */
+
+const _name = Symbol("[[name]]");
+const _lastModified = Symbol("[[lastModified]]");
+
+export class File extends Blob {
+	/** @type {string} */
+	[_name];
+	/** @type {number} */
+	[_lastModified];
+
+	/**
+	 * @param {Iterable<BlobPart>} fileBits
+	 * @param {string} fileName
+	 * @param {FilePropertyBag} [options]
+	 */
+	constructor(fileBits, fileName, options = {}) {
+		if (arguments.length < 2) {
+			throw new TypeError("File requires file bits and a file name");
+		}
+		super(fileBits, options);
+
+		this[_name] = String(fileName).toWellFormed();
+		this[_lastModified] = options?.lastModified === undefined
+			? Date.now()
+			: Math.trunc(Number(options.lastModified)) || 0;
+	}
+
+	/** @returns {string} */
+	get name() {
+		assertFile(this);
+		return this[_name];
+	}
+
+	/**
+	 * Milliseconds since the UNIX epoch
+	 * @returns {number}
+	 */
+	get lastModified() {
+		assertFile(this);
+		return this[_lastModified];
+	}
+
+	get [Symbol.toStringTag]() {
+		return "File";
+	}
+}
+
+/**
+ * @param {any} file
+ */
+function assertFile(file) {
+	if (!(file instanceof File)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Creates `blob:` URL for the blob, which keeps its contents alive until it gets revoked
+ * @param {Blob} blob
+ * @returns {string}
+ */
+export function createObjectURL(blob) {
+	if (!(blob instanceof Blob)) {
+		throw new TypeError("Object URLs can only be created for blobs");
+	}
+	return op_blob_create_object_url(
+		blob[_type],
+		blob[_parts].map(({ handle, start, end }) => [handle.id, start, end]),
+	);
+}
+
+/**
+ * @param {string} url
+ */
+export function revokeObjectURL(url) {
+	op_blob_revoke_object_url(String(url));
+}
+
+/**
+ * Returns blob which the `blob:` URL references, `null` when it has been revoked
+ * @param {string} url
+ * @returns {Blob | null}
+ */
+export function blobFromObjectURL(url) {
+	const info = op_blob_from_object_url(url);
+	if (info === null) {
+		return null;
+	}
+
+	const blob = new Blob();
+	blob[_parts] = info.parts.map(([id, size]) => ({
+		handle: new PartHandle(id, size),
+		start: 0,
+		end: size,
+	}));
+	blob[_size] = info.parts.reduce((sum, [, size]) => sum + size, 0);
+	blob[_type] = info.mediaType;
+	return blob;
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bytes::Bytes;
+use deno_core::{
+    error::{type_error, AnyError},
+    op2, OpState,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Contents of blobs, which are kept outside of the JS heap
+///
+/// Clones share the contents, so that object URLs can be used by other runtimes
+#[derive(Clone, Default)]
+pub struct BlobStore(Arc<Mutex<BlobStoreInner>>);
+
+#[derive(Default)]
+struct BlobStoreInner {
+    next_id: u64,
+    /// Parts referenced by `Blob`s, removed once `Blob`s referencing them get garbage collected
+    parts: HashMap<u64, Bytes>,
+    /// Blobs referenced by object URLs, removed once their URL gets revoked
+    object_urls: HashMap<String, ObjectUrlBlob>,
+}
+
+struct ObjectUrlBlob {
+    media_type: String,
+    parts: Vec<Bytes>,
+}
+
+impl BlobStore {
+    fn lock(&self) -> MutexGuard<'_, BlobStoreInner> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl BlobStoreInner {
+    fn insert_part(&mut self, bytes: Bytes) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.parts.insert(id, bytes);
+        id
+    }
+
+    /// Returns `start..end` range of the part, sharing its memory
+    fn part_range(&self, id: u64, start: usize, end: usize) -> Result<Bytes, AnyError> {
+        let part = self
+            .parts
+            .get(&id)
+            .ok_or_else(|| type_error(format!("Blob part {id} doesn't exist")))?;
+        if start > end || end > part.len() {
+            return Err(type_error(format!(
+                "Range {start}..{end} is out of bounds of blob part {id}"
+            )));
+        }
+        Ok(part.slice(start..end))
+    }
+}
+
+/// Stores bytes of a blob part, returns id of the part
+#[op2]
+#[number]
+pub fn op_blob_create_part(state: &OpState, #[anybuffer] data: &[u8]) -> u64 {
+    state
+        .borrow::<BlobStore>()
+        .lock()
+        .insert_part(Bytes::copy_from_slice(data))
+}
+
+/// Reads `start..end` range of the blob part
+#[op2]
+#[buffer]
+pub fn op_blob_read_part(
+    state: &OpState,
+    #[number] id: u64,
+    #[number] start: usize,
+    #[number] end: usize,
+) -> Result<Vec<u8>, AnyError> {
+    let store = state.borrow::<BlobStore>().lock();
+    Ok(store.part_range(id, start, end)?.to_vec())
+}
+
+/// Removes the blob part, called once it's no longer referenced by any `Blob`
+#[op2(fast)]
+pub fn op_blob_remove_part(state: &OpState, #[number] id: u64) {
+    state.borrow::<BlobStore>().lock().parts.remove(&id);
+}
+
+/// Creates `blob:` URL which references given ranges of blob parts, until it gets revoked
+#[op2]
+#[string]
+pub fn op_blob_create_object_url(
+    state: &OpState,
+    #[string] media_type: String,
+    #[serde] parts: Vec<(u64, usize, usize)>,
+) -> Result<String, AnyError> {
+    let mut store = state.borrow::<BlobStore>().lock();
+    let parts = parts
+        .into_iter()
+        .map(|(id, start, end)| store.part_range(id, start, end))
+        .collect::<Result<_, AnyError>>()?;
+
+    let url = format!("blob:null/{}", Uuid::new_v4());
+    store
+        .object_urls
+        .insert(url.clone(), ObjectUrlBlob { media_type, parts });
+    Ok(url)
+}
+
+#[op2(fast)]
+pub fn op_blob_revoke_object_url(state: &OpState, #[string] url: &str) {
+    state.borrow::<BlobStore>().lock().object_urls.remove(url);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectUrlBlobInfo {
+    media_type: String,
+    /// Ids and sizes of parts, which got created for the returned blob
+    parts: Vec<(u64, usize)>,
+}
+
+/// Returns blob which the `blob:` URL references, `null` when it doesn't exist or has been revoked
+#[op2]
+#[serde]
+pub fn op_blob_from_object_url(state: &OpState, #[string] url: &str) -> Option<ObjectUrlBlobInfo> {
+    let mut store = state.borrow::<BlobStore>().lock();
+    let blob = store.object_urls.get(url)?;
+    let media_type = blob.media_type.clone();
+    let parts = blob.parts.clone();
+
+    let parts = parts
+        .into_iter()
+        .map(|bytes| {
+            let size = bytes.len();
+            (store.insert_part(bytes), size)
+        })
+        .collect();
+    Some(ObjectUrlBlobInfo { media_type, parts })
+}
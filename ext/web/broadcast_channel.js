@@ -0,0 +1,109 @@
+import {
+	op_broadcast_recv,
+	op_broadcast_send,
+	op_broadcast_subscribe,
+	op_close,
+} from "ext:core/ops";
+
+import {
+	defineEventHandler,
+	EventTarget,
+	fireTrustedEvent,
+	MessageEvent,
+} from "ext:sable/web/events.js";
+import { DOMException } from "ext:sable/web/exception.js";
+import { deserialize, serialize } from "ext:sable/web/serialization.js";
+
+// https://html.spec.whatwg.org/multipage/web-messaging.html#broadcasting-to-other-browsing-contexts
+
+const _rid = Symbol("[[rid]]");
+const _name = Symbol("[[name]]");
+
+/**
+ * Sends messages to every other channel with the same name, including ones in workers
+ */
+export class BroadcastChannel extends EventTarget {
+	/** @type {number | null} Resource id of the channel, `null` once it has been closed */
+	[_rid] = null;
+	/** @type {string} */
+	[_name];
+
+	/**
+	 * Channel keeps the runtime alive until it gets closed
+	 * @param {string} name
+	 */
+	constructor(name) {
+		super();
+		if (arguments.length === 0) {
+			throw new TypeError("BroadcastChannel requires a name");
+		}
+		this[_name] = String(name);
+		this[_rid] = op_broadcast_subscribe(this[_name]);
+		receiveMessages(this);
+	}
+
+	/** @returns {string} */
+	get name() {
+		assertBroadcastChannel(this);
+		return this[_name];
+	}
+
+	/**
+	 * @param {any} message
+	 */
+	postMessage(message) {
+		assertBroadcastChannel(this);
+		if (arguments.length === 0) {
+			throw new TypeError("BroadcastChannel.postMessage requires a message");
+		}
+		if (this[_rid] === null) {
+			throw new DOMException("BroadcastChannel is closed", "InvalidStateError");
+		}
+		op_broadcast_send(this[_rid], serialize(message).data);
+	}
+
+	/** Stops sending and receiving messages */
+	close() {
+		assertBroadcastChannel(this);
+		if (this[_rid] === null) return;
+		op_close(this[_rid]);
+		this[_rid] = null;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "BroadcastChannel";
+	}
+}
+
+defineEventHandler(BroadcastChannel.prototype, "message");
+defineEventHandler(BroadcastChannel.prototype, "messageerror");
+
+/**
+ * @param {any} channel
+ */
+function assertBroadcastChannel(channel) {
+	if (!(channel instanceof BroadcastChannel)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Dispatches messages sent by other channels until the channel gets closed
+ * @param {BroadcastChannel} channel
+ */
+async function receiveMessages(channel) {
+	while (channel[_rid] !== null) {
+		const data = await op_broadcast_recv(channel[_rid]);
+		if (data === null || channel[_rid] === null) break;
+
+		let event;
+		try {
+			event = new MessageEvent("message", {
+				data: deserialize({ data, arrayBuffers: [] }),
+			});
+		} catch {
+			event = new MessageEvent("messageerror");
+		}
+		fireTrustedEvent(event, channel);
+	}
+}
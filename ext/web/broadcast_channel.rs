@@ -0,0 +1,185 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use deno_core::{
+    error::AnyError, op2, AsyncRefCell, CancelFuture, CancelHandle, JsBuffer, OpState, RcRef,
+    Resource, ResourceId, ToJsBuffer,
+};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Delivers messages to every `BroadcastChannel` with the same name
+///
+/// Clones share the subscribers, so that channels in other runtimes receive the messages.
+/// Every channel has its own unbounded queue, so a channel which doesn't keep up never loses messages
+#[derive(Clone, Default)]
+pub struct BroadcastHub(Arc<Mutex<HashMap<Arc<str>, Vec<Subscriber>>>>);
+
+struct Subscriber {
+    id: Uuid,
+    sender: mpsc::UnboundedSender<Arc<[u8]>>,
+}
+
+impl BroadcastHub {
+    /// Returns id of the subscriber and receiver of messages sent to the name by other subscribers
+    fn subscribe(&self, name: Arc<str>) -> (Uuid, mpsc::UnboundedReceiver<Arc<[u8]>>) {
+        let id = Uuid::new_v4();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.0
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .push(Subscriber { id, sender });
+        (id, receiver)
+    }
+
+    fn unsubscribe(&self, name: &str, id: Uuid) {
+        let mut subscribers = self.0.lock().unwrap();
+        if let Some(channels) = subscribers.get_mut(name) {
+            channels.retain(|subscriber| subscriber.id != id);
+            if channels.is_empty() {
+                subscribers.remove(name);
+            }
+        }
+    }
+
+    /// Queues the message for every subscriber of the name, except for the one which sent it
+    fn send(&self, name: &str, sender: Uuid, data: Arc<[u8]>) {
+        let subscribers = self.0.lock().unwrap();
+        for subscriber in subscribers.get(name).into_iter().flatten() {
+            if subscriber.id != sender {
+                // Receiver is only gone while its channel is being dropped, which unsubscribes it
+                let _ = subscriber.sender.send(data.clone());
+            }
+        }
+    }
+}
+
+pub struct BroadcastChannelResource {
+    id: Uuid,
+    name: Arc<str>,
+    hub: BroadcastHub,
+    receiver: AsyncRefCell<mpsc::UnboundedReceiver<Arc<[u8]>>>,
+    /// Interrupts pending `op_broadcast_recv` when the channel gets closed
+    cancel: CancelHandle,
+}
+
+impl Resource for BroadcastChannelResource {
+    fn name(&self) -> Cow<'_, str> {
+        "broadcastChannel".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+        self.hub.unsubscribe(&self.name, self.id);
+    }
+}
+
+impl Drop for BroadcastChannelResource {
+    fn drop(&mut self) {
+        // Runtime can be dropped without closing its channels
+        self.hub.unsubscribe(&self.name, self.id);
+    }
+}
+
+#[op2(fast)]
+#[smi]
+pub fn op_broadcast_subscribe(state: &mut OpState, #[string] name: String) -> ResourceId {
+    let hub = state.borrow::<BroadcastHub>().clone();
+    let name: Arc<str> = name.into();
+    let (id, receiver) = hub.subscribe(name.clone());
+    state.resource_table.add(BroadcastChannelResource {
+        id,
+        name,
+        hub,
+        receiver: AsyncRefCell::new(receiver),
+        cancel: CancelHandle::new(),
+    })
+}
+
+/// Sends serialized message to other channels with the same name
+#[op2]
+pub fn op_broadcast_send(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+    #[buffer] data: JsBuffer,
+) -> Result<(), AnyError> {
+    let resource = state.resource_table.get::<BroadcastChannelResource>(rid)?;
+    resource
+        .hub
+        .send(&resource.name, resource.id, data.to_vec().into());
+    Ok(())
+}
+
+/// Waits for the next message sent to the channel's name, `null` once the channel gets closed
+#[op2(async)]
+#[serde]
+pub async fn op_broadcast_recv(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<Option<ToJsBuffer>, AnyError> {
+    let resource = state
+        .borrow()
+        .resource_table
+        .get::<BroadcastChannelResource>(rid)?;
+    let mut receiver = RcRef::map(&resource, |resource| &resource.receiver)
+        .borrow_mut()
+        .await;
+    let cancel = RcRef::map(&resource, |resource| &resource.cancel);
+
+    match receiver.recv().or_cancel(cancel).await {
+        Ok(Some(data)) => Ok(Some(data.to_vec().into())),
+        Ok(None) | Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_reach_other_subscribers_of_the_name() {
+        let hub = BroadcastHub::default();
+        let (sender, mut own) = hub.subscribe("updates".into());
+        let (_, mut same_name) = hub.subscribe("updates".into());
+        let (_, mut other_name) = hub.subscribe("other".into());
+
+        hub.send("updates", sender, Arc::from(&b"hello"[..]));
+
+        assert_eq!(&*same_name.try_recv().unwrap(), b"hello");
+        assert!(own.try_recv().is_err());
+        assert!(other_name.try_recv().is_err());
+    }
+
+    #[test]
+    fn slow_subscribers_lose_no_messages() {
+        let hub = BroadcastHub::default();
+        let (sender, _own) = hub.subscribe("updates".into());
+        let (_, mut receiver) = hub.subscribe("updates".into());
+
+        for i in 0..10_000u32 {
+            hub.send("updates", sender, Arc::from(&i.to_le_bytes()[..]));
+        }
+        for i in 0..10_000u32 {
+            assert_eq!(*receiver.try_recv().unwrap(), i.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn unsubscribed_names_get_removed() {
+        let hub = BroadcastHub::default();
+        let (first, _) = hub.subscribe("updates".into());
+        let (second, _) = hub.subscribe("updates".into());
+
+        hub.unsubscribe("updates", first);
+        assert_eq!(hub.0.lock().unwrap()["updates"].len(), 1);
+        hub.unsubscribe("updates", second);
+        assert!(hub.0.lock().unwrap().is_empty());
+    }
+}
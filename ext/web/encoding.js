@@ -1,9 +1,12 @@
 import {
 	op_encode,
+	op_encoding_decode,
 	op_encoding_decode_single,
 	op_encoding_decode_utf8,
+	op_encoding_new_decoder,
 	op_encoding_normalize_label,
 } from "ext:core/ops";
+import { TransformStream } from "ext:sable/web/streams.js";
 
 /**
  * @typedef {Int8Array | Uint8Array | Uint8ClampedArray | Int16Array | Uint16Array | Int32Array | Uint32Array | Float32Array | Float64Array} TypedArray
@@ -14,7 +17,7 @@ import {
  */
 
 /**
- * @typedef {{ stream: boolean}} TextDecodeOptions
+ * @typedef {{ stream?: boolean }} TextDecodeOptions
  */
 
 /**
@@ -30,6 +33,8 @@ export class TextDecoder {
 	#ignoreBOM;
 	/** @type {boolean} */
 	#utf8SinglePass;
+	/** @type {import("ext:core/ops").TextDecoderResource | null} Keeps incomplete sequences while streaming */
+	#decoder = null;
 
 	/**
 	 * @param {string} label
@@ -38,9 +43,9 @@ export class TextDecoder {
 	constructor(label = "utf-8", options = {}) {
 		const encoding = op_encoding_normalize_label(label);
 		this.#encoding = encoding;
-		this.#fatal = options.fatal;
-		this.#ignoreBOM = options.ignoreBOM;
-		this.#utf8SinglePass = encoding === "utf-8" && !options.fatal;
+		this.#fatal = Boolean(options.fatal);
+		this.#ignoreBOM = Boolean(options.ignoreBOM);
+		this.#utf8SinglePass = encoding === "utf-8" && !this.#fatal;
 	}
 
 	/** @returns {string} */
@@ -59,14 +64,12 @@ export class TextDecoder {
 	}
 
 	/**
+	 * Decodes the input, with `stream` incomplete sequence at its end is kept until the next call
 	 * @param {BufferSource} [input]
 	 * @param {TextDecodeOptions} options
 	 */
 	decode(input = new Uint8Array(), options = undefined) {
-		let stream = false;
-		if (options !== undefined) {
-			stream = options.stream;
-		}
+		const stream = Boolean(options?.stream);
 
 		/** @type {ArrayBufferLike} */
 		let buffer = input;
@@ -79,7 +82,7 @@ export class TextDecoder {
 			throw new Error("SharedArrayBuffer is not yet implemented");
 		}
 
-		if (!stream) {
+		if (!stream && this.#decoder === null) {
 			// Fast path for utf8 single pass encoding.
 			if (this.#utf8SinglePass) {
 				return op_encoding_decode_utf8(input, this.#ignoreBOM);
@@ -93,8 +96,17 @@ export class TextDecoder {
 			);
 		}
 
-		// TODO(lino-levan): Implement stream decoding.
-		throw new Error("Unimplemented");
+		this.#decoder ??= op_encoding_new_decoder(
+			this.#encoding,
+			this.#fatal,
+			this.#ignoreBOM,
+		);
+		try {
+			return op_encoding_decode(input, this.#decoder, stream);
+		} finally {
+			// Call without `stream` ends the stream, next one starts a new one
+			if (!stream) this.#decoder = null;
+		}
 	}
 }
 
@@ -114,3 +126,115 @@ export class TextEncoder {
 
 	// TODO(lino-levan): Implement encodeInto
 }
+
+/** U+FFFD replacement character encoded as UTF-8 */
+const REPLACEMENT_CHARACTER = [0xef, 0xbf, 0xbd];
+
+/**
+ * Encodes stream of strings into UTF-8 bytes, surrogate pairs may be split between chunks
+ */
+export class TextEncoderStream {
+	/** @type {string | null} High surrogate at the end of the last chunk */
+	#pendingHighSurrogate = null;
+	/** @type {TransformStream<string, Uint8Array>} */
+	#transform;
+
+	constructor() {
+		this.#transform = new TransformStream({
+			transform: (chunk, controller) => {
+				let text = String(chunk);
+				if (this.#pendingHighSurrogate !== null) {
+					text = this.#pendingHighSurrogate + text;
+					this.#pendingHighSurrogate = null;
+				}
+
+				const lastCode = text.charCodeAt(text.length - 1);
+				if (lastCode >= 0xd800 && lastCode <= 0xdbff) {
+					this.#pendingHighSurrogate = text.slice(-1);
+					text = text.slice(0, -1);
+				}
+
+				if (text !== "") {
+					controller.enqueue(op_encode(text));
+				}
+			},
+			flush: (controller) => {
+				if (this.#pendingHighSurrogate !== null) {
+					controller.enqueue(new Uint8Array(REPLACEMENT_CHARACTER));
+				}
+			},
+		});
+	}
+
+	/** @returns {string} */
+	get encoding() {
+		return "utf-8";
+	}
+
+	/** @returns {ReadableStream<Uint8Array>} */
+	get readable() {
+		return this.#transform.readable;
+	}
+
+	/** @returns {WritableStream<string>} */
+	get writable() {
+		return this.#transform.writable;
+	}
+}
+
+/**
+ * Decodes stream of bytes into strings, multi-byte sequences may be split between chunks
+ */
+export class TextDecoderStream {
+	/** @type {TextDecoder} */
+	#decoder;
+	/** @type {TransformStream<BufferSource, string>} */
+	#transform;
+
+	/**
+	 * @param {string} label
+	 * @param {TextDecoderOptions} options
+	 */
+	constructor(label = "utf-8", options = {}) {
+		this.#decoder = new TextDecoder(label, options);
+		this.#transform = new TransformStream({
+			transform: (chunk, controller) => {
+				const text = this.#decoder.decode(chunk, { stream: true });
+				if (text !== "") {
+					controller.enqueue(text);
+				}
+			},
+			flush: (controller) => {
+				const text = this.#decoder.decode();
+				if (text !== "") {
+					controller.enqueue(text);
+				}
+			},
+		});
+	}
+
+	/** @returns {string} */
+	get encoding() {
+		return this.#decoder.encoding;
+	}
+
+	/** @returns {boolean} */
+	get fatal() {
+		return this.#decoder.fatal;
+	}
+
+	/** @returns {boolean} */
+	get ignoreBOM() {
+		return this.#decoder.ignoreBOM;
+	}
+
+	/** @returns {ReadableStream<string>} */
+	get readable() {
+		return this.#transform.readable;
+	}
+
+	/** @returns {WritableStream<BufferSource>} */
+	get writable() {
+		return this.#transform.writable;
+	}
+}
@@ -0,0 +1,89 @@
+import { core } from "ext:core/mod.js";
+import { op_report_unhandled_rejection } from "ext:core/ops";
+
+import {
+	ErrorEvent,
+	fireTrustedEvent,
+	PromiseRejectionEvent,
+} from "ext:sable/web/events.js";
+
+/** Whether an "error" event is being dispatched right now */
+let reportingError = false;
+
+/**
+ * Dispatches "error" event on the global scope, exception is treated as uncaught unless the event got canceled
+ *
+ * https://html.spec.whatwg.org/multipage/webappapis.html#report-the-exception
+ * @param {any} error
+ */
+export function reportError(error) {
+	// Exception thrown by an "error" event listener would get reported again, endlessly
+	if (reportingError) {
+		core.reportUnhandledException(error);
+		return;
+	}
+
+	let message = String(error);
+	let filename = "";
+	let lineno = 0;
+	let colno = 0;
+	if (error instanceof Error) {
+		message = error.message;
+
+		const frame = core.destructureError(error).frames?.[0];
+		if (frame) {
+			filename = frame.fileName ?? "";
+			lineno = frame.lineNumber ?? 0;
+			colno = frame.columnNumber ?? 0;
+		}
+	}
+
+	const event = new ErrorEvent("error", {
+		cancelable: true,
+		message,
+		filename,
+		lineno,
+		colno,
+		error,
+	});
+
+	reportingError = true;
+	let notCanceled;
+	try {
+		notCanceled = fireTrustedEvent(event, globalThis);
+	} finally {
+		reportingError = false;
+	}
+
+	if (notCanceled) {
+		core.reportUnhandledException(error);
+	}
+}
+
+// Rejections are processed after each event loop tick
+// Returning true marks rejection as handled, otherwise it crashes the runtime
+core.setUnhandledPromiseRejectionHandler((promise, reason) => {
+	const event = new PromiseRejectionEvent("unhandledrejection", {
+		cancelable: true,
+		promise,
+		reason,
+	});
+	const handled = !fireTrustedEvent(event, globalThis);
+	if (!handled) {
+		// Runtime stops with the rejection, which makes sable exit with a dedicated code
+		op_report_unhandled_rejection();
+	}
+	return handled;
+});
+
+// Called when a handler gets attached to a promise, which has already been reported as unhandled
+core.setHandledPromiseRejectionHandler((promise, reason) => {
+	const event = new PromiseRejectionEvent("rejectionhandled", {
+		promise,
+		reason,
+	});
+	fireTrustedEvent(event, globalThis);
+});
+
+// Exceptions thrown by queueMicrotask callbacks
+core.setReportExceptionCallback(reportError);
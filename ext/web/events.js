@@ -1,4 +1,11 @@
-import { defineInterfaceConstants } from "ext:sable/webidl/mod.ts";
+import {
+	defineInterfaceConstants,
+	IDL_TYPE,
+	toInt,
+} from "ext:sable/webidl/mod.ts";
+import { reportError } from "ext:sable/web/errors.js";
+import { DOMException } from "ext:sable/web/exception.js";
+import { setUnrefTimeout } from "ext:sable/timers/mod.js";
 
 /**
  * @typedef {{
@@ -228,6 +235,146 @@ export class CustomEvent extends Event {
 	}
 }
 
+/**
+ * @typedef {EventInit & {
+ *   message?: string;
+ *   filename?: string;
+ *   lineno?: number;
+ *   colno?: number;
+ *   error?: any;
+ * }} ErrorEventInit
+ */
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#the-errorevent-interface
+export class ErrorEvent extends Event {
+	#message;
+	#filename;
+	#lineno;
+	#colno;
+	#error;
+
+	/**
+	 * @param {string} type
+	 * @param {ErrorEventInit} [eventInitDict]
+	 */
+	constructor(type, eventInitDict) {
+		super(type, eventInitDict);
+		this.#message = String(eventInitDict?.message ?? "");
+		this.#filename = String(eventInitDict?.filename ?? "");
+		this.#lineno = Number(eventInitDict?.lineno ?? 0);
+		this.#colno = Number(eventInitDict?.colno ?? 0);
+		this.#error = eventInitDict?.error;
+	}
+
+	get message() {
+		return this.#message;
+	}
+
+	get filename() {
+		return this.#filename;
+	}
+
+	get lineno() {
+		return this.#lineno;
+	}
+
+	get colno() {
+		return this.#colno;
+	}
+
+	get error() {
+		return this.#error;
+	}
+}
+
+/**
+ * @typedef {EventInit & {
+ *   data?: any;
+ *   origin?: string;
+ *   lastEventId?: string;
+ *   source?: object | null;
+ *   ports?: object[];
+ * }} MessageEventInit
+ */
+
+// https://html.spec.whatwg.org/multipage/comms.html#the-messageevent-interface
+export class MessageEvent extends Event {
+	#data;
+	#origin;
+	#lastEventId;
+	#source;
+	#ports;
+
+	/**
+	 * @param {string} type
+	 * @param {MessageEventInit} [eventInitDict]
+	 */
+	constructor(type, eventInitDict) {
+		super(type, eventInitDict);
+		this.#data = eventInitDict?.data ?? null;
+		this.#origin = String(eventInitDict?.origin ?? "");
+		this.#lastEventId = String(eventInitDict?.lastEventId ?? "");
+		this.#source = eventInitDict?.source ?? null;
+		this.#ports = Object.freeze([...(eventInitDict?.ports ?? [])]);
+	}
+
+	get data() {
+		return this.#data;
+	}
+
+	get origin() {
+		return this.#origin;
+	}
+
+	get lastEventId() {
+		return this.#lastEventId;
+	}
+
+	get source() {
+		return this.#source;
+	}
+
+	get ports() {
+		return this.#ports;
+	}
+}
+
+/**
+ * @typedef {EventInit & {
+ *   promise: Promise<any>;
+ *   reason?: any;
+ * }} PromiseRejectionEventInit
+ */
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#the-promiserejectionevent-interface
+export class PromiseRejectionEvent extends Event {
+	#promise;
+	#reason;
+
+	/**
+	 * @param {string} type
+	 * @param {PromiseRejectionEventInit} eventInitDict
+	 */
+	constructor(type, eventInitDict) {
+		super(type, eventInitDict);
+		if (!(eventInitDict?.promise instanceof Promise)) {
+			throw new TypeError(
+				"PromiseRejectionEvent requires 'promise' to be a Promise",
+			);
+		}
+		this.#promise = eventInitDict.promise;
+		this.#reason = eventInitDict.reason;
+	}
+
+	get promise() {
+		return this.#promise;
+	}
+
+	get reason() {
+		return this.#reason;
+	}
+}
+
 /**
  * @typedef { ((event: Event) => void) | { handleEvent(event: Event): void } } EventListener
  */
@@ -316,6 +463,7 @@ export class EventTarget {
 	 * @param {AddEventListenerOptions | boolean} [options]
 	 */
 	addEventListener(type, callback, options) {
+		const target = this ?? globalThis;
 		const listener = {
 			type,
 			callback: callback ?? null,
@@ -328,7 +476,7 @@ export class EventTarget {
 		}
 
 		// Do we have an equivalent event listener? If so, return.
-		for (const l of this[_listeners]) {
+		for (const l of target[_listeners]) {
 			if (
 				l.type === listener.type && l.callback === listener.callback &&
 				l.capture === listener.capture
@@ -337,15 +485,15 @@ export class EventTarget {
 			}
 		}
 
-		this[_listeners].push(listener);
+		target[_listeners].push(listener);
 
 		if (listener.signal !== null) {
 			listener.signal[_addAbortSteps](() => {
 				listener.removed = true;
-				if (this[_dispatchCount] === 0) {
-					const index = this[_listeners].indexOf(listener);
+				if (target[_dispatchCount] === 0) {
+					const index = target[_listeners].indexOf(listener);
 					if (index >= 0) {
-						this[_listeners].splice(index, 1);
+						target[_listeners].splice(index, 1);
 					}
 				}
 			});
@@ -358,16 +506,17 @@ export class EventTarget {
 	 * @param {EventListenerOptions | boolean} [options]
 	 */
 	removeEventListener(type, callback, options) {
+		const target = this ?? globalThis;
 		type = String(type);
 		const capture = flattenCapture(options);
-		const listenerIndex = this[_listeners].findIndex((listener) => {
+		const listenerIndex = target[_listeners].findIndex((listener) => {
 			return listener.type === type && listener.callback === callback &&
 				listener.capture === capture;
 		});
 		if (listenerIndex >= 0) {
-			this[_listeners][listenerIndex].removed = true;
-			if (this[_dispatchCount] === 0) {
-				this[_listeners].splice(listenerIndex, 1);
+			target[_listeners][listenerIndex].removed = true;
+			if (target[_dispatchCount] === 0) {
+				target[_listeners].splice(listenerIndex, 1);
 			}
 		}
 	}
@@ -377,14 +526,15 @@ export class EventTarget {
 	 * @returns {boolean}
 	 */
 	dispatchEvent(event) {
+		const target = this ?? globalThis;
 		if (event[_dispatchFlag]) {
-			// TODO: We don't yet have DOMException
-			throw new Error(
-				"InvalidStateError: Trying to dispatch an event while it's already being dispatched",
+			throw new DOMException(
+				"Trying to dispatch an event while it's already being dispatched",
+				"InvalidStateError",
 			);
 		}
 		event[_isTrusted] = false;
-		return innerDispatch(event, this);
+		return innerDispatch(event, target);
 	}
 }
 
@@ -467,8 +617,7 @@ function invoke(event, target, capture) {
 				listener.callback.handleEvent(event);
 			}
 		} catch (err) {
-			// TODO: "Report the exception" (window.reportError).
-			console.error(err);
+			reportError(err);
 		}
 
 		event[_inPassiveListener] = false;
@@ -492,19 +641,377 @@ export function fireTrustedEvent(event, target) {
 	return innerDispatch(event, target);
 }
 
+/**
+ * Fires lifecycle event like "load" on the global scope, the runtime calls it through `op_set_global_event_callback`
+ * @param {string} type
+ * @param {boolean} cancelable
+ * @returns {boolean} false when a listener canceled the event
+ */
+export function fireGlobalEvent(type, cancelable) {
+	return fireTrustedEvent(new Event(type, { cancelable }), globalThis);
+}
+
+// Global scope is an EventTarget, so that "error", "load" and other global events can be listened to
+// Methods of EventTarget fall back to it when they get called without receiver, like `addEventListener(...)`
+Object.setPrototypeOf(globalThis, EventTarget.prototype);
+globalThis[_listeners] = [];
+globalThis[_dispatchCount] = 0;
+
+const _eventHandlers = Symbol("[[eventHandlers]]");
+
+/**
+ * Defines `on{type}` event handler attribute on prototype of the `EventTarget` subclass
+ *
+ * https://html.spec.whatwg.org/multipage/webappapis.html#event-handler-attributes
+ * @param {object} prototype
+ * @param {string} type
+ */
+export function defineEventHandler(prototype, type) {
+	Object.defineProperty(prototype, `on${type}`, {
+		get() {
+			if (!(this instanceof EventTarget)) {
+				throw new TypeError("Illegal invocation");
+			}
+			return this[_eventHandlers]?.get(type)?.handler ?? null;
+		},
+		set(value) {
+			if (!(this instanceof EventTarget)) {
+				throw new TypeError("Illegal invocation");
+			}
+			this[_eventHandlers] ??= new Map();
+			const current = this[_eventHandlers].get(type);
+
+			if (typeof value !== "object" && typeof value !== "function") {
+				value = null;
+			}
+			if (value === null) {
+				if (current !== undefined) {
+					this.removeEventListener(type, current.listener);
+					this[_eventHandlers].delete(type);
+				}
+				return;
+			}
+			if (current !== undefined) {
+				// Listener keeps its position, only the handler it calls changes
+				current.handler = value;
+				return;
+			}
+
+			const entry = {
+				handler: value,
+				/** @param {Event} event */
+				listener: (event) => {
+					if (typeof entry.handler !== "function") return;
+					if (entry.handler.call(this, event) === false) {
+						event.preventDefault();
+					}
+				},
+			};
+			this[_eventHandlers].set(type, entry);
+			this.addEventListener(type, entry.listener);
+		},
+		configurable: true,
+		enumerable: true,
+	});
+}
+
+// https://dom.spec.whatwg.org/#interface-abortcontroller
+
+const _aborted = Symbol("[[aborted]]");
+const _reason = Symbol("[[reason]]");
+const _abortAlgorithms = Symbol("[[abortAlgorithms]]");
+const _dependent = Symbol("[[dependent]]");
+const _sourceSignals = Symbol("[[sourceSignals]]");
+const _dependentSignals = Symbol("[[dependentSignals]]");
 const _addAbortSteps = Symbol("[[addAbortSteps]]");
+const _signal = Symbol("[[signal]]");
+
+/** Allows creating AbortSignal only from within this module */
+const illegalConstructorKey = Symbol("illegalConstructorKey");
+
+/**
+ * Dependent signals which something waits for, their sources reference them only weakly
+ *
+ * https://dom.spec.whatwg.org/#abort-signal-garbage-collection
+ * @type {Set<AbortSignal>}
+ */
+const retainedSignals = new Set();
+
+/**
+ * Keeps the dependent signal alive while it has abort listeners or algorithms and hasn't been aborted
+ * @param {AbortSignal} signal
+ */
+function updateSignalRetention(signal) {
+	// Signal is missing when the methods got called without receiver, only dependent signals need retaining
+	if (!signal?.[_dependent]) return;
+	const listened = signal[_abortAlgorithms].size > 0 ||
+		signal[_listeners].some((listener) =>
+			listener.type === "abort" && !listener.removed
+		);
+	if (listened && !signal[_aborted]) {
+		retainedSignals.add(signal);
+	} else {
+		retainedSignals.delete(signal);
+	}
+}
+
+export class AbortSignal extends EventTarget {
+	/** @type {boolean} */
+	[_aborted] = false;
+	/** @type {any} */
+	[_reason] = undefined;
+	/** @type {Set<() => void>} */
+	[_abortAlgorithms] = new Set();
+	/** @type {boolean} Whether the signal follows other signals, see `AbortSignal.any()` */
+	[_dependent] = false;
+	/** @type {AbortSignal[]} */
+	[_sourceSignals] = [];
+	/**
+	 * Referenced weakly, so that a long-lived signal doesn't keep every signal following it alive
+	 * @type {Set<WeakRef<AbortSignal>>}
+	 */
+	[_dependentSignals] = new Set();
 
-// TODO(Im-Beast): Implement AbortSignal
-class AbortSignal {
+	/**
+	 * @param {symbol} key
+	 */
+	constructor(key = undefined) {
+		if (key !== illegalConstructorKey) {
+			throw new TypeError("Illegal constructor");
+		}
+		super();
+	}
+
+	/**
+	 * Returns signal which is already aborted
+	 * @param {any} [reason] defaults to "AbortError" DOMException
+	 * @returns {AbortSignal}
+	 */
+	static abort(reason = undefined) {
+		const signal = createAbortSignal();
+		signal[_aborted] = true;
+		signal[_reason] = reason === undefined ? abortError() : reason;
+		return signal;
+	}
+
+	/**
+	 * Returns signal which gets aborted with "TimeoutError" DOMException after given time
+	 * @param {number} milliseconds
+	 * @returns {AbortSignal}
+	 */
+	static timeout(milliseconds) {
+		milliseconds = toInt(milliseconds, 64, false, IDL_TYPE.enforceRange);
+
+		const signal = createAbortSignal();
+		// Waiting for the signal alone doesn't keep the runtime alive
+		setUnrefTimeout(() => {
+			signalAbort(
+				signal,
+				new DOMException("Signal timed out", "TimeoutError"),
+			);
+		}, milliseconds);
+		return signal;
+	}
+
+	/**
+	 * Returns signal which gets aborted as soon as any of the signals gets aborted, with the same reason
+	 * @param {Iterable<AbortSignal>} signals
+	 * @returns {AbortSignal}
+	 */
+	static any(signals) {
+		const list = [...signals];
+		for (const signal of list) {
+			if (!(signal instanceof AbortSignal)) {
+				throw new TypeError("Signals must be instances of AbortSignal");
+			}
+		}
+		return createDependentAbortSignal(list);
+	}
+
+	/** @returns {boolean} */
 	get aborted() {
-		// TODO
-		return false;
+		assertAbortSignal(this);
+		return this[_aborted];
+	}
+
+	/** @returns {any} */
+	get reason() {
+		assertAbortSignal(this);
+		return this[_reason];
 	}
 
 	/**
+	 * Throws the abort reason when the signal has been aborted
+	 */
+	throwIfAborted() {
+		assertAbortSignal(this);
+		if (this[_aborted]) {
+			throw this[_reason];
+		}
+	}
+
+	/**
+	 * @param {string} type
+	 * @param {EventListener} [callback]
+	 * @param {AddEventListenerOptions | boolean} [options]
+	 */
+	addEventListener(type, callback, options) {
+		super.addEventListener(type, callback, options);
+		updateSignalRetention(this);
+	}
+
+	/**
+	 * @param {string} type
+	 * @param {EventListener} [callback]
+	 * @param {EventListenerOptions | boolean} [options]
+	 */
+	removeEventListener(type, callback, options) {
+		super.removeEventListener(type, callback, options);
+		updateSignalRetention(this);
+	}
+
+	/**
+	 * Runs the callback once the signal gets aborted, before "abort" event gets dispatched
 	 * @param {() => void} callback
 	 */
 	[_addAbortSteps](callback) {
-		// TODO
+		if (!this[_aborted]) {
+			this[_abortAlgorithms].add(callback);
+			updateSignalRetention(this);
+		}
+	}
+
+	get [Symbol.toStringTag]() {
+		return "AbortSignal";
+	}
+}
+
+defineEventHandler(AbortSignal.prototype, "abort");
+
+/**
+ * @param {any} signal
+ */
+function assertAbortSignal(signal) {
+	if (!(signal instanceof AbortSignal)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @returns {DOMException}
+ */
+function abortError() {
+	return new DOMException("Signal has been aborted", "AbortError");
+}
+
+/**
+ * @returns {AbortSignal}
+ */
+function createAbortSignal() {
+	return new AbortSignal(illegalConstructorKey);
+}
+
+/**
+ * Creates signal which follows the signals, e.g. `Request` follows signal it's been given
+ *
+ * https://dom.spec.whatwg.org/#create-a-dependent-abort-signal
+ * @param {AbortSignal[]} signals
+ * @returns {AbortSignal}
+ */
+export function createDependentAbortSignal(signals) {
+	const result = createAbortSignal();
+	for (const signal of signals) {
+		if (signal[_aborted]) {
+			result[_aborted] = true;
+			result[_reason] = signal[_reason];
+			return result;
+		}
+	}
+
+	result[_dependent] = true;
+	for (const signal of signals) {
+		// Dependent signals are never sources, since they get aborted only through their own sources
+		const sources = signal[_dependent] ? signal[_sourceSignals] : [signal];
+		for (const source of sources) {
+			if (!result[_sourceSignals].includes(source)) {
+				result[_sourceSignals].push(source);
+				source[_dependentSignals].add(new WeakRef(result));
+			}
+		}
+	}
+	return result;
+}
+
+/**
+ * https://dom.spec.whatwg.org/#abortsignal-signal-abort
+ * @param {AbortSignal} signal
+ * @param {any} reason
+ */
+function signalAbort(signal, reason) {
+	if (signal[_aborted]) return;
+	signal[_aborted] = true;
+	signal[_reason] = reason === undefined ? abortError() : reason;
+
+	/** @type {AbortSignal[]} */
+	const dependentSignals = [];
+	for (const ref of signal[_dependentSignals]) {
+		const dependent = ref.deref();
+		if (dependent !== undefined && !dependent[_aborted]) {
+			dependent[_aborted] = true;
+			dependent[_reason] = signal[_reason];
+			dependentSignals.push(dependent);
+		}
+	}
+	signal[_dependentSignals].clear();
+
+	runAbortSteps(signal);
+	for (const dependent of dependentSignals) {
+		runAbortSteps(dependent);
+	}
+}
+
+/**
+ * @param {AbortSignal} signal
+ */
+function runAbortSteps(signal) {
+	const algorithms = signal[_abortAlgorithms];
+	signal[_abortAlgorithms] = new Set();
+	retainedSignals.delete(signal);
+	for (const algorithm of algorithms) {
+		algorithm();
+	}
+	fireTrustedEvent(new Event("abort"), signal);
+}
+
+export class AbortController {
+	/** @type {AbortSignal} */
+	[_signal] = createAbortSignal();
+
+	/** @returns {AbortSignal} */
+	get signal() {
+		assertAbortController(this);
+		return this[_signal];
+	}
+
+	/**
+	 * Aborts the signal, unless it has already been aborted
+	 * @param {any} [reason] defaults to "AbortError" DOMException
+	 */
+	abort(reason = undefined) {
+		assertAbortController(this);
+		signalAbort(this[_signal], reason);
+	}
+
+	get [Symbol.toStringTag]() {
+		return "AbortController";
+	}
+}
+
+/**
+ * @param {any} controller
+ */
+function assertAbortController(controller) {
+	if (!(controller instanceof AbortController)) {
+		throw new TypeError("Illegal invocation");
 	}
 }
@@ -0,0 +1,136 @@
+import { core } from "ext:core/mod.js";
+
+import { defineInterfaceConstants } from "ext:sable/webidl/mod.ts";
+
+// https://webidl.spec.whatwg.org/#idl-DOMException
+
+/** Legacy codes of names which had them before being replaced by names */
+const LEGACY_CODES = {
+	IndexSizeError: 1,
+	HierarchyRequestError: 3,
+	WrongDocumentError: 4,
+	InvalidCharacterError: 5,
+	NoModificationAllowedError: 7,
+	NotFoundError: 8,
+	NotSupportedError: 9,
+	InUseAttributeError: 10,
+	InvalidStateError: 11,
+	SyntaxError: 12,
+	InvalidModificationError: 13,
+	NamespaceError: 14,
+	InvalidAccessError: 15,
+	TypeMismatchError: 17,
+	SecurityError: 18,
+	NetworkError: 19,
+	AbortError: 20,
+	URLMismatchError: 21,
+	QuotaExceededError: 22,
+	TimeoutError: 23,
+	InvalidNodeTypeError: 24,
+	DataCloneError: 25,
+};
+
+const _name = Symbol("[[name]]");
+const _message = Symbol("[[message]]");
+
+export class DOMException {
+	/** @type {string} */
+	[_message];
+	/** @type {string} */
+	[_name];
+
+	/**
+	 * @param {string} [message]
+	 * @param {string} [name]
+	 */
+	constructor(message = "", name = "Error") {
+		this[_message] = String(message);
+		this[_name] = String(name);
+		// Stack is created after the name and the message are set, since its first line contains them
+		Error.captureStackTrace(this, DOMException);
+	}
+
+	/** @returns {string} */
+	get message() {
+		assertDOMException(this);
+		return this[_message];
+	}
+
+	/** @returns {string} */
+	get name() {
+		assertDOMException(this);
+		return this[_name];
+	}
+
+	/**
+	 * Legacy code of the name, 0 for names which don't have one
+	 * @returns {number}
+	 */
+	get code() {
+		assertDOMException(this);
+		return Object.hasOwn(LEGACY_CODES, this[_name])
+			? LEGACY_CODES[this[_name]]
+			: 0;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "DOMException";
+	}
+}
+
+// DOMException isn't an ECMAScript error, but its instances still inherit from Error.prototype
+Object.setPrototypeOf(DOMException.prototype, Error.prototype);
+
+defineInterfaceConstants(DOMException, {
+	INDEX_SIZE_ERR: 1,
+	DOMSTRING_SIZE_ERR: 2,
+	HIERARCHY_REQUEST_ERR: 3,
+	WRONG_DOCUMENT_ERR: 4,
+	INVALID_CHARACTER_ERR: 5,
+	NO_DATA_ALLOWED_ERR: 6,
+	NO_MODIFICATION_ALLOWED_ERR: 7,
+	NOT_FOUND_ERR: 8,
+	NOT_SUPPORTED_ERR: 9,
+	INUSE_ATTRIBUTE_ERR: 10,
+	INVALID_STATE_ERR: 11,
+	SYNTAX_ERR: 12,
+	INVALID_MODIFICATION_ERR: 13,
+	NAMESPACE_ERR: 14,
+	INVALID_ACCESS_ERR: 15,
+	VALIDATION_ERR: 16,
+	TYPE_MISMATCH_ERR: 17,
+	SECURITY_ERR: 18,
+	NETWORK_ERR: 19,
+	ABORT_ERR: 20,
+	URL_MISMATCH_ERR: 21,
+	QUOTA_EXCEEDED_ERR: 22,
+	TIMEOUT_ERR: 23,
+	INVALID_NODE_TYPE_ERR: 24,
+	DATA_CLONE_ERR: 25,
+});
+
+/**
+ * @param {any} exception
+ */
+function assertDOMException(exception) {
+	if (
+		typeof exception !== "object" || exception === null ||
+		!(_name in exception)
+	) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Message of errors created by `dom_exception` in `errors/lib.rs`
+ *
+ * `{name}: {message}`
+ */
+const DOM_EXCEPTION_MESSAGE = /^(\w+): (.*)$/s;
+
+core.registerErrorBuilder("DOMException", (message) => {
+	const match = DOM_EXCEPTION_MESSAGE.exec(message);
+	return match
+		? new DOMException(match[2], match[1])
+		: new DOMException(message);
+});
@@ -1,12 +1,54 @@
+use std::cell::RefCell;
+
 use deno_core::error::range_error;
 use deno_core::error::type_error;
 use deno_core::v8;
 use deno_core::U16String;
-use deno_core::{error::AnyError, op2};
+use deno_core::{error::AnyError, op2, OpState};
 use encoding_rs::CoderResult;
+use encoding_rs::Decoder;
 use encoding_rs::DecoderResult;
 use encoding_rs::Encoding;
 
+mod blob;
+pub use blob::{
+    op_blob_create_object_url, op_blob_create_part, op_blob_from_object_url, op_blob_read_part,
+    op_blob_remove_part, op_blob_revoke_object_url, BlobStore,
+};
+
+mod broadcast_channel;
+pub use broadcast_channel::{
+    op_broadcast_recv, op_broadcast_send, op_broadcast_subscribe, BroadcastHub,
+};
+
+mod message_port;
+pub use message_port::{
+    op_message_port_create_entangled, op_message_port_post_message, op_message_port_recv_message,
+    MessageData, MessagePort, PostedMessage, ReceivedMessage,
+};
+
+/// Put into the state once a promise rejection hasn't been handled by any handler or event listener
+pub struct UnhandledRejection;
+
+/** Marks the runtime as being terminated by an unhandled promise rejection */
+#[op2(fast)]
+pub fn op_report_unhandled_rejection(state: &mut OpState) {
+    state.put(UnhandledRejection);
+}
+
+/// Fires trusted lifecycle event on the global scope, e.g. "load", and returns false when a listener canceled it
+///
+/// Takes the event type and whether the event is cancelable
+pub struct GlobalEventCallback(pub v8::Global<v8::Function>);
+
+#[op2]
+pub fn op_set_global_event_callback(
+    state: &mut OpState,
+    #[global] callback: v8::Global<v8::Function>,
+) {
+    state.put(GlobalEventCallback(callback));
+}
+
 #[op2]
 #[string]
 pub fn op_encoding_normalize_label(#[string] label: String) -> Result<String, AnyError> {
@@ -59,6 +101,58 @@ pub fn op_encoding_decode_single(
         encoding.new_decoder_with_bom_removal()
     };
 
+    decode(&mut decoder, data, fatal, true)
+}
+
+/// Decoder which keeps incomplete sequences between calls, used for streaming decoding
+pub struct TextDecoderResource {
+    decoder: RefCell<Decoder>,
+    fatal: bool,
+}
+
+#[op2]
+#[cppgc]
+pub fn op_encoding_new_decoder(
+    #[string] label: String,
+    fatal: bool,
+    ignore_bom: bool,
+) -> Result<TextDecoderResource, AnyError> {
+    let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+        range_error(format!(
+            "The encoding label provided ('{label}') is invalid."
+        ))
+    })?;
+
+    let decoder = if ignore_bom {
+        encoding.new_decoder_without_bom_handling()
+    } else {
+        encoding.new_decoder_with_bom_removal()
+    };
+
+    Ok(TextDecoderResource {
+        decoder: RefCell::new(decoder),
+        fatal,
+    })
+}
+
+/// Decodes the chunk, incomplete sequence at the end is kept for the next call when streaming
+#[op2]
+#[serde]
+pub fn op_encoding_decode(
+    #[anybuffer] data: &[u8],
+    #[cppgc] resource: &TextDecoderResource,
+    stream: bool,
+) -> Result<U16String, AnyError> {
+    let mut decoder = resource.decoder.borrow_mut();
+    decode(&mut decoder, data, resource.fatal, !stream)
+}
+
+fn decode(
+    decoder: &mut Decoder,
+    data: &[u8],
+    fatal: bool,
+    last: bool,
+) -> Result<U16String, AnyError> {
     let max_buffer_length = decoder
         .max_utf16_buffer_length(data.len())
         .ok_or_else(|| range_error("Value too large to decode."))?;
@@ -67,7 +161,7 @@ pub fn op_encoding_decode_single(
 
     if fatal {
         let (result, _, written) =
-            decoder.decode_to_utf16_without_replacement(data, &mut output, true);
+            decoder.decode_to_utf16_without_replacement(data, &mut output, last);
         match result {
             DecoderResult::InputEmpty => {
                 output.truncate(written);
@@ -77,7 +171,7 @@ pub fn op_encoding_decode_single(
             DecoderResult::Malformed(_, _) => Err(type_error("The encoded data is not valid.")),
         }
     } else {
-        let (result, _, written, _) = decoder.decode_to_utf16(data, &mut output, true);
+        let (result, _, written, _) = decoder.decode_to_utf16(data, &mut output, last);
         match result {
             CoderResult::InputEmpty => {
                 output.truncate(written);
@@ -0,0 +1,266 @@
+import {
+	op_close,
+	op_message_port_create_entangled,
+	op_message_port_post_message,
+	op_message_port_recv_message,
+} from "ext:core/ops";
+import { core } from "ext:core/mod.js";
+
+import {
+	defineEventHandler,
+	EventTarget,
+	fireTrustedEvent,
+	MessageEvent,
+} from "ext:sable/web/events.js";
+import { DOMException } from "ext:sable/web/exception.js";
+import { deserialize, serialize } from "ext:sable/web/serialization.js";
+
+// https://html.spec.whatwg.org/multipage/web-messaging.html#message-channels
+
+/**
+ * @typedef {import("ext:sable/web/serialization.js").SerializedValue & {
+ *   ports: number[];
+ * }} SerializedMessage
+ *
+ * `ports` holds resource ids of the transferred ports, which get moved to the receiving runtime along with the message
+ */
+
+/**
+ * @typedef {Iterable<ArrayBuffer | MessagePort> | {
+ *   transfer?: Iterable<ArrayBuffer | MessagePort>;
+ * }} TransferOrOptions
+ */
+
+const _rid = Symbol("[[rid]]");
+const _started = Symbol("[[started]]");
+
+/** Allows creating MessagePort only from within this module */
+const illegalConstructorKey = Symbol("illegalConstructorKey");
+
+export class MessagePort extends EventTarget {
+	/** @type {number | null} Resource id of the port, `null` once it has been closed or transferred */
+	[_rid] = null;
+	/** @type {boolean} Messages are queued until the port gets started */
+	[_started] = false;
+	/** Makes the serializer write the port as a reference into the list of transferred ports */
+	[core.hostObjectBrand] = null;
+
+	/**
+	 * @param {symbol} key
+	 * @param {number} rid
+	 */
+	constructor(key = undefined, rid = undefined) {
+		if (key !== illegalConstructorKey) {
+			throw new TypeError("Illegal constructor");
+		}
+		super();
+		this[_rid] = rid;
+	}
+
+	/**
+	 * Sends message to the entangled port
+	 * @param {any} message
+	 * @param {TransferOrOptions} [transferOrOptions]
+	 */
+	postMessage(message, transferOrOptions = undefined) {
+		assertMessagePort(this);
+		if (arguments.length === 0) {
+			throw new TypeError("MessagePort.postMessage requires a message");
+		}
+		const serialized = serializeMessage(message, transferOrOptions, this);
+		if (this[_rid] !== null) {
+			op_message_port_post_message(this[_rid], serialized);
+		}
+	}
+
+	/** Starts dispatching received messages, setting `onmessage` does so as well */
+	start() {
+		assertMessagePort(this);
+		if (this[_started] || this[_rid] === null) return;
+		this[_started] = true;
+		receiveMessages(this);
+	}
+
+	/** Disentangles the port, messages which haven't been received get dropped */
+	close() {
+		assertMessagePort(this);
+		if (this[_rid] === null) return;
+		op_close(this[_rid]);
+		this[_rid] = null;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "MessagePort";
+	}
+}
+
+defineEventHandler(MessagePort.prototype, "message");
+defineEventHandler(MessagePort.prototype, "messageerror");
+
+const messageHandler = Object.getOwnPropertyDescriptor(
+	MessagePort.prototype,
+	"onmessage",
+);
+Object.defineProperty(MessagePort.prototype, "onmessage", {
+	...messageHandler,
+	set(value) {
+		messageHandler.set.call(this, value);
+		this.start();
+	},
+});
+
+/**
+ * @param {any} port
+ */
+function assertMessagePort(port) {
+	if (!(port instanceof MessagePort)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {number} rid
+ * @returns {MessagePort}
+ */
+function createMessagePort(rid) {
+	return new MessagePort(illegalConstructorKey, rid);
+}
+
+/**
+ * Dispatches messages received by the port until it gets closed, transferred or its entangled port gets closed
+ * @param {MessagePort} port
+ */
+async function receiveMessages(port) {
+	while (port[_rid] !== null) {
+		const message = await op_message_port_recv_message(port[_rid]);
+		if (message === null) break;
+		dispatchMessage(port, message);
+	}
+}
+
+export class MessageChannel {
+	/** @type {MessagePort} */
+	#port1;
+	/** @type {MessagePort} */
+	#port2;
+
+	constructor() {
+		const [rid1, rid2] = op_message_port_create_entangled();
+		this.#port1 = createMessagePort(rid1);
+		this.#port2 = createMessagePort(rid2);
+	}
+
+	get port1() {
+		return this.#port1;
+	}
+
+	get port2() {
+		return this.#port2;
+	}
+
+	get [Symbol.toStringTag]() {
+		return "MessageChannel";
+	}
+}
+
+/**
+ * @param {string} message
+ * @returns {DOMException}
+ */
+function dataCloneError(message) {
+	return new DOMException(message, "DataCloneError");
+}
+
+/**
+ * Serializes the message, `postMessage` accepts either the transfer list or options containing it
+ *
+ * Transferred ports get detached, they can't be used by the sending runtime anymore
+ * @param {any} message
+ * @param {TransferOrOptions | undefined} transferOrOptions
+ * @param {MessagePort | null} [sourcePort] - port which sends the message, it can't transfer itself
+ * @returns {SerializedMessage}
+ */
+export function serializeMessage(
+	message,
+	transferOrOptions,
+	sourcePort = null,
+) {
+	let transfer = [];
+	if (transferOrOptions !== undefined && transferOrOptions !== null) {
+		if (typeof transferOrOptions[Symbol.iterator] === "function") {
+			transfer = [...transferOrOptions];
+		} else if (typeof transferOrOptions === "object") {
+			transfer = [...(transferOrOptions.transfer ?? [])];
+		} else {
+			throw new TypeError(
+				"Transfer list must be a sequence or an options object",
+			);
+		}
+	}
+
+	/** @type {MessagePort[]} */
+	const ports = [];
+	/** @type {ArrayBuffer[]} */
+	const arrayBuffers = [];
+	for (const object of transfer) {
+		if (!(object instanceof MessagePort)) {
+			arrayBuffers.push(object);
+			continue;
+		}
+		if (object === sourcePort) {
+			throw dataCloneError("MessagePort can't transfer itself");
+		}
+		if (ports.includes(object)) {
+			throw dataCloneError("MessagePort is transferred more than once");
+		}
+		if (object[_rid] === null) {
+			throw dataCloneError("MessagePort is closed or has been transferred");
+		}
+		ports.push(object);
+	}
+
+	const serialized = serialize(message, {
+		transfer: arrayBuffers,
+		hostObjects: ports,
+	});
+	// Ports are detached only once the message has been serialized, so that failures leave them usable
+	const rids = ports.map((port) => {
+		const rid = port[_rid];
+		port[_rid] = null;
+		return rid;
+	});
+	return { ...serialized, ports: rids };
+}
+
+/**
+ * Deserializes message created by `serializeMessage`, transferred ports get recreated in this runtime
+ * @param {SerializedMessage} message
+ * @returns {{ data: any; ports: MessagePort[] }}
+ */
+export function deserializeMessage(message) {
+	const ports = message.ports.map(createMessagePort);
+	try {
+		return { data: deserialize(message, { hostObjects: ports }), ports };
+	} catch (error) {
+		for (const port of ports) {
+			port.close();
+		}
+		throw error;
+	}
+}
+
+/**
+ * Fires "message" event with the deserialized data, or "messageerror" when it can't be deserialized
+ * @param {EventTarget} target
+ * @param {SerializedMessage} message
+ */
+export function dispatchMessage(target, message) {
+	let deserialized;
+	try {
+		deserialized = deserializeMessage(message);
+	} catch {
+		fireTrustedEvent(new MessageEvent("messageerror"), target);
+		return;
+	}
+	fireTrustedEvent(new MessageEvent("message", deserialized), target);
+}
@@ -0,0 +1,169 @@
+use std::{borrow::Cow, cell::RefCell, rc::Rc, sync::Arc};
+
+use deno_core::{
+    error::AnyError, op2, CancelFuture, CancelHandle, JsBuffer, OpState, RcRef, Resource,
+    ResourceId, ToJsBuffer,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+
+/// Message serialized by `serializeMessage` in `web/message_port.js`
+///
+/// Transferred ArrayBuffers wait in the `SharedArrayBufferStore` shared by all runtimes,
+/// transferred ports are resource ids of the sending runtime
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostedMessage {
+    data: JsBuffer,
+    array_buffers: Vec<u32>,
+    ports: Vec<ResourceId>,
+}
+
+/// Message passed to `deserializeMessage` in `web/message_port.js`, ports are resource ids of the receiving runtime
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedMessage {
+    data: ToJsBuffer,
+    array_buffers: Vec<u32>,
+    ports: Vec<ResourceId>,
+}
+
+/// Message on its way to another port or runtime, possibly on another thread
+pub struct MessageData {
+    data: Vec<u8>,
+    array_buffers: Vec<u32>,
+    ports: Vec<MessagePort>,
+}
+
+impl MessageData {
+    /// Takes the transferred ports out of the sending runtime
+    pub fn from_posted(state: &mut OpState, message: PostedMessage) -> Result<Self, AnyError> {
+        let ports = message
+            .ports
+            .into_iter()
+            .map(|rid| {
+                let resource = state.resource_table.take::<MessagePortResource>(rid)?;
+                // Receiving end gets picked up by the new owner once the pending receive lets go of it
+                resource.cancel.cancel();
+                Ok(resource.port.clone())
+            })
+            .collect::<Result<_, AnyError>>()?;
+
+        Ok(Self {
+            data: message.data.to_vec(),
+            array_buffers: message.array_buffers,
+            ports,
+        })
+    }
+
+    /// Adds the transferred ports to the receiving runtime
+    pub fn into_received(self, state: &mut OpState) -> ReceivedMessage {
+        ReceivedMessage {
+            data: self.data.into(),
+            array_buffers: self.array_buffers,
+            ports: self
+                .ports
+                .into_iter()
+                .map(|port| state.resource_table.add(MessagePortResource::new(port)))
+                .collect(),
+        }
+    }
+}
+
+/// One end of a message channel, it can be moved to other runtimes
+#[derive(Clone)]
+pub struct MessagePort {
+    sender: mpsc::UnboundedSender<MessageData>,
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<MessageData>>>,
+}
+
+impl MessagePort {
+    /// Creates two ports, messages sent by one of them are received by the other one
+    pub fn entangled() -> (Self, Self) {
+        let (sender1, receiver1) = mpsc::unbounded_channel();
+        let (sender2, receiver2) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender: sender1,
+                receiver: Arc::new(Mutex::new(receiver2)),
+            },
+            Self {
+                sender: sender2,
+                receiver: Arc::new(Mutex::new(receiver1)),
+            },
+        )
+    }
+}
+
+pub struct MessagePortResource {
+    port: MessagePort,
+    /// Interrupts pending `op_message_port_recv_message` when the port gets closed or transferred
+    cancel: CancelHandle,
+}
+
+impl MessagePortResource {
+    fn new(port: MessagePort) -> Self {
+        Self {
+            port,
+            cancel: CancelHandle::new(),
+        }
+    }
+}
+
+impl Resource for MessagePortResource {
+    fn name(&self) -> Cow<'_, str> {
+        "messagePort".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.cancel.cancel();
+    }
+}
+
+#[op2]
+#[serde]
+pub fn op_message_port_create_entangled(state: &mut OpState) -> (ResourceId, ResourceId) {
+    let (port1, port2) = MessagePort::entangled();
+    (
+        state.resource_table.add(MessagePortResource::new(port1)),
+        state.resource_table.add(MessagePortResource::new(port2)),
+    )
+}
+
+/// Sends message to the entangled port, messages to ports which have been closed get dropped
+#[op2]
+pub fn op_message_port_post_message(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+    #[serde] message: PostedMessage,
+) -> Result<(), AnyError> {
+    let resource = state.resource_table.get::<MessagePortResource>(rid)?;
+    let message = MessageData::from_posted(state, message)?;
+    let _ = resource.port.sender.send(message);
+    Ok(())
+}
+
+/// Waits for the next message, `null` once the entangled port has been closed or this port got closed or transferred
+#[op2(async)]
+#[serde]
+pub async fn op_message_port_recv_message(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<Option<ReceivedMessage>, AnyError> {
+    let resource = state
+        .borrow()
+        .resource_table
+        .get::<MessagePortResource>(rid)?;
+    let cancel = RcRef::map(&resource, |resource| &resource.cancel);
+    let received = async {
+        let mut receiver = resource.port.receiver.lock().await;
+        receiver.recv().await
+    }
+    .or_cancel(cancel)
+    .await;
+
+    let Ok(Some(message)) = received else {
+        return Ok(None);
+    };
+    Ok(Some(message.into_received(&mut state.borrow_mut())))
+}
@@ -1,22 +1,81 @@
-import { TextDecoder, TextEncoder } from "ext:sable/web/encoding.js";
-import { CustomEvent, Event, EventTarget } from "ext:sable/web/events.js";
+import { atob, btoa } from "ext:sable/web/base64.js";
+import { Blob, File } from "ext:sable/web/blob.js";
+import { BroadcastChannel } from "ext:sable/web/broadcast_channel.js";
+import {
+	TextDecoder,
+	TextDecoderStream,
+	TextEncoder,
+	TextEncoderStream,
+} from "ext:sable/web/encoding.js";
+import {
+	AbortController,
+	AbortSignal,
+	CustomEvent,
+	ErrorEvent,
+	Event,
+	EventTarget,
+	MessageEvent,
+	PromiseRejectionEvent,
+} from "ext:sable/web/events.js";
+import { reportError } from "ext:sable/web/errors.js";
+import { DOMException } from "ext:sable/web/exception.js";
+import { MessageChannel, MessagePort } from "ext:sable/web/message_port.js";
+import { structuredClone } from "ext:sable/web/serialization.js";
+import {
+	ByteLengthQueuingStrategy,
+	CountQueuingStrategy,
+	ReadableByteStreamController,
+	ReadableStream,
+	ReadableStreamBYOBReader,
+	ReadableStreamBYOBRequest,
+	ReadableStreamDefaultController,
+	ReadableStreamDefaultReader,
+	TransformStream,
+	TransformStreamDefaultController,
+	WritableStream,
+	WritableStreamDefaultController,
+	WritableStreamDefaultWriter,
+} from "ext:sable/web/streams.js";
 
+globalThis.self = globalThis;
 globalThis.navigator = {};
 globalThis.Event = Event;
 globalThis.CustomEvent = CustomEvent;
+globalThis.ErrorEvent = ErrorEvent;
+globalThis.MessageEvent = MessageEvent;
+globalThis.PromiseRejectionEvent = PromiseRejectionEvent;
 globalThis.EventTarget = EventTarget;
+globalThis.reportError = reportError;
+// queueMicrotask is installed by deno_core, exceptions thrown by its callbacks go through reportError
+globalThis.DOMException = DOMException;
+globalThis.AbortController = AbortController;
+globalThis.AbortSignal = AbortSignal;
+globalThis.structuredClone = structuredClone;
+globalThis.atob = atob;
+globalThis.btoa = btoa;
+
+globalThis.MessageChannel = MessageChannel;
+globalThis.MessagePort = MessagePort;
+globalThis.BroadcastChannel = BroadcastChannel;
+
+globalThis.Blob = Blob;
+globalThis.File = File;
 
 globalThis.TextDecoder = TextDecoder;
 globalThis.TextEncoder = TextEncoder;
+globalThis.TextDecoderStream = TextDecoderStream;
+globalThis.TextEncoderStream = TextEncoderStream;
 
-// TODO(lino-levan): Make globalThis extend EventTarget
-const globalEventTarget = new EventTarget();
-globalThis.addEventListener = globalEventTarget.addEventListener.bind(
-	globalEventTarget,
-);
-globalThis.removeEventListener = globalEventTarget.removeEventListener.bind(
-	globalEventTarget,
-);
-globalThis.dispatchEvent = globalEventTarget.dispatchEvent.bind(
-	globalEventTarget,
-);
+globalThis.ReadableStream = ReadableStream;
+globalThis.ReadableStreamDefaultReader = ReadableStreamDefaultReader;
+globalThis.ReadableStreamBYOBReader = ReadableStreamBYOBReader;
+globalThis.ReadableStreamBYOBRequest = ReadableStreamBYOBRequest;
+globalThis.ReadableStreamDefaultController = ReadableStreamDefaultController;
+globalThis.ReadableByteStreamController = ReadableByteStreamController;
+globalThis.WritableStream = WritableStream;
+globalThis.WritableStreamDefaultWriter = WritableStreamDefaultWriter;
+globalThis.WritableStreamDefaultController = WritableStreamDefaultController;
+globalThis.TransformStream = TransformStream;
+globalThis.TransformStreamDefaultController = TransformStreamDefaultController;
+globalThis.ByteLengthQueuingStrategy = ByteLengthQueuingStrategy;
+globalThis.CountQueuingStrategy = CountQueuingStrategy;
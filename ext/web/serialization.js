@@ -0,0 +1,99 @@
+import { core } from "ext:core/mod.js";
+
+import { DOMException } from "ext:sable/web/exception.js";
+
+// https://html.spec.whatwg.org/multipage/structured-data.html
+//
+// Used by `structuredClone`, message ports, workers and `BroadcastChannel`.
+// Web Storage only keeps strings, so nothing gets persisted through it
+
+/**
+ * @typedef {{
+ *   data: Uint8Array;
+ *   arrayBuffers: number[];
+ * }} SerializedValue
+ *
+ * `arrayBuffers` holds ids of transferred ArrayBuffers, whose contents are kept
+ * in a store shared by all runtimes until the value gets deserialized
+ */
+
+/**
+ * @typedef {{
+ *   transfer?: Iterable<ArrayBuffer>;
+ *   hostObjects?: object[];
+ * }} SerializeOptions
+ *
+ * `hostObjects` are objects branded with `core.hostObjectBrand`, which get serialized as their index in the list
+ */
+
+/**
+ * @param {string} message
+ * @returns {DOMException}
+ */
+function dataCloneError(message) {
+	return new DOMException(message, "DataCloneError");
+}
+
+/**
+ * Serializes the value with V8's ValueSerializer, detaching the transferred ArrayBuffers
+ * @param {any} value
+ * @param {SerializeOptions} [options]
+ * @returns {SerializedValue}
+ */
+export function serialize(value, options = {}) {
+	/** @type {any[]} Gets filled with ids of the ArrayBuffers by the serializer */
+	const arrayBuffers = [];
+	for (const object of options.transfer ?? []) {
+		if (!(object instanceof ArrayBuffer)) {
+			throw dataCloneError("Value is not transferable");
+		}
+		if (arrayBuffers.includes(object)) {
+			throw dataCloneError("ArrayBuffer is transferred more than once");
+		}
+		if (object.detached) {
+			throw dataCloneError("ArrayBuffer is detached");
+		}
+		arrayBuffers.push(object);
+	}
+
+	const data = core.serialize(
+		value,
+		{
+			hostObjects: options.hostObjects,
+			transferredArrayBuffers: arrayBuffers,
+		},
+		(message) => {
+			throw dataCloneError(message);
+		},
+	);
+	return { data, arrayBuffers };
+}
+
+/**
+ * Deserializes value created by `serialize`, transferred ArrayBuffers can be received only once
+ *
+ * Host objects are replaced by objects at the same index in `hostObjects`
+ * @param {SerializedValue} serialized
+ * @param {{ hostObjects?: object[] }} [options]
+ * @returns {any}
+ */
+export function deserialize(serialized, options = {}) {
+	return core.deserialize(serialized.data, {
+		hostObjects: options.hostObjects,
+		transferredArrayBuffers: [...serialized.arrayBuffers],
+	});
+}
+
+/**
+ * Deep copies the value, moving the transferred ArrayBuffers into the copy
+ * @template T
+ * @param {T} value
+ * @param {{ transfer?: Iterable<ArrayBuffer> }} [options]
+ * @returns {T}
+ */
+export function structuredClone(value, options = undefined) {
+	if (arguments.length === 0) {
+		throw new TypeError("structuredClone requires a value to clone");
+	}
+	return deserialize(serialize(value, { transfer: options?.transfer }));
+}
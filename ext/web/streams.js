@@ -0,0 +1,4732 @@
+import { op_close, op_read, op_write } from "ext:core/ops";
+import {
+	brandedCheck,
+	createBranded,
+	IDL_TYPE,
+	illegalConstructor,
+	toInt,
+} from "ext:sable/webidl/mod.ts";
+import { AbortSignal } from "ext:sable/web/events.js";
+
+// https://streams.spec.whatwg.org/
+//
+// Algorithms are written after the spec, `tests/streams.test.js` runs them against
+// a selection of web-platform-tests, `tests/wpt/streams/expected_failures.txt` lists the deviations.
+// Streams can't be transferred through `postMessage()`.
+
+/**
+ * @typedef {{
+ *   highWaterMark?: number;
+ *   size?: (chunk: any) => number;
+ * }} QueuingStrategy
+ */
+
+/**
+ * @template T
+ * @typedef {{
+ *   promise: Promise<T>;
+ *   resolve: (value: T) => void;
+ *   reject: (reason: any) => void;
+ *   state: "pending" | "fulfilled" | "rejected";
+ * }} Deferred
+ */
+
+/**
+ * @typedef {{
+ *   chunkSteps: (chunk: any) => void;
+ *   closeSteps: (chunk?: ArrayBufferView) => void;
+ *   errorSteps: (error: any) => void;
+ * }} ReadRequest
+ */
+
+/**
+ * @typedef {{
+ *   buffer: ArrayBuffer;
+ *   bufferByteLength: number;
+ *   byteOffset: number;
+ *   byteLength: number;
+ *   bytesFilled: number;
+ *   minimumFill: number;
+ *   elementSize: number;
+ *   viewConstructor: new (buffer: ArrayBuffer, byteOffset: number, length: number) => ArrayBufferView;
+ *   readerType: "default" | "byob" | "none";
+ * }} PullIntoDescriptor
+ */
+
+/** Size of chunks read by streams created for resources */
+const DEFAULT_CHUNK_SIZE = 64 * 1024;
+
+const _state = Symbol("[[state]]");
+const _storedError = Symbol("[[storedError]]");
+const _disturbed = Symbol("[[disturbed]]");
+const _reader = Symbol("[[reader]]");
+const _writer = Symbol("[[writer]]");
+const _controller = Symbol("[[controller]]");
+const _stream = Symbol("[[stream]]");
+const _closedPromise = Symbol("[[closedPromise]]");
+const _readyPromise = Symbol("[[readyPromise]]");
+const _readRequests = Symbol("[[readRequests]]");
+const _readIntoRequests = Symbol("[[readIntoRequests]]");
+const _queue = Symbol("[[queue]]");
+const _queueTotalSize = Symbol("[[queueTotalSize]]");
+const _started = Symbol("[[started]]");
+const _closeRequested = Symbol("[[closeRequested]]");
+const _pullAgain = Symbol("[[pullAgain]]");
+const _pulling = Symbol("[[pulling]]");
+const _strategySizeAlgorithm = Symbol("[[strategySizeAlgorithm]]");
+const _strategyHWM = Symbol("[[strategyHWM]]");
+const _pullAlgorithm = Symbol("[[pullAlgorithm]]");
+const _cancelAlgorithm = Symbol("[[cancelAlgorithm]]");
+const _autoAllocateChunkSize = Symbol("[[autoAllocateChunkSize]]");
+const _byobRequest = Symbol("[[byobRequest]]");
+const _pendingPullIntos = Symbol("[[pendingPullIntos]]");
+const _view = Symbol("[[view]]");
+const _backpressure = Symbol("[[backpressure]]");
+const _closeRequest = Symbol("[[closeRequest]]");
+const _inFlightWriteRequest = Symbol("[[inFlightWriteRequest]]");
+const _inFlightCloseRequest = Symbol("[[inFlightCloseRequest]]");
+const _pendingAbortRequest = Symbol("[[pendingAbortRequest]]");
+const _writeRequests = Symbol("[[writeRequests]]");
+const _writeAlgorithm = Symbol("[[writeAlgorithm]]");
+const _closeAlgorithm = Symbol("[[closeAlgorithm]]");
+const _abortAlgorithm = Symbol("[[abortAlgorithm]]");
+const _readable = Symbol("[[readable]]");
+const _writable = Symbol("[[writable]]");
+const _backpressureChangePromise = Symbol("[[backpressureChangePromise]]");
+const _transformAlgorithm = Symbol("[[transformAlgorithm]]");
+const _flushAlgorithm = Symbol("[[flushAlgorithm]]");
+const _finishPromise = Symbol("[[finishPromise]]");
+const _highWaterMark = Symbol("[[highWaterMark]]");
+const _preventCancel = Symbol("[[preventCancel]]");
+const _ongoingPromise = Symbol("[[ongoingPromise]]");
+const _isFinished = Symbol("[[isFinished]]");
+
+const _cancelSteps = Symbol("[[CancelSteps]]");
+const _pullSteps = Symbol("[[PullSteps]]");
+const _releaseSteps = Symbol("[[ReleaseSteps]]");
+const _abortSteps = Symbol("[[AbortSteps]]");
+const _errorSteps = Symbol("[[ErrorSteps]]");
+
+/** Marks the close request in the queue of a writable stream controller */
+const closeSentinel = Symbol("close sentinel");
+
+// Promise helpers
+
+/**
+ * @template T
+ * @returns {Deferred<T>}
+ */
+function createDeferred() {
+	/** @type {Deferred<T>} */
+	const deferred = { state: "pending" };
+	deferred.promise = new Promise((resolve, reject) => {
+		deferred.resolve = (value) => {
+			if (deferred.state !== "pending") return;
+			deferred.state = "fulfilled";
+			resolve(value);
+		};
+		deferred.reject = (reason) => {
+			if (deferred.state !== "pending") return;
+			deferred.state = "rejected";
+			reject(reason);
+		};
+	});
+	return deferred;
+}
+
+/**
+ * @param {any} value
+ * @returns {Deferred<any>}
+ */
+function resolvedDeferred(value) {
+	const deferred = createDeferred();
+	deferred.resolve(value);
+	return deferred;
+}
+
+/**
+ * @param {any} reason
+ * @returns {Deferred<any>}
+ */
+function rejectedDeferred(reason) {
+	const deferred = createDeferred();
+	deferred.reject(reason);
+	setPromiseIsHandled(deferred.promise);
+	return deferred;
+}
+
+/**
+ * Prevents the rejection from being reported as unhandled
+ * @param {Promise<any>} promise
+ */
+function setPromiseIsHandled(promise) {
+	promise.then(undefined, () => {});
+}
+
+/**
+ * @param {Promise<any>} promise
+ * @param {(value: any) => void} [onFulfilled]
+ * @param {(reason: any) => void} [onRejected]
+ */
+function uponPromise(promise, onFulfilled, onRejected) {
+	setPromiseIsHandled(promise.then(onFulfilled, onRejected));
+}
+
+/**
+ * Runs the callback in a microtask
+ * @param {() => void} callback
+ */
+function queueTask(callback) {
+	Promise.resolve().then(callback);
+}
+
+/**
+ * Calls the function and returns a promise of its result, thrown errors reject the promise
+ *
+ * `Reflect.apply` is used, as callbacks can have own `call` and `apply` properties
+ * @param {Function} fn
+ * @param {any} thisArg
+ * @param {...any} args
+ * @returns {Promise<any>}
+ */
+function promiseCall(fn, thisArg, ...args) {
+	try {
+		return Promise.resolve(Reflect.apply(fn, thisArg, args));
+	} catch (error) {
+		return Promise.reject(error);
+	}
+}
+
+/**
+ * Returns the method of a dictionary member, which has to be callable if it's present
+ * @param {object | undefined} dict
+ * @param {string} name
+ * @param {string} context
+ * @returns {Function | undefined}
+ */
+function getMethod(dict, name, context) {
+	const method = dict?.[name];
+	if (method === undefined) return undefined;
+	if (typeof method !== "function") {
+		throw new TypeError(`${context}: ${name} must be a function`);
+	}
+	return method;
+}
+
+// Queue with sizes
+
+/**
+ * @param {object} container
+ * @returns {any}
+ */
+function dequeueValue(container) {
+	const pair = container[_queue].shift();
+	container[_queueTotalSize] -= pair.size;
+	// Rounding errors could make it slightly negative
+	if (container[_queueTotalSize] < 0) container[_queueTotalSize] = 0;
+	return pair.value;
+}
+
+/**
+ * @param {object} container
+ * @param {any} value
+ * @param {number} size
+ */
+function enqueueValueWithSize(container, value, size) {
+	if (!isNonNegativeNumber(size) || size === Infinity) {
+		throw new RangeError("Chunk size must be a finite, non-negative number");
+	}
+	container[_queue].push({ value, size });
+	container[_queueTotalSize] += size;
+}
+
+/**
+ * @param {object} container
+ * @returns {any}
+ */
+function peekQueueValue(container) {
+	return container[_queue][0].value;
+}
+
+/**
+ * @param {object} container
+ */
+function resetQueue(container) {
+	container[_queue] = [];
+	container[_queueTotalSize] = 0;
+}
+
+/**
+ * @param {any} value
+ * @returns {boolean}
+ */
+function isNonNegativeNumber(value) {
+	return typeof value === "number" && !Number.isNaN(value) && value >= 0;
+}
+
+/**
+ * @param {QueuingStrategy | undefined} strategy
+ * @param {number} defaultHWM
+ * @returns {number}
+ */
+function extractHighWaterMark(strategy, defaultHWM) {
+	if (strategy?.highWaterMark === undefined) return defaultHWM;
+	const highWaterMark = Number(strategy.highWaterMark);
+	if (Number.isNaN(highWaterMark) || highWaterMark < 0) {
+		throw new RangeError("highWaterMark must be a non-negative number");
+	}
+	return highWaterMark;
+}
+
+/**
+ * @param {QueuingStrategy | undefined} strategy
+ * @returns {(chunk: any) => number}
+ */
+function extractSizeAlgorithm(strategy) {
+	const size = getMethod(strategy, "size", "QueuingStrategy");
+	if (size === undefined) return () => 1;
+	return (chunk) => size(chunk);
+}
+
+// Array buffers
+
+/**
+ * Moves contents of the buffer to a new one, given buffer gets detached
+ * @param {ArrayBuffer} buffer
+ * @returns {ArrayBuffer}
+ */
+function transferArrayBuffer(buffer) {
+	if (buffer.detached) {
+		throw new TypeError("ArrayBuffer is detached");
+	}
+	return buffer.transfer();
+}
+
+/**
+ * @param {ArrayBufferView} view
+ * @returns {Uint8Array}
+ */
+function cloneAsUint8Array(view) {
+	const buffer = view.buffer.slice(
+		view.byteOffset,
+		view.byteOffset + view.byteLength,
+	);
+	return new Uint8Array(buffer);
+}
+
+// ReadableStream
+
+/**
+ * @param {any} value
+ * @returns {value is ReadableStream}
+ */
+function isReadableStream(value) {
+	return typeof value === "object" && value !== null && _state in value &&
+		_reader in value;
+}
+
+/**
+ * @param {ReadableStream} stream
+ */
+function initializeReadableStream(stream) {
+	stream[_state] = "readable";
+	stream[_reader] = undefined;
+	stream[_storedError] = undefined;
+	stream[_disturbed] = false;
+	stream[_controller] = undefined;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {boolean}
+ */
+function isReadableStreamLocked(stream) {
+	return stream[_reader] !== undefined;
+}
+
+/**
+ * Creates readable stream with a default controller out of given algorithms
+ * @param {() => any} startAlgorithm
+ * @param {() => Promise<void>} pullAlgorithm
+ * @param {(reason: any) => Promise<void>} cancelAlgorithm
+ * @param {number} [highWaterMark=1]
+ * @param {(chunk: any) => number} [sizeAlgorithm]
+ * @returns {ReadableStream}
+ */
+function createReadableStream(
+	startAlgorithm,
+	pullAlgorithm,
+	cancelAlgorithm,
+	highWaterMark = 1,
+	sizeAlgorithm = () => 1,
+) {
+	const stream = Object.create(ReadableStream.prototype);
+	initializeReadableStream(stream);
+	const controller = createBranded(ReadableStreamDefaultController);
+	setUpReadableStreamDefaultController(
+		stream,
+		controller,
+		startAlgorithm,
+		pullAlgorithm,
+		cancelAlgorithm,
+		highWaterMark,
+		sizeAlgorithm,
+	);
+	return stream;
+}
+
+/**
+ * Creates readable byte stream out of given algorithms
+ * @param {() => any} startAlgorithm
+ * @param {() => Promise<void>} pullAlgorithm
+ * @param {(reason: any) => Promise<void>} cancelAlgorithm
+ * @returns {ReadableStream}
+ */
+function createReadableByteStream(
+	startAlgorithm,
+	pullAlgorithm,
+	cancelAlgorithm,
+) {
+	const stream = Object.create(ReadableStream.prototype);
+	initializeReadableStream(stream);
+	const controller = createBranded(ReadableByteStreamController);
+	setUpReadableByteStreamController(
+		stream,
+		controller,
+		startAlgorithm,
+		pullAlgorithm,
+		cancelAlgorithm,
+		0,
+		undefined,
+	);
+	return stream;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {any} reason
+ * @returns {Promise<void>}
+ */
+function readableStreamCancel(stream, reason) {
+	stream[_disturbed] = true;
+	if (stream[_state] === "closed") {
+		return Promise.resolve(undefined);
+	}
+	if (stream[_state] === "errored") {
+		return Promise.reject(stream[_storedError]);
+	}
+
+	readableStreamClose(stream);
+	const reader = stream[_reader];
+	if (reader instanceof ReadableStreamBYOBReader) {
+		const readIntoRequests = reader[_readIntoRequests];
+		reader[_readIntoRequests] = [];
+		for (const readIntoRequest of readIntoRequests) {
+			readIntoRequest.closeSteps(undefined);
+		}
+	}
+
+	const sourceCancelPromise = stream[_controller][_cancelSteps](reason);
+	return sourceCancelPromise.then(() => undefined);
+}
+
+/**
+ * @param {ReadableStream} stream
+ */
+function readableStreamClose(stream) {
+	stream[_state] = "closed";
+	const reader = stream[_reader];
+	if (reader === undefined) return;
+
+	reader[_closedPromise].resolve(undefined);
+	if (reader instanceof ReadableStreamDefaultReader) {
+		const readRequests = reader[_readRequests];
+		reader[_readRequests] = [];
+		for (const readRequest of readRequests) {
+			readRequest.closeSteps();
+		}
+	}
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {any} error
+ */
+function readableStreamError(stream, error) {
+	stream[_state] = "errored";
+	stream[_storedError] = error;
+	const reader = stream[_reader];
+	if (reader === undefined) return;
+
+	reader[_closedPromise].reject(error);
+	setPromiseIsHandled(reader[_closedPromise].promise);
+	if (reader instanceof ReadableStreamDefaultReader) {
+		readableStreamDefaultReaderErrorReadRequests(reader, error);
+	} else {
+		readableStreamBYOBReaderErrorReadIntoRequests(reader, error);
+	}
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {ReadRequest} readRequest
+ */
+function readableStreamAddReadRequest(stream, readRequest) {
+	stream[_reader][_readRequests].push(readRequest);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {ReadRequest} readIntoRequest
+ */
+function readableStreamAddReadIntoRequest(stream, readIntoRequest) {
+	stream[_reader][_readIntoRequests].push(readIntoRequest);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {any} chunk
+ * @param {boolean} done
+ */
+function readableStreamFulfillReadRequest(stream, chunk, done) {
+	const readRequest = stream[_reader][_readRequests].shift();
+	if (done) {
+		readRequest.closeSteps();
+	} else {
+		readRequest.chunkSteps(chunk);
+	}
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {ArrayBufferView} chunk
+ * @param {boolean} done
+ */
+function readableStreamFulfillReadIntoRequest(stream, chunk, done) {
+	const readIntoRequest = stream[_reader][_readIntoRequests].shift();
+	if (done) {
+		readIntoRequest.closeSteps(chunk);
+	} else {
+		readIntoRequest.chunkSteps(chunk);
+	}
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {number}
+ */
+function readableStreamGetNumReadRequests(stream) {
+	return stream[_reader][_readRequests].length;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {number}
+ */
+function readableStreamGetNumReadIntoRequests(stream) {
+	return stream[_reader][_readIntoRequests].length;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {boolean}
+ */
+function readableStreamHasDefaultReader(stream) {
+	return stream[_reader] instanceof ReadableStreamDefaultReader;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {boolean}
+ */
+function readableStreamHasBYOBReader(stream) {
+	return stream[_reader] instanceof ReadableStreamBYOBReader;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {ReadableStreamDefaultReader}
+ */
+function acquireReadableStreamDefaultReader(stream) {
+	const reader = Object.create(ReadableStreamDefaultReader.prototype);
+	setUpReadableStreamDefaultReader(reader, stream);
+	return reader;
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {ReadableStreamBYOBReader}
+ */
+function acquireReadableStreamBYOBReader(stream) {
+	const reader = Object.create(ReadableStreamBYOBReader.prototype);
+	setUpReadableStreamBYOBReader(reader, stream);
+	return reader;
+}
+
+export class ReadableStream {
+	/**
+	 * @param {object} [underlyingSource]
+	 * @param {QueuingStrategy} [strategy]
+	 */
+	constructor(underlyingSource = undefined, strategy = {}) {
+		const context = "Failed to construct 'ReadableStream'";
+		if (underlyingSource === null) underlyingSource = undefined;
+		initializeReadableStream(this);
+
+		const type = underlyingSource?.type;
+		if (type !== undefined && String(type) !== "bytes") {
+			throw new TypeError(`${context}: Invalid type '${type}'`);
+		}
+
+		if (type !== undefined) {
+			if (strategy?.size !== undefined) {
+				throw new RangeError(
+					`${context}: Byte streams can't have a size function`,
+				);
+			}
+			const highWaterMark = extractHighWaterMark(strategy, 0);
+			setUpReadableByteStreamControllerFromUnderlyingSource(
+				this,
+				underlyingSource,
+				highWaterMark,
+			);
+		} else {
+			const sizeAlgorithm = extractSizeAlgorithm(strategy);
+			const highWaterMark = extractHighWaterMark(strategy, 1);
+			setUpReadableStreamDefaultControllerFromUnderlyingSource(
+				this,
+				underlyingSource,
+				highWaterMark,
+				sizeAlgorithm,
+			);
+		}
+	}
+
+	/**
+	 * Creates stream out of an async or sync iterable
+	 * @param {AsyncIterable<any> | Iterable<any>} asyncIterable
+	 * @returns {ReadableStream}
+	 */
+	static from(asyncIterable) {
+		return readableStreamFromIterable(asyncIterable);
+	}
+
+	/** @returns {boolean} */
+	get locked() {
+		assertReadableStream(this);
+		return isReadableStreamLocked(this);
+	}
+
+	/**
+	 * @param {any} [reason]
+	 * @returns {Promise<void>}
+	 */
+	cancel(reason) {
+		if (!isReadableStream(this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (isReadableStreamLocked(this)) {
+			return Promise.reject(
+				new TypeError("Can't cancel a stream which is locked to a reader"),
+			);
+		}
+		return readableStreamCancel(this, reason);
+	}
+
+	/**
+	 * @param {{ mode?: "byob" }} [options]
+	 * @returns {ReadableStreamDefaultReader | ReadableStreamBYOBReader}
+	 */
+	getReader(options = {}) {
+		assertReadableStream(this);
+		const mode = options?.mode;
+		if (mode === undefined) {
+			return acquireReadableStreamDefaultReader(this);
+		}
+		if (String(mode) !== "byob") {
+			throw new TypeError(`Invalid reader mode '${mode}'`);
+		}
+		return acquireReadableStreamBYOBReader(this);
+	}
+
+	/**
+	 * @template T
+	 * @param {{ writable: WritableStream, readable: ReadableStream<T> }} transform
+	 * @param {StreamPipeOptions} [options]
+	 * @returns {ReadableStream<T>}
+	 */
+	pipeThrough(transform, options = {}) {
+		assertReadableStream(this);
+		const { readable, writable } = transform;
+		if (!isReadableStream(readable)) {
+			throw new TypeError("transform.readable must be a ReadableStream");
+		}
+		if (!isWritableStream(writable)) {
+			throw new TypeError("transform.writable must be a WritableStream");
+		}
+		if (isReadableStreamLocked(this)) {
+			throw new TypeError("Can't pipe a stream which is locked to a reader");
+		}
+		if (isWritableStreamLocked(writable)) {
+			throw new TypeError(
+				"Can't pipe to a stream which is locked to a writer",
+			);
+		}
+
+		const { preventClose, preventAbort, preventCancel, signal } =
+			convertPipeOptions(options);
+		const promise = readableStreamPipeTo(
+			this,
+			writable,
+			preventClose,
+			preventAbort,
+			preventCancel,
+			signal,
+		);
+		setPromiseIsHandled(promise);
+		return readable;
+	}
+
+	/**
+	 * @param {WritableStream} destination
+	 * @param {StreamPipeOptions} [options]
+	 * @returns {Promise<void>}
+	 */
+	pipeTo(destination, options = {}) {
+		try {
+			assertReadableStream(this);
+			if (!isWritableStream(destination)) {
+				throw new TypeError("destination must be a WritableStream");
+			}
+			const { preventClose, preventAbort, preventCancel, signal } =
+				convertPipeOptions(options);
+			if (isReadableStreamLocked(this)) {
+				throw new TypeError(
+					"Can't pipe a stream which is locked to a reader",
+				);
+			}
+			if (isWritableStreamLocked(destination)) {
+				throw new TypeError(
+					"Can't pipe to a stream which is locked to a writer",
+				);
+			}
+
+			return readableStreamPipeTo(
+				this,
+				destination,
+				preventClose,
+				preventAbort,
+				preventCancel,
+				signal,
+			);
+		} catch (error) {
+			return Promise.reject(error);
+		}
+	}
+
+	/**
+	 * Splits the stream into two branches, which receive the same chunks
+	 * @returns {[ReadableStream, ReadableStream]}
+	 */
+	tee() {
+		assertReadableStream(this);
+		return readableStreamTee(this, false);
+	}
+
+	/**
+	 * @param {{ preventCancel?: boolean }} [options]
+	 * @returns {AsyncIterableIterator<any>}
+	 */
+	values(options = {}) {
+		assertReadableStream(this);
+		const reader = acquireReadableStreamDefaultReader(this);
+		const iterator = Object.create(readableStreamAsyncIteratorPrototype);
+		iterator[_reader] = reader;
+		iterator[_preventCancel] = Boolean(options?.preventCancel);
+		iterator[_ongoingPromise] = undefined;
+		iterator[_isFinished] = false;
+		return iterator;
+	}
+
+	[Symbol.asyncIterator](options) {
+		return this.values(options);
+	}
+}
+
+/**
+ * @param {any} stream
+ */
+function assertReadableStream(stream) {
+	if (!isReadableStream(stream)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @typedef {{
+ *   preventClose?: boolean;
+ *   preventAbort?: boolean;
+ *   preventCancel?: boolean;
+ *   signal?: AbortSignal;
+ * }} StreamPipeOptions
+ */
+
+/**
+ * @param {StreamPipeOptions} options
+ * @returns {Required<Omit<StreamPipeOptions, "signal">> & { signal: AbortSignal | undefined }}
+ */
+function convertPipeOptions(options) {
+	const signal = options?.signal;
+	if (signal !== undefined && !(signal instanceof AbortSignal)) {
+		throw new TypeError("signal must be an AbortSignal");
+	}
+	return {
+		preventClose: Boolean(options?.preventClose),
+		preventAbort: Boolean(options?.preventAbort),
+		preventCancel: Boolean(options?.preventCancel),
+		signal,
+	};
+}
+
+const asyncIteratorPrototype = Object.getPrototypeOf(
+	Object.getPrototypeOf(async function* () {}).prototype,
+);
+
+const readableStreamAsyncIteratorPrototype = Object.setPrototypeOf({
+	/** @returns {Promise<IteratorResult<any>>} */
+	next() {
+		const nextSteps = () => {
+			if (this[_isFinished]) {
+				return Promise.resolve({ value: undefined, done: true });
+			}
+			return readableStreamAsyncIteratorNext(this).then(
+				(result) => {
+					this[_ongoingPromise] = undefined;
+					if (result.done) this[_isFinished] = true;
+					return result;
+				},
+				(reason) => {
+					this[_ongoingPromise] = undefined;
+					this[_isFinished] = true;
+					throw reason;
+				},
+			);
+		};
+
+		this[_ongoingPromise] = this[_ongoingPromise]
+			? this[_ongoingPromise].then(nextSteps, nextSteps)
+			: nextSteps();
+		return this[_ongoingPromise];
+	},
+
+	/**
+	 * @param {any} value
+	 * @returns {Promise<IteratorResult<any>>}
+	 */
+	return(value) {
+		const returnSteps = () => {
+			if (this[_isFinished]) {
+				return Promise.resolve({ value, done: true });
+			}
+			this[_isFinished] = true;
+			return readableStreamAsyncIteratorReturn(this, value);
+		};
+
+		const ongoingPromise = this[_ongoingPromise];
+		this[_ongoingPromise] = ongoingPromise
+			? ongoingPromise.then(returnSteps, returnSteps)
+			: returnSteps();
+		return this[_ongoingPromise].then(() => ({ value, done: true }));
+	},
+}, asyncIteratorPrototype);
+
+/**
+ * @param {object} iterator
+ * @returns {Promise<IteratorResult<any>>}
+ */
+function readableStreamAsyncIteratorNext(iterator) {
+	const reader = iterator[_reader];
+	if (reader[_stream] === undefined) {
+		return Promise.reject(
+			new TypeError("Can't iterate a stream after its reader was released"),
+		);
+	}
+
+	const deferred = createDeferred();
+	readableStreamDefaultReaderRead(reader, {
+		chunkSteps: (chunk) => deferred.resolve({ value: chunk, done: false }),
+		closeSteps: () => {
+			readableStreamDefaultReaderRelease(reader);
+			deferred.resolve({ value: undefined, done: true });
+		},
+		errorSteps: (error) => {
+			readableStreamDefaultReaderRelease(reader);
+			deferred.reject(error);
+		},
+	});
+	return deferred.promise;
+}
+
+/**
+ * @param {object} iterator
+ * @param {any} value
+ * @returns {Promise<void>}
+ */
+function readableStreamAsyncIteratorReturn(iterator, value) {
+	const reader = iterator[_reader];
+	if (reader[_stream] === undefined) {
+		return Promise.resolve(undefined);
+	}
+	if (!iterator[_preventCancel]) {
+		const result = readableStreamReaderGenericCancel(reader, value);
+		readableStreamDefaultReaderRelease(reader);
+		return result;
+	}
+	readableStreamDefaultReaderRelease(reader);
+	return Promise.resolve(undefined);
+}
+
+/**
+ * Awaits values of the sync iterator, like CreateAsyncFromSyncIterator does
+ *
+ * Unlike `yield*` in an async generator, `return()` reaches the sync iterator even before the first `next()`
+ * @param {Iterator<any>} syncIterator
+ * @returns {AsyncIterator<any>}
+ */
+function asyncIteratorFromSyncIterator(syncIterator) {
+	if (typeof syncIterator !== "object" || syncIterator === null) {
+		throw new TypeError("Iterator must be an object");
+	}
+
+	/**
+	 * @param {IteratorResult<any>} result
+	 * @returns {Promise<IteratorResult<any>>}
+	 */
+	const continuation = (result) => {
+		if (typeof result !== "object" || result === null) {
+			throw new TypeError("Iterator result must be an object");
+		}
+		const done = Boolean(result.done);
+		return Promise.resolve(result.value).then((value) => ({ value, done }));
+	};
+
+	return {
+		next() {
+			return continuation(syncIterator.next());
+		},
+		return(value) {
+			const returnMethod = syncIterator.return;
+			if (returnMethod == null) {
+				return Promise.resolve({ value, done: true });
+			}
+			return continuation(returnMethod.call(syncIterator, value));
+		},
+	};
+}
+
+/**
+ * @param {AsyncIterable<any> | Iterable<any>} asyncIterable
+ * @returns {ReadableStream}
+ */
+function readableStreamFromIterable(asyncIterable) {
+	/** @type {AsyncIterator<any>} */
+	let iterator;
+	const asyncMethod = asyncIterable?.[Symbol.asyncIterator];
+	if (asyncMethod != null) {
+		iterator = asyncMethod.call(asyncIterable);
+	} else {
+		const syncMethod = asyncIterable?.[Symbol.iterator];
+		if (syncMethod == null) {
+			throw new TypeError("ReadableStream.from requires an iterable");
+		}
+		iterator = asyncIteratorFromSyncIterator(syncMethod.call(asyncIterable));
+	}
+	if (typeof iterator !== "object" || iterator === null) {
+		throw new TypeError("Iterator must be an object");
+	}
+
+	/** @type {ReadableStream} */
+	let stream;
+
+	const pullAlgorithm = () => {
+		let nextResult;
+		try {
+			nextResult = iterator.next();
+		} catch (error) {
+			return Promise.reject(error);
+		}
+		return Promise.resolve(nextResult).then((iterResult) => {
+			if (typeof iterResult !== "object" || iterResult === null) {
+				throw new TypeError("Iterator result must be an object");
+			}
+			if (iterResult.done) {
+				readableStreamDefaultControllerClose(stream[_controller]);
+			} else {
+				readableStreamDefaultControllerEnqueue(
+					stream[_controller],
+					iterResult.value,
+				);
+			}
+		});
+	};
+
+	const cancelAlgorithm = (reason) => {
+		let returnResult;
+		try {
+			const returnMethod = iterator.return;
+			if (returnMethod == null) return Promise.resolve(undefined);
+			returnResult = returnMethod.call(iterator, reason);
+		} catch (error) {
+			return Promise.reject(error);
+		}
+		return Promise.resolve(returnResult).then((iterResult) => {
+			if (typeof iterResult !== "object" || iterResult === null) {
+				throw new TypeError("Iterator result must be an object");
+			}
+		});
+	};
+
+	stream = createReadableStream(
+		() => undefined,
+		pullAlgorithm,
+		cancelAlgorithm,
+		0,
+	);
+	return stream;
+}
+
+// Readers
+
+/**
+ * @param {ReadableStreamDefaultReader | ReadableStreamBYOBReader} reader
+ * @param {ReadableStream} stream
+ */
+function readableStreamReaderGenericInitialize(reader, stream) {
+	reader[_stream] = stream;
+	stream[_reader] = reader;
+
+	switch (stream[_state]) {
+		case "readable":
+			reader[_closedPromise] = createDeferred();
+			break;
+		case "closed":
+			reader[_closedPromise] = resolvedDeferred(undefined);
+			break;
+		default:
+			reader[_closedPromise] = rejectedDeferred(stream[_storedError]);
+			break;
+	}
+}
+
+/**
+ * @param {ReadableStreamDefaultReader | ReadableStreamBYOBReader} reader
+ * @param {any} reason
+ * @returns {Promise<void>}
+ */
+function readableStreamReaderGenericCancel(reader, reason) {
+	return readableStreamCancel(reader[_stream], reason);
+}
+
+/**
+ * @param {ReadableStreamDefaultReader | ReadableStreamBYOBReader} reader
+ */
+function readableStreamReaderGenericRelease(reader) {
+	const stream = reader[_stream];
+	const error = new TypeError("Reader has been released");
+	if (stream[_state] === "readable") {
+		reader[_closedPromise].reject(error);
+	} else {
+		reader[_closedPromise] = rejectedDeferred(error);
+	}
+	setPromiseIsHandled(reader[_closedPromise].promise);
+
+	stream[_controller][_releaseSteps]();
+	stream[_reader] = undefined;
+	reader[_stream] = undefined;
+}
+
+/**
+ * @param {ReadableStreamDefaultReader} reader
+ * @param {ReadableStream} stream
+ */
+function setUpReadableStreamDefaultReader(reader, stream) {
+	if (isReadableStreamLocked(stream)) {
+		throw new TypeError("Stream is already locked to a reader");
+	}
+	readableStreamReaderGenericInitialize(reader, stream);
+	reader[_readRequests] = [];
+}
+
+/**
+ * @param {ReadableStreamBYOBReader} reader
+ * @param {ReadableStream} stream
+ */
+function setUpReadableStreamBYOBReader(reader, stream) {
+	if (isReadableStreamLocked(stream)) {
+		throw new TypeError("Stream is already locked to a reader");
+	}
+	if (!(stream[_controller] instanceof ReadableByteStreamController)) {
+		throw new TypeError("BYOB readers can only be used with byte streams");
+	}
+	readableStreamReaderGenericInitialize(reader, stream);
+	reader[_readIntoRequests] = [];
+}
+
+/**
+ * @param {ReadableStreamDefaultReader} reader
+ * @param {ReadRequest} readRequest
+ */
+function readableStreamDefaultReaderRead(reader, readRequest) {
+	const stream = reader[_stream];
+	stream[_disturbed] = true;
+	switch (stream[_state]) {
+		case "closed":
+			readRequest.closeSteps();
+			break;
+		case "errored":
+			readRequest.errorSteps(stream[_storedError]);
+			break;
+		default:
+			stream[_controller][_pullSteps](readRequest);
+			break;
+	}
+}
+
+/**
+ * @param {ReadableStreamDefaultReader} reader
+ */
+function readableStreamDefaultReaderRelease(reader) {
+	readableStreamReaderGenericRelease(reader);
+	readableStreamDefaultReaderErrorReadRequests(
+		reader,
+		new TypeError("Reader has been released"),
+	);
+}
+
+/**
+ * @param {ReadableStreamDefaultReader} reader
+ * @param {any} error
+ */
+function readableStreamDefaultReaderErrorReadRequests(reader, error) {
+	const readRequests = reader[_readRequests];
+	reader[_readRequests] = [];
+	for (const readRequest of readRequests) {
+		readRequest.errorSteps(error);
+	}
+}
+
+/**
+ * @param {ReadableStreamBYOBReader} reader
+ * @param {ArrayBufferView} view
+ * @param {number} min
+ * @param {ReadRequest} readIntoRequest
+ */
+function readableStreamBYOBReaderRead(reader, view, min, readIntoRequest) {
+	const stream = reader[_stream];
+	stream[_disturbed] = true;
+	if (stream[_state] === "errored") {
+		readIntoRequest.errorSteps(stream[_storedError]);
+	} else {
+		readableByteStreamControllerPullInto(
+			stream[_controller],
+			view,
+			min,
+			readIntoRequest,
+		);
+	}
+}
+
+/**
+ * @param {ReadableStreamBYOBReader} reader
+ */
+function readableStreamBYOBReaderRelease(reader) {
+	readableStreamReaderGenericRelease(reader);
+	readableStreamBYOBReaderErrorReadIntoRequests(
+		reader,
+		new TypeError("Reader has been released"),
+	);
+}
+
+/**
+ * @param {ReadableStreamBYOBReader} reader
+ * @param {any} error
+ */
+function readableStreamBYOBReaderErrorReadIntoRequests(reader, error) {
+	const readIntoRequests = reader[_readIntoRequests];
+	reader[_readIntoRequests] = [];
+	for (const readIntoRequest of readIntoRequests) {
+		readIntoRequest.errorSteps(error);
+	}
+}
+
+export class ReadableStreamDefaultReader {
+	/**
+	 * @param {ReadableStream} stream
+	 */
+	constructor(stream) {
+		if (!isReadableStream(stream)) {
+			throw new TypeError(
+				"Failed to construct 'ReadableStreamDefaultReader': stream must be a ReadableStream",
+			);
+		}
+		setUpReadableStreamDefaultReader(this, stream);
+	}
+
+	/** @returns {Promise<void>} */
+	get closed() {
+		if (!(_readRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		return this[_closedPromise].promise;
+	}
+
+	/**
+	 * @param {any} [reason]
+	 * @returns {Promise<void>}
+	 */
+	cancel(reason) {
+		if (!(_readRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Reader has been released"));
+		}
+		return readableStreamReaderGenericCancel(this, reason);
+	}
+
+	/** @returns {Promise<ReadableStreamReadResult<any>>} */
+	read() {
+		if (!(_readRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Reader has been released"));
+		}
+
+		const deferred = createDeferred();
+		readableStreamDefaultReaderRead(this, {
+			chunkSteps: (chunk) => deferred.resolve({ value: chunk, done: false }),
+			closeSteps: () => deferred.resolve({ value: undefined, done: true }),
+			errorSteps: (error) => deferred.reject(error),
+		});
+		return deferred.promise;
+	}
+
+	releaseLock() {
+		if (!(_readRequests in this)) {
+			throw new TypeError("Illegal invocation");
+		}
+		if (this[_stream] === undefined) return;
+		readableStreamDefaultReaderRelease(this);
+	}
+}
+
+export class ReadableStreamBYOBReader {
+	/**
+	 * @param {ReadableStream} stream
+	 */
+	constructor(stream) {
+		if (!isReadableStream(stream)) {
+			throw new TypeError(
+				"Failed to construct 'ReadableStreamBYOBReader': stream must be a ReadableStream",
+			);
+		}
+		setUpReadableStreamBYOBReader(this, stream);
+	}
+
+	/** @returns {Promise<void>} */
+	get closed() {
+		if (!(_readIntoRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		return this[_closedPromise].promise;
+	}
+
+	/**
+	 * @param {any} [reason]
+	 * @returns {Promise<void>}
+	 */
+	cancel(reason) {
+		if (!(_readIntoRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Reader has been released"));
+		}
+		return readableStreamReaderGenericCancel(this, reason);
+	}
+
+	/**
+	 * Reads into the view, which gets transferred and returned filled with at least `min` elements
+	 * @template {ArrayBufferView} T
+	 * @param {T} view
+	 * @param {{ min?: number }} [options]
+	 * @returns {Promise<ReadableStreamReadResult<T>>}
+	 */
+	read(view, options = {}) {
+		if (!(_readIntoRequests in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (!ArrayBuffer.isView(view)) {
+			return Promise.reject(new TypeError("view must be an ArrayBufferView"));
+		}
+		if (view.byteLength === 0) {
+			return Promise.reject(new TypeError("view must not be empty"));
+		}
+		if (view.buffer.byteLength === 0 || view.buffer.detached) {
+			return Promise.reject(
+				new TypeError("view's buffer must not be empty or detached"),
+			);
+		}
+
+		let min;
+		try {
+			min = toInt(options?.min ?? 1, 64, false, IDL_TYPE.enforceRange);
+		} catch (error) {
+			return Promise.reject(error);
+		}
+		if (min === 0) {
+			return Promise.reject(new TypeError("min must be greater than 0"));
+		}
+		const length = view instanceof DataView ? view.byteLength : view.length;
+		if (min > length) {
+			return Promise.reject(
+				new RangeError("min must not be greater than length of the view"),
+			);
+		}
+
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Reader has been released"));
+		}
+
+		const deferred = createDeferred();
+		readableStreamBYOBReaderRead(this, view, min, {
+			chunkSteps: (chunk) => deferred.resolve({ value: chunk, done: false }),
+			closeSteps: (chunk) => deferred.resolve({ value: chunk, done: true }),
+			errorSteps: (error) => deferred.reject(error),
+		});
+		return deferred.promise;
+	}
+
+	releaseLock() {
+		if (!(_readIntoRequests in this)) {
+			throw new TypeError("Illegal invocation");
+		}
+		if (this[_stream] === undefined) return;
+		readableStreamBYOBReaderRelease(this);
+	}
+}
+
+// ReadableStreamDefaultController
+
+export class ReadableStreamDefaultController {
+	constructor() {
+		illegalConstructor();
+	}
+
+	/** @returns {number | null} */
+	get desiredSize() {
+		assertReadableStreamDefaultController(this);
+		return readableStreamDefaultControllerGetDesiredSize(this);
+	}
+
+	close() {
+		assertReadableStreamDefaultController(this);
+		if (!readableStreamDefaultControllerCanCloseOrEnqueue(this)) {
+			throw new TypeError("Stream is already closing or closed");
+		}
+		readableStreamDefaultControllerClose(this);
+	}
+
+	/**
+	 * @param {any} chunk
+	 */
+	enqueue(chunk) {
+		assertReadableStreamDefaultController(this);
+		if (!readableStreamDefaultControllerCanCloseOrEnqueue(this)) {
+			throw new TypeError("Can't enqueue into a closing or closed stream");
+		}
+		readableStreamDefaultControllerEnqueue(this, chunk);
+	}
+
+	/**
+	 * @param {any} error
+	 */
+	error(error) {
+		assertReadableStreamDefaultController(this);
+		readableStreamDefaultControllerError(this, error);
+	}
+
+	/**
+	 * @param {any} reason
+	 * @returns {Promise<void>}
+	 */
+	[_cancelSteps](reason) {
+		resetQueue(this);
+		const result = this[_cancelAlgorithm](reason);
+		readableStreamDefaultControllerClearAlgorithms(this);
+		return result;
+	}
+
+	/**
+	 * @param {ReadRequest} readRequest
+	 */
+	[_pullSteps](readRequest) {
+		const stream = this[_stream];
+		if (this[_queue].length > 0) {
+			const chunk = dequeueValue(this);
+			if (this[_closeRequested] && this[_queue].length === 0) {
+				readableStreamDefaultControllerClearAlgorithms(this);
+				readableStreamClose(stream);
+			} else {
+				readableStreamDefaultControllerCallPullIfNeeded(this);
+			}
+			readRequest.chunkSteps(chunk);
+		} else {
+			readableStreamAddReadRequest(stream, readRequest);
+			readableStreamDefaultControllerCallPullIfNeeded(this);
+		}
+	}
+
+	[_releaseSteps]() {}
+}
+
+/**
+ * @param {any} controller
+ */
+function assertReadableStreamDefaultController(controller) {
+	brandedCheck(controller, ReadableStreamDefaultController);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {object | undefined} underlyingSource
+ * @param {number} highWaterMark
+ * @param {(chunk: any) => number} sizeAlgorithm
+ */
+function setUpReadableStreamDefaultControllerFromUnderlyingSource(
+	stream,
+	underlyingSource,
+	highWaterMark,
+	sizeAlgorithm,
+) {
+	const context = "Failed to construct 'ReadableStream'";
+	const start = getMethod(underlyingSource, "start", context);
+	const pull = getMethod(underlyingSource, "pull", context);
+	const cancel = getMethod(underlyingSource, "cancel", context);
+
+	const controller = createBranded(ReadableStreamDefaultController);
+	setUpReadableStreamDefaultController(
+		stream,
+		controller,
+		() => start && Reflect.apply(start, underlyingSource, [controller]),
+		pull
+			? () => promiseCall(pull, underlyingSource, controller)
+			: () => Promise.resolve(undefined),
+		cancel
+			? (reason) => promiseCall(cancel, underlyingSource, reason)
+			: () => Promise.resolve(undefined),
+		highWaterMark,
+		sizeAlgorithm,
+	);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {ReadableStreamDefaultController} controller
+ * @param {() => any} startAlgorithm
+ * @param {() => Promise<void>} pullAlgorithm
+ * @param {(reason: any) => Promise<void>} cancelAlgorithm
+ * @param {number} highWaterMark
+ * @param {(chunk: any) => number} sizeAlgorithm
+ */
+function setUpReadableStreamDefaultController(
+	stream,
+	controller,
+	startAlgorithm,
+	pullAlgorithm,
+	cancelAlgorithm,
+	highWaterMark,
+	sizeAlgorithm,
+) {
+	controller[_stream] = stream;
+	resetQueue(controller);
+	controller[_started] = false;
+	controller[_closeRequested] = false;
+	controller[_pullAgain] = false;
+	controller[_pulling] = false;
+	controller[_strategySizeAlgorithm] = sizeAlgorithm;
+	controller[_strategyHWM] = highWaterMark;
+	controller[_pullAlgorithm] = pullAlgorithm;
+	controller[_cancelAlgorithm] = cancelAlgorithm;
+	stream[_controller] = controller;
+
+	const startResult = startAlgorithm();
+	uponPromise(
+		Promise.resolve(startResult),
+		() => {
+			controller[_started] = true;
+			readableStreamDefaultControllerCallPullIfNeeded(controller);
+		},
+		(reason) => readableStreamDefaultControllerError(controller, reason),
+	);
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ */
+function readableStreamDefaultControllerCallPullIfNeeded(controller) {
+	if (!readableStreamDefaultControllerShouldCallPull(controller)) return;
+	if (controller[_pulling]) {
+		controller[_pullAgain] = true;
+		return;
+	}
+
+	controller[_pulling] = true;
+	uponPromise(
+		controller[_pullAlgorithm](),
+		() => {
+			controller[_pulling] = false;
+			if (controller[_pullAgain]) {
+				controller[_pullAgain] = false;
+				readableStreamDefaultControllerCallPullIfNeeded(controller);
+			}
+		},
+		(reason) => readableStreamDefaultControllerError(controller, reason),
+	);
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @returns {boolean}
+ */
+function readableStreamDefaultControllerShouldCallPull(controller) {
+	const stream = controller[_stream];
+	if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller)) {
+		return false;
+	}
+	if (!controller[_started]) return false;
+	if (
+		isReadableStreamLocked(stream) &&
+		readableStreamGetNumReadRequests(stream) > 0
+	) {
+		return true;
+	}
+	return readableStreamDefaultControllerGetDesiredSize(controller) > 0;
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ */
+function readableStreamDefaultControllerClearAlgorithms(controller) {
+	controller[_pullAlgorithm] = undefined;
+	controller[_cancelAlgorithm] = undefined;
+	controller[_strategySizeAlgorithm] = undefined;
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ */
+function readableStreamDefaultControllerClose(controller) {
+	if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller)) return;
+	controller[_closeRequested] = true;
+	if (controller[_queue].length === 0) {
+		readableStreamDefaultControllerClearAlgorithms(controller);
+		readableStreamClose(controller[_stream]);
+	}
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @param {any} chunk
+ */
+function readableStreamDefaultControllerEnqueue(controller, chunk) {
+	if (!readableStreamDefaultControllerCanCloseOrEnqueue(controller)) return;
+
+	const stream = controller[_stream];
+	if (
+		isReadableStreamLocked(stream) &&
+		readableStreamGetNumReadRequests(stream) > 0
+	) {
+		readableStreamFulfillReadRequest(stream, chunk, false);
+	} else {
+		try {
+			const chunkSize = controller[_strategySizeAlgorithm](chunk);
+			enqueueValueWithSize(controller, chunk, chunkSize);
+		} catch (error) {
+			readableStreamDefaultControllerError(controller, error);
+			throw error;
+		}
+	}
+	readableStreamDefaultControllerCallPullIfNeeded(controller);
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @param {any} error
+ */
+function readableStreamDefaultControllerError(controller, error) {
+	const stream = controller[_stream];
+	if (stream[_state] !== "readable") return;
+	resetQueue(controller);
+	readableStreamDefaultControllerClearAlgorithms(controller);
+	readableStreamError(stream, error);
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @returns {number | null}
+ */
+function readableStreamDefaultControllerGetDesiredSize(controller) {
+	switch (controller[_stream][_state]) {
+		case "errored":
+			return null;
+		case "closed":
+			return 0;
+		default:
+			return controller[_strategyHWM] - controller[_queueTotalSize];
+	}
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @returns {boolean}
+ */
+function readableStreamDefaultControllerHasBackpressure(controller) {
+	return !readableStreamDefaultControllerShouldCallPull(controller);
+}
+
+/**
+ * @param {ReadableStreamDefaultController} controller
+ * @returns {boolean}
+ */
+function readableStreamDefaultControllerCanCloseOrEnqueue(controller) {
+	return !controller[_closeRequested] &&
+		controller[_stream][_state] === "readable";
+}
+
+// ReadableByteStreamController
+
+export class ReadableByteStreamController {
+	constructor() {
+		illegalConstructor();
+	}
+
+	/** @returns {ReadableStreamBYOBRequest | null} */
+	get byobRequest() {
+		assertReadableByteStreamController(this);
+		return readableByteStreamControllerGetBYOBRequest(this);
+	}
+
+	/** @returns {number | null} */
+	get desiredSize() {
+		assertReadableByteStreamController(this);
+		return readableByteStreamControllerGetDesiredSize(this);
+	}
+
+	close() {
+		assertReadableByteStreamController(this);
+		if (this[_closeRequested]) {
+			throw new TypeError("Stream is already closing");
+		}
+		if (this[_stream][_state] !== "readable") {
+			throw new TypeError("Stream is not readable");
+		}
+		readableByteStreamControllerClose(this);
+	}
+
+	/**
+	 * @param {ArrayBufferView} chunk
+	 */
+	enqueue(chunk) {
+		assertReadableByteStreamController(this);
+		if (!ArrayBuffer.isView(chunk)) {
+			throw new TypeError("chunk must be an ArrayBufferView");
+		}
+		if (chunk.byteLength === 0) {
+			throw new TypeError("chunk must not be empty");
+		}
+		if (chunk.buffer.byteLength === 0) {
+			throw new TypeError("chunk's buffer must not be empty");
+		}
+		if (this[_closeRequested]) {
+			throw new TypeError("Stream is already closing");
+		}
+		if (this[_stream][_state] !== "readable") {
+			throw new TypeError("Stream is not readable");
+		}
+		readableByteStreamControllerEnqueue(this, chunk);
+	}
+
+	/**
+	 * @param {any} error
+	 */
+	error(error) {
+		assertReadableByteStreamController(this);
+		readableByteStreamControllerError(this, error);
+	}
+
+	/**
+	 * @param {any} reason
+	 * @returns {Promise<void>}
+	 */
+	[_cancelSteps](reason) {
+		readableByteStreamControllerClearPendingPullIntos(this);
+		resetQueue(this);
+		const result = this[_cancelAlgorithm](reason);
+		readableByteStreamControllerClearAlgorithms(this);
+		return result;
+	}
+
+	/**
+	 * @param {ReadRequest} readRequest
+	 */
+	[_pullSteps](readRequest) {
+		const stream = this[_stream];
+		if (this[_queueTotalSize] > 0) {
+			readableByteStreamControllerFillReadRequestFromQueue(this, readRequest);
+			return;
+		}
+
+		const autoAllocateChunkSize = this[_autoAllocateChunkSize];
+		if (autoAllocateChunkSize !== undefined) {
+			let buffer;
+			try {
+				buffer = new ArrayBuffer(autoAllocateChunkSize);
+			} catch (error) {
+				readRequest.errorSteps(error);
+				return;
+			}
+			this[_pendingPullIntos].push({
+				buffer,
+				bufferByteLength: autoAllocateChunkSize,
+				byteOffset: 0,
+				byteLength: autoAllocateChunkSize,
+				bytesFilled: 0,
+				minimumFill: 1,
+				elementSize: 1,
+				viewConstructor: Uint8Array,
+				readerType: "default",
+			});
+		}
+
+		readableStreamAddReadRequest(stream, readRequest);
+		readableByteStreamControllerCallPullIfNeeded(this);
+	}
+
+	[_releaseSteps]() {
+		if (this[_pendingPullIntos].length > 0) {
+			const firstPendingPullInto = this[_pendingPullIntos][0];
+			firstPendingPullInto.readerType = "none";
+			this[_pendingPullIntos] = [firstPendingPullInto];
+		}
+	}
+}
+
+/**
+ * @param {any} controller
+ */
+function assertReadableByteStreamController(controller) {
+	brandedCheck(controller, ReadableByteStreamController);
+}
+
+export class ReadableStreamBYOBRequest {
+	constructor() {
+		illegalConstructor();
+	}
+
+	/** @returns {ArrayBufferView | null} */
+	get view() {
+		assertReadableStreamBYOBRequest(this);
+		return this[_view];
+	}
+
+	/**
+	 * Signals that given amount of bytes has been written into the view
+	 * @param {number} bytesWritten
+	 */
+	respond(bytesWritten) {
+		assertReadableStreamBYOBRequest(this);
+		bytesWritten = toInt(bytesWritten, 64, false, IDL_TYPE.enforceRange);
+		if (this[_controller] === undefined) {
+			throw new TypeError("Request has already been responded to");
+		}
+		if (this[_view].buffer.detached) {
+			throw new TypeError("View's buffer has been detached");
+		}
+		readableByteStreamControllerRespond(this[_controller], bytesWritten);
+	}
+
+	/**
+	 * Signals that the bytes have been written into given view, which replaces the request's one
+	 * @param {ArrayBufferView} view
+	 */
+	respondWithNewView(view) {
+		assertReadableStreamBYOBRequest(this);
+		if (!ArrayBuffer.isView(view)) {
+			throw new TypeError("view must be an ArrayBufferView");
+		}
+		if (this[_controller] === undefined) {
+			throw new TypeError("Request has already been responded to");
+		}
+		if (view.buffer.detached) {
+			throw new TypeError("View's buffer has been detached");
+		}
+		readableByteStreamControllerRespondWithNewView(this[_controller], view);
+	}
+}
+
+/**
+ * @param {any} request
+ */
+function assertReadableStreamBYOBRequest(request) {
+	brandedCheck(request, ReadableStreamBYOBRequest);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {object} underlyingSource
+ * @param {number} highWaterMark
+ */
+function setUpReadableByteStreamControllerFromUnderlyingSource(
+	stream,
+	underlyingSource,
+	highWaterMark,
+) {
+	const context = "Failed to construct 'ReadableStream'";
+	const start = getMethod(underlyingSource, "start", context);
+	const pull = getMethod(underlyingSource, "pull", context);
+	const cancel = getMethod(underlyingSource, "cancel", context);
+
+	let autoAllocateChunkSize = underlyingSource.autoAllocateChunkSize;
+	if (autoAllocateChunkSize !== undefined) {
+		autoAllocateChunkSize = toInt(
+			autoAllocateChunkSize,
+			64,
+			false,
+			IDL_TYPE.enforceRange,
+		);
+		if (autoAllocateChunkSize === 0) {
+			throw new TypeError(
+				`${context}: autoAllocateChunkSize must be greater than 0`,
+			);
+		}
+	}
+
+	const controller = createBranded(ReadableByteStreamController);
+	setUpReadableByteStreamController(
+		stream,
+		controller,
+		() => start && Reflect.apply(start, underlyingSource, [controller]),
+		pull
+			? () => promiseCall(pull, underlyingSource, controller)
+			: () => Promise.resolve(undefined),
+		cancel
+			? (reason) => promiseCall(cancel, underlyingSource, reason)
+			: () => Promise.resolve(undefined),
+		highWaterMark,
+		autoAllocateChunkSize,
+	);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {ReadableByteStreamController} controller
+ * @param {() => any} startAlgorithm
+ * @param {() => Promise<void>} pullAlgorithm
+ * @param {(reason: any) => Promise<void>} cancelAlgorithm
+ * @param {number} highWaterMark
+ * @param {number | undefined} autoAllocateChunkSize
+ */
+function setUpReadableByteStreamController(
+	stream,
+	controller,
+	startAlgorithm,
+	pullAlgorithm,
+	cancelAlgorithm,
+	highWaterMark,
+	autoAllocateChunkSize,
+) {
+	controller[_stream] = stream;
+	controller[_pullAgain] = false;
+	controller[_pulling] = false;
+	controller[_byobRequest] = null;
+	resetQueue(controller);
+	controller[_closeRequested] = false;
+	controller[_started] = false;
+	controller[_strategyHWM] = highWaterMark;
+	controller[_pullAlgorithm] = pullAlgorithm;
+	controller[_cancelAlgorithm] = cancelAlgorithm;
+	controller[_autoAllocateChunkSize] = autoAllocateChunkSize;
+	controller[_pendingPullIntos] = [];
+	stream[_controller] = controller;
+
+	const startResult = startAlgorithm();
+	uponPromise(
+		Promise.resolve(startResult),
+		() => {
+			controller[_started] = true;
+			readableByteStreamControllerCallPullIfNeeded(controller);
+		},
+		(reason) => readableByteStreamControllerError(controller, reason),
+	);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerCallPullIfNeeded(controller) {
+	if (!readableByteStreamControllerShouldCallPull(controller)) return;
+	if (controller[_pulling]) {
+		controller[_pullAgain] = true;
+		return;
+	}
+
+	controller[_pulling] = true;
+	uponPromise(
+		controller[_pullAlgorithm](),
+		() => {
+			controller[_pulling] = false;
+			if (controller[_pullAgain]) {
+				controller[_pullAgain] = false;
+				readableByteStreamControllerCallPullIfNeeded(controller);
+			}
+		},
+		(reason) => readableByteStreamControllerError(controller, reason),
+	);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @returns {boolean}
+ */
+function readableByteStreamControllerShouldCallPull(controller) {
+	const stream = controller[_stream];
+	if (stream[_state] !== "readable") return false;
+	if (controller[_closeRequested]) return false;
+	if (!controller[_started]) return false;
+	if (
+		readableStreamHasDefaultReader(stream) &&
+		readableStreamGetNumReadRequests(stream) > 0
+	) {
+		return true;
+	}
+	if (
+		readableStreamHasBYOBReader(stream) &&
+		readableStreamGetNumReadIntoRequests(stream) > 0
+	) {
+		return true;
+	}
+	return readableByteStreamControllerGetDesiredSize(controller) > 0;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerClearAlgorithms(controller) {
+	controller[_pullAlgorithm] = undefined;
+	controller[_cancelAlgorithm] = undefined;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerClearPendingPullIntos(controller) {
+	readableByteStreamControllerInvalidateBYOBRequest(controller);
+	controller[_pendingPullIntos] = [];
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerClose(controller) {
+	const stream = controller[_stream];
+	if (controller[_closeRequested] || stream[_state] !== "readable") return;
+
+	if (controller[_queueTotalSize] > 0) {
+		controller[_closeRequested] = true;
+		return;
+	}
+
+	if (controller[_pendingPullIntos].length > 0) {
+		const firstPendingPullInto = controller[_pendingPullIntos][0];
+		if (
+			firstPendingPullInto.bytesFilled % firstPendingPullInto.elementSize !== 0
+		) {
+			const error = new TypeError(
+				"Stream closed in the middle of an element of the view",
+			);
+			readableByteStreamControllerError(controller, error);
+			throw error;
+		}
+	}
+
+	readableByteStreamControllerClearAlgorithms(controller);
+	readableStreamClose(stream);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ */
+function readableByteStreamControllerCommitPullIntoDescriptor(
+	stream,
+	pullIntoDescriptor,
+) {
+	const done = stream[_state] === "closed";
+	const filledView = readableByteStreamControllerConvertPullIntoDescriptor(
+		pullIntoDescriptor,
+	);
+	if (pullIntoDescriptor.readerType === "default") {
+		readableStreamFulfillReadRequest(stream, filledView, done);
+	} else {
+		readableStreamFulfillReadIntoRequest(stream, filledView, done);
+	}
+}
+
+/**
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ * @returns {ArrayBufferView}
+ */
+function readableByteStreamControllerConvertPullIntoDescriptor(
+	pullIntoDescriptor,
+) {
+	const { bytesFilled, elementSize } = pullIntoDescriptor;
+	const buffer = transferArrayBuffer(pullIntoDescriptor.buffer);
+	return new pullIntoDescriptor.viewConstructor(
+		buffer,
+		pullIntoDescriptor.byteOffset,
+		bytesFilled / elementSize,
+	);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ArrayBufferView} chunk
+ */
+function readableByteStreamControllerEnqueue(controller, chunk) {
+	const stream = controller[_stream];
+	if (controller[_closeRequested] || stream[_state] !== "readable") return;
+
+	const { buffer, byteOffset, byteLength } = chunk;
+	if (buffer.detached) {
+		throw new TypeError("chunk's buffer has been detached");
+	}
+	const transferredBuffer = transferArrayBuffer(buffer);
+
+	if (controller[_pendingPullIntos].length > 0) {
+		const firstPendingPullInto = controller[_pendingPullIntos][0];
+		if (firstPendingPullInto.buffer.detached) {
+			throw new TypeError("BYOB request's buffer has been detached");
+		}
+		readableByteStreamControllerInvalidateBYOBRequest(controller);
+		firstPendingPullInto.buffer = transferArrayBuffer(
+			firstPendingPullInto.buffer,
+		);
+		if (firstPendingPullInto.readerType === "none") {
+			readableByteStreamControllerEnqueueDetachedPullIntoToQueue(
+				controller,
+				firstPendingPullInto,
+			);
+		}
+	}
+
+	if (readableStreamHasDefaultReader(stream)) {
+		readableByteStreamControllerProcessReadRequestsUsingQueue(controller);
+		if (readableStreamGetNumReadRequests(stream) === 0) {
+			readableByteStreamControllerEnqueueChunkToQueue(
+				controller,
+				transferredBuffer,
+				byteOffset,
+				byteLength,
+			);
+		} else {
+			if (controller[_pendingPullIntos].length > 0) {
+				readableByteStreamControllerShiftPendingPullInto(controller);
+			}
+			const transferredView = new Uint8Array(
+				transferredBuffer,
+				byteOffset,
+				byteLength,
+			);
+			readableStreamFulfillReadRequest(stream, transferredView, false);
+		}
+	} else if (readableStreamHasBYOBReader(stream)) {
+		readableByteStreamControllerEnqueueChunkToQueue(
+			controller,
+			transferredBuffer,
+			byteOffset,
+			byteLength,
+		);
+		const filledPullIntos =
+			readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(
+				controller,
+			);
+		for (const filledPullInto of filledPullIntos) {
+			readableByteStreamControllerCommitPullIntoDescriptor(
+				stream,
+				filledPullInto,
+			);
+		}
+	} else {
+		readableByteStreamControllerEnqueueChunkToQueue(
+			controller,
+			transferredBuffer,
+			byteOffset,
+			byteLength,
+		);
+	}
+
+	readableByteStreamControllerCallPullIfNeeded(controller);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ArrayBuffer} buffer
+ * @param {number} byteOffset
+ * @param {number} byteLength
+ */
+function readableByteStreamControllerEnqueueChunkToQueue(
+	controller,
+	buffer,
+	byteOffset,
+	byteLength,
+) {
+	controller[_queue].push({ buffer, byteOffset, byteLength });
+	controller[_queueTotalSize] += byteLength;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ArrayBuffer} buffer
+ * @param {number} byteOffset
+ * @param {number} byteLength
+ */
+function readableByteStreamControllerEnqueueClonedChunkToQueue(
+	controller,
+	buffer,
+	byteOffset,
+	byteLength,
+) {
+	let clone;
+	try {
+		clone = buffer.slice(byteOffset, byteOffset + byteLength);
+	} catch (error) {
+		readableByteStreamControllerError(controller, error);
+		throw error;
+	}
+	readableByteStreamControllerEnqueueChunkToQueue(
+		controller,
+		clone,
+		0,
+		byteLength,
+	);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ */
+function readableByteStreamControllerEnqueueDetachedPullIntoToQueue(
+	controller,
+	pullIntoDescriptor,
+) {
+	if (pullIntoDescriptor.bytesFilled > 0) {
+		readableByteStreamControllerEnqueueClonedChunkToQueue(
+			controller,
+			pullIntoDescriptor.buffer,
+			pullIntoDescriptor.byteOffset,
+			pullIntoDescriptor.bytesFilled,
+		);
+	}
+	readableByteStreamControllerShiftPendingPullInto(controller);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {any} error
+ */
+function readableByteStreamControllerError(controller, error) {
+	const stream = controller[_stream];
+	if (stream[_state] !== "readable") return;
+	readableByteStreamControllerClearPendingPullIntos(controller);
+	resetQueue(controller);
+	readableByteStreamControllerClearAlgorithms(controller);
+	readableStreamError(stream, error);
+}
+
+/**
+ * @param {ReadableByteStreamController} _controller
+ * @param {number} size
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ */
+function readableByteStreamControllerFillHeadPullIntoDescriptor(
+	_controller,
+	size,
+	pullIntoDescriptor,
+) {
+	pullIntoDescriptor.bytesFilled += size;
+}
+
+/**
+ * Copies bytes from the queue into the descriptor, returns whether it has been filled enough
+ * @param {ReadableByteStreamController} controller
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ * @returns {boolean}
+ */
+function readableByteStreamControllerFillPullIntoDescriptorFromQueue(
+	controller,
+	pullIntoDescriptor,
+) {
+	const maxBytesToCopy = Math.min(
+		controller[_queueTotalSize],
+		pullIntoDescriptor.byteLength - pullIntoDescriptor.bytesFilled,
+	);
+	const maxBytesFilled = pullIntoDescriptor.bytesFilled + maxBytesToCopy;
+	let totalBytesToCopyRemaining = maxBytesToCopy;
+	let ready = false;
+
+	const remainderBytes = maxBytesFilled % pullIntoDescriptor.elementSize;
+	const maxAlignedBytes = maxBytesFilled - remainderBytes;
+	if (maxAlignedBytes >= pullIntoDescriptor.minimumFill) {
+		totalBytesToCopyRemaining = maxAlignedBytes -
+			pullIntoDescriptor.bytesFilled;
+		ready = true;
+	}
+
+	const queue = controller[_queue];
+	while (totalBytesToCopyRemaining > 0) {
+		const headOfQueue = queue[0];
+		const bytesToCopy = Math.min(
+			totalBytesToCopyRemaining,
+			headOfQueue.byteLength,
+		);
+		const destStart = pullIntoDescriptor.byteOffset +
+			pullIntoDescriptor.bytesFilled;
+		new Uint8Array(pullIntoDescriptor.buffer, destStart, bytesToCopy).set(
+			new Uint8Array(headOfQueue.buffer, headOfQueue.byteOffset, bytesToCopy),
+		);
+
+		if (headOfQueue.byteLength === bytesToCopy) {
+			queue.shift();
+		} else {
+			headOfQueue.byteOffset += bytesToCopy;
+			headOfQueue.byteLength -= bytesToCopy;
+		}
+		controller[_queueTotalSize] -= bytesToCopy;
+		readableByteStreamControllerFillHeadPullIntoDescriptor(
+			controller,
+			bytesToCopy,
+			pullIntoDescriptor,
+		);
+		totalBytesToCopyRemaining -= bytesToCopy;
+	}
+
+	return ready;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ReadRequest} readRequest
+ */
+function readableByteStreamControllerFillReadRequestFromQueue(
+	controller,
+	readRequest,
+) {
+	const entry = controller[_queue].shift();
+	controller[_queueTotalSize] -= entry.byteLength;
+	readableByteStreamControllerHandleQueueDrain(controller);
+	const view = new Uint8Array(entry.buffer, entry.byteOffset, entry.byteLength);
+	readRequest.chunkSteps(view);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @returns {ReadableStreamBYOBRequest | null}
+ */
+function readableByteStreamControllerGetBYOBRequest(controller) {
+	if (
+		controller[_byobRequest] === null &&
+		controller[_pendingPullIntos].length > 0
+	) {
+		const firstDescriptor = controller[_pendingPullIntos][0];
+		const view = new Uint8Array(
+			firstDescriptor.buffer,
+			firstDescriptor.byteOffset + firstDescriptor.bytesFilled,
+			firstDescriptor.byteLength - firstDescriptor.bytesFilled,
+		);
+		const byobRequest = createBranded(ReadableStreamBYOBRequest);
+		byobRequest[_controller] = controller;
+		byobRequest[_view] = view;
+		controller[_byobRequest] = byobRequest;
+	}
+	return controller[_byobRequest];
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @returns {number | null}
+ */
+function readableByteStreamControllerGetDesiredSize(controller) {
+	switch (controller[_stream][_state]) {
+		case "errored":
+			return null;
+		case "closed":
+			return 0;
+		default:
+			return controller[_strategyHWM] - controller[_queueTotalSize];
+	}
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerHandleQueueDrain(controller) {
+	if (controller[_queueTotalSize] === 0 && controller[_closeRequested]) {
+		readableByteStreamControllerClearAlgorithms(controller);
+		readableStreamClose(controller[_stream]);
+	} else {
+		readableByteStreamControllerCallPullIfNeeded(controller);
+	}
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerInvalidateBYOBRequest(controller) {
+	const byobRequest = controller[_byobRequest];
+	if (byobRequest === null) return;
+	byobRequest[_controller] = undefined;
+	byobRequest[_view] = null;
+	controller[_byobRequest] = null;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @returns {PullIntoDescriptor[]}
+ */
+function readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(
+	controller,
+) {
+	const filledPullIntos = [];
+	while (controller[_pendingPullIntos].length > 0) {
+		if (controller[_queueTotalSize] === 0) break;
+		const pullIntoDescriptor = controller[_pendingPullIntos][0];
+		if (
+			readableByteStreamControllerFillPullIntoDescriptorFromQueue(
+				controller,
+				pullIntoDescriptor,
+			)
+		) {
+			readableByteStreamControllerShiftPendingPullInto(controller);
+			filledPullIntos.push(pullIntoDescriptor);
+		}
+	}
+	return filledPullIntos;
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ */
+function readableByteStreamControllerProcessReadRequestsUsingQueue(
+	controller,
+) {
+	const reader = controller[_stream][_reader];
+	while (reader[_readRequests].length > 0) {
+		if (controller[_queueTotalSize] === 0) return;
+		const readRequest = reader[_readRequests].shift();
+		readableByteStreamControllerFillReadRequestFromQueue(
+			controller,
+			readRequest,
+		);
+	}
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ArrayBufferView} view
+ * @param {number} min
+ * @param {ReadRequest} readIntoRequest
+ */
+function readableByteStreamControllerPullInto(
+	controller,
+	view,
+	min,
+	readIntoRequest,
+) {
+	const stream = controller[_stream];
+	let elementSize = 1;
+	let viewConstructor = DataView;
+	if (!(view instanceof DataView)) {
+		elementSize = view.constructor.BYTES_PER_ELEMENT;
+		viewConstructor = view.constructor;
+	}
+
+	const minimumFill = min * elementSize;
+	const { byteOffset, byteLength } = view;
+
+	let buffer;
+	try {
+		buffer = transferArrayBuffer(view.buffer);
+	} catch (error) {
+		readIntoRequest.errorSteps(error);
+		return;
+	}
+
+	/** @type {PullIntoDescriptor} */
+	const pullIntoDescriptor = {
+		buffer,
+		bufferByteLength: buffer.byteLength,
+		byteOffset,
+		byteLength,
+		bytesFilled: 0,
+		minimumFill,
+		elementSize,
+		viewConstructor,
+		readerType: "byob",
+	};
+
+	if (controller[_pendingPullIntos].length > 0) {
+		controller[_pendingPullIntos].push(pullIntoDescriptor);
+		readableStreamAddReadIntoRequest(stream, readIntoRequest);
+		return;
+	}
+
+	if (stream[_state] === "closed") {
+		const emptyView = new viewConstructor(
+			pullIntoDescriptor.buffer,
+			pullIntoDescriptor.byteOffset,
+			0,
+		);
+		readIntoRequest.closeSteps(emptyView);
+		return;
+	}
+
+	if (controller[_queueTotalSize] > 0) {
+		if (
+			readableByteStreamControllerFillPullIntoDescriptorFromQueue(
+				controller,
+				pullIntoDescriptor,
+			)
+		) {
+			const filledView = readableByteStreamControllerConvertPullIntoDescriptor(
+				pullIntoDescriptor,
+			);
+			readableByteStreamControllerHandleQueueDrain(controller);
+			readIntoRequest.chunkSteps(filledView);
+			return;
+		}
+
+		if (controller[_closeRequested]) {
+			const error = new TypeError(
+				"Stream closed in the middle of an element of the view",
+			);
+			readableByteStreamControllerError(controller, error);
+			readIntoRequest.errorSteps(error);
+			return;
+		}
+	}
+
+	controller[_pendingPullIntos].push(pullIntoDescriptor);
+	readableStreamAddReadIntoRequest(stream, readIntoRequest);
+	readableByteStreamControllerCallPullIfNeeded(controller);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {number} bytesWritten
+ */
+function readableByteStreamControllerRespond(controller, bytesWritten) {
+	const firstDescriptor = controller[_pendingPullIntos][0];
+	if (controller[_stream][_state] === "closed") {
+		if (bytesWritten !== 0) {
+			throw new TypeError("bytesWritten must be 0 after the stream closed");
+		}
+	} else {
+		if (bytesWritten === 0) {
+			throw new TypeError("bytesWritten must be greater than 0");
+		}
+		if (
+			firstDescriptor.bytesFilled + bytesWritten > firstDescriptor.byteLength
+		) {
+			throw new RangeError("bytesWritten is out of range of the view");
+		}
+	}
+
+	firstDescriptor.buffer = transferArrayBuffer(firstDescriptor.buffer);
+	readableByteStreamControllerRespondInternal(controller, bytesWritten);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {PullIntoDescriptor} firstDescriptor
+ */
+function readableByteStreamControllerRespondInClosedState(
+	controller,
+	firstDescriptor,
+) {
+	if (firstDescriptor.readerType === "none") {
+		readableByteStreamControllerShiftPendingPullInto(controller);
+	}
+
+	const stream = controller[_stream];
+	if (readableStreamHasBYOBReader(stream)) {
+		const filledPullIntos = [];
+		while (
+			filledPullIntos.length < readableStreamGetNumReadIntoRequests(stream)
+		) {
+			filledPullIntos.push(
+				readableByteStreamControllerShiftPendingPullInto(controller),
+			);
+		}
+		for (const filledPullInto of filledPullIntos) {
+			readableByteStreamControllerCommitPullIntoDescriptor(
+				stream,
+				filledPullInto,
+			);
+		}
+	}
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {number} bytesWritten
+ * @param {PullIntoDescriptor} pullIntoDescriptor
+ */
+function readableByteStreamControllerRespondInReadableState(
+	controller,
+	bytesWritten,
+	pullIntoDescriptor,
+) {
+	readableByteStreamControllerFillHeadPullIntoDescriptor(
+		controller,
+		bytesWritten,
+		pullIntoDescriptor,
+	);
+
+	const stream = controller[_stream];
+	if (pullIntoDescriptor.readerType === "none") {
+		readableByteStreamControllerEnqueueDetachedPullIntoToQueue(
+			controller,
+			pullIntoDescriptor,
+		);
+		const filledPullIntos =
+			readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(
+				controller,
+			);
+		for (const filledPullInto of filledPullIntos) {
+			readableByteStreamControllerCommitPullIntoDescriptor(
+				stream,
+				filledPullInto,
+			);
+		}
+		return;
+	}
+
+	if (pullIntoDescriptor.bytesFilled < pullIntoDescriptor.minimumFill) return;
+
+	readableByteStreamControllerShiftPendingPullInto(controller);
+	const remainderSize = pullIntoDescriptor.bytesFilled %
+		pullIntoDescriptor.elementSize;
+	if (remainderSize > 0) {
+		const end = pullIntoDescriptor.byteOffset + pullIntoDescriptor.bytesFilled;
+		readableByteStreamControllerEnqueueClonedChunkToQueue(
+			controller,
+			pullIntoDescriptor.buffer,
+			end - remainderSize,
+			remainderSize,
+		);
+	}
+	pullIntoDescriptor.bytesFilled -= remainderSize;
+
+	const filledPullIntos =
+		readableByteStreamControllerProcessPullIntoDescriptorsUsingQueue(
+			controller,
+		);
+	readableByteStreamControllerCommitPullIntoDescriptor(
+		stream,
+		pullIntoDescriptor,
+	);
+	for (const filledPullInto of filledPullIntos) {
+		readableByteStreamControllerCommitPullIntoDescriptor(
+			stream,
+			filledPullInto,
+		);
+	}
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {number} bytesWritten
+ */
+function readableByteStreamControllerRespondInternal(controller, bytesWritten) {
+	const firstDescriptor = controller[_pendingPullIntos][0];
+	readableByteStreamControllerInvalidateBYOBRequest(controller);
+	if (controller[_stream][_state] === "closed") {
+		readableByteStreamControllerRespondInClosedState(
+			controller,
+			firstDescriptor,
+		);
+	} else {
+		readableByteStreamControllerRespondInReadableState(
+			controller,
+			bytesWritten,
+			firstDescriptor,
+		);
+	}
+	readableByteStreamControllerCallPullIfNeeded(controller);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @param {ArrayBufferView} view
+ */
+function readableByteStreamControllerRespondWithNewView(controller, view) {
+	const firstDescriptor = controller[_pendingPullIntos][0];
+	if (controller[_stream][_state] === "closed") {
+		if (view.byteLength !== 0) {
+			throw new TypeError("View must be empty after the stream closed");
+		}
+	} else if (view.byteLength === 0) {
+		throw new TypeError("View must not be empty");
+	}
+
+	if (
+		firstDescriptor.byteOffset + firstDescriptor.bytesFilled !==
+			view.byteOffset
+	) {
+		throw new RangeError("View has to start where the request's view starts");
+	}
+	if (firstDescriptor.bufferByteLength !== view.buffer.byteLength) {
+		throw new RangeError("View's buffer has to be the request's buffer");
+	}
+	if (
+		firstDescriptor.bytesFilled + view.byteLength > firstDescriptor.byteLength
+	) {
+		throw new RangeError("View is larger than the request's view");
+	}
+
+	const viewByteLength = view.byteLength;
+	firstDescriptor.buffer = transferArrayBuffer(view.buffer);
+	readableByteStreamControllerRespondInternal(controller, viewByteLength);
+}
+
+/**
+ * @param {ReadableByteStreamController} controller
+ * @returns {PullIntoDescriptor}
+ */
+function readableByteStreamControllerShiftPendingPullInto(controller) {
+	return controller[_pendingPullIntos].shift();
+}
+
+// Tee
+
+/**
+ * @param {ReadableStream} stream
+ * @param {boolean} cloneForBranch2
+ * @returns {[ReadableStream, ReadableStream]}
+ */
+function readableStreamTee(stream, cloneForBranch2) {
+	if (stream[_controller] instanceof ReadableByteStreamController) {
+		return readableByteStreamTee(stream);
+	}
+	return readableStreamDefaultTee(stream, cloneForBranch2);
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @param {boolean} _cloneForBranch2 - chunks are only cloned by structured clone, which isn't needed for tee()
+ * @returns {[ReadableStream, ReadableStream]}
+ */
+function readableStreamDefaultTee(stream, _cloneForBranch2) {
+	const reader = acquireReadableStreamDefaultReader(stream);
+	let reading = false;
+	let readAgain = false;
+	let canceled1 = false;
+	let canceled2 = false;
+	let reason1;
+	let reason2;
+	/** @type {ReadableStream} */
+	let branch1;
+	/** @type {ReadableStream} */
+	let branch2;
+	const cancelPromise = createDeferred();
+
+	const pullAlgorithm = () => {
+		if (reading) {
+			readAgain = true;
+			return Promise.resolve(undefined);
+		}
+		reading = true;
+
+		readableStreamDefaultReaderRead(reader, {
+			chunkSteps: (chunk) => {
+				// Lets the read promise settle first, so errors from enqueueing don't reject it
+				queueTask(() => {
+					readAgain = false;
+					if (!canceled1) {
+						readableStreamDefaultControllerEnqueue(branch1[_controller], chunk);
+					}
+					if (!canceled2) {
+						readableStreamDefaultControllerEnqueue(branch2[_controller], chunk);
+					}
+					reading = false;
+					if (readAgain) pullAlgorithm();
+				});
+			},
+			closeSteps: () => {
+				reading = false;
+				if (!canceled1) {
+					readableStreamDefaultControllerClose(branch1[_controller]);
+				}
+				if (!canceled2) {
+					readableStreamDefaultControllerClose(branch2[_controller]);
+				}
+				if (!canceled1 || !canceled2) cancelPromise.resolve(undefined);
+			},
+			errorSteps: () => {
+				reading = false;
+			},
+		});
+		return Promise.resolve(undefined);
+	};
+
+	const cancel1Algorithm = (reason) => {
+		canceled1 = true;
+		reason1 = reason;
+		if (canceled2) {
+			cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+		}
+		return cancelPromise.promise;
+	};
+
+	const cancel2Algorithm = (reason) => {
+		canceled2 = true;
+		reason2 = reason;
+		if (canceled1) {
+			cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+		}
+		return cancelPromise.promise;
+	};
+
+	const startAlgorithm = () => undefined;
+	branch1 = createReadableStream(
+		startAlgorithm,
+		pullAlgorithm,
+		cancel1Algorithm,
+	);
+	branch2 = createReadableStream(
+		startAlgorithm,
+		pullAlgorithm,
+		cancel2Algorithm,
+	);
+
+	uponPromise(reader[_closedPromise].promise, undefined, (reason) => {
+		readableStreamDefaultControllerError(branch1[_controller], reason);
+		readableStreamDefaultControllerError(branch2[_controller], reason);
+		if (!canceled1 || !canceled2) cancelPromise.resolve(undefined);
+	});
+
+	return [branch1, branch2];
+}
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {[ReadableStream, ReadableStream]}
+ */
+function readableByteStreamTee(stream) {
+	/** @type {ReadableStreamDefaultReader | ReadableStreamBYOBReader} */
+	let reader = acquireReadableStreamDefaultReader(stream);
+	let reading = false;
+	let readAgainForBranch1 = false;
+	let readAgainForBranch2 = false;
+	let canceled1 = false;
+	let canceled2 = false;
+	let reason1;
+	let reason2;
+	/** @type {ReadableStream} */
+	let branch1;
+	/** @type {ReadableStream} */
+	let branch2;
+	const cancelPromise = createDeferred();
+
+	const forwardReaderError = (thisReader) => {
+		uponPromise(thisReader[_closedPromise].promise, undefined, (reason) => {
+			if (thisReader !== reader) return;
+			readableByteStreamControllerError(branch1[_controller], reason);
+			readableByteStreamControllerError(branch2[_controller], reason);
+			if (!canceled1 || !canceled2) cancelPromise.resolve(undefined);
+		});
+	};
+
+	const cloneOrCancel = (chunk) => {
+		try {
+			return cloneAsUint8Array(chunk);
+		} catch (error) {
+			readableByteStreamControllerError(branch1[_controller], error);
+			readableByteStreamControllerError(branch2[_controller], error);
+			cancelPromise.resolve(readableStreamCancel(stream, error));
+			return undefined;
+		}
+	};
+
+	const pullWithDefaultReader = () => {
+		if (reader instanceof ReadableStreamBYOBReader) {
+			readableStreamBYOBReaderRelease(reader);
+			reader = acquireReadableStreamDefaultReader(stream);
+			forwardReaderError(reader);
+		}
+
+		readableStreamDefaultReaderRead(reader, {
+			chunkSteps: (chunk) => {
+				queueTask(() => {
+					readAgainForBranch1 = false;
+					readAgainForBranch2 = false;
+					const chunk1 = chunk;
+					let chunk2 = chunk;
+					if (!canceled1 && !canceled2) {
+						chunk2 = cloneOrCancel(chunk);
+						if (chunk2 === undefined) return;
+					}
+					if (!canceled1) {
+						readableByteStreamControllerEnqueue(branch1[_controller], chunk1);
+					}
+					if (!canceled2) {
+						readableByteStreamControllerEnqueue(branch2[_controller], chunk2);
+					}
+					reading = false;
+					if (readAgainForBranch1) {
+						pull1Algorithm();
+					} else if (readAgainForBranch2) {
+						pull2Algorithm();
+					}
+				});
+			},
+			closeSteps: () => {
+				reading = false;
+				if (!canceled1) readableByteStreamControllerClose(branch1[_controller]);
+				if (!canceled2) readableByteStreamControllerClose(branch2[_controller]);
+				if (branch1[_controller][_pendingPullIntos].length > 0) {
+					readableByteStreamControllerRespond(branch1[_controller], 0);
+				}
+				if (branch2[_controller][_pendingPullIntos].length > 0) {
+					readableByteStreamControllerRespond(branch2[_controller], 0);
+				}
+				if (!canceled1 || !canceled2) cancelPromise.resolve(undefined);
+			},
+			errorSteps: () => {
+				reading = false;
+			},
+		});
+	};
+
+	const pullWithBYOBReader = (view, forBranch2) => {
+		if (reader instanceof ReadableStreamDefaultReader) {
+			readableStreamDefaultReaderRelease(reader);
+			reader = acquireReadableStreamBYOBReader(stream);
+			forwardReaderError(reader);
+		}
+
+		const byobBranch = forBranch2 ? branch2 : branch1;
+		const otherBranch = forBranch2 ? branch1 : branch2;
+		readableStreamBYOBReaderRead(reader, view, 1, {
+			chunkSteps: (chunk) => {
+				queueTask(() => {
+					readAgainForBranch1 = false;
+					readAgainForBranch2 = false;
+					const byobCanceled = forBranch2 ? canceled2 : canceled1;
+					const otherCanceled = forBranch2 ? canceled1 : canceled2;
+					if (!otherCanceled) {
+						const clonedChunk = cloneOrCancel(chunk);
+						if (clonedChunk === undefined) return;
+						if (!byobCanceled) {
+							readableByteStreamControllerRespondWithNewView(
+								byobBranch[_controller],
+								chunk,
+							);
+						}
+						readableByteStreamControllerEnqueue(
+							otherBranch[_controller],
+							clonedChunk,
+						);
+					} else if (!byobCanceled) {
+						readableByteStreamControllerRespondWithNewView(
+							byobBranch[_controller],
+							chunk,
+						);
+					}
+					reading = false;
+					if (readAgainForBranch1) {
+						pull1Algorithm();
+					} else if (readAgainForBranch2) {
+						pull2Algorithm();
+					}
+				});
+			},
+			closeSteps: (chunk) => {
+				reading = false;
+				const byobCanceled = forBranch2 ? canceled2 : canceled1;
+				const otherCanceled = forBranch2 ? canceled1 : canceled2;
+				if (!byobCanceled) {
+					readableByteStreamControllerClose(byobBranch[_controller]);
+				}
+				if (!otherCanceled) {
+					readableByteStreamControllerClose(otherBranch[_controller]);
+				}
+				if (chunk !== undefined) {
+					if (!byobCanceled) {
+						readableByteStreamControllerRespondWithNewView(
+							byobBranch[_controller],
+							chunk,
+						);
+					}
+					if (
+						!otherCanceled &&
+						otherBranch[_controller][_pendingPullIntos].length > 0
+					) {
+						readableByteStreamControllerRespond(otherBranch[_controller], 0);
+					}
+				}
+				if (!byobCanceled || !otherCanceled) {
+					cancelPromise.resolve(undefined);
+				}
+			},
+			errorSteps: () => {
+				reading = false;
+			},
+		});
+	};
+
+	const pull1Algorithm = () => {
+		if (reading) {
+			readAgainForBranch1 = true;
+			return Promise.resolve(undefined);
+		}
+		reading = true;
+		const byobRequest = readableByteStreamControllerGetBYOBRequest(
+			branch1[_controller],
+		);
+		if (byobRequest === null) {
+			pullWithDefaultReader();
+		} else {
+			pullWithBYOBReader(byobRequest[_view], false);
+		}
+		return Promise.resolve(undefined);
+	};
+
+	const pull2Algorithm = () => {
+		if (reading) {
+			readAgainForBranch2 = true;
+			return Promise.resolve(undefined);
+		}
+		reading = true;
+		const byobRequest = readableByteStreamControllerGetBYOBRequest(
+			branch2[_controller],
+		);
+		if (byobRequest === null) {
+			pullWithDefaultReader();
+		} else {
+			pullWithBYOBReader(byobRequest[_view], true);
+		}
+		return Promise.resolve(undefined);
+	};
+
+	const cancel1Algorithm = (reason) => {
+		canceled1 = true;
+		reason1 = reason;
+		if (canceled2) {
+			cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+		}
+		return cancelPromise.promise;
+	};
+
+	const cancel2Algorithm = (reason) => {
+		canceled2 = true;
+		reason2 = reason;
+		if (canceled1) {
+			cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+		}
+		return cancelPromise.promise;
+	};
+
+	const startAlgorithm = () => undefined;
+	branch1 = createReadableByteStream(
+		startAlgorithm,
+		pull1Algorithm,
+		cancel1Algorithm,
+	);
+	branch2 = createReadableByteStream(
+		startAlgorithm,
+		pull2Algorithm,
+		cancel2Algorithm,
+	);
+
+	forwardReaderError(reader);
+	return [branch1, branch2];
+}
+
+// Piping
+
+/**
+ * @param {ReadableStream} source
+ * @param {WritableStream} dest
+ * @param {boolean} preventClose
+ * @param {boolean} preventAbort
+ * @param {boolean} preventCancel
+ * @param {AbortSignal | undefined} signal
+ * @returns {Promise<void>}
+ */
+function readableStreamPipeTo(
+	source,
+	dest,
+	preventClose,
+	preventAbort,
+	preventCancel,
+	signal,
+) {
+	const reader = acquireReadableStreamDefaultReader(source);
+	const writer = acquireWritableStreamDefaultWriter(dest);
+	source[_disturbed] = true;
+
+	let shuttingDown = false;
+	/** @type {Promise<void>} */
+	let currentWrite = Promise.resolve(undefined);
+	const promise = createDeferred();
+
+	/** @type {(() => void) | undefined} */
+	let abortAlgorithm;
+	if (signal !== undefined) {
+		abortAlgorithm = () => {
+			const error = signal.reason;
+			const actions = [];
+			if (!preventAbort) {
+				actions.push(() =>
+					dest[_state] === "writable"
+						? writableStreamAbort(dest, error)
+						: Promise.resolve(undefined)
+				);
+			}
+			if (!preventCancel) {
+				actions.push(() =>
+					source[_state] === "readable"
+						? readableStreamCancel(source, error)
+						: Promise.resolve(undefined)
+				);
+			}
+			shutdownWithAction(
+				() => Promise.all(actions.map((action) => action())),
+				true,
+				error,
+			);
+		};
+
+		// Shutdown helpers below are function declarations, so they can already be used here
+		if (signal.aborted) {
+			abortAlgorithm();
+			return promise.promise;
+		}
+		signal.addEventListener("abort", abortAlgorithm, { once: true });
+	}
+
+	function waitForWritesToFinish() {
+		const oldCurrentWrite = currentWrite;
+		return oldCurrentWrite.then(() =>
+			oldCurrentWrite !== currentWrite ? waitForWritesToFinish() : undefined
+		);
+	}
+
+	function finalize(isError, error) {
+		writableStreamDefaultWriterRelease(writer);
+		readableStreamDefaultReaderRelease(reader);
+		if (signal !== undefined) {
+			signal.removeEventListener("abort", abortAlgorithm);
+		}
+		if (isError) {
+			promise.reject(error);
+		} else {
+			promise.resolve(undefined);
+		}
+	}
+
+	function shutdownWithAction(action, originalIsError, originalError) {
+		if (shuttingDown) return;
+		shuttingDown = true;
+
+		const doTheRest = () => {
+			uponPromise(
+				action(),
+				() => finalize(originalIsError, originalError),
+				(newError) => finalize(true, newError),
+			);
+		};
+
+		if (
+			dest[_state] === "writable" &&
+			!writableStreamCloseQueuedOrInFlight(dest)
+		) {
+			uponPromise(waitForWritesToFinish(), doTheRest);
+		} else {
+			doTheRest();
+		}
+	}
+
+	const shutdown = (isError, error) => {
+		if (shuttingDown) return;
+		shuttingDown = true;
+
+		if (
+			dest[_state] === "writable" &&
+			!writableStreamCloseQueuedOrInFlight(dest)
+		) {
+			uponPromise(waitForWritesToFinish(), () => finalize(isError, error));
+		} else {
+			finalize(isError, error);
+		}
+	};
+
+	// Resolves with true once there's nothing more to pipe
+	const pipeStep = () => {
+		if (shuttingDown) return Promise.resolve(true);
+
+		return writer[_readyPromise].promise.then(() => {
+			const deferred = createDeferred();
+			readableStreamDefaultReaderRead(reader, {
+				chunkSteps: (chunk) => {
+					currentWrite = writableStreamDefaultWriterWrite(writer, chunk).then(
+						undefined,
+						() => {},
+					);
+					deferred.resolve(false);
+				},
+				closeSteps: () => deferred.resolve(true),
+				errorSteps: deferred.reject,
+			});
+			return deferred.promise;
+		});
+	};
+
+	const pipeLoop = () => {
+		const loop = createDeferred();
+		const next = (done) => {
+			if (done) {
+				loop.resolve(undefined);
+			} else {
+				uponPromise(pipeStep(), next, loop.reject);
+			}
+		};
+		next(false);
+		return loop.promise;
+	};
+
+	// Errors must be propagated forward
+	if (source[_state] === "errored") {
+		sourceErrored(source[_storedError]);
+	} else {
+		uponPromise(reader[_closedPromise].promise, undefined, sourceErrored);
+	}
+	function sourceErrored(storedError) {
+		if (!preventAbort) {
+			shutdownWithAction(
+				() => writableStreamAbort(dest, storedError),
+				true,
+				storedError,
+			);
+		} else {
+			shutdown(true, storedError);
+		}
+	}
+
+	// Errors must be propagated backward
+	if (dest[_state] === "errored") {
+		destErrored(dest[_storedError]);
+	} else {
+		uponPromise(writer[_closedPromise].promise, undefined, destErrored);
+	}
+	function destErrored(storedError) {
+		if (!preventCancel) {
+			shutdownWithAction(
+				() => readableStreamCancel(source, storedError),
+				true,
+				storedError,
+			);
+		} else {
+			shutdown(true, storedError);
+		}
+	}
+
+	// Closing must be propagated forward
+	if (source[_state] === "closed") {
+		sourceClosed();
+	} else {
+		uponPromise(reader[_closedPromise].promise, sourceClosed);
+	}
+	function sourceClosed() {
+		if (!preventClose) {
+			shutdownWithAction(() =>
+				writableStreamDefaultWriterCloseWithErrorPropagation(writer)
+			);
+		} else {
+			shutdown();
+		}
+	}
+
+	// Closing must be propagated backward
+	if (
+		writableStreamCloseQueuedOrInFlight(dest) || dest[_state] === "closed"
+	) {
+		const destClosed = new TypeError(
+			"Destination stream is closing or closed",
+		);
+		if (!preventCancel) {
+			shutdownWithAction(
+				() => readableStreamCancel(source, destClosed),
+				true,
+				destClosed,
+			);
+		} else {
+			shutdown(true, destClosed);
+		}
+	}
+
+	setPromiseIsHandled(pipeLoop());
+	return promise.promise;
+}
+
+// WritableStream
+
+/**
+ * @param {any} value
+ * @returns {value is WritableStream}
+ */
+function isWritableStream(value) {
+	return typeof value === "object" && value !== null && _state in value &&
+		_writer in value;
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function initializeWritableStream(stream) {
+	stream[_state] = "writable";
+	stream[_storedError] = undefined;
+	stream[_writer] = undefined;
+	stream[_controller] = undefined;
+	stream[_inFlightWriteRequest] = undefined;
+	stream[_closeRequest] = undefined;
+	stream[_inFlightCloseRequest] = undefined;
+	stream[_pendingAbortRequest] = undefined;
+	stream[_writeRequests] = [];
+	stream[_backpressure] = false;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {boolean}
+ */
+function isWritableStreamLocked(stream) {
+	return stream[_writer] !== undefined;
+}
+
+/**
+ * Creates writable stream out of given algorithms
+ * @param {() => any} startAlgorithm
+ * @param {(chunk: any) => Promise<void>} writeAlgorithm
+ * @param {() => Promise<void>} closeAlgorithm
+ * @param {(reason: any) => Promise<void>} abortAlgorithm
+ * @param {number} highWaterMark
+ * @param {(chunk: any) => number} sizeAlgorithm
+ * @returns {WritableStream}
+ */
+function createWritableStream(
+	startAlgorithm,
+	writeAlgorithm,
+	closeAlgorithm,
+	abortAlgorithm,
+	highWaterMark,
+	sizeAlgorithm,
+) {
+	const stream = Object.create(WritableStream.prototype);
+	initializeWritableStream(stream);
+	const controller = createBranded(WritableStreamDefaultController);
+	setUpWritableStreamDefaultController(
+		stream,
+		controller,
+		startAlgorithm,
+		writeAlgorithm,
+		closeAlgorithm,
+		abortAlgorithm,
+		highWaterMark,
+		sizeAlgorithm,
+	);
+	return stream;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {WritableStreamDefaultWriter}
+ */
+function acquireWritableStreamDefaultWriter(stream) {
+	const writer = Object.create(WritableStreamDefaultWriter.prototype);
+	setUpWritableStreamDefaultWriter(writer, stream);
+	return writer;
+}
+
+export class WritableStream {
+	/**
+	 * @param {object} [underlyingSink]
+	 * @param {QueuingStrategy} [strategy]
+	 */
+	constructor(underlyingSink = undefined, strategy = {}) {
+		if (underlyingSink === null) underlyingSink = undefined;
+		if (underlyingSink?.type !== undefined) {
+			throw new RangeError(
+				"Failed to construct 'WritableStream': type must be undefined",
+			);
+		}
+		initializeWritableStream(this);
+		const sizeAlgorithm = extractSizeAlgorithm(strategy);
+		const highWaterMark = extractHighWaterMark(strategy, 1);
+		setUpWritableStreamDefaultControllerFromUnderlyingSink(
+			this,
+			underlyingSink,
+			highWaterMark,
+			sizeAlgorithm,
+		);
+	}
+
+	/** @returns {boolean} */
+	get locked() {
+		assertWritableStream(this);
+		return isWritableStreamLocked(this);
+	}
+
+	/**
+	 * @param {any} [reason]
+	 * @returns {Promise<void>}
+	 */
+	abort(reason) {
+		if (!isWritableStream(this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (isWritableStreamLocked(this)) {
+			return Promise.reject(
+				new TypeError("Can't abort a stream which is locked to a writer"),
+			);
+		}
+		return writableStreamAbort(this, reason);
+	}
+
+	/** @returns {Promise<void>} */
+	close() {
+		if (!isWritableStream(this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (isWritableStreamLocked(this)) {
+			return Promise.reject(
+				new TypeError("Can't close a stream which is locked to a writer"),
+			);
+		}
+		if (writableStreamCloseQueuedOrInFlight(this)) {
+			return Promise.reject(new TypeError("Stream is already closing"));
+		}
+		return writableStreamClose(this);
+	}
+
+	/** @returns {WritableStreamDefaultWriter} */
+	getWriter() {
+		assertWritableStream(this);
+		return acquireWritableStreamDefaultWriter(this);
+	}
+}
+
+/**
+ * @param {any} stream
+ */
+function assertWritableStream(stream) {
+	if (!isWritableStream(stream)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {any} reason
+ * @returns {Promise<void>}
+ */
+function writableStreamAbort(stream, reason) {
+	if (stream[_state] === "closed" || stream[_state] === "errored") {
+		return Promise.resolve(undefined);
+	}
+	if (stream[_pendingAbortRequest] !== undefined) {
+		return stream[_pendingAbortRequest].promise.promise;
+	}
+
+	let wasAlreadyErroring = false;
+	if (stream[_state] === "erroring") {
+		wasAlreadyErroring = true;
+		reason = undefined;
+	}
+
+	const promise = createDeferred();
+	stream[_pendingAbortRequest] = { promise, reason, wasAlreadyErroring };
+	if (!wasAlreadyErroring) {
+		writableStreamStartErroring(stream, reason);
+	}
+	return promise.promise;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {Promise<void>}
+ */
+function writableStreamClose(stream) {
+	const state = stream[_state];
+	if (state === "closed" || state === "errored") {
+		return Promise.reject(
+			new TypeError("Can't close a stream which is closed or errored"),
+		);
+	}
+
+	const promise = createDeferred();
+	stream[_closeRequest] = promise;
+	const writer = stream[_writer];
+	if (writer !== undefined && stream[_backpressure] && state === "writable") {
+		writer[_readyPromise].resolve(undefined);
+	}
+	writableStreamDefaultControllerClose(stream[_controller]);
+	return promise.promise;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {Promise<void>}
+ */
+function writableStreamAddWriteRequest(stream) {
+	const promise = createDeferred();
+	stream[_writeRequests].push(promise);
+	return promise.promise;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {boolean}
+ */
+function writableStreamCloseQueuedOrInFlight(stream) {
+	return stream[_closeRequest] !== undefined ||
+		stream[_inFlightCloseRequest] !== undefined;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {any} error
+ */
+function writableStreamDealWithRejection(stream, error) {
+	if (stream[_state] === "writable") {
+		writableStreamStartErroring(stream, error);
+		return;
+	}
+	writableStreamFinishErroring(stream);
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamFinishErroring(stream) {
+	stream[_state] = "errored";
+	stream[_controller][_errorSteps]();
+
+	const storedError = stream[_storedError];
+	for (const writeRequest of stream[_writeRequests]) {
+		writeRequest.reject(storedError);
+	}
+	stream[_writeRequests] = [];
+
+	const abortRequest = stream[_pendingAbortRequest];
+	if (abortRequest === undefined) {
+		writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+		return;
+	}
+	stream[_pendingAbortRequest] = undefined;
+
+	if (abortRequest.wasAlreadyErroring) {
+		abortRequest.promise.reject(storedError);
+		writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+		return;
+	}
+
+	uponPromise(
+		stream[_controller][_abortSteps](abortRequest.reason),
+		() => {
+			abortRequest.promise.resolve(undefined);
+			writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+		},
+		(reason) => {
+			abortRequest.promise.reject(reason);
+			writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+		},
+	);
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamFinishInFlightClose(stream) {
+	stream[_inFlightCloseRequest].resolve(undefined);
+	stream[_inFlightCloseRequest] = undefined;
+
+	if (stream[_state] === "erroring") {
+		stream[_storedError] = undefined;
+		if (stream[_pendingAbortRequest] !== undefined) {
+			stream[_pendingAbortRequest].promise.resolve(undefined);
+			stream[_pendingAbortRequest] = undefined;
+		}
+	}
+
+	stream[_state] = "closed";
+	const writer = stream[_writer];
+	if (writer !== undefined) {
+		writer[_closedPromise].resolve(undefined);
+	}
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {any} error
+ */
+function writableStreamFinishInFlightCloseWithError(stream, error) {
+	stream[_inFlightCloseRequest].reject(error);
+	stream[_inFlightCloseRequest] = undefined;
+	if (stream[_pendingAbortRequest] !== undefined) {
+		stream[_pendingAbortRequest].promise.reject(error);
+		stream[_pendingAbortRequest] = undefined;
+	}
+	writableStreamDealWithRejection(stream, error);
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamFinishInFlightWrite(stream) {
+	stream[_inFlightWriteRequest].resolve(undefined);
+	stream[_inFlightWriteRequest] = undefined;
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {any} error
+ */
+function writableStreamFinishInFlightWriteWithError(stream, error) {
+	stream[_inFlightWriteRequest].reject(error);
+	stream[_inFlightWriteRequest] = undefined;
+	writableStreamDealWithRejection(stream, error);
+}
+
+/**
+ * @param {WritableStream} stream
+ * @returns {boolean}
+ */
+function writableStreamHasOperationMarkedInFlight(stream) {
+	return stream[_inFlightWriteRequest] !== undefined ||
+		stream[_inFlightCloseRequest] !== undefined;
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamMarkCloseRequestInFlight(stream) {
+	stream[_inFlightCloseRequest] = stream[_closeRequest];
+	stream[_closeRequest] = undefined;
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamMarkFirstWriteRequestInFlight(stream) {
+	stream[_inFlightWriteRequest] = stream[_writeRequests].shift();
+}
+
+/**
+ * @param {WritableStream} stream
+ */
+function writableStreamRejectCloseAndClosedPromiseIfNeeded(stream) {
+	const storedError = stream[_storedError];
+	if (stream[_closeRequest] !== undefined) {
+		stream[_closeRequest].reject(storedError);
+		stream[_closeRequest] = undefined;
+	}
+	const writer = stream[_writer];
+	if (writer !== undefined) {
+		writer[_closedPromise].reject(storedError);
+		setPromiseIsHandled(writer[_closedPromise].promise);
+	}
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {any} reason
+ */
+function writableStreamStartErroring(stream, reason) {
+	const controller = stream[_controller];
+	stream[_state] = "erroring";
+	stream[_storedError] = reason;
+	const writer = stream[_writer];
+	if (writer !== undefined) {
+		writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, reason);
+	}
+	if (
+		!writableStreamHasOperationMarkedInFlight(stream) && controller[_started]
+	) {
+		writableStreamFinishErroring(stream);
+	}
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {boolean} backpressure
+ */
+function writableStreamUpdateBackpressure(stream, backpressure) {
+	const writer = stream[_writer];
+	if (writer !== undefined && backpressure !== stream[_backpressure]) {
+		if (backpressure) {
+			writer[_readyPromise] = createDeferred();
+		} else {
+			writer[_readyPromise].resolve(undefined);
+		}
+	}
+	stream[_backpressure] = backpressure;
+}
+
+// WritableStreamDefaultWriter
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @param {WritableStream} stream
+ */
+function setUpWritableStreamDefaultWriter(writer, stream) {
+	if (isWritableStreamLocked(stream)) {
+		throw new TypeError("Stream is already locked to a writer");
+	}
+	writer[_stream] = stream;
+	stream[_writer] = writer;
+
+	const state = stream[_state];
+	if (state === "writable") {
+		if (!writableStreamCloseQueuedOrInFlight(stream) && stream[_backpressure]) {
+			writer[_readyPromise] = createDeferred();
+		} else {
+			writer[_readyPromise] = resolvedDeferred(undefined);
+		}
+		writer[_closedPromise] = createDeferred();
+	} else if (state === "erroring") {
+		writer[_readyPromise] = rejectedDeferred(stream[_storedError]);
+		writer[_closedPromise] = createDeferred();
+	} else if (state === "closed") {
+		writer[_readyPromise] = resolvedDeferred(undefined);
+		writer[_closedPromise] = resolvedDeferred(undefined);
+	} else {
+		writer[_readyPromise] = rejectedDeferred(stream[_storedError]);
+		writer[_closedPromise] = rejectedDeferred(stream[_storedError]);
+	}
+}
+
+export class WritableStreamDefaultWriter {
+	/**
+	 * @param {WritableStream} stream
+	 */
+	constructor(stream) {
+		if (!isWritableStream(stream)) {
+			throw new TypeError(
+				"Failed to construct 'WritableStreamDefaultWriter': stream must be a WritableStream",
+			);
+		}
+		setUpWritableStreamDefaultWriter(this, stream);
+	}
+
+	/** @returns {Promise<void>} */
+	get closed() {
+		if (!(_readyPromise in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		return this[_closedPromise].promise;
+	}
+
+	/** @returns {number | null} */
+	get desiredSize() {
+		assertWritableStreamDefaultWriter(this);
+		if (this[_stream] === undefined) {
+			throw new TypeError("Writer has been released");
+		}
+		return writableStreamDefaultWriterGetDesiredSize(this);
+	}
+
+	/** @returns {Promise<void>} */
+	get ready() {
+		if (!(_readyPromise in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		return this[_readyPromise].promise;
+	}
+
+	/**
+	 * @param {any} [reason]
+	 * @returns {Promise<void>}
+	 */
+	abort(reason) {
+		if (!(_readyPromise in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Writer has been released"));
+		}
+		return writableStreamAbort(this[_stream], reason);
+	}
+
+	/** @returns {Promise<void>} */
+	close() {
+		if (!(_readyPromise in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		const stream = this[_stream];
+		if (stream === undefined) {
+			return Promise.reject(new TypeError("Writer has been released"));
+		}
+		if (writableStreamCloseQueuedOrInFlight(stream)) {
+			return Promise.reject(new TypeError("Stream is already closing"));
+		}
+		return writableStreamClose(stream);
+	}
+
+	releaseLock() {
+		assertWritableStreamDefaultWriter(this);
+		if (this[_stream] === undefined) return;
+		writableStreamDefaultWriterRelease(this);
+	}
+
+	/**
+	 * @param {any} chunk
+	 * @returns {Promise<void>}
+	 */
+	write(chunk) {
+		if (!(_readyPromise in this)) {
+			return Promise.reject(new TypeError("Illegal invocation"));
+		}
+		if (this[_stream] === undefined) {
+			return Promise.reject(new TypeError("Writer has been released"));
+		}
+		return writableStreamDefaultWriterWrite(this, chunk);
+	}
+}
+
+/**
+ * @param {any} writer
+ */
+function assertWritableStreamDefaultWriter(writer) {
+	if (!(writer instanceof WritableStreamDefaultWriter)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @returns {Promise<void>}
+ */
+function writableStreamDefaultWriterCloseWithErrorPropagation(writer) {
+	const stream = writer[_stream];
+	const state = stream[_state];
+	if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+		return Promise.resolve(undefined);
+	}
+	if (state === "errored") {
+		return Promise.reject(stream[_storedError]);
+	}
+	return writableStreamClose(stream);
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @param {any} error
+ */
+function writableStreamDefaultWriterEnsureClosedPromiseRejected(writer, error) {
+	if (writer[_closedPromise].state === "pending") {
+		writer[_closedPromise].reject(error);
+	} else {
+		writer[_closedPromise] = rejectedDeferred(error);
+	}
+	setPromiseIsHandled(writer[_closedPromise].promise);
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @param {any} error
+ */
+function writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, error) {
+	if (writer[_readyPromise].state === "pending") {
+		writer[_readyPromise].reject(error);
+	} else {
+		writer[_readyPromise] = rejectedDeferred(error);
+	}
+	setPromiseIsHandled(writer[_readyPromise].promise);
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @returns {number | null}
+ */
+function writableStreamDefaultWriterGetDesiredSize(writer) {
+	const stream = writer[_stream];
+	switch (stream[_state]) {
+		case "errored":
+		case "erroring":
+			return null;
+		case "closed":
+			return 0;
+		default:
+			return writableStreamDefaultControllerGetDesiredSize(
+				stream[_controller],
+			);
+	}
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ */
+function writableStreamDefaultWriterRelease(writer) {
+	const stream = writer[_stream];
+	const releasedError = new TypeError("Writer has been released");
+	writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, releasedError);
+	writableStreamDefaultWriterEnsureClosedPromiseRejected(
+		writer,
+		releasedError,
+	);
+	stream[_writer] = undefined;
+	writer[_stream] = undefined;
+}
+
+/**
+ * @param {WritableStreamDefaultWriter} writer
+ * @param {any} chunk
+ * @returns {Promise<void>}
+ */
+function writableStreamDefaultWriterWrite(writer, chunk) {
+	const stream = writer[_stream];
+	const controller = stream[_controller];
+	const chunkSize = writableStreamDefaultControllerGetChunkSize(
+		controller,
+		chunk,
+	);
+	if (stream !== writer[_stream]) {
+		return Promise.reject(new TypeError("Writer has been released"));
+	}
+
+	const state = stream[_state];
+	if (state === "errored") {
+		return Promise.reject(stream[_storedError]);
+	}
+	if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+		return Promise.reject(
+			new TypeError("Can't write to a stream which is closing or closed"),
+		);
+	}
+	if (state === "erroring") {
+		return Promise.reject(stream[_storedError]);
+	}
+
+	const promise = writableStreamAddWriteRequest(stream);
+	writableStreamDefaultControllerWrite(controller, chunk, chunkSize);
+	return promise;
+}
+
+// WritableStreamDefaultController
+
+export class WritableStreamDefaultController {
+	constructor() {
+		illegalConstructor();
+	}
+
+	/**
+	 * @param {any} [error]
+	 */
+	error(error) {
+		brandedCheck(this, WritableStreamDefaultController);
+		if (this[_stream][_state] !== "writable") return;
+		writableStreamDefaultControllerError(this, error);
+	}
+
+	/**
+	 * @param {any} reason
+	 * @returns {Promise<void>}
+	 */
+	[_abortSteps](reason) {
+		const result = this[_abortAlgorithm](reason);
+		writableStreamDefaultControllerClearAlgorithms(this);
+		return result;
+	}
+
+	[_errorSteps]() {
+		resetQueue(this);
+	}
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {object | undefined} underlyingSink
+ * @param {number} highWaterMark
+ * @param {(chunk: any) => number} sizeAlgorithm
+ */
+function setUpWritableStreamDefaultControllerFromUnderlyingSink(
+	stream,
+	underlyingSink,
+	highWaterMark,
+	sizeAlgorithm,
+) {
+	const context = "Failed to construct 'WritableStream'";
+	const start = getMethod(underlyingSink, "start", context);
+	const write = getMethod(underlyingSink, "write", context);
+	const close = getMethod(underlyingSink, "close", context);
+	const abort = getMethod(underlyingSink, "abort", context);
+
+	const controller = createBranded(WritableStreamDefaultController);
+	setUpWritableStreamDefaultController(
+		stream,
+		controller,
+		() => start && Reflect.apply(start, underlyingSink, [controller]),
+		write
+			? (chunk) => promiseCall(write, underlyingSink, chunk, controller)
+			: () => Promise.resolve(undefined),
+		close
+			? () => promiseCall(close, underlyingSink)
+			: () => Promise.resolve(undefined),
+		abort
+			? (reason) => promiseCall(abort, underlyingSink, reason)
+			: () => Promise.resolve(undefined),
+		highWaterMark,
+		sizeAlgorithm,
+	);
+}
+
+/**
+ * @param {WritableStream} stream
+ * @param {WritableStreamDefaultController} controller
+ * @param {() => any} startAlgorithm
+ * @param {(chunk: any) => Promise<void>} writeAlgorithm
+ * @param {() => Promise<void>} closeAlgorithm
+ * @param {(reason: any) => Promise<void>} abortAlgorithm
+ * @param {number} highWaterMark
+ * @param {(chunk: any) => number} sizeAlgorithm
+ */
+function setUpWritableStreamDefaultController(
+	stream,
+	controller,
+	startAlgorithm,
+	writeAlgorithm,
+	closeAlgorithm,
+	abortAlgorithm,
+	highWaterMark,
+	sizeAlgorithm,
+) {
+	controller[_stream] = stream;
+	stream[_controller] = controller;
+	resetQueue(controller);
+	controller[_started] = false;
+	controller[_strategySizeAlgorithm] = sizeAlgorithm;
+	controller[_strategyHWM] = highWaterMark;
+	controller[_writeAlgorithm] = writeAlgorithm;
+	controller[_closeAlgorithm] = closeAlgorithm;
+	controller[_abortAlgorithm] = abortAlgorithm;
+
+	const backpressure = writableStreamDefaultControllerGetBackpressure(
+		controller,
+	);
+	writableStreamUpdateBackpressure(stream, backpressure);
+
+	const startResult = startAlgorithm();
+	uponPromise(
+		Promise.resolve(startResult),
+		() => {
+			controller[_started] = true;
+			writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+		},
+		(reason) => {
+			controller[_started] = true;
+			writableStreamDealWithRejection(stream, reason);
+		},
+	);
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ */
+function writableStreamDefaultControllerAdvanceQueueIfNeeded(controller) {
+	const stream = controller[_stream];
+	if (!controller[_started]) return;
+	if (stream[_inFlightWriteRequest] !== undefined) return;
+
+	if (stream[_state] === "erroring") {
+		writableStreamFinishErroring(stream);
+		return;
+	}
+	if (controller[_queue].length === 0) return;
+
+	const value = peekQueueValue(controller);
+	if (value === closeSentinel) {
+		writableStreamDefaultControllerProcessClose(controller);
+	} else {
+		writableStreamDefaultControllerProcessWrite(controller, value);
+	}
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ */
+function writableStreamDefaultControllerClearAlgorithms(controller) {
+	controller[_writeAlgorithm] = undefined;
+	controller[_closeAlgorithm] = undefined;
+	controller[_abortAlgorithm] = undefined;
+	controller[_strategySizeAlgorithm] = undefined;
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ */
+function writableStreamDefaultControllerClose(controller) {
+	enqueueValueWithSize(controller, closeSentinel, 0);
+	writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @param {any} error
+ */
+function writableStreamDefaultControllerError(controller, error) {
+	writableStreamDefaultControllerClearAlgorithms(controller);
+	writableStreamStartErroring(controller[_stream], error);
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @param {any} error
+ */
+function writableStreamDefaultControllerErrorIfNeeded(controller, error) {
+	if (controller[_stream][_state] === "writable") {
+		writableStreamDefaultControllerError(controller, error);
+	}
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @returns {boolean}
+ */
+function writableStreamDefaultControllerGetBackpressure(controller) {
+	return writableStreamDefaultControllerGetDesiredSize(controller) <= 0;
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @param {any} chunk
+ * @returns {number}
+ */
+function writableStreamDefaultControllerGetChunkSize(controller, chunk) {
+	if (controller[_strategySizeAlgorithm] === undefined) return 1;
+	try {
+		return controller[_strategySizeAlgorithm](chunk);
+	} catch (error) {
+		writableStreamDefaultControllerErrorIfNeeded(controller, error);
+		return 1;
+	}
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @returns {number}
+ */
+function writableStreamDefaultControllerGetDesiredSize(controller) {
+	return controller[_strategyHWM] - controller[_queueTotalSize];
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ */
+function writableStreamDefaultControllerProcessClose(controller) {
+	const stream = controller[_stream];
+	writableStreamMarkCloseRequestInFlight(stream);
+	dequeueValue(controller);
+	const sinkClosePromise = controller[_closeAlgorithm]();
+	writableStreamDefaultControllerClearAlgorithms(controller);
+	uponPromise(
+		sinkClosePromise,
+		() => writableStreamFinishInFlightClose(stream),
+		(reason) => writableStreamFinishInFlightCloseWithError(stream, reason),
+	);
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @param {any} chunk
+ */
+function writableStreamDefaultControllerProcessWrite(controller, chunk) {
+	const stream = controller[_stream];
+	writableStreamMarkFirstWriteRequestInFlight(stream);
+	uponPromise(
+		controller[_writeAlgorithm](chunk),
+		() => {
+			writableStreamFinishInFlightWrite(stream);
+			dequeueValue(controller);
+			if (
+				!writableStreamCloseQueuedOrInFlight(stream) &&
+				stream[_state] === "writable"
+			) {
+				const backpressure = writableStreamDefaultControllerGetBackpressure(
+					controller,
+				);
+				writableStreamUpdateBackpressure(stream, backpressure);
+			}
+			writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+		},
+		(reason) => {
+			if (stream[_state] === "writable") {
+				writableStreamDefaultControllerClearAlgorithms(controller);
+			}
+			writableStreamFinishInFlightWriteWithError(stream, reason);
+		},
+	);
+}
+
+/**
+ * @param {WritableStreamDefaultController} controller
+ * @param {any} chunk
+ * @param {number} chunkSize
+ */
+function writableStreamDefaultControllerWrite(controller, chunk, chunkSize) {
+	try {
+		enqueueValueWithSize(controller, chunk, chunkSize);
+	} catch (error) {
+		writableStreamDefaultControllerErrorIfNeeded(controller, error);
+		return;
+	}
+
+	const stream = controller[_stream];
+	if (
+		!writableStreamCloseQueuedOrInFlight(stream) &&
+		stream[_state] === "writable"
+	) {
+		const backpressure = writableStreamDefaultControllerGetBackpressure(
+			controller,
+		);
+		writableStreamUpdateBackpressure(stream, backpressure);
+	}
+	writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+}
+
+// TransformStream
+
+export class TransformStream {
+	/**
+	 * @param {object} [transformer]
+	 * @param {QueuingStrategy} [writableStrategy]
+	 * @param {QueuingStrategy} [readableStrategy]
+	 */
+	constructor(
+		transformer = undefined,
+		writableStrategy = {},
+		readableStrategy = {},
+	) {
+		const context = "Failed to construct 'TransformStream'";
+		if (transformer === null) transformer = undefined;
+		if (transformer?.readableType !== undefined) {
+			throw new RangeError(`${context}: readableType must be undefined`);
+		}
+		if (transformer?.writableType !== undefined) {
+			throw new RangeError(`${context}: writableType must be undefined`);
+		}
+
+		const readableHighWaterMark = extractHighWaterMark(readableStrategy, 0);
+		const readableSizeAlgorithm = extractSizeAlgorithm(readableStrategy);
+		const writableHighWaterMark = extractHighWaterMark(writableStrategy, 1);
+		const writableSizeAlgorithm = extractSizeAlgorithm(writableStrategy);
+
+		const start = getMethod(transformer, "start", context);
+		const startPromise = createDeferred();
+		initializeTransformStream(
+			this,
+			startPromise.promise,
+			writableHighWaterMark,
+			writableSizeAlgorithm,
+			readableHighWaterMark,
+			readableSizeAlgorithm,
+		);
+		setUpTransformStreamDefaultControllerFromTransformer(this, transformer);
+
+		if (start !== undefined) {
+			startPromise.resolve(
+				Reflect.apply(start, transformer, [this[_controller]]),
+			);
+		} else {
+			startPromise.resolve(undefined);
+		}
+	}
+
+	/** @returns {ReadableStream} */
+	get readable() {
+		assertTransformStream(this);
+		return this[_readable];
+	}
+
+	/** @returns {WritableStream} */
+	get writable() {
+		assertTransformStream(this);
+		return this[_writable];
+	}
+}
+
+/**
+ * @param {any} stream
+ */
+function assertTransformStream(stream) {
+	if (!(stream instanceof TransformStream)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {Promise<void>} startPromise
+ * @param {number} writableHighWaterMark
+ * @param {(chunk: any) => number} writableSizeAlgorithm
+ * @param {number} readableHighWaterMark
+ * @param {(chunk: any) => number} readableSizeAlgorithm
+ */
+function initializeTransformStream(
+	stream,
+	startPromise,
+	writableHighWaterMark,
+	writableSizeAlgorithm,
+	readableHighWaterMark,
+	readableSizeAlgorithm,
+) {
+	const startAlgorithm = () => startPromise;
+
+	stream[_writable] = createWritableStream(
+		startAlgorithm,
+		(chunk) => transformStreamDefaultSinkWriteAlgorithm(stream, chunk),
+		() => transformStreamDefaultSinkCloseAlgorithm(stream),
+		(reason) => transformStreamDefaultSinkAbortAlgorithm(stream, reason),
+		writableHighWaterMark,
+		writableSizeAlgorithm,
+	);
+	stream[_readable] = createReadableStream(
+		startAlgorithm,
+		() => transformStreamDefaultSourcePullAlgorithm(stream),
+		(reason) => transformStreamDefaultSourceCancelAlgorithm(stream, reason),
+		readableHighWaterMark,
+		readableSizeAlgorithm,
+	);
+
+	stream[_backpressure] = undefined;
+	stream[_backpressureChangePromise] = undefined;
+	transformStreamSetBackpressure(stream, true);
+	stream[_controller] = undefined;
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {any} error
+ */
+function transformStreamError(stream, error) {
+	readableStreamDefaultControllerError(stream[_readable][_controller], error);
+	transformStreamErrorWritableAndUnblockWrite(stream, error);
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {any} error
+ */
+function transformStreamErrorWritableAndUnblockWrite(stream, error) {
+	transformStreamDefaultControllerClearAlgorithms(stream[_controller]);
+	writableStreamDefaultControllerErrorIfNeeded(
+		stream[_writable][_controller],
+		error,
+	);
+	transformStreamUnblockWrite(stream);
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {boolean} backpressure
+ */
+function transformStreamSetBackpressure(stream, backpressure) {
+	if (stream[_backpressureChangePromise] !== undefined) {
+		stream[_backpressureChangePromise].resolve(undefined);
+	}
+	stream[_backpressureChangePromise] = createDeferred();
+	stream[_backpressure] = backpressure;
+}
+
+/**
+ * @param {TransformStream} stream
+ */
+function transformStreamUnblockWrite(stream) {
+	if (stream[_backpressure]) {
+		transformStreamSetBackpressure(stream, false);
+	}
+}
+
+export class TransformStreamDefaultController {
+	constructor() {
+		illegalConstructor();
+	}
+
+	/** @returns {number | null} */
+	get desiredSize() {
+		assertTransformStreamDefaultController(this);
+		return readableStreamDefaultControllerGetDesiredSize(
+			this[_stream][_readable][_controller],
+		);
+	}
+
+	/**
+	 * @param {any} chunk
+	 */
+	enqueue(chunk) {
+		assertTransformStreamDefaultController(this);
+		transformStreamDefaultControllerEnqueue(this, chunk);
+	}
+
+	/**
+	 * @param {any} [reason]
+	 */
+	error(reason) {
+		assertTransformStreamDefaultController(this);
+		transformStreamError(this[_stream], reason);
+	}
+
+	/** Closes the readable side and errors the writable side */
+	terminate() {
+		assertTransformStreamDefaultController(this);
+		transformStreamDefaultControllerTerminate(this);
+	}
+}
+
+/**
+ * @param {any} controller
+ */
+function assertTransformStreamDefaultController(controller) {
+	brandedCheck(controller, TransformStreamDefaultController);
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {object | undefined} transformer
+ */
+function setUpTransformStreamDefaultControllerFromTransformer(
+	stream,
+	transformer,
+) {
+	const context = "Failed to construct 'TransformStream'";
+	const transform = getMethod(transformer, "transform", context);
+	const flush = getMethod(transformer, "flush", context);
+	const cancel = getMethod(transformer, "cancel", context);
+
+	const controller = createBranded(TransformStreamDefaultController);
+	controller[_stream] = stream;
+	stream[_controller] = controller;
+	controller[_transformAlgorithm] = transform
+		? (chunk) => promiseCall(transform, transformer, chunk, controller)
+		: (chunk) => {
+			try {
+				transformStreamDefaultControllerEnqueue(controller, chunk);
+				return Promise.resolve(undefined);
+			} catch (error) {
+				return Promise.reject(error);
+			}
+		};
+	controller[_flushAlgorithm] = flush
+		? () => promiseCall(flush, transformer, controller)
+		: () => Promise.resolve(undefined);
+	controller[_cancelAlgorithm] = cancel
+		? (reason) => promiseCall(cancel, transformer, reason)
+		: () => Promise.resolve(undefined);
+	controller[_finishPromise] = undefined;
+}
+
+/**
+ * @param {TransformStreamDefaultController} controller
+ */
+function transformStreamDefaultControllerClearAlgorithms(controller) {
+	controller[_transformAlgorithm] = undefined;
+	controller[_flushAlgorithm] = undefined;
+	controller[_cancelAlgorithm] = undefined;
+}
+
+/**
+ * @param {TransformStreamDefaultController} controller
+ * @param {any} chunk
+ */
+function transformStreamDefaultControllerEnqueue(controller, chunk) {
+	const stream = controller[_stream];
+	const readableController = stream[_readable][_controller];
+	if (!readableStreamDefaultControllerCanCloseOrEnqueue(readableController)) {
+		throw new TypeError("Readable side is not in a state to be enqueued to");
+	}
+
+	try {
+		readableStreamDefaultControllerEnqueue(readableController, chunk);
+	} catch (error) {
+		transformStreamErrorWritableAndUnblockWrite(stream, error);
+		throw stream[_readable][_storedError];
+	}
+
+	const backpressure = readableStreamDefaultControllerHasBackpressure(
+		readableController,
+	);
+	if (backpressure !== stream[_backpressure]) {
+		transformStreamSetBackpressure(stream, true);
+	}
+}
+
+/**
+ * @param {TransformStreamDefaultController} controller
+ * @param {any} chunk
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultControllerPerformTransform(controller, chunk) {
+	return controller[_transformAlgorithm](chunk).then(undefined, (reason) => {
+		transformStreamError(controller[_stream], reason);
+		throw reason;
+	});
+}
+
+/**
+ * @param {TransformStreamDefaultController} controller
+ */
+function transformStreamDefaultControllerTerminate(controller) {
+	const stream = controller[_stream];
+	readableStreamDefaultControllerClose(stream[_readable][_controller]);
+	transformStreamErrorWritableAndUnblockWrite(
+		stream,
+		new TypeError("TransformStream has been terminated"),
+	);
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {any} chunk
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultSinkWriteAlgorithm(stream, chunk) {
+	const controller = stream[_controller];
+	if (stream[_backpressure]) {
+		return stream[_backpressureChangePromise].promise.then(() => {
+			const writable = stream[_writable];
+			if (writable[_state] === "erroring") {
+				throw writable[_storedError];
+			}
+			return transformStreamDefaultControllerPerformTransform(
+				controller,
+				chunk,
+			);
+		});
+	}
+	return transformStreamDefaultControllerPerformTransform(controller, chunk);
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {any} reason
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultSinkAbortAlgorithm(stream, reason) {
+	const controller = stream[_controller];
+	if (controller[_finishPromise] !== undefined) {
+		return controller[_finishPromise].promise;
+	}
+
+	const readable = stream[_readable];
+	const finishPromise = createDeferred();
+	controller[_finishPromise] = finishPromise;
+	const cancelPromise = controller[_cancelAlgorithm](reason);
+	transformStreamDefaultControllerClearAlgorithms(controller);
+	uponPromise(
+		cancelPromise,
+		() => {
+			if (readable[_state] === "errored") {
+				finishPromise.reject(readable[_storedError]);
+			} else {
+				readableStreamDefaultControllerError(readable[_controller], reason);
+				finishPromise.resolve(undefined);
+			}
+		},
+		(error) => {
+			readableStreamDefaultControllerError(readable[_controller], error);
+			finishPromise.reject(error);
+		},
+	);
+	return finishPromise.promise;
+}
+
+/**
+ * @param {TransformStream} stream
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultSinkCloseAlgorithm(stream) {
+	const controller = stream[_controller];
+	if (controller[_finishPromise] !== undefined) {
+		return controller[_finishPromise].promise;
+	}
+
+	const readable = stream[_readable];
+	const finishPromise = createDeferred();
+	controller[_finishPromise] = finishPromise;
+	const flushPromise = controller[_flushAlgorithm]();
+	transformStreamDefaultControllerClearAlgorithms(controller);
+	uponPromise(
+		flushPromise,
+		() => {
+			if (readable[_state] === "errored") {
+				finishPromise.reject(readable[_storedError]);
+			} else {
+				readableStreamDefaultControllerClose(readable[_controller]);
+				finishPromise.resolve(undefined);
+			}
+		},
+		(error) => {
+			readableStreamDefaultControllerError(readable[_controller], error);
+			finishPromise.reject(error);
+		},
+	);
+	return finishPromise.promise;
+}
+
+/**
+ * @param {TransformStream} stream
+ * @param {any} reason
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultSourceCancelAlgorithm(stream, reason) {
+	const controller = stream[_controller];
+	if (controller[_finishPromise] !== undefined) {
+		return controller[_finishPromise].promise;
+	}
+
+	const writable = stream[_writable];
+	const finishPromise = createDeferred();
+	controller[_finishPromise] = finishPromise;
+	const cancelPromise = controller[_cancelAlgorithm](reason);
+	transformStreamDefaultControllerClearAlgorithms(controller);
+	uponPromise(
+		cancelPromise,
+		() => {
+			if (writable[_state] === "errored") {
+				finishPromise.reject(writable[_storedError]);
+			} else {
+				writableStreamDefaultControllerErrorIfNeeded(
+					writable[_controller],
+					reason,
+				);
+				transformStreamUnblockWrite(stream);
+				finishPromise.resolve(undefined);
+			}
+		},
+		(error) => {
+			writableStreamDefaultControllerErrorIfNeeded(
+				writable[_controller],
+				error,
+			);
+			transformStreamUnblockWrite(stream);
+			finishPromise.reject(error);
+		},
+	);
+	return finishPromise.promise;
+}
+
+/**
+ * @param {TransformStream} stream
+ * @returns {Promise<void>}
+ */
+function transformStreamDefaultSourcePullAlgorithm(stream) {
+	transformStreamSetBackpressure(stream, false);
+	return stream[_backpressureChangePromise].promise;
+}
+
+// Queuing strategies
+
+/**
+ * @param {{ highWaterMark: number }} init
+ * @param {string} name
+ * @returns {number}
+ */
+function convertQueuingStrategyInit(init, name) {
+	if (init?.highWaterMark === undefined) {
+		throw new TypeError(
+			`Failed to construct '${name}': highWaterMark is required`,
+		);
+	}
+	return Number(init.highWaterMark);
+}
+
+// Methods aren't constructors and have no `prototype`, like the spec's size functions
+const byteLengthSize = {
+	/** @param {ArrayBufferView} chunk */
+	size(chunk) {
+		return chunk.byteLength;
+	},
+}.size;
+
+export class ByteLengthQueuingStrategy {
+	/**
+	 * @param {{ highWaterMark: number }} init
+	 */
+	constructor(init) {
+		this[_highWaterMark] = convertQueuingStrategyInit(
+			init,
+			"ByteLengthQueuingStrategy",
+		);
+	}
+
+	/** @returns {number} */
+	get highWaterMark() {
+		if (!(this instanceof ByteLengthQueuingStrategy)) {
+			throw new TypeError("Illegal invocation");
+		}
+		return this[_highWaterMark];
+	}
+
+	/** @returns {(chunk: ArrayBufferView) => number} */
+	get size() {
+		if (!(this instanceof ByteLengthQueuingStrategy)) {
+			throw new TypeError("Illegal invocation");
+		}
+		return byteLengthSize;
+	}
+}
+
+const countSize = {
+	size() {
+		return 1;
+	},
+}.size;
+
+export class CountQueuingStrategy {
+	/**
+	 * @param {{ highWaterMark: number }} init
+	 */
+	constructor(init) {
+		this[_highWaterMark] = convertQueuingStrategyInit(
+			init,
+			"CountQueuingStrategy",
+		);
+	}
+
+	/** @returns {number} */
+	get highWaterMark() {
+		if (!(this instanceof CountQueuingStrategy)) {
+			throw new TypeError("Illegal invocation");
+		}
+		return this[_highWaterMark];
+	}
+
+	/** @returns {() => number} */
+	get size() {
+		if (!(this instanceof CountQueuingStrategy)) {
+			throw new TypeError("Illegal invocation");
+		}
+		return countSize;
+	}
+}
+
+// Resources
+
+/**
+ * Closes the resource, unless it has already been closed
+ * @param {number} rid
+ */
+function tryClose(rid) {
+	try {
+		op_close(rid);
+	} catch {
+		// Already closed
+	}
+}
+
+/**
+ * Creates byte stream reading the resource, which supports BYOB readers
+ * @param {number} rid resource id
+ * @param {boolean} [autoClose=true] whether the resource gets closed once it's been read to the end or the stream got canceled
+ * @param {() => void} [onFinish] called once the resource has been read to the end, failed or the stream got canceled
+ * @returns {ReadableStream<Uint8Array>}
+ */
+export function readableStreamForRid(rid, autoClose = true, onFinish = undefined) {
+	return new ReadableStream({
+		type: "bytes",
+		autoAllocateChunkSize: DEFAULT_CHUNK_SIZE,
+		async pull(controller) {
+			const byobRequest = controller.byobRequest;
+			try {
+				const read = await op_read(rid, byobRequest.view);
+				if (read === 0) {
+					if (autoClose) tryClose(rid);
+					controller.close();
+					byobRequest.respond(0);
+					onFinish?.();
+				} else {
+					byobRequest.respond(read);
+				}
+			} catch (error) {
+				controller.error(error);
+				if (autoClose) tryClose(rid);
+				onFinish?.();
+			}
+		},
+		cancel() {
+			if (autoClose) tryClose(rid);
+			onFinish?.();
+		},
+	});
+}
+
+/**
+ * Creates stream writing to the resource
+ * @param {number} rid resource id
+ * @param {boolean} [autoClose=true] whether the resource gets closed along with the stream
+ * @returns {WritableStream<Uint8Array>}
+ */
+export function writableStreamForRid(rid, autoClose = true) {
+	return new WritableStream({
+		async write(chunk) {
+			let written = 0;
+			while (written < chunk.byteLength) {
+				written += await op_write(rid, chunk.subarray(written));
+			}
+		},
+		close() {
+			if (autoClose) tryClose(rid);
+		},
+		abort() {
+			if (autoClose) tryClose(rid);
+		},
+	});
+}
+
+/**
+ * Whether the stream has been read from or canceled, which is how fetch bodies get marked as used
+ * @param {ReadableStream} stream
+ * @returns {boolean}
+ */
+export function isReadableStreamDisturbed(stream) {
+	return stream[_disturbed];
+}
+
+export { isReadableStreamLocked };
+
+/**
+ * Errors the stream from outside of its underlying source, e.g. when the request it belongs to gets aborted
+ * @param {ReadableStream} stream
+ * @param {any} error
+ */
+export function errorReadableStream(stream, error) {
+	const controller = stream[_controller];
+	if (controller instanceof ReadableByteStreamController) {
+		readableByteStreamControllerError(controller, error);
+	} else {
+		readableStreamDefaultControllerError(controller, error);
+	}
+}
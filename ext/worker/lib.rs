@@ -0,0 +1,325 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    env,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use deno_core::{
+    error::{type_error, AnyError, JsError},
+    op2,
+    url::Url,
+    v8, AsyncRefCell, CancelFuture, CancelHandle, OpState, RcRef, Resource, ResourceId,
+};
+use sable_ext_permissions::{ChildPermissionsArg, Permissions, PermissionsContainer};
+use sable_ext_web::{MessageData, PostedMessage, ReceivedMessage};
+use serde::Serialize;
+use tokio::sync::{mpsc, watch, Notify};
+
+/// Uncaught error which ended the worker
+#[derive(Serialize)]
+pub struct WorkerError {
+    message: String,
+    filename: String,
+    lineno: i64,
+    colno: i64,
+}
+
+impl WorkerError {
+    fn new(error: &AnyError) -> Self {
+        let Some(error) = error.downcast_ref::<JsError>() else {
+            // Errors which didn't come from JavaScript, like failures to load the module
+            return Self {
+                message: error.to_string(),
+                filename: String::new(),
+                lineno: 0,
+                colno: 0,
+            };
+        };
+
+        let frame = error.frames.first();
+        Self {
+            message: error.exception_message.clone(),
+            filename: frame
+                .and_then(|frame| frame.file_name.clone())
+                .unwrap_or_default(),
+            lineno: frame.and_then(|frame| frame.line_number).unwrap_or(0),
+            colno: frame.and_then(|frame| frame.column_number).unwrap_or(0),
+        }
+    }
+}
+
+/// Event which a worker sends to its parent
+pub enum WorkerEvent {
+    Message(MessageData),
+    Error(WorkerError),
+}
+
+/// `WorkerEvent` with transferred ports added to the parent's resource table
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceivedWorkerEvent {
+    Message(ReceivedMessage),
+    Error(WorkerError),
+}
+
+/// Stops the worker, either from its parent or from inside of it
+#[derive(Clone, Default)]
+pub struct WorkerTermination(Arc<WorkerTerminationInner>);
+
+#[derive(Default)]
+struct WorkerTerminationInner {
+    terminated: AtomicBool,
+    /// Isolate of the worker, `None` until its runtime gets created
+    isolate: Mutex<Option<v8::IsolateHandle>>,
+    notify: Notify,
+}
+
+impl WorkerTermination {
+    /// Registers isolate of the worker, returns `false` if the worker has been terminated before it started
+    pub fn set_isolate(&self, isolate: v8::IsolateHandle) -> bool {
+        let mut slot = self.0.isolate.lock().unwrap();
+        if self.is_terminated() {
+            return false;
+        }
+        *slot = Some(isolate);
+        true
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.0.terminated.load(Ordering::SeqCst)
+    }
+
+    /// Stops the event loop of the worker once the currently running script finishes
+    pub fn close(&self) {
+        self.0.terminated.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Stops the worker right away, even if it's running a script
+    pub fn terminate(&self) {
+        let isolate = self.0.isolate.lock().unwrap();
+        self.close();
+        if let Some(isolate) = isolate.as_ref() {
+            isolate.terminate_execution();
+        }
+    }
+
+    /// Resolves once the worker gets closed or terminated
+    pub async fn terminated(&self) {
+        loop {
+            // Created before the check, so that a notification in between doesn't get lost
+            let notified = self.0.notify.notified();
+            if self.is_terminated() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Worker's side of the channels to its parent, kept in `OpState` of the worker
+#[derive(Clone)]
+pub struct WorkerScope {
+    name: String,
+    events: mpsc::UnboundedSender<WorkerEvent>,
+    messages: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<MessageData>>>,
+    /// Whether messages from the parent get dispatched, they wait until the main module gets evaluated
+    receiving: watch::Sender<bool>,
+    termination: WorkerTermination,
+}
+
+impl WorkerScope {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn termination(&self) -> &WorkerTermination {
+        &self.termination
+    }
+
+    /// Starts dispatching messages from the parent, so that handlers set up by the main module receive all of them
+    pub fn start_receiving(&self) {
+        self.receiving.send_replace(true);
+    }
+
+    /// Sends error which ended the worker to its parent
+    pub fn report_error(&self, error: &AnyError) {
+        let _ = self
+            .events
+            .send(WorkerEvent::Error(WorkerError::new(error)));
+    }
+}
+
+pub struct WorkerOptions {
+    pub main_module: Url,
+    pub permissions: Permissions,
+    pub scope: WorkerScope,
+}
+
+/// Starts runtime of a worker on a new thread
+///
+/// Runtimes get created by the embedder, which knows the snapshot and extensions they need
+#[derive(Clone)]
+pub struct CreateWorker(pub Arc<dyn Fn(WorkerOptions) -> Result<(), AnyError> + Send + Sync>);
+
+/// Worker created by `op_worker_create`, closing it terminates the worker
+pub struct WorkerResource {
+    messages: mpsc::UnboundedSender<MessageData>,
+    events: AsyncRefCell<mpsc::UnboundedReceiver<WorkerEvent>>,
+    termination: WorkerTermination,
+    /// Interrupts pending `op_worker_recv_event` when the worker gets terminated
+    cancel: CancelHandle,
+}
+
+impl Resource for WorkerResource {
+    fn name(&self) -> Cow<'_, str> {
+        "worker".into()
+    }
+
+    fn close(self: Rc<Self>) {
+        self.termination.terminate();
+        self.cancel.cancel();
+    }
+}
+
+/// Resolves specifier of the main module, relative paths are resolved against the current directory
+fn resolve_main_module(specifier: &str) -> Result<Url, AnyError> {
+    if let Ok(url) = Url::parse(specifier) {
+        return Ok(url);
+    }
+    Ok(deno_core::resolve_path(specifier, &env::current_dir()?)?)
+}
+
+/// Starts a worker, which gets permissions of the current runtime unless they're narrowed down
+#[op2]
+#[smi]
+pub fn op_worker_create(
+    state: &mut OpState,
+    #[string] specifier: String,
+    #[string] name: String,
+    #[serde] permissions: Option<ChildPermissionsArg>,
+) -> Result<ResourceId, AnyError> {
+    let main_module = resolve_main_module(&specifier)?;
+    let permissions = {
+        let parent = state.borrow::<PermissionsContainer>().0.borrow();
+        match permissions {
+            Some(permissions) => parent.create_child(&permissions)?,
+            None => parent.clone(),
+        }
+    };
+    let create_worker = state
+        .try_borrow::<CreateWorker>()
+        .cloned()
+        .ok_or_else(|| type_error("Workers can't be created in this runtime"))?;
+
+    let (message_sender, message_receiver) = mpsc::unbounded_channel();
+    let (event_sender, event_receiver) = mpsc::unbounded_channel();
+    let termination = WorkerTermination::default();
+
+    (create_worker.0)(WorkerOptions {
+        main_module,
+        permissions,
+        scope: WorkerScope {
+            name,
+            events: event_sender,
+            messages: Arc::new(tokio::sync::Mutex::new(message_receiver)),
+            receiving: watch::Sender::new(false),
+            termination: termination.clone(),
+        },
+    })?;
+
+    Ok(state.resource_table.add(WorkerResource {
+        messages: message_sender,
+        events: AsyncRefCell::new(event_receiver),
+        termination,
+        cancel: CancelHandle::new(),
+    }))
+}
+
+/// Sends message to the worker, messages to workers which have ended get dropped
+#[op2]
+pub fn op_worker_post_message(
+    state: &mut OpState,
+    #[smi] rid: ResourceId,
+    #[serde] message: PostedMessage,
+) -> Result<(), AnyError> {
+    let resource = state.resource_table.get::<WorkerResource>(rid)?;
+    let message = MessageData::from_posted(state, message)?;
+    let _ = resource.messages.send(message);
+    Ok(())
+}
+
+/// Waits for the next event from the worker, `null` once the worker has ended
+#[op2(async)]
+#[serde]
+pub async fn op_worker_recv_event(
+    state: Rc<RefCell<OpState>>,
+    #[smi] rid: ResourceId,
+) -> Result<Option<ReceivedWorkerEvent>, AnyError> {
+    let resource = state.borrow().resource_table.get::<WorkerResource>(rid)?;
+    let mut events = RcRef::map(&resource, |resource| &resource.events)
+        .borrow_mut()
+        .await;
+    let cancel = RcRef::map(&resource, |resource| &resource.cancel);
+    let Ok(Some(event)) = events.recv().or_cancel(cancel).await else {
+        return Ok(None);
+    };
+
+    Ok(Some(match event {
+        WorkerEvent::Message(message) => {
+            ReceivedWorkerEvent::Message(message.into_received(&mut state.borrow_mut()))
+        }
+        WorkerEvent::Error(error) => ReceivedWorkerEvent::Error(error),
+    }))
+}
+
+fn worker_scope(state: &OpState) -> Result<&WorkerScope, AnyError> {
+    state
+        .try_borrow::<WorkerScope>()
+        .ok_or_else(|| type_error("Only workers can communicate with their parent"))
+}
+
+#[op2]
+#[string]
+pub fn op_worker_scope_name(state: &OpState) -> Result<String, AnyError> {
+    Ok(worker_scope(state)?.name.clone())
+}
+
+#[op2]
+pub fn op_worker_scope_post_message(
+    state: &mut OpState,
+    #[serde] message: PostedMessage,
+) -> Result<(), AnyError> {
+    let events = worker_scope(state)?.events.clone();
+    let message = MessageData::from_posted(state, message)?;
+    let _ = events.send(WorkerEvent::Message(message));
+    Ok(())
+}
+
+/// Waits for the next message from the parent, `null` once the parent has terminated the worker
+#[op2(async)]
+#[serde]
+pub async fn op_worker_scope_recv_message(
+    state: Rc<RefCell<OpState>>,
+) -> Result<Option<ReceivedMessage>, AnyError> {
+    let (messages, mut receiving) = {
+        let state = state.borrow();
+        let scope = worker_scope(&state)?;
+        (scope.messages.clone(), scope.receiving.subscribe())
+    };
+    receiving.wait_for(|receiving| *receiving).await?;
+    let message = messages.lock().await.recv().await;
+    Ok(message.map(|message| message.into_received(&mut state.borrow_mut())))
+}
+
+/// Closes the worker from inside, once the currently running script finishes
+#[op2(fast)]
+pub fn op_worker_scope_close(state: &OpState) -> Result<(), AnyError> {
+    worker_scope(state)?.termination.close();
+    Ok(())
+}
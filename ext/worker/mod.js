@@ -0,0 +1,3 @@
+import { Worker } from "ext:sable/worker/worker.js";
+
+globalThis.Worker = Worker;
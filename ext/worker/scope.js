@@ -0,0 +1,56 @@
+import {
+	op_worker_scope_close,
+	op_worker_scope_name,
+	op_worker_scope_post_message,
+	op_worker_scope_recv_message,
+} from "ext:core/ops";
+import { defineEventHandler } from "ext:sable/web/events.js";
+import {
+	dispatchMessage,
+	serializeMessage,
+} from "ext:sable/web/message_port.js";
+
+// Evaluated only in workers, on top of the regular global scope
+// https://html.spec.whatwg.org/multipage/workers.html#dedicated-workers-and-the-dedicatedworkerglobalscope-interface
+
+const name = op_worker_scope_name();
+let closing = false;
+
+Object.defineProperty(globalThis, "name", {
+	get: () => name,
+	configurable: true,
+	enumerable: true,
+});
+
+/**
+ * Sends message to the parent of the worker
+ * @param {any} message
+ * @param {import("ext:sable/web/message_port.js").TransferOrOptions} [transferOrOptions]
+ */
+globalThis.postMessage = function postMessage(
+	message,
+	transferOrOptions = undefined,
+) {
+	if (arguments.length === 0) {
+		throw new TypeError("postMessage requires a message");
+	}
+	op_worker_scope_post_message(serializeMessage(message, transferOrOptions));
+};
+
+/** Ends the worker once the currently running task finishes */
+globalThis.close = function close() {
+	if (closing) return;
+	closing = true;
+	op_worker_scope_close();
+};
+
+defineEventHandler(globalThis, "message");
+defineEventHandler(globalThis, "messageerror");
+
+(async () => {
+	while (!closing) {
+		const message = await op_worker_scope_recv_message();
+		if (message === null || closing) break;
+		dispatchMessage(globalThis, message);
+	}
+})();
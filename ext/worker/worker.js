@@ -0,0 +1,163 @@
+import {
+	op_close,
+	op_worker_create,
+	op_worker_post_message,
+	op_worker_recv_event,
+} from "ext:core/ops";
+import {
+	defineEventHandler,
+	ErrorEvent,
+	EventTarget,
+	fireTrustedEvent,
+} from "ext:sable/web/events.js";
+import { reportError } from "ext:sable/web/errors.js";
+import {
+	dispatchMessage,
+	serializeMessage,
+} from "ext:sable/web/message_port.js";
+
+// https://html.spec.whatwg.org/multipage/workers.html
+
+/**
+ * @typedef {import("ext:sable/web/message_port.js").TransferOrOptions} TransferOrOptions
+ */
+
+/**
+ * @typedef {"inherit" | "none" | {
+ *   read?: boolean | string[];
+ *   write?: boolean | string[];
+ *   net?: boolean | string[];
+ *   env?: boolean | string[];
+ *   run?: boolean | string[];
+ *   sys?: boolean | string[];
+ * }} WorkerPermissions
+ *
+ * Kinds which aren't listed are inherited, lists can only contain resources which the parent has access to
+ */
+
+/**
+ * @typedef {{
+ *   type?: "classic" | "module";
+ *   name?: string;
+ *   permissions?: WorkerPermissions;
+ * }} WorkerOptions
+ */
+
+const _rid = Symbol("[[rid]]");
+const _name = Symbol("[[name]]");
+
+export class Worker extends EventTarget {
+	/** @type {number | null} Resource id of the worker, `null` once it has been terminated */
+	[_rid] = null;
+	/** @type {string} */
+	[_name];
+
+	/**
+	 * Starts the module on a new thread, workers have to be terminated for the runtime to exit
+	 * @param {string | URL} specifier
+	 * @param {WorkerOptions} [options]
+	 */
+	constructor(specifier, options = {}) {
+		super();
+		if (arguments.length === 0) {
+			throw new TypeError("Worker requires a module specifier");
+		}
+
+		const type = options?.type ?? "classic";
+		if (type !== "module") {
+			throw new TypeError(
+				`Worker type '${type}' is not supported, only module workers are`,
+			);
+		}
+
+		this[_name] = String(options.name ?? "");
+		this[_rid] = op_worker_create(
+			String(specifier),
+			this[_name],
+			options.permissions,
+		);
+		receiveEvents(this);
+	}
+
+	/**
+	 * @param {any} message
+	 * @param {TransferOrOptions} [transferOrOptions]
+	 */
+	postMessage(message, transferOrOptions = undefined) {
+		assertWorker(this);
+		if (arguments.length === 0) {
+			throw new TypeError("Worker.postMessage requires a message");
+		}
+		const serialized = serializeMessage(message, transferOrOptions);
+		if (this[_rid] !== null) {
+			op_worker_post_message(this[_rid], serialized);
+		}
+	}
+
+	/** Stops the worker right away, messages which it hasn't received get dropped */
+	terminate() {
+		assertWorker(this);
+		if (this[_rid] === null) return;
+		const rid = this[_rid];
+		this[_rid] = null;
+		try {
+			op_close(rid);
+		} catch {
+			// Worker has already ended on its own
+		}
+	}
+
+	get [Symbol.toStringTag]() {
+		return "Worker";
+	}
+}
+
+defineEventHandler(Worker.prototype, "message");
+defineEventHandler(Worker.prototype, "messageerror");
+defineEventHandler(Worker.prototype, "error");
+
+/**
+ * @param {any} worker
+ */
+function assertWorker(worker) {
+	if (!(worker instanceof Worker)) {
+		throw new TypeError("Illegal invocation");
+	}
+}
+
+/**
+ * Dispatches events sent by the worker until it ends
+ * @param {Worker} worker
+ */
+async function receiveEvents(worker) {
+	while (worker[_rid] !== null) {
+		const event = await op_worker_recv_event(worker[_rid]);
+		if (event === null) break;
+
+		if (event.message) {
+			dispatchMessage(worker, event.message);
+			continue;
+		}
+
+		const { message, filename, lineno, colno } = event.error;
+		const errorEvent = new ErrorEvent("error", {
+			cancelable: true,
+			message,
+			filename,
+			lineno,
+			colno,
+		});
+		// Errors which the worker's parent doesn't handle are uncaught in the parent as well
+		if (fireTrustedEvent(errorEvent, worker)) {
+			const name = worker[_name] ? ` "${worker[_name]}"` : "";
+			reportError(new Error(`Worker${name} failed: ${message}`));
+		}
+	}
+
+	if (worker[_rid] !== null) {
+		// Worker has ended on its own, its resource is no longer needed
+		const rid = worker[_rid];
+		worker[_rid] = null;
+		op_close(rid);
+	}
+}
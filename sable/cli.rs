@@ -2,18 +2,119 @@ extern crate clap;
 
 use std::str::FromStr;
 
-use clap::ArgAction;
-use sable_ext::extensions::runtime::RuntimeState;
-use std::process::ExitCode;
+use clap::{value_parser, ArgAction, ArgMatches};
+use sable_ext::extensions::{
+    permissions::PermissionsOptions,
+    runtime::RuntimeState,
+    testing::{BenchOptions, TestOptions},
+};
+use std::{env, path::PathBuf, process::ExitCode};
 
 use self::clap::{arg, Arg, Command};
 
 use crate::{
+    config::SableConfig,
+    errors::{format_error, is_unhandled_rejection, UNHANDLED_REJECTION_EXIT_CODE},
     sable_run,
-    tools::fmt::{fmt, FormatOptions},
+    tools::{
+        coverage::coverage,
+        fmt::{fmt, FormatOptions},
+        watch::{watch_run, watch_test},
+    },
     SableOptions,
 };
 
+/// Flags which grant permissions, with names and descriptions of values they take
+const PERMISSION_FLAGS: [(&str, &str, &str); 6] = [
+    (
+        "allow-read",
+        "PATH",
+        "Allow reading from the file system, optionally only from given paths",
+    ),
+    (
+        "allow-write",
+        "PATH",
+        "Allow writing to the file system, optionally only to given paths",
+    ),
+    (
+        "allow-net",
+        "HOST",
+        "Allow network access, optionally only to given hosts",
+    ),
+    (
+        "allow-env",
+        "VARIABLE",
+        "Allow access to environment variables, optionally only to given ones",
+    ),
+    (
+        "allow-run",
+        "COMMAND",
+        "Allow running subprocesses, optionally only given commands",
+    ),
+    (
+        "allow-sys",
+        "KIND",
+        "Allow access to system information, optionally only to given kinds",
+    ),
+];
+
+fn permission_args() -> Vec<Arg> {
+    let mut args: Vec<Arg> = PERMISSION_FLAGS
+        .iter()
+        .map(|(name, value_name, help)| {
+            Arg::new(*name)
+                .long(*name)
+                .value_name(*value_name)
+                .num_args(0..)
+                .value_delimiter(',')
+                .require_equals(true)
+                .help(*help)
+        })
+        .collect();
+
+    args.push(
+        Arg::new("allow-all")
+            .long("allow-all")
+            .short('A')
+            .action(ArgAction::SetTrue)
+            .help("Allow all permissions"),
+    );
+    args.push(
+        Arg::new("no-prompt")
+            .long("no-prompt")
+            .action(ArgAction::SetTrue)
+            .help("Deny permissions which haven't been granted, instead of asking for them"),
+    );
+
+    args
+}
+
+fn permissions_options(matches: &ArgMatches) -> PermissionsOptions {
+    if matches.get_flag("allow-all") {
+        return PermissionsOptions::allow_all();
+    }
+
+    // Flag without values allows everything
+    let allowed = |name: &str| {
+        matches.contains_id(name).then(|| {
+            matches
+                .get_many::<String>(name)
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default()
+        })
+    };
+
+    PermissionsOptions {
+        allow_read: allowed("allow-read"),
+        allow_write: allowed("allow-write"),
+        allow_net: allowed("allow-net"),
+        allow_env: allowed("allow-env"),
+        allow_run: allowed("allow-run"),
+        allow_sys: allowed("allow-sys"),
+        prompt: !matches.get_flag("no-prompt"),
+    }
+}
+
 pub fn cli() -> Command {
     let reload_cache_arg = Arg::new("reload-cache")
         .long("reload-cache")
@@ -22,6 +123,15 @@ pub fn cli() -> Command {
         .help("Reload cache of the ran module")
         .conflicts_with("clean-cache");
 
+    let watch_arg = Arg::new("watch")
+        .long("watch")
+        .value_name("PATH")
+        .value_parser(value_parser!(PathBuf))
+        .num_args(0..)
+        .value_delimiter(',')
+        .require_equals(true)
+        .help("Restart when loaded local modules change, optionally also when given paths change");
+
     let clean_cache_arg = Arg::new("clean-cache")
         .long("clean-cache")
         .action(ArgAction::SetTrue)
@@ -38,16 +148,34 @@ pub fn cli() -> Command {
                 .arg(arg!(<MODULE_PATH> "Module path to run"))
                 .arg_required_else_help(true)
                 .arg(&reload_cache_arg)
-                .arg(&clean_cache_arg),
+                .arg(&clean_cache_arg)
+                .arg(&watch_arg)
+                .args(permission_args()),
         )
         // TODO(Im-Beast): Automatically find and test *.test.{ts,js} files by default
         .subcommand(
             Command::new("test")
-                .about("Run tests in given module")
-                .arg(arg!(<MODULE_PATH> "Module path to test"))
+                .about("Run tests in given modules")
+                .arg(arg!(<MODULE_PATH>... "Module paths to test"))
                 .arg_required_else_help(true)
                 .arg(&reload_cache_arg)
-                .arg(&clean_cache_arg),
+                .arg(&clean_cache_arg)
+                .arg(watch_arg)
+                .args(permission_args())
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("MS")
+                        .value_parser(value_parser!(u64))
+                        .help("Time in milliseconds after which asynchronous tests time out, 0 disables timeouts"),
+                )
+                .arg(
+                    Arg::new("coverage")
+                        .long("coverage")
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Collect coverage of executed modules into given directory"),
+                ),
         )
         // TODO(Im-Beast): Automatically find and bench *.bench.{ts,js} files by default
         .subcommand(
@@ -56,7 +184,36 @@ pub fn cli() -> Command {
                 .arg(arg!(<MODULE_PATH> "Module path to bench"))
                 .arg_required_else_help(true)
                 .arg(reload_cache_arg)
-                .arg(clean_cache_arg),
+                .arg(clean_cache_arg)
+                .args(permission_args())
+                .arg(
+                    Arg::new("save-baseline")
+                        .long("save-baseline")
+                        .value_name("NAME")
+                        .help("Save benchmark results as a baseline with given name"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("NAME")
+                        .help("Compare benchmark results against a baseline with given name"),
+                )
+                .arg(
+                    Arg::new("regression-threshold")
+                        .long("regression-threshold")
+                        .value_name("PERCENT")
+                        .value_parser(value_parser!(f64))
+                        .help("Change of the mean past which a significantly slower benchmark fails the run"),
+                ),
+        )
+        .subcommand(
+            Command::new("coverage")
+                .about("Print and generate lcov and HTML reports of collected coverage")
+                .arg(
+                    arg!(<DIR> "Directory with coverage collected using `sable test --coverage`")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg_required_else_help(true),
         )
         .subcommand(
             Command::new("fmt")
@@ -79,19 +236,102 @@ pub async fn parse_cli() -> ExitCode {
         Some((subcommand @ "run", sub_matches))
         | Some((subcommand @ "test", sub_matches))
         | Some((subcommand @ "bench", sub_matches)) => {
-            let module_path = sub_matches
-                .get_one::<String>("MODULE_PATH")
-                .expect("Required");
+            let module_paths: Vec<String> = sub_matches
+                .get_many::<String>("MODULE_PATH")
+                .expect("Required")
+                .cloned()
+                .collect();
+
+            let config = match SableConfig::load(&env::current_dir().unwrap()) {
+                Ok(config) => config,
+                Err(error) => {
+                    eprintln!("{}", format_error(&error));
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let mut test_options = TestOptions::default();
+            if let Some(timeout) = sub_matches
+                .try_get_one::<u64>("timeout")
+                .ok()
+                .flatten()
+                .or(config.test.timeout.as_ref())
+            {
+                test_options.timeout = *timeout;
+            }
 
-            let options = SableOptions {
+            let mut bench_options = BenchOptions::default();
+            if let Some(regression_threshold) = sub_matches
+                .try_get_one::<f64>("regression-threshold")
+                .ok()
+                .flatten()
+                .or(config.bench.regression_threshold.as_ref())
+            {
+                bench_options.regression_threshold = *regression_threshold;
+            }
+
+            let mut options = SableOptions {
                 reload_cache: sub_matches.get_flag("reload-cache"),
                 clean_cache: sub_matches.get_flag("clean-cache"),
                 state: RuntimeState::from_str(subcommand).unwrap(),
+                permissions: permissions_options(sub_matches),
+                test_options,
+                bench_options,
+                baseline: sub_matches
+                    .try_get_one::<String>("baseline")
+                    .ok()
+                    .flatten()
+                    .cloned(),
+                save_baseline: sub_matches
+                    .try_get_one::<String>("save-baseline")
+                    .ok()
+                    .flatten()
+                    .cloned(),
+                coverage_dir: sub_matches
+                    .try_get_one::<PathBuf>("coverage")
+                    .ok()
+                    .flatten()
+                    .cloned(),
+                loaded_modules: None,
             };
 
-            if let Err(error) = sable_run(&module_path, options).await {
-                // TODO: better looking errors
-                eprintln!("error: {}", error);
+            if sub_matches.try_contains_id("watch").unwrap_or(false) {
+                let watched_paths: Vec<PathBuf> = sub_matches
+                    .get_many::<PathBuf>("watch")
+                    .map(|paths| paths.cloned().collect())
+                    .unwrap_or_default();
+
+                let result = if subcommand == "test" {
+                    watch_test(&module_paths, options, &watched_paths).await
+                } else {
+                    watch_run(&module_paths[0], options, &watched_paths).await
+                };
+
+                if let Err(error) = result {
+                    eprintln!("{}", format_error(&error));
+                    code = ExitCode::FAILURE;
+                }
+                return code;
+            }
+
+            for module_path in &module_paths {
+                if let Err(error) = sable_run(module_path, options.clone()).await {
+                    eprintln!("{}", format_error(&error));
+                    code = if is_unhandled_rejection(&error) {
+                        ExitCode::from(UNHANDLED_REJECTION_EXIT_CODE)
+                    } else {
+                        ExitCode::FAILURE
+                    };
+                }
+                // Cache only has to be cleaned once
+                options.clean_cache = false;
+            }
+        }
+        Some(("coverage", sub_matches)) => {
+            let dir = sub_matches.get_one::<PathBuf>("DIR").expect("Required");
+
+            if let Err(error) = coverage(dir).await {
+                eprintln!("{}", format_error(&error));
                 code = ExitCode::FAILURE;
             }
         }
@@ -103,7 +343,7 @@ pub async fn parse_cli() -> ExitCode {
                 .unwrap_or(&default_glob);
 
             if let Err(error) = fmt(FormatOptions::new(check, glob)).await {
-                eprintln!("error: {}", error);
+                eprintln!("{}", format_error(&error));
                 code = ExitCode::FAILURE;
             }
         }
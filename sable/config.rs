@@ -0,0 +1,52 @@
+use deno_core::{anyhow::Error, error::generic_error, serde_json};
+use serde::Deserialize;
+use std::path::Path;
+
+pub const CONFIG_FILE_NAME: &str = "sable.json";
+
+/// Configuration read from `sable.json` in the current working directory
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SableConfig {
+    pub test: TestConfig,
+    pub bench: BenchConfig,
+}
+
+/// Configuration of `sable test`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TestConfig {
+    /// Time in milliseconds after which asynchronous tests time out, 0 disables timeouts
+    pub timeout: Option<u64>,
+}
+
+/// Configuration of `sable bench`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct BenchConfig {
+    /// Change of the mean in percent, past which a slower benchmark counts as a regression
+    pub regression_threshold: Option<f64>,
+}
+
+impl SableConfig {
+    /// Reads config from given directory, if there's no config file it returns the default one
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        let path = dir.join(CONFIG_FILE_NAME);
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        serde_json::from_str(&contents).map_err(|error| {
+            generic_error(format!(
+                "Failed parsing config at {}: {}",
+                path.display(),
+                error
+            ))
+        })
+    }
+}
@@ -0,0 +1,555 @@
+use std::{
+    collections::HashMap,
+    env, fmt,
+    fmt::Write,
+    io::{self, IsTerminal},
+    sync::Arc,
+};
+
+use deno_core::error::{format_file_name, AnyError, JsError, JsStackFrame};
+use owo_colors::{OwoColorize, Style};
+
+use crate::loader::{ModuleLoadError, ModuleSources};
+
+/// Amount of lines shown before and after the throwing line in code frames
+const CODE_FRAME_CONTEXT_LINES: usize = 2;
+
+/// Exit code of the process, when a promise rejection wasn't handled
+pub const UNHANDLED_REJECTION_EXIT_CODE: u8 = 3;
+
+/// Prefix of the exception message, which V8 uses for rejections without a handler
+const IN_PROMISE_PREFIX: &str = "Uncaught (in promise) ";
+
+/// Exception which hasn't been caught, along with code of the modules in its stack trace
+#[derive(Debug)]
+pub struct UncaughtError {
+    pub error: JsError,
+    /// Whether it's a promise rejection, which no handler or event listener has handled
+    pub in_promise: bool,
+    /// Code keyed by module URL, as it has been executed
+    sources: HashMap<String, Arc<str>>,
+}
+
+impl UncaughtError {
+    fn new(error: JsError, in_promise: bool, module_sources: &ModuleSources) -> Self {
+        let mut sources = HashMap::new();
+        collect_sources(&error, module_sources, &mut sources);
+        Self {
+            error,
+            in_promise,
+            sources,
+        }
+    }
+}
+
+impl fmt::Display for UncaughtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for UncaughtError {}
+
+fn collect_sources(
+    error: &JsError,
+    module_sources: &ModuleSources,
+    sources: &mut HashMap<String, Arc<str>>,
+) {
+    for file_name in error
+        .frames
+        .iter()
+        .filter_map(|frame| frame.file_name.as_ref())
+    {
+        if let Some(source) = module_sources.get(file_name) {
+            sources.insert(file_name.clone(), source);
+        }
+    }
+
+    let nested = error
+        .aggregated
+        .iter()
+        .flatten()
+        .chain(error.cause.as_deref());
+    for error in nested {
+        collect_sources(error, module_sources, sources);
+    }
+}
+
+/// Turns JavaScript error which terminated the runtime into `UncaughtError`
+///
+/// `in_promise` tells whether the runtime reported it as an unhandled promise rejection
+pub fn uncaught_error(
+    error: AnyError,
+    in_promise: bool,
+    module_sources: &ModuleSources,
+) -> AnyError {
+    match error.downcast::<JsError>() {
+        Ok(error) => UncaughtError::new(error, in_promise, module_sources).into(),
+        Err(error) => error,
+    }
+}
+
+/// Styles of the printed errors, which are all plain when colors are disabled
+struct Styles {
+    error: Style,
+    bold: Style,
+    dimmed: Style,
+    location: Style,
+}
+
+impl Styles {
+    fn new(colors: bool) -> Self {
+        if !colors {
+            return Self {
+                error: Style::new(),
+                bold: Style::new(),
+                dimmed: Style::new(),
+                location: Style::new(),
+            };
+        }
+
+        Self {
+            error: Style::new().red().bold(),
+            bold: Style::new().bold(),
+            dimmed: Style::new().dimmed(),
+            location: Style::new().cyan(),
+        }
+    }
+}
+
+/// Whether stderr gets colored, which can be disabled using `NO_COLOR`
+///
+/// https://no-color.org
+fn use_colors() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color && io::stderr().is_terminal()
+}
+
+fn is_internal(frame: &JsStackFrame) -> bool {
+    frame
+        .file_name
+        .as_deref()
+        .is_some_and(|file_name| file_name.trim_start_matches('[').starts_with("ext:"))
+}
+
+fn format_location(frame: &JsStackFrame) -> String {
+    if frame.is_promise_all {
+        let index = frame.promise_index.unwrap_or_default();
+        return format!("Promise.all (index {})", index);
+    }
+
+    let Some(file_name) = &frame.file_name else {
+        return if frame.is_native {
+            "native".to_string()
+        } else {
+            "<anonymous>".to_string()
+        };
+    };
+
+    let mut location = format_file_name(file_name);
+    if let Some(line_number) = frame.line_number {
+        write!(location, ":{}", line_number).unwrap();
+        if let Some(column_number) = frame.column_number {
+            write!(location, ":{}", column_number).unwrap();
+        }
+    }
+    location
+}
+
+fn format_function_name(frame: &JsStackFrame) -> Option<String> {
+    let function_name = match (&frame.type_name, &frame.function_name, &frame.method_name) {
+        (_, Some(function_name), _) if frame.is_constructor => format!("new {}", function_name),
+        (Some(type_name), Some(function_name), _) if frame.is_top_level != Some(true) => {
+            if function_name.starts_with(type_name.as_str()) {
+                function_name.clone()
+            } else {
+                format!("{}.{}", type_name, function_name)
+            }
+        }
+        (Some(type_name), None, Some(method_name)) => format!("{}.{}", type_name, method_name),
+        (_, Some(function_name), _) => function_name.clone(),
+        _ => return None,
+    };
+
+    if frame.is_async {
+        Some(format!("async {}", function_name))
+    } else {
+        Some(function_name)
+    }
+}
+
+fn format_frame(frame: &JsStackFrame, styles: &Styles) -> String {
+    let location = format_location(frame);
+    let function_name = format_function_name(frame);
+
+    if is_internal(frame) {
+        let frame = match function_name {
+            Some(function_name) => format!("at {} ({})", function_name, location),
+            None => format!("at {}", location),
+        };
+        return frame.style(styles.dimmed).to_string();
+    }
+
+    match function_name {
+        Some(function_name) => format!(
+            "{} {} ({})",
+            "at".style(styles.dimmed),
+            function_name.style(styles.bold),
+            location.style(styles.location)
+        ),
+        None => format!(
+            "{} {}",
+            "at".style(styles.dimmed),
+            location.style(styles.location)
+        ),
+    }
+}
+
+/// Renders lines around the throwing line with a caret pointing at the throwing column
+fn format_code_frame(
+    error: &JsError,
+    sources: &HashMap<String, Arc<str>>,
+    styles: &Styles,
+) -> Option<String> {
+    let (index, frame) = error
+        .frames
+        .iter()
+        .enumerate()
+        .find(|(_, frame)| !is_internal(frame))?;
+    let line_number = usize::try_from(frame.line_number?).ok()?;
+    let column_number = usize::try_from(frame.column_number.unwrap_or(1)).ok()?;
+
+    // Positions in the stack trace point into the executed code, which might differ from the file on disk
+    let source = frame
+        .file_name
+        .as_ref()
+        .and_then(|file_name| sources.get(file_name));
+
+    let lines: Vec<(usize, &str)> = match source {
+        Some(source) => source
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .skip(line_number.saturating_sub(CODE_FRAME_CONTEXT_LINES + 1))
+            .take(CODE_FRAME_CONTEXT_LINES * 2 + 1)
+            .collect(),
+        None if error.source_line_frame_index == Some(index) => {
+            vec![(line_number, error.source_line.as_deref()?)]
+        }
+        None => return None,
+    };
+
+    if !lines.iter().any(|(number, _)| *number == line_number) {
+        return None;
+    }
+
+    let gutter_width = lines.last()?.0.to_string().len();
+    let mut code_frame = String::new();
+    for (number, line) in lines {
+        let gutter = format!("{:>gutter_width$} |", number);
+        if number == line_number {
+            writeln!(code_frame, "{} {}", gutter.style(styles.bold), line).unwrap();
+
+            // Keep tabs, so caret lines up with the code
+            let padding: String = line
+                .chars()
+                .take(column_number.saturating_sub(1))
+                .map(|char| if char == '\t' { '\t' } else { ' ' })
+                .collect();
+            let empty_gutter = format!("{:>gutter_width$} |", "");
+            writeln!(
+                code_frame,
+                "{} {}{}",
+                empty_gutter.style(styles.dimmed),
+                padding,
+                "^".style(styles.error)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                code_frame,
+                "{} {}",
+                gutter.style(styles.dimmed),
+                line.style(styles.dimmed)
+            )
+            .unwrap();
+        }
+    }
+
+    Some(code_frame)
+}
+
+fn format_js_error_inner(
+    error: &JsError,
+    sources: &HashMap<String, Arc<str>>,
+    styles: &Styles,
+    indent: usize,
+    output: &mut String,
+) {
+    let padding = " ".repeat(indent);
+
+    let code_frame = format_code_frame(error, sources, styles);
+    if let Some(code_frame) = code_frame {
+        for line in code_frame.lines() {
+            writeln!(output, "{}{}", padding, line).unwrap();
+        }
+    }
+
+    for frame in &error.frames {
+        writeln!(output, "{}    {}", padding, format_frame(frame, styles)).unwrap();
+    }
+
+    if let Some(aggregated) = &error.aggregated {
+        for (index, error) in aggregated.iter().enumerate() {
+            writeln!(
+                output,
+                "{}  {} {}",
+                padding,
+                format!("[{}]", index).style(styles.dimmed),
+                format_header(error, styles)
+            )
+            .unwrap();
+            format_js_error_inner(error, sources, styles, indent + 4, output);
+        }
+    }
+
+    if let Some(cause) = &error.cause {
+        writeln!(
+            output,
+            "{}{} {}",
+            padding,
+            "Caused by:".style(styles.bold),
+            format_header(cause, styles)
+        )
+        .unwrap();
+        format_js_error_inner(cause, sources, styles, indent, output);
+    }
+}
+
+/// Returns name and message of the error, or the exception message for values which aren't errors
+fn format_header(error: &JsError, styles: &Styles) -> String {
+    match (&error.name, &error.message) {
+        (Some(name), Some(message)) if !message.is_empty() => {
+            format!("{}: {}", name.style(styles.error), message)
+        }
+        (Some(name), _) => name.style(styles.error).to_string(),
+        _ => error
+            .exception_message
+            .trim_start_matches(IN_PROMISE_PREFIX)
+            .trim_start_matches("Uncaught ")
+            .to_string(),
+    }
+}
+
+fn format_js_error(
+    error: &JsError,
+    in_promise: bool,
+    sources: &HashMap<String, Arc<str>>,
+    styles: &Styles,
+) -> String {
+    let uncaught = if in_promise {
+        "Uncaught (in promise)"
+    } else {
+        "Uncaught"
+    };
+    let mut output = format!(
+        "{} {} {}\n",
+        "error:".style(styles.error),
+        uncaught,
+        format_header(error, styles)
+    );
+    format_js_error_inner(error, sources, styles, 0, &mut output);
+    output.trim_end().to_string()
+}
+
+fn format_module_load_error(error: &ModuleLoadError, styles: &Styles) -> String {
+    let mut output = format!("{} {:#}", "error:".style(styles.error), error.error);
+
+    if let Some(specifier) = &error.specifier {
+        write!(
+            output,
+            "\n    {} {}",
+            "while loading".style(styles.dimmed),
+            specifier.style(styles.location)
+        )
+        .unwrap();
+    }
+    for importer in &error.import_chain {
+        write!(
+            output,
+            "\n    {} {}",
+            "imported from".style(styles.dimmed),
+            importer.style(styles.location)
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+/// Whether error was caused by a promise rejection, which no handler or event listener has handled
+pub fn is_unhandled_rejection(error: &AnyError) -> bool {
+    error
+        .downcast_ref::<UncaughtError>()
+        .is_some_and(|error| error.in_promise)
+}
+
+fn format_error_with_styles(error: &AnyError, styles: &Styles) -> String {
+    if let Some(error) = error.downcast_ref::<UncaughtError>() {
+        return format_js_error(&error.error, error.in_promise, &error.sources, styles);
+    }
+
+    if let Some(error) = error.downcast_ref::<JsError>() {
+        return format_js_error(error, false, &HashMap::new(), styles);
+    }
+
+    if let Some(error) = error.downcast_ref::<ModuleLoadError>() {
+        return format_module_load_error(error, styles);
+    }
+
+    format!("{} {:#}", "error:".style(styles.error), error)
+}
+
+/// Formats error for printing in the terminal
+pub fn format_error(error: &AnyError) -> String {
+    format_error_with_styles(error, &Styles::new(use_colors()))
+}
+
+#[cfg(test)]
+mod tests {
+    use deno_core::{error::generic_error, ModuleSpecifier};
+
+    use super::*;
+
+    const URL: &str = "file:///project/main.js";
+
+    fn js_error(message: &str, frames: Vec<JsStackFrame>) -> JsError {
+        JsError {
+            name: Some("Error".to_string()),
+            message: Some(message.to_string()),
+            stack: None,
+            cause: None,
+            exception_message: format!("Uncaught Error: {}", message),
+            frames,
+            source_line: None,
+            source_line_frame_index: None,
+            aggregated: None,
+        }
+    }
+
+    fn frame(file_name: &str, line_number: i64, column_number: i64) -> JsStackFrame {
+        JsStackFrame::from_location(
+            Some(file_name.to_string()),
+            Some(line_number),
+            Some(column_number),
+        )
+    }
+
+    fn uncaught(error: JsError, source: &str) -> AnyError {
+        UncaughtError {
+            error,
+            in_promise: false,
+            sources: HashMap::from([(URL.to_string(), Arc::from(source))]),
+        }
+        .into()
+    }
+
+    #[test]
+    fn code_frame_uses_executed_source() {
+        let source = "const a = 1;\nconst b = 2;\n\tthrow new Error(\"boom\");\nconst c = 3;\n";
+        let error = uncaught(js_error("boom", vec![frame(URL, 3, 2)]), source);
+
+        assert_eq!(
+            format_error_with_styles(&error, &Styles::new(false)),
+            [
+                "error: Uncaught Error: boom",
+                "1 | const a = 1;",
+                "2 | const b = 2;",
+                "3 | \tthrow new Error(\"boom\");",
+                "  | \t^",
+                "4 | const c = 3;",
+                "    at file:///project/main.js:3:2",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn code_frame_skips_internal_frames() {
+        let frames = vec![frame("ext:sable/web/events.js", 10, 5), frame(URL, 1, 7)];
+        let error = uncaught(js_error("boom", frames), "foo(); bar();");
+
+        let output = format_error_with_styles(&error, &Styles::new(false));
+        assert!(output.contains("1 | foo(); bar();\n  |       ^\n"));
+        assert!(output.contains("at ext:sable/web/events.js:10:5"));
+    }
+
+    #[test]
+    fn code_frame_falls_back_to_source_line() {
+        let mut error = js_error("boom", vec![frame(URL, 7, 1)]);
+        error.source_line = Some("boom();".to_string());
+        error.source_line_frame_index = Some(0);
+
+        let output = format_error_with_styles(&error.into(), &Styles::new(false));
+        assert!(output.contains("7 | boom();\n  | ^\n"));
+    }
+
+    #[test]
+    fn colors_can_be_disabled() {
+        let error = uncaught(js_error("boom", vec![frame(URL, 1, 1)]), "boom();");
+
+        assert!(format_error_with_styles(&error, &Styles::new(true)).contains('\x1b'));
+        assert!(!format_error_with_styles(&error, &Styles::new(false)).contains('\x1b'));
+    }
+
+    #[test]
+    fn only_marked_rejections_are_unhandled() {
+        // Message alone doesn't make the error an unhandled rejection
+        let mut error = js_error("boom", vec![]);
+        error.exception_message = format!("{}Error: boom", IN_PROMISE_PREFIX);
+        assert!(!is_unhandled_rejection(&error.clone().into()));
+
+        let rejection = UncaughtError::new(error, true, &ModuleSources::default()).into();
+        assert!(is_unhandled_rejection(&rejection));
+        assert_eq!(
+            format_error_with_styles(&rejection, &Styles::new(false)),
+            "error: Uncaught (in promise) Error: boom"
+        );
+
+        let exception =
+            UncaughtError::new(js_error("boom", vec![]), false, &ModuleSources::default());
+        assert!(!is_unhandled_rejection(&exception.into()));
+    }
+
+    #[test]
+    fn causes_are_formatted() {
+        let mut error = js_error("outer", vec![]);
+        error.cause = Some(Box::new(js_error("inner", vec![])));
+
+        assert_eq!(
+            format_error_with_styles(&error.into(), &Styles::new(false)),
+            "error: Uncaught Error: outer\nCaused by: Error: inner"
+        );
+    }
+
+    #[test]
+    fn module_load_errors_show_import_chain() {
+        let specifier = |path: &str| ModuleSpecifier::parse(&format!("file:///{}", path)).unwrap();
+        let error = ModuleLoadError {
+            error: generic_error("Not found"),
+            specifier: Some(specifier("missing.js")),
+            import_chain: vec![specifier("lib.js"), specifier("main.js")],
+        };
+
+        assert_eq!(
+            format_error_with_styles(&error.into(), &Styles::new(false)),
+            [
+                "error: Not found",
+                "    while loading file:///missing.js",
+                "    imported from file:///lib.js",
+                "    imported from file:///main.js",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,10 +1,11 @@
-use std::sync::Arc;
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc, sync::Arc};
 
 use deno_ast::MediaType;
 use deno_core::{
     anyhow::Error, error::generic_error, futures::FutureExt, ModuleCodeBytes, ModuleLoadResponse,
     ModuleLoader, ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType, RequestedModuleType,
 };
+use sable_ext::extensions::permissions::PermissionsContainer;
 use tokio::fs;
 
 use crate::{module_cache::ModuleCache, SableOptions};
@@ -12,6 +13,83 @@ use crate::{module_cache::ModuleCache, SableOptions};
 pub struct SableModuleLoader {
     pub module_cache: Arc<ModuleCache>,
     pub options: SableOptions,
+    pub permissions: PermissionsContainer,
+    /// Module which first imported given module, used to show how failed modules were reached
+    pub importers: RefCell<HashMap<ModuleSpecifier, ModuleSpecifier>>,
+    pub sources: ModuleSources,
+}
+
+/// Code of loaded modules keyed by their URL, exactly as it has been given to V8
+#[derive(Clone, Default)]
+pub struct ModuleSources(Rc<RefCell<HashMap<String, Arc<str>>>>);
+
+impl ModuleSources {
+    fn insert(&self, specifier: &ModuleSpecifier, code: &ModuleSourceCode) {
+        let code = String::from_utf8_lossy(code.as_bytes());
+        self.0
+            .borrow_mut()
+            .insert(specifier.to_string(), Arc::from(code));
+    }
+
+    pub fn get(&self, url: &str) -> Option<Arc<str>> {
+        self.0.borrow().get(url).cloned()
+    }
+}
+
+/// Error which happened while resolving or loading a module
+#[derive(Debug)]
+pub struct ModuleLoadError {
+    pub error: Error,
+    /// Module which failed to load, if it has been resolved
+    pub specifier: Option<ModuleSpecifier>,
+    /// Modules which led to the failed one, starting with the one that imported it
+    pub import_chain: Vec<ModuleSpecifier>,
+}
+
+impl fmt::Display for ModuleLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.error)?;
+        if let Some(specifier) = &self.specifier {
+            write!(f, "\n    while loading {}", specifier)?;
+        }
+        for importer in &self.import_chain {
+            write!(f, "\n    imported from {}", importer)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ModuleLoadError {}
+
+impl SableModuleLoader {
+    pub fn new(
+        module_cache: Arc<ModuleCache>,
+        options: SableOptions,
+        permissions: PermissionsContainer,
+        sources: ModuleSources,
+    ) -> Self {
+        Self {
+            module_cache,
+            options,
+            permissions,
+            importers: Default::default(),
+            sources,
+        }
+    }
+
+    /// Returns given module followed by modules which transitively imported it
+    fn import_chain(&self, specifier: &ModuleSpecifier) -> Vec<ModuleSpecifier> {
+        let importers = self.importers.borrow();
+        let mut chain = vec![specifier.clone()];
+        while let Some(importer) = importers.get(chain.last().unwrap()) {
+            // Guard against import cycles
+            if chain.contains(importer) {
+                break;
+            }
+            chain.push(importer.clone());
+        }
+        chain
+    }
 }
 
 fn media_type_to_module_type(media_type: &MediaType) -> Result<ModuleType, Error> {
@@ -36,7 +114,32 @@ impl ModuleLoader for SableModuleLoader {
         referrer: &str,
         _kind: deno_core::ResolutionKind,
     ) -> Result<ModuleSpecifier, deno_core::error::AnyError> {
-        deno_core::resolve_import(specifier, referrer).map_err(|e| e.into())
+        let referrer = ModuleSpecifier::parse(referrer).ok();
+
+        let resolved = deno_core::resolve_import(
+            specifier,
+            referrer.as_ref().map_or(".", |referrer| referrer.as_str()),
+        );
+        match (resolved, referrer) {
+            (Ok(resolved), Some(referrer)) => {
+                if resolved != referrer {
+                    self.importers
+                        .borrow_mut()
+                        .entry(resolved.clone())
+                        .or_insert(referrer);
+                }
+                Ok(resolved)
+            }
+            (Ok(resolved), None) => Ok(resolved),
+            (Err(error), referrer) => Err(ModuleLoadError {
+                error: error.into(),
+                specifier: None,
+                import_chain: referrer
+                    .map(|referrer| self.import_chain(&referrer))
+                    .unwrap_or_default(),
+            }
+            .into()),
+        }
     }
 
     fn load(
@@ -49,14 +152,35 @@ impl ModuleLoader for SableModuleLoader {
         let module_specifier = module_specifier.clone();
         let module_cache = self.module_cache.clone();
         let reload_cache = self.options.reload_cache;
+        let loaded_modules = self.options.loaded_modules.clone();
+        let sources = self.sources.clone();
+        let import_chain = self.import_chain(&module_specifier).split_off(1);
 
+        // Remote modules count as network access, even if they're cached
+        if let "http" | "https" = module_specifier.scheme() {
+            if let Err(error) = self
+                .permissions
+                .check_net_url(&module_specifier, "import()")
+            {
+                return ModuleLoadResponse::Sync(Err(ModuleLoadError {
+                    error,
+                    specifier: Some(module_specifier),
+                    import_chain,
+                }
+                .into()));
+            }
+        }
+
+        let specifier = module_specifier.clone();
         let module_source = async move {
             if !reload_cache {
                 if let Ok(source_code) = module_cache.get(&module_specifier).await {
                     println!("Using cached {}", module_specifier);
+                    let source_code = ModuleSourceCode::Bytes(ModuleCodeBytes::Boxed(source_code));
+                    sources.insert(&module_specifier, &source_code);
                     return Ok(ModuleSource::new(
                         media_type_to_module_type(&MediaType::from_specifier(&module_specifier))?,
-                        ModuleSourceCode::Bytes(ModuleCodeBytes::Boxed(source_code)),
+                        source_code,
                         &module_specifier,
                         None,
                     ));
@@ -95,6 +219,11 @@ impl ModuleLoader for SableModuleLoader {
                         ))
                     })?;
 
+                    if let Some(loaded_modules) = &loaded_modules {
+                        // Receiver is gone once the watcher stops caring about this run
+                        let _ = loaded_modules.send(path.clone());
+                    }
+
                     let file_contents = fs::read(path).await?;
                     let source_code = ModuleSourceCode::Bytes(ModuleCodeBytes::Boxed(
                         file_contents.into_boxed_slice(),
@@ -120,15 +249,71 @@ impl ModuleLoader for SableModuleLoader {
                 )));
             }
 
+            sources.insert(&module_specifier, &source_code);
             Ok(ModuleSource::new(
                 module_type,
                 source_code,
                 &module_specifier,
                 None,
             ))
-        }
-        .boxed_local();
+        };
+
+        ModuleLoadResponse::Async(
+            async move {
+                module_source.await.map_err(|error| {
+                    ModuleLoadError {
+                        error,
+                        specifier: Some(specifier),
+                        import_chain,
+                    }
+                    .into()
+                })
+            }
+            .boxed_local(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use sable_ext::extensions::permissions::{Permissions, PermissionsOptions};
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::test_options;
+
+    #[tokio::test]
+    async fn loaded_local_modules_are_reported() {
+        let path = env::temp_dir().join(format!("sable_loader_{}.js", std::process::id()));
+        fs::write(&path, "export {};").unwrap();
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut options = test_options();
+        options.reload_cache = true;
+        options.loaded_modules = Some(sender);
+        let permissions = PermissionsOptions::allow_all();
+        let loader = SableModuleLoader::new(
+            Arc::new(ModuleCache::new(env::temp_dir().join("sable_loader_cache"))),
+            options,
+            PermissionsContainer::new(Permissions::new(&permissions)),
+            ModuleSources::default(),
+        );
+
+        let specifier = ModuleSpecifier::from_file_path(&path).unwrap();
+        let ModuleLoadResponse::Async(response) =
+            loader.load(&specifier, None, false, RequestedModuleType::None)
+        else {
+            panic!("Local module got loaded synchronously");
+        };
+        let source = response.await;
+        fs::remove_file(&path).unwrap();
 
-        ModuleLoadResponse::Async(module_source)
+        assert!(source.is_ok());
+        assert_eq!(receiver.try_recv().ok(), Some(path));
+        assert!(loader.sources.get(specifier.as_str()).is_some());
+        // Only local modules are watched
+        assert!(receiver.try_recv().is_err());
     }
 }
@@ -2,41 +2,88 @@ extern crate deno_core;
 extern crate sable_ext;
 
 use deno_core::{
-    error::AnyError, url::Url, Extension, JsRuntime, OpMetricsSummaryTracker, RuntimeOptions,
+    anyhow::bail, error::AnyError, url::Url, v8, Extension, JsRuntime, OpMetricsSummaryTracker,
+    RuntimeOptions, SharedArrayBufferStore,
 };
-use loader::SableModuleLoader;
+use errors::uncaught_error;
+use loader::{ModuleSources, SableModuleLoader};
 use std::{
     env,
     hash::{DefaultHasher, Hash, Hasher},
+    mem,
     path::PathBuf,
+    pin::pin,
     process::ExitCode,
     rc::Rc,
     sync::Arc,
 };
 
 mod cli;
+mod config;
+mod errors;
 mod loader;
 mod module_cache;
 mod tools;
 mod utils;
+mod worker;
 
 use cli::parse_cli;
 use module_cache::ModuleCache;
+use tools::{
+    bench::{baseline_path, load_baseline, save_baseline},
+    coverage::CoverageCollector,
+    watch::LoadedModulesSender,
+};
+use worker::{create_worker_callback, SharedRuntimeState};
 
 use sable_ext::extensions::{
-    runtime::RuntimeState, sable, sable_cleanup, storage::LocalStoragePath,
+    errors::get_error_class_name,
+    permissions::{Permissions, PermissionsContainer, PermissionsOptions},
+    runtime::RuntimeState,
+    sable, sable_cleanup,
+    storage::LocalStoragePath,
+    testing::{
+        BenchOptions, BenchReport, ModuleFinishCallback, OpNames, TestFailures, TestOptions,
+    },
+    web::{BlobStore, BroadcastHub, GlobalEventCallback, UnhandledRejection},
 };
 
 static RUNTIME_SNAPSHOT: &[u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/SABLE_RUNTIME_SNAPSHOT.bin"));
 
+#[derive(Clone)]
 pub struct SableOptions {
     clean_cache: bool,
     reload_cache: bool,
     state: RuntimeState,
+    permissions: PermissionsOptions,
+    test_options: TestOptions,
+    coverage_dir: Option<PathBuf>,
+    bench_options: BenchOptions,
+    baseline: Option<String>,
+    save_baseline: Option<String>,
+    /// Receives paths of local modules as they get loaded, used by `--watch`
+    loaded_modules: Option<LoadedModulesSender>,
 }
 
-pub async fn sable_run(file_path: &str, options: SableOptions) -> Result<(), AnyError> {
+/// Options of a plain `sable run`, without any flags
+#[cfg(test)]
+fn test_options() -> SableOptions {
+    SableOptions {
+        clean_cache: false,
+        reload_cache: false,
+        state: RuntimeState::Default,
+        permissions: PermissionsOptions::default(),
+        test_options: TestOptions::default(),
+        coverage_dir: None,
+        bench_options: BenchOptions::default(),
+        baseline: None,
+        save_baseline: None,
+        loaded_modules: None,
+    }
+}
+
+pub async fn sable_run(file_path: &str, mut options: SableOptions) -> Result<(), AnyError> {
     let current_dir = env::current_dir().unwrap();
     let main_module = if let Ok(url) = Url::parse(file_path) {
         url
@@ -47,20 +94,44 @@ pub async fn sable_run(file_path: &str, options: SableOptions) -> Result<(), Any
 
     let cache_path = PathBuf::from(shellexpand::full("~/.cache/sable/")?.into_owned());
     let module_cache = Arc::new(ModuleCache::new(cache_path.join("modules")));
-    let local_storage_path = {
+    let hashed_module_path = {
         let mut hasher = DefaultHasher::new();
         absolute_module_path.hash(&mut hasher);
-        let hashed_module_path = hasher.finish();
-
-        cache_path
-            .join("local_storage/")
-            .join(hashed_module_path.to_string())
+        hasher.finish().to_string()
     };
+    let local_storage_path = cache_path.join("local_storage/").join(&hashed_module_path);
+    let baselines_path = cache_path
+        .join("bench_baselines/")
+        .join(&hashed_module_path);
 
     if options.clean_cache {
         module_cache.clear().await?;
     }
 
+    let runtime_state = options.state;
+    let coverage_dir = options.coverage_dir.clone();
+
+    let mut bench_options = mem::take(&mut options.bench_options);
+    if let Some(name) = &options.baseline {
+        let path = baseline_path(&baselines_path, name)?;
+        bench_options.baseline = Some(load_baseline(&path).await?);
+    }
+    let save_baseline_path = match &options.save_baseline {
+        Some(name) => Some(baseline_path(&baselines_path, name)?),
+        None => None,
+    };
+    let permissions = PermissionsContainer::new(Permissions::new(&options.permissions));
+
+    // Transferred ArrayBuffers and SharedArrayBuffers are kept here, workers share it with the main runtime
+    let shared_array_buffer_store = SharedArrayBufferStore::default();
+    let shared = SharedRuntimeState {
+        module_cache: module_cache.clone(),
+        options: options.clone(),
+        shared_array_buffer_store: shared_array_buffer_store.clone(),
+        blob_store: BlobStore::default(),
+        broadcast_hub: BroadcastHub::default(),
+    };
+
     let mut maybe_tracker: Option<Rc<OpMetricsSummaryTracker>> = None;
     let mut extensions = vec![sable::init_ops(), sable_cleanup::init_ops_and_esm()];
 
@@ -72,6 +143,8 @@ pub async fn sable_run(file_path: &str, options: SableOptions) -> Result<(), Any
                 name: "sable_testing",
                 op_state_fn: Some(Box::new(move |state| {
                     state.put(options.state);
+                    state.put(options.test_options);
+                    state.put(bench_options);
                     state.put(maybe_tracker);
                 })),
                 ..Default::default()
@@ -80,30 +153,153 @@ pub async fn sable_run(file_path: &str, options: SableOptions) -> Result<(), Any
         _ => {}
     }
 
+    let module_sources = ModuleSources::default();
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
         startup_snapshot: Some(RUNTIME_SNAPSHOT),
-        op_metrics_factory_fn: maybe_tracker
-            .map(|tracker| tracker.op_metrics_factory_fn(|op| op.is_async)),
-        module_loader: Some(Rc::new(SableModuleLoader {
+        op_metrics_factory_fn: maybe_tracker.map(|tracker| {
+            // Test timeouts get canceled right before the sanitization, so they can't be tracked
+            tracker.op_metrics_factory_fn(|op| op.is_async && op.name != "op_test_timeout")
+        }),
+        module_loader: Some(Rc::new(SableModuleLoader::new(
             module_cache,
             options,
-        })),
+            permissions.clone(),
+            module_sources.clone(),
+        ))),
+        get_error_class_fn: Some(&get_error_class_name),
+        shared_array_buffer_store: Some(shared_array_buffer_store),
         extensions,
+        inspector: coverage_dir.is_some(),
+        is_main: true,
         ..Default::default()
     });
 
     {
+        let op_names = js_runtime.op_names();
         let state = js_runtime.op_state();
-        state
+        let mut state = state.borrow_mut();
+        state.put(Some(LocalStoragePath(local_storage_path)));
+        state.put(OpNames(op_names));
+        state.put(permissions);
+        state.put(shared.blob_store.clone());
+        state.put(shared.broadcast_hub.clone());
+        state.put(create_worker_callback(shared));
+    }
+
+    let coverage_collector = match coverage_dir {
+        Some(dir) => Some(CoverageCollector::start(&mut js_runtime, dir).await?),
+        None => None,
+    };
+
+    let mod_id = js_runtime
+        .load_main_es_module(&main_module)
+        .await
+        .map_err(|error| uncaught_error(error, false, &module_sources))?;
+    let result = pin!(js_runtime.mod_evaluate(mod_id));
+    let mut evaluation = match js_runtime
+        .with_event_loop_promise(result, Default::default())
+        .await
+    {
+        Ok(()) => run_main_event_loop(&mut js_runtime).await,
+        Err(error) => Err(error),
+    };
+
+    if let RuntimeState::Test | RuntimeState::Bench = runtime_state {
+        // File level afterAll hooks and benchmark group tables have to run even if some tests failed
+        let finish_callback = js_runtime
+            .op_state()
             .borrow_mut()
-            .put(Some(LocalStoragePath(local_storage_path)));
+            .try_take::<ModuleFinishCallback>();
+
+        if let Some(ModuleFinishCallback(callback)) = finish_callback {
+            let call = js_runtime.call(&callback);
+            let finished = js_runtime
+                .with_event_loop_promise(call, Default::default())
+                .await;
+
+            // Errors of tests take precedence over errors of afterAll hooks
+            evaluation = evaluation.and(finished.map(|_| ()));
+        }
+    }
+
+    if let Some(coverage_collector) = coverage_collector {
+        coverage_collector.stop(&mut js_runtime).await?;
+    }
+
+    let in_promise = js_runtime.op_state().borrow().has::<UnhandledRejection>();
+    evaluation.map_err(|error| uncaught_error(error, in_promise, &module_sources))?;
+
+    if runtime_state == RuntimeState::Test {
+        let failures = js_runtime.op_state().borrow().borrow::<TestFailures>().0;
+        if failures > 0 {
+            bail!("{failures} test(s) failed");
+        }
+    }
+
+    if runtime_state == RuntimeState::Bench {
+        let state = js_runtime.op_state();
+        let BenchReport {
+            results,
+            regressions,
+        } = state.borrow_mut().take::<BenchReport>();
+
+        if let Some(path) = save_baseline_path {
+            save_baseline(&path, results).await?;
+            println!("Saved baseline to {}", path.display());
+        }
+
+        if regressions > 0 {
+            let threshold = state.borrow().borrow::<BenchOptions>().regression_threshold;
+            bail!("{regressions} benchmark(s) regressed by more than {threshold}%");
+        }
     }
 
-    let mod_id = js_runtime.load_main_es_module(&main_module).await?;
-    let result = js_runtime.mod_evaluate(mod_id);
-    js_runtime.run_event_loop(Default::default()).await?;
+    Ok(())
+}
+
+/// Fires `load`, runs the event loop until a `beforeunload` event doesn't get canceled and fires `unload`
+///
+/// https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-beforeunloadevent-interface
+async fn run_main_event_loop(js_runtime: &mut JsRuntime) -> Result<(), AnyError> {
+    dispatch_global_event(js_runtime, "load", false).await?;
+    loop {
+        js_runtime.run_event_loop(Default::default()).await?;
+        // Listeners keep the runtime alive by canceling the event and scheduling more work
+        if dispatch_global_event(js_runtime, "beforeunload", true).await? {
+            break;
+        }
+    }
+    dispatch_global_event(js_runtime, "unload", false).await?;
+    Ok(())
+}
 
-    result.await
+/// Fires trusted event on the global scope, returns false when a listener canceled it
+///
+/// Event gets created and dispatched by the callback registered in `cleanup.js`,
+/// so globals like `Event` or `dispatchEvent` which user code overrode aren't involved
+async fn dispatch_global_event(
+    js_runtime: &mut JsRuntime,
+    event: &str,
+    cancelable: bool,
+) -> Result<bool, AnyError> {
+    let callback = js_runtime
+        .op_state()
+        .borrow()
+        .borrow::<GlobalEventCallback>()
+        .0
+        .clone();
+    let args = {
+        let scope = &mut js_runtime.handle_scope();
+        let event = v8::String::new(scope, event).unwrap();
+        let cancelable = v8::Boolean::new(scope, cancelable);
+        [
+            v8::Global::new(scope, v8::Local::<v8::Value>::from(event)),
+            v8::Global::new(scope, v8::Local::<v8::Value>::from(cancelable)),
+        ]
+    };
+    let result = js_runtime.call_with_args(&callback, &args).await?;
+    let scope = &mut js_runtime.handle_scope();
+    Ok(v8::Local::new(scope, result).is_true())
 }
 
 #[tokio::main(flavor = "current_thread")]
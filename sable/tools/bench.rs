@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use deno_core::{anyhow::Error, error::generic_error, serde_json};
+use sable_ext::extensions::testing::BenchResult;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::utils::fs::atomic_write;
+
+/// Benchmark results saved using `sable bench --save-baseline`
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    benchmarks: Vec<BenchResult>,
+}
+
+/// Returns path of the baseline with given name stored in given directory
+pub fn baseline_path(dir: &Path, name: &str) -> Result<PathBuf, Error> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.'))
+        && !name.starts_with('.');
+
+    if !is_valid {
+        return Err(generic_error(format!(
+            "Invalid baseline name '{}', only alphanumeric characters, '-', '_' and '.' are allowed",
+            name
+        )));
+    }
+
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Reads baseline results keyed by benchmark name
+pub async fn load_baseline(path: &Path) -> Result<HashMap<String, BenchResult>, Error> {
+    let contents = fs::read(path).await.map_err(|error| {
+        generic_error(format!(
+            "Failed reading baseline at {}: {}",
+            path.display(),
+            error
+        ))
+    })?;
+
+    let baseline: Baseline = serde_json::from_slice(&contents).map_err(|error| {
+        generic_error(format!(
+            "Failed parsing baseline at {}: {}",
+            path.display(),
+            error
+        ))
+    })?;
+
+    Ok(baseline
+        .benchmarks
+        .into_iter()
+        .map(|result| (result.name.clone(), result))
+        .collect())
+}
+
+pub async fn save_baseline(path: &Path, results: Vec<BenchResult>) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let baseline = Baseline {
+        benchmarks: results,
+    };
+    atomic_write(path, serde_json::to_vec_pretty(&baseline)?, 0o644).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn result(name: &str, mean: f64) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            r#async: false,
+            samples: 10,
+            outliers: 1,
+            mean,
+            min: mean,
+            max: mean,
+            p75: mean,
+            p99: mean,
+            std_dev: 0.0,
+            iter_per_sec: 1000.0 / mean,
+        }
+    }
+
+    #[test]
+    fn baseline_names_are_validated() {
+        let dir = Path::new("/baselines");
+        assert_eq!(
+            baseline_path(dir, "main-1.0_x").unwrap(),
+            dir.join("main-1.0_x.json")
+        );
+
+        for name in ["", ".hidden", "../escape", "a/b", "a b"] {
+            assert!(baseline_path(dir, name).is_err(), "{name:?} is valid");
+        }
+    }
+
+    #[tokio::test]
+    async fn saved_baseline_can_be_loaded() {
+        let dir = env::temp_dir().join(format!("sable_baseline_test_{}", std::process::id()));
+        let path = baseline_path(&dir, "saved").unwrap();
+
+        save_baseline(&path, vec![result("a", 1.0), result("b", 2.0)])
+            .await
+            .unwrap();
+        let baseline = load_baseline(&path).await.unwrap();
+        fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline["a"].mean, 1.0);
+        assert_eq!(baseline["b"].mean, 2.0);
+        assert_eq!(baseline["b"].outliers, 1);
+    }
+
+    #[tokio::test]
+    async fn missing_baseline_fails_to_load() {
+        let path = env::temp_dir().join("sable_baseline_test_missing.json");
+        let Err(error) = load_baseline(&path).await else {
+            panic!("Missing baseline has been loaded");
+        };
+        assert!(error.to_string().starts_with("Failed reading baseline"));
+    }
+}
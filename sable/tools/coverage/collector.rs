@@ -0,0 +1,119 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+    process,
+    time::SystemTime,
+};
+
+use deno_core::{
+    anyhow::Error,
+    error::generic_error,
+    futures::FutureExt,
+    serde_json::{self, json, Value},
+    JsRuntime, LocalInspectorSession,
+};
+use serde::Deserialize;
+use tokio::fs;
+
+use super::ScriptCoverage;
+
+#[derive(Deserialize)]
+struct TakePreciseCoverageResult {
+    result: Vec<ScriptCoverage>,
+}
+
+/// Collects precise V8 coverage of modules ran in the runtime using a local inspector session
+pub struct CoverageCollector {
+    dir: PathBuf,
+    session: LocalInspectorSession,
+}
+
+impl CoverageCollector {
+    /// Starts collecting coverage, it has to be called before loading the main module
+    pub async fn start(js_runtime: &mut JsRuntime, dir: PathBuf) -> Result<Self, Error> {
+        let session = js_runtime.inspector().borrow().create_local_session();
+        let mut collector = Self { dir, session };
+
+        collector.post(js_runtime, "Profiler.enable", None).await?;
+        collector
+            .post(
+                js_runtime,
+                "Profiler.startPreciseCoverage",
+                Some(json!({ "callCount": true, "detailed": true })),
+            )
+            .await?;
+        // Debugger is used only to retrieve script sources, `debugger` statements should not pause
+        collector.post(js_runtime, "Debugger.enable", None).await?;
+        collector
+            .post(
+                js_runtime,
+                "Debugger.setSkipAllPauses",
+                Some(json!({ "skip": true })),
+            )
+            .await?;
+
+        Ok(collector)
+    }
+
+    /// Stops collecting coverage and writes coverage of every executed module, except `ext:` ones, to the directory
+    pub async fn stop(mut self, js_runtime: &mut JsRuntime) -> Result<(), Error> {
+        let coverage = self
+            .post(js_runtime, "Profiler.takePreciseCoverage", None)
+            .await?;
+        let coverage: TakePreciseCoverageResult = serde_json::from_value(coverage)?;
+
+        fs::create_dir_all(&self.dir).await.map_err(|error| {
+            generic_error(format!(
+                "Failed creating coverage directory {}: {}",
+                self.dir.display(),
+                error
+            ))
+        })?;
+
+        for mut script in coverage.result {
+            if script.url.is_empty() || script.url.starts_with("ext:") {
+                continue;
+            }
+
+            let source = self
+                .post(
+                    js_runtime,
+                    "Debugger.getScriptSource",
+                    Some(json!({ "scriptId": script.script_id })),
+                )
+                .await?;
+            script.source = source["scriptSource"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+
+            // Coverage of the same module from multiple runs gets merged when generating reports
+            let mut hasher = DefaultHasher::new();
+            (&script.url, process::id(), SystemTime::now()).hash(&mut hasher);
+            let path = self.dir.join(format!("{}.json", hasher.finish()));
+
+            fs::write(path, serde_json::to_vec(&script)?).await?;
+        }
+
+        self.post(js_runtime, "Profiler.stopPreciseCoverage", None)
+            .await?;
+        self.post(js_runtime, "Profiler.disable", None).await?;
+        self.post(js_runtime, "Debugger.disable", None).await?;
+
+        Ok(())
+    }
+
+    async fn post(
+        &mut self,
+        js_runtime: &mut JsRuntime,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value, Error> {
+        js_runtime
+            .with_event_loop_future(
+                self.session.post_message(method, params).boxed_local(),
+                Default::default(),
+            )
+            .await
+    }
+}
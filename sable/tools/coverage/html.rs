@@ -0,0 +1,161 @@
+use std::{fmt::Write, path::Path};
+
+use deno_core::anyhow::Error;
+use tokio::fs;
+
+use super::{
+    display_name,
+    report::{CoverageCount, CoverageSummary, FileReport},
+};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.25em 0.75em; text-align: left; }
+tr.summary td { border-top: 1px solid #888; font-weight: bold; }
+.high { color: #2a7d2a; }
+.medium { color: #a66f00; }
+.low { color: #b52a2a; }
+table.source td { padding: 0 0.5em; font-family: monospace; white-space: pre; }
+table.source td.number, table.source td.hits { color: #888; text-align: right; }
+tr.covered td.hits { background: #d4f7d4; }
+tr.uncovered td.hits, tr.uncovered td.code { background: #fcd4d4; }
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Name of the page with file's coverage
+fn page_name(report: &FileReport) -> String {
+    let name: String = display_name(&report.url)
+        .chars()
+        .map(|char| match char {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => char,
+            _ => '_',
+        })
+        .collect();
+    format!("{}.html", name)
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn count_cell(count: CoverageCount) -> String {
+    let percent = count.percent();
+    let class = if percent >= 80.0 {
+        "high"
+    } else if percent >= 50.0 {
+        "medium"
+    } else {
+        "low"
+    };
+    format!(
+        "<td class=\"{}\">{:.2}% ({}/{})</td>",
+        class, percent, count.hit, count.found
+    )
+}
+
+fn summary_cells(summary: CoverageSummary) -> String {
+    format!(
+        "{}{}{}",
+        count_cell(summary.lines),
+        count_cell(summary.branches),
+        count_cell(summary.functions)
+    )
+}
+
+const SUMMARY_HEADER: &str =
+    "<tr><th>File</th><th>Lines</th><th>Branches</th><th>Functions</th></tr>\n";
+
+fn index_page(reports: &[FileReport]) -> String {
+    let mut body = String::from("<h1>Coverage report</h1>\n<table>\n");
+    body.push_str(SUMMARY_HEADER);
+
+    for report in reports {
+        writeln!(
+            body,
+            "<tr><td><a href=\"{}\">{}</a></td>{}</tr>",
+            escape(&page_name(report)),
+            escape(&display_name(&report.url)),
+            summary_cells(report.summary())
+        )
+        .unwrap();
+    }
+
+    let total: CoverageSummary = reports.iter().map(FileReport::summary).sum();
+    writeln!(
+        body,
+        "<tr class=\"summary\"><td>All files</td>{}</tr>",
+        summary_cells(total)
+    )
+    .unwrap();
+    body.push_str("</table>\n");
+
+    page("Coverage report", &body)
+}
+
+fn file_page(report: &FileReport) -> String {
+    let name = display_name(&report.url);
+
+    let mut body = format!(
+        "<h1><a href=\"index.html\">All files</a> / {}</h1>\n<table>\n",
+        escape(&name)
+    );
+    body.push_str(SUMMARY_HEADER);
+    writeln!(
+        body,
+        "<tr><td>{}</td>{}</tr>\n</table>\n",
+        escape(&name),
+        summary_cells(report.summary())
+    )
+    .unwrap();
+
+    let Some(source) = &report.source else {
+        body.push_str("<p>Source is not available</p>\n");
+        return page(&name, &body);
+    };
+
+    body.push_str("<table class=\"source\">\n");
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let (class, hits) = match report.lines.get(&line) {
+            Some(0) => ("uncovered", "0".to_string()),
+            Some(count) => ("covered", format!("{}x", count)),
+            None => ("", String::new()),
+        };
+        writeln!(
+            body,
+            "<tr class=\"{}\"><td class=\"number\">{}</td><td class=\"hits\">{}</td><td class=\"code\">{}</td></tr>",
+            class,
+            line,
+            hits,
+            escape(text)
+        )
+        .unwrap();
+    }
+    body.push_str("</table>\n");
+
+    page(&name, &body)
+}
+
+/// Writes HTML report with an index page and a page with annotated source for every file
+pub async fn report(dir: &Path, reports: &[FileReport]) -> Result<(), Error> {
+    fs::create_dir_all(dir).await?;
+
+    fs::write(dir.join("index.html"), index_page(reports)).await?;
+    for report in reports {
+        fs::write(dir.join(page_name(report)), file_page(report)).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use std::{collections::HashSet, fmt::Write};
+
+use deno_core::url::Url;
+
+use super::report::{FileReport, FunctionReport};
+
+/// lcov identifies functions by their names, so functions which share a name get their line appended,
+/// functions sharing both get their position on the line appended as well
+fn function_names(functions: &[FunctionReport]) -> Vec<String> {
+    let mut names = Vec::with_capacity(functions.len());
+    let mut used = HashSet::new();
+
+    for function in functions {
+        let shared = function.name == "(anonymous)"
+            || functions
+                .iter()
+                .filter(|other| other.name == function.name)
+                .count()
+                > 1;
+
+        let mut name = if shared {
+            format!("{}:{}", function.name, function.line)
+        } else {
+            function.name.clone()
+        };
+        let base_len = name.len();
+        let mut position = 1;
+        while used.contains(&name) {
+            position += 1;
+            name.truncate(base_len);
+            write!(name, ":{}", position).unwrap();
+        }
+
+        used.insert(name.clone());
+        names.push(name);
+    }
+
+    names
+}
+
+/// Formats reports in the lcov tracefile format
+pub fn report(reports: &[FileReport]) -> String {
+    let mut lcov = String::new();
+
+    for report in reports {
+        let source_file = Url::parse(&report.url)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .map_or_else(|| report.url.clone(), |path| path.display().to_string());
+        let summary = report.summary();
+
+        writeln!(lcov, "SF:{}", source_file).unwrap();
+
+        let names = function_names(&report.functions);
+        for (function, name) in report.functions.iter().zip(&names) {
+            writeln!(lcov, "FN:{},{}", function.line, name).unwrap();
+        }
+        for (function, name) in report.functions.iter().zip(&names) {
+            writeln!(lcov, "FNDA:{},{}", function.count, name).unwrap();
+        }
+        writeln!(lcov, "FNF:{}", summary.functions.found).unwrap();
+        writeln!(lcov, "FNH:{}", summary.functions.hit).unwrap();
+
+        for branch in &report.branches {
+            writeln!(
+                lcov,
+                "BRDA:{},{},{},{}",
+                branch.line, branch.block, branch.branch, branch.count
+            )
+            .unwrap();
+        }
+        writeln!(lcov, "BRF:{}", summary.branches.found).unwrap();
+        writeln!(lcov, "BRH:{}", summary.branches.hit).unwrap();
+
+        for (line, count) in &report.lines {
+            writeln!(lcov, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(lcov, "LF:{}", summary.lines.found).unwrap();
+        writeln!(lcov, "LH:{}", summary.lines.hit).unwrap();
+
+        writeln!(lcov, "end_of_record").unwrap();
+    }
+
+    lcov
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::coverage::report::BranchReport;
+
+    fn function(name: &str, line: usize, count: u64) -> FunctionReport {
+        FunctionReport {
+            name: name.to_string(),
+            line,
+            count,
+        }
+    }
+
+    #[test]
+    fn report_formats_tracefile() {
+        let mut report = FileReport {
+            url: "file:///project/main.js".to_string(),
+            functions: vec![function("main", 1, 1), function("(anonymous)", 3, 0)],
+            branches: vec![BranchReport {
+                line: 2,
+                block: 1,
+                branch: 0,
+                count: 1,
+            }],
+            ..Default::default()
+        };
+        report.lines.extend([(1, 1), (2, 1), (3, 0)]);
+
+        assert_eq!(
+            super::report(&[report]),
+            "SF:/project/main.js
+FN:1,main
+FN:3,(anonymous):3
+FNDA:1,main
+FNDA:0,(anonymous):3
+FNF:2
+FNH:1
+BRDA:2,1,0,1
+BRF:1
+BRH:1
+DA:1,1
+DA:2,1
+DA:3,0
+LF:3
+LH:2
+end_of_record
+"
+        );
+    }
+
+    #[test]
+    fn function_names_are_unique() {
+        let functions = [
+            function("(anonymous)", 1, 0),
+            function("(anonymous)", 2, 0),
+            function("(anonymous)", 2, 0),
+            function("helper", 4, 0),
+            function("helper", 8, 0),
+            function("main", 10, 0),
+        ];
+
+        assert_eq!(
+            function_names(&functions),
+            [
+                "(anonymous):1",
+                "(anonymous):2",
+                "(anonymous):2:2",
+                "helper:4",
+                "helper:8",
+                "main",
+            ]
+        );
+    }
+}
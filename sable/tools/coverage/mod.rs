@@ -0,0 +1,183 @@
+use std::{collections::BTreeMap, env, ffi::OsStr, path::Path};
+
+use deno_core::{anyhow::Error, error::generic_error, serde_json, url::Url};
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+mod collector;
+mod html;
+mod lcov;
+mod report;
+
+pub use collector::CoverageCollector;
+use report::{load_source_map, script_reports, CoverageCount, CoverageSummary, FileReport};
+
+/// Coverage of a single script, as returned by V8's `Profiler.takePreciseCoverage`
+/// together with the source code of the script
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCoverage {
+    pub script_id: String,
+    pub url: String,
+    pub functions: Vec<FunctionCoverage>,
+    #[serde(default)]
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCoverage {
+    pub function_name: String,
+    pub ranges: Vec<CoverageRange>,
+    pub is_block_coverage: bool,
+}
+
+/// Range of UTF-16 code unit offsets into the script source
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageRange {
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub count: u64,
+}
+
+/// Returns path relative to the current working directory for local files, otherwise the url itself
+fn display_name(url: &str) -> String {
+    let path = Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok());
+
+    if let Some(path) = path {
+        let current_dir = env::current_dir().unwrap();
+        if let Ok(relative_path) = path.strip_prefix(current_dir) {
+            return relative_path.display().to_string();
+        }
+        return path.display().to_string();
+    }
+
+    url.to_string()
+}
+
+fn format_count(count: CoverageCount) -> String {
+    let percent = count.percent();
+    let text = format!("{:.2}% ({}/{})", percent, count.hit, count.found);
+    let text = format!("{:>22}", text);
+    if percent >= 80.0 {
+        text.green().to_string()
+    } else if percent >= 50.0 {
+        text.yellow().to_string()
+    } else {
+        text.red().to_string()
+    }
+}
+
+fn print_summary(reports: &[FileReport]) {
+    let total: CoverageSummary = reports.iter().map(FileReport::summary).sum();
+    let names: Vec<String> = reports
+        .iter()
+        .map(|report| display_name(&report.url))
+        .collect();
+    let width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("All files".len());
+
+    println!(
+        "{:width$} | {:>22} | {:>22} | {:>22}",
+        "File", "Lines", "Branches", "Functions"
+    );
+    let summaries = reports.iter().map(FileReport::summary);
+    for (name, summary) in names
+        .iter()
+        .zip(summaries)
+        .chain([(&"All files".to_string(), total)])
+    {
+        println!(
+            "{:width$} | {} | {} | {}",
+            name,
+            format_count(summary.lines),
+            format_count(summary.branches),
+            format_count(summary.functions),
+        );
+    }
+}
+
+/// Generates coverage reports out of coverage collected using `sable test --coverage=<dir>`
+///
+/// Prints summary to the terminal, writes lcov report to `<dir>/lcov.info` and HTML report to `<dir>/html/`
+pub async fn coverage(dir: &Path) -> Result<(), Error> {
+    let mut files: BTreeMap<String, FileReport> = BTreeMap::new();
+
+    let mut entries = fs::read_dir(dir).await.map_err(|error| {
+        generic_error(format!(
+            "Failed reading coverage directory {}: {}",
+            dir.display(),
+            error
+        ))
+    })?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if !path.is_file() || path.extension() != Some(OsStr::new("json")) {
+            continue;
+        }
+
+        let contents = fs::read(&path).await?;
+        let script: ScriptCoverage = serde_json::from_slice(&contents).map_err(|error| {
+            generic_error(format!(
+                "Failed parsing coverage at {}: {}",
+                path.display(),
+                error
+            ))
+        })?;
+
+        let source_map = load_source_map(&script).await?;
+        for report in script_reports(&script, source_map.as_ref()) {
+            match files.get_mut(&report.url) {
+                Some(file) => file.merge(report),
+                None => {
+                    files.insert(report.url.clone(), report);
+                }
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return Err(generic_error(format!(
+            "No coverage found in {}",
+            dir.display()
+        )));
+    }
+
+    let mut reports: Vec<FileReport> = files.into_values().collect();
+    for report in &mut reports {
+        // Source maps don't have to contain original sources, try to read them from disk
+        if report.source.is_none() {
+            let path = Url::parse(&report.url)
+                .ok()
+                .and_then(|url| url.to_file_path().ok());
+            if let Some(path) = path {
+                report.source = fs::read_to_string(path).await.ok();
+            }
+        }
+    }
+
+    print_summary(&reports);
+
+    let lcov_path = dir.join("lcov.info");
+    fs::write(&lcov_path, lcov::report(&reports)).await?;
+    println!("\nlcov report written to {}", lcov_path.display());
+
+    let html_path = dir.join("html");
+    html::report(&html_path, &reports).await?;
+    println!(
+        "HTML report written to {}",
+        html_path.join("index.html").display()
+    );
+
+    Ok(())
+}
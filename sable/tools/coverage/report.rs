@@ -0,0 +1,485 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    iter::Sum,
+};
+
+use deno_ast::{lex, MediaType, TokenOrComment};
+use deno_core::{anyhow::Error, error::generic_error, url::Url};
+use sourcemap::{locate_sourcemap_reference_slice, DecodedMap, SourceMap};
+use tokio::fs;
+
+use super::{CoverageRange, ScriptCoverage};
+
+/// Number of found and hit lines, branches or functions
+#[derive(Clone, Copy, Default)]
+pub struct CoverageCount {
+    pub hit: usize,
+    pub found: usize,
+}
+
+fn count<'a>(counts: impl Iterator<Item = &'a u64>) -> CoverageCount {
+    counts.fold(CoverageCount::default(), |total, count| CoverageCount {
+        hit: total.hit + (*count > 0) as usize,
+        found: total.found + 1,
+    })
+}
+
+impl CoverageCount {
+    pub fn percent(&self) -> f64 {
+        if self.found == 0 {
+            100.0
+        } else {
+            self.hit as f64 / self.found as f64 * 100.0
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct CoverageSummary {
+    pub lines: CoverageCount,
+    pub branches: CoverageCount,
+    pub functions: CoverageCount,
+}
+
+impl Sum for CoverageSummary {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, summary| {
+            let add = |a: CoverageCount, b: CoverageCount| CoverageCount {
+                hit: a.hit + b.hit,
+                found: a.found + b.found,
+            };
+            Self {
+                lines: add(total.lines, summary.lines),
+                branches: add(total.branches, summary.branches),
+                functions: add(total.functions, summary.functions),
+            }
+        })
+    }
+}
+
+pub struct FunctionReport {
+    pub name: String,
+    pub line: usize,
+    pub count: u64,
+}
+
+pub struct BranchReport {
+    pub line: usize,
+    pub block: usize,
+    pub branch: usize,
+    pub count: u64,
+}
+
+/// Coverage of a single source file, line numbers are 1-based
+#[derive(Default)]
+pub struct FileReport {
+    pub url: String,
+    pub source: Option<String>,
+    /// Hit counts of lines containing code
+    pub lines: BTreeMap<usize, u64>,
+    pub functions: Vec<FunctionReport>,
+    pub branches: Vec<BranchReport>,
+}
+
+impl FileReport {
+    fn new(url: String, source: Option<String>) -> Self {
+        Self {
+            url,
+            source,
+            ..Default::default()
+        }
+    }
+
+    pub fn summary(&self) -> CoverageSummary {
+        CoverageSummary {
+            lines: count(self.lines.values()),
+            branches: count(self.branches.iter().map(|branch| &branch.count)),
+            functions: count(self.functions.iter().map(|function| &function.count)),
+        }
+    }
+
+    /// Sums hit counts of the same file collected in different runs
+    pub fn merge(&mut self, other: FileReport) {
+        if self.source.is_none() {
+            self.source = other.source;
+        }
+
+        for (line, count) in other.lines {
+            *self.lines.entry(line).or_default() += count;
+        }
+
+        for function in other.functions {
+            match self
+                .functions
+                .iter_mut()
+                .find(|f| f.line == function.line && f.name == function.name)
+            {
+                Some(existing) => existing.count += function.count,
+                None => self.functions.push(function),
+            }
+        }
+
+        for branch in other.branches {
+            match self.branches.iter_mut().find(|b| {
+                b.line == branch.line && b.block == branch.block && b.branch == branch.branch
+            }) {
+                Some(existing) => existing.count += branch.count,
+                None => self.branches.push(branch),
+            }
+        }
+    }
+
+    fn add_line(&mut self, line: usize, count: u64) {
+        match self.lines.entry(line) {
+            // Multiple generated lines can map to the same original line,
+            // it's only covered if all of them are
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                *existing = (*existing).min(count);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(count);
+            }
+        }
+    }
+}
+
+struct SourceLine {
+    /// UTF-16 offset of the start of the line
+    offset: usize,
+    /// UTF-16 offsets of the first and past the last non-whitespace character
+    start: usize,
+    end: usize,
+    /// Whether line contains anything other than whitespace and comments
+    is_code: bool,
+}
+
+fn source_lines(source: &str, media_type: MediaType) -> Vec<SourceLine> {
+    let mut lines = Vec::new();
+    let mut byte_offsets = Vec::new();
+    let mut offset = 0;
+    let mut byte_offset = 0;
+
+    for text in source.split_inclusive('\n') {
+        let leading = text.len() - text.trim_start().len();
+        let start = offset + text[..leading].encode_utf16().count();
+        lines.push(SourceLine {
+            offset,
+            start,
+            end: start + text.trim().encode_utf16().count(),
+            is_code: false,
+        });
+        byte_offsets.push(byte_offset);
+
+        offset += text.encode_utf16().count();
+        byte_offset += text.len();
+    }
+
+    for item in lex(source, media_type) {
+        if let TokenOrComment::Comment { .. } = item.inner {
+            continue;
+        }
+
+        let first = byte_offsets.partition_point(|offset| *offset <= item.range.start) - 1;
+        let last = byte_offsets.partition_point(|offset| *offset < item.range.end) - 1;
+        for line in &mut lines[first..=last.max(first)] {
+            line.is_code = true;
+        }
+    }
+
+    lines
+}
+
+/// V8 ranges are nested, count of the innermost one applies
+fn line_hit_count(line: &SourceLine, ranges: &[&CoverageRange]) -> u64 {
+    // Line is not fully covered if any part of it wasn't executed
+    let uncovered = ranges.iter().any(|range| {
+        range.count == 0 && range.start_offset < line.end && range.end_offset > line.start
+    });
+    if uncovered {
+        return 0;
+    }
+
+    ranges
+        .iter()
+        .filter(|range| range.start_offset <= line.start && line.start < range.end_offset)
+        .min_by_key(|range| range.end_offset - range.start_offset)
+        .map_or(0, |range| range.count)
+}
+
+/// Source map of the script and url its sources are relative to
+pub struct ScriptSourceMap {
+    map: SourceMap,
+    base: Url,
+}
+
+/// Loads source map referenced by `sourceMappingURL` comment of the script
+///
+/// Source maps which can't be loaded are skipped, so that the script gets reported on its own
+pub async fn load_source_map(script: &ScriptCoverage) -> Result<Option<ScriptSourceMap>, Error> {
+    let Ok(reference) = locate_sourcemap_reference_slice(script.source.as_bytes()) else {
+        return Ok(None);
+    };
+    let Some(reference) = reference else {
+        return Ok(None);
+    };
+    let script_url = Url::parse(&script.url)?;
+
+    let (decoded, base) = if let Some(decoded) = reference.get_embedded_sourcemap()? {
+        (decoded, script_url)
+    } else {
+        let url = script_url.join(reference.get_url())?;
+        match load_external_source_map(&url).await {
+            Ok(decoded) => (decoded, url),
+            Err(error) => {
+                eprintln!("Skipping source map {} of {}: {}", url, script.url, error);
+                return Ok(None);
+            }
+        }
+    };
+
+    match decoded {
+        DecodedMap::Regular(map) => Ok(Some(ScriptSourceMap { map, base })),
+        _ => Ok(None),
+    }
+}
+
+async fn load_external_source_map(url: &Url) -> Result<DecodedMap, Error> {
+    let contents = match url.scheme() {
+        "file" => fs::read(url.to_file_path().unwrap_or_default()).await?,
+        "http" | "https" => reqwest::get(url.as_str())
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec(),
+        scheme => return Err(generic_error(format!("Unsupported scheme {}", scheme))),
+    };
+    Ok(DecodedMap::from_reader(contents.as_slice())?)
+}
+
+/// Computes reports of files that the script was generated from,
+/// or just the script itself if it has no source map
+pub fn script_reports(
+    script: &ScriptCoverage,
+    source_map: Option<&ScriptSourceMap>,
+) -> Vec<FileReport> {
+    let media_type = Url::parse(&script.url)
+        .map(|url| MediaType::from_specifier(&url))
+        .unwrap_or(MediaType::JavaScript);
+    let lines = source_lines(&script.source, media_type);
+    let ranges: Vec<&CoverageRange> = script
+        .functions
+        .iter()
+        .flat_map(|function| &function.ranges)
+        .collect();
+
+    let mut reports = ScriptReports {
+        script,
+        source_map,
+        lines: &lines,
+        reports: BTreeMap::new(),
+    };
+
+    for line in lines.iter().filter(|line| line.is_code) {
+        let count = line_hit_count(line, &ranges);
+        if let Some((report, line)) = reports.locate(line.start) {
+            report.add_line(line, count);
+        }
+    }
+
+    for (block, function) in script.functions.iter().enumerate() {
+        let Some(range) = function.ranges.first() else {
+            continue;
+        };
+
+        // First function is the module itself
+        let is_module = block == 0 && function.function_name.is_empty() && range.start_offset == 0;
+        if !is_module {
+            if let Some((report, line)) = reports.locate(range.start_offset) {
+                let name = match function.function_name.as_str() {
+                    "" => "(anonymous)".to_string(),
+                    name => name.to_string(),
+                };
+                report.functions.push(FunctionReport {
+                    name,
+                    line,
+                    count: range.count,
+                });
+            }
+        }
+
+        if !function.is_block_coverage {
+            continue;
+        }
+
+        for (branch, range) in function.ranges.iter().enumerate().skip(1) {
+            if let Some((report, line)) = reports.locate(range.start_offset) {
+                report.branches.push(BranchReport {
+                    line,
+                    block,
+                    branch: branch - 1,
+                    count: range.count,
+                });
+            }
+        }
+    }
+
+    reports.reports.into_values().collect()
+}
+
+struct ScriptReports<'a> {
+    script: &'a ScriptCoverage,
+    source_map: Option<&'a ScriptSourceMap>,
+    lines: &'a [SourceLine],
+    reports: BTreeMap<String, FileReport>,
+}
+
+impl ScriptReports<'_> {
+    /// Maps UTF-16 offset in the script to the report and 1-based line of the original file
+    fn locate(&mut self, offset: usize) -> Option<(&mut FileReport, usize)> {
+        let index = self
+            .lines
+            .partition_point(|line| line.offset <= offset)
+            .max(1)
+            - 1;
+
+        let Some(ScriptSourceMap { map, base }) = self.source_map else {
+            let script = self.script;
+            let report = self.reports.entry(script.url.clone()).or_insert_with(|| {
+                FileReport::new(script.url.clone(), Some(script.source.clone()))
+            });
+            return Some((report, index + 1));
+        };
+
+        let column = offset - self.lines.get(index).map_or(0, |line| line.offset);
+        let token = map
+            .lookup_token(index as u32, column as u32)
+            .filter(|token| token.get_dst_line() == index as u32)?;
+        let url = base.join(token.get_source()?).ok()?.to_string();
+
+        let report = self.reports.entry(url.clone()).or_insert_with(|| {
+            let source = map.get_source_contents(token.get_src_id());
+            FileReport::new(url, source.map(ToString::to_string))
+        });
+        Some((report, token.get_src_line() as usize + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::coverage::FunctionCoverage;
+
+    fn range(start_offset: usize, end_offset: usize, count: u64) -> CoverageRange {
+        CoverageRange {
+            start_offset,
+            end_offset,
+            count,
+        }
+    }
+
+    fn line(start: usize, end: usize) -> SourceLine {
+        SourceLine {
+            offset: 0,
+            start,
+            end,
+            is_code: true,
+        }
+    }
+
+    #[test]
+    fn source_lines_skip_comments_and_blank_lines() {
+        let lines = source_lines(
+            "const a = 1;\n// comment\n\n  foo();\n",
+            MediaType::JavaScript,
+        );
+
+        let is_code: Vec<bool> = lines.iter().map(|line| line.is_code).collect();
+        assert_eq!(is_code, [true, false, false, true]);
+
+        let last = &lines[3];
+        assert_eq!(last.offset, 25);
+        assert_eq!(last.start, 27);
+        assert_eq!(last.end, 33);
+    }
+
+    #[test]
+    fn source_lines_use_utf16_offsets() {
+        let lines = source_lines("const s = \"😀\";\nx;\n", MediaType::JavaScript);
+        assert_eq!(lines[0].end, 15);
+        assert_eq!(lines[1].offset, 16);
+    }
+
+    #[test]
+    fn source_lines_mark_every_line_of_multiline_tokens() {
+        let lines = source_lines("const s = `a\n\nb`;\n", MediaType::JavaScript);
+        assert!(lines.iter().all(|line| line.is_code));
+    }
+
+    #[test]
+    fn line_hit_count_uses_innermost_range() {
+        let outer = range(0, 100, 1);
+        let inner = range(20, 50, 3);
+        let ranges = [&outer, &inner];
+
+        assert_eq!(line_hit_count(&line(5, 15), &ranges), 1);
+        assert_eq!(line_hit_count(&line(25, 30), &ranges), 3);
+        assert_eq!(line_hit_count(&line(100, 110), &ranges), 0);
+    }
+
+    #[test]
+    fn line_hit_count_is_zero_when_part_of_line_is_uncovered() {
+        let outer = range(0, 100, 2);
+        let skipped = range(10, 20, 0);
+        let ranges = [&outer, &skipped];
+
+        assert_eq!(line_hit_count(&line(5, 15), &ranges), 0);
+        assert_eq!(line_hit_count(&line(30, 40), &ranges), 2);
+    }
+
+    #[test]
+    fn script_reports_count_functions_and_lines() {
+        let script = ScriptCoverage {
+            script_id: "1".to_string(),
+            url: "file:///main.js".to_string(),
+            functions: vec![
+                FunctionCoverage {
+                    function_name: String::new(),
+                    ranges: vec![range(0, 30, 1)],
+                    is_block_coverage: false,
+                },
+                FunctionCoverage {
+                    function_name: "f".to_string(),
+                    ranges: vec![range(0, 15, 0)],
+                    is_block_coverage: false,
+                },
+            ],
+            source: "function f() {}\nf;\n".to_string(),
+        };
+
+        let reports = script_reports(&script, None);
+        assert_eq!(reports.len(), 1);
+
+        let report = &reports[0];
+        assert_eq!(
+            report.lines.iter().collect::<Vec<_>>(),
+            [(&1, &0), (&2, &1)]
+        );
+        assert_eq!(report.functions.len(), 1);
+        assert_eq!(report.functions[0].name, "f");
+        assert_eq!(report.functions[0].count, 0);
+    }
+
+    #[tokio::test]
+    async fn unavailable_source_maps_are_skipped() {
+        let script = ScriptCoverage {
+            script_id: "1".to_string(),
+            url: "file:///nonexistent/main.js".to_string(),
+            functions: vec![],
+            source: "x;\n//# sourceMappingURL=main.js.map\n".to_string(),
+        };
+
+        assert!(load_source_map(&script).await.unwrap().is_none());
+    }
+}
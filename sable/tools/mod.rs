@@ -1 +1,4 @@
+pub mod bench;
+pub mod coverage;
 pub mod fmt;
+pub mod watch;
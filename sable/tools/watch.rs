@@ -0,0 +1,478 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    path::{Path, PathBuf},
+};
+
+use deno_core::error::AnyError;
+use owo_colors::OwoColorize;
+use sable_ext::extensions::fs::Watcher;
+use tokio::sync::mpsc;
+
+use crate::{errors::format_error, sable_run, SableOptions};
+
+/// Moves the cursor to the top left corner and clears the terminal
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+/// Paths of local modules, which `SableModuleLoader` sends as they get loaded
+pub type LoadedModulesSender = mpsc::UnboundedSender<PathBuf>;
+
+/// Change which should cause a restart
+enum Change {
+    /// Local modules which have been changed
+    Modules(Vec<PathBuf>),
+    /// Something changed inside of a path passed using `--watch=PATH`
+    WatchedPath(PathBuf),
+}
+
+/// Watches loaded local modules and paths passed using `--watch=PATH`
+struct ModuleWatcher {
+    /// Watches directories of loaded modules, since editors often replace files instead of writing to them
+    module_dirs: Watcher,
+    watched_dirs: HashSet<PathBuf>,
+    modules: HashSet<PathBuf>,
+    watched_paths: Watcher,
+}
+
+impl ModuleWatcher {
+    fn new(watched_paths: &[PathBuf]) -> Result<Self, AnyError> {
+        let mut watcher = Watcher::new(true)?;
+        for path in watched_paths {
+            watcher.add_watch(&path.canonicalize()?)?;
+        }
+
+        Ok(Self {
+            module_dirs: Watcher::new(false)?,
+            watched_dirs: HashSet::new(),
+            modules: HashSet::new(),
+            watched_paths: watcher,
+        })
+    }
+
+    /// Returns path under which the module is reported, when it changes
+    fn watch_module(&mut self, path: &Path) -> Result<PathBuf, AnyError> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if let Some(dir) = path.parent() {
+            if !self.watched_dirs.contains(dir) {
+                self.module_dirs.add_watch(dir)?;
+                self.watched_dirs.insert(dir.to_path_buf());
+            }
+        }
+        self.modules.insert(path.clone());
+        Ok(path)
+    }
+
+    /// Watches modules sent by the loader and returns paths they are reported under
+    fn watch_loaded_modules(
+        &mut self,
+        receiver: &mut mpsc::UnboundedReceiver<PathBuf>,
+    ) -> Result<HashSet<PathBuf>, AnyError> {
+        let mut modules = HashSet::new();
+        while let Ok(path) = receiver.try_recv() {
+            modules.insert(self.watch_module(&path)?);
+        }
+        Ok(modules)
+    }
+
+    /// Waits until a watched module or path changes, changes are debounced by `Watcher`
+    ///
+    /// Cancel safe, as no changes get lost when the future gets dropped
+    async fn changed(&mut self) -> Result<Change, AnyError> {
+        loop {
+            tokio::select! {
+                events = self.module_dirs.next_events() => {
+                    let changed: Vec<PathBuf> = events?
+                        .into_iter()
+                        .flat_map(|event| event.paths)
+                        .map(PathBuf::from)
+                        .filter(|path| self.modules.contains(path))
+                        .collect();
+                    if !changed.is_empty() {
+                        return Ok(Change::Modules(changed));
+                    }
+                }
+                events = self.watched_paths.next_events() => {
+                    if let Some(path) = events?.into_iter().flat_map(|event| event.paths).next() {
+                        return Ok(Change::WatchedPath(PathBuf::from(path)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Clears the terminal and prints what caused the restart
+fn clear_screen(change: Option<&Change>) {
+    print!("{}", CLEAR_SCREEN);
+
+    let Some(change) = change else {
+        return;
+    };
+    let path = match change {
+        Change::Modules(paths) => &paths[0],
+        Change::WatchedPath(path) => path,
+    };
+    println!(
+        "{} {} changed, restarting",
+        "Watcher".magenta().bold(),
+        path.display()
+    );
+}
+
+fn print_waiting() {
+    println!(
+        "{} Finished, waiting for changes",
+        "Watcher".magenta().bold()
+    );
+}
+
+/// Runs the module, while watching local modules it loads, until it finishes or something changes
+///
+/// Paths under which the loaded modules are reported get added to `modules`,
+/// returns the change which interrupted the run
+async fn run_module(
+    run: impl Future<Output = Result<(), AnyError>>,
+    receiver: &mut mpsc::UnboundedReceiver<PathBuf>,
+    watcher: &mut ModuleWatcher,
+    modules: &mut HashSet<PathBuf>,
+) -> Result<Option<Change>, AnyError> {
+    tokio::pin!(run);
+
+    // Dropping the run future drops its `JsRuntime`, which stops everything it was doing
+    loop {
+        tokio::select! {
+            result = &mut run => {
+                if let Err(error) = result {
+                    eprintln!("{}", format_error(&error));
+                }
+                break;
+            }
+            Some(path) = receiver.recv() => {
+                modules.insert(watcher.watch_module(&path)?);
+            }
+            change = watcher.changed() => return Ok(Some(change?)),
+        }
+    }
+
+    modules.extend(watcher.watch_loaded_modules(receiver)?);
+    Ok(None)
+}
+
+/// Returns options for the next run, which make the loader report loaded modules to the receiver
+fn next_run_options(
+    options: &mut SableOptions,
+) -> (SableOptions, mpsc::UnboundedReceiver<PathBuf>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut run_options = options.clone();
+    run_options.loaded_modules = Some(sender);
+    // Cache only has to be cleaned once
+    options.clean_cache = false;
+    (run_options, receiver)
+}
+
+/// Indexes of the test modules which depend on a changed module, all of them when a watched path changes
+fn affected_tests(module_graphs: &[HashSet<PathBuf>], change: &Change) -> Vec<usize> {
+    match change {
+        Change::Modules(paths) => module_graphs
+            .iter()
+            .enumerate()
+            .filter(|(_, graph)| paths.iter().any(|path| graph.contains(path)))
+            .map(|(index, _)| index)
+            .collect(),
+        Change::WatchedPath(_) => (0..module_graphs.len()).collect(),
+    }
+}
+
+/// Runs the module and restarts it from scratch whenever a loaded local module or a watched path changes
+///
+/// Only returns when watching fails
+pub async fn watch_run(
+    module_path: &str,
+    options: SableOptions,
+    watched_paths: &[PathBuf],
+) -> Result<(), AnyError> {
+    watch_run_with(
+        module_path,
+        options,
+        watched_paths,
+        |path, options| async move { sable_run(&path, options).await },
+    )
+    .await
+}
+
+async fn watch_run_with<F, Fut>(
+    module_path: &str,
+    mut options: SableOptions,
+    watched_paths: &[PathBuf],
+    mut run: F,
+) -> Result<(), AnyError>
+where
+    F: FnMut(String, SableOptions) -> Fut,
+    Fut: Future<Output = Result<(), AnyError>>,
+{
+    let mut watcher = ModuleWatcher::new(watched_paths)?;
+    let mut change = None;
+
+    loop {
+        clear_screen(change.as_ref());
+
+        let (run_options, mut receiver) = next_run_options(&mut options);
+        let run = run(module_path.to_string(), run_options);
+        let interrupted = run_module(run, &mut receiver, &mut watcher, &mut HashSet::new()).await?;
+
+        change = Some(match interrupted {
+            Some(change) => change,
+            None => {
+                print_waiting();
+                watcher.changed().await?
+            }
+        });
+    }
+}
+
+/// Runs the test modules and reruns the ones which depend on a changed module, all of them when a watched path changes
+///
+/// Change during a run interrupts it, the interrupted module and ones which haven't ran yet get ran again
+///
+/// Only returns when watching fails
+pub async fn watch_test(
+    module_paths: &[String],
+    options: SableOptions,
+    watched_paths: &[PathBuf],
+) -> Result<(), AnyError> {
+    watch_test_with(
+        module_paths,
+        options,
+        watched_paths,
+        |path, options| async move { sable_run(&path, options).await },
+    )
+    .await
+}
+
+async fn watch_test_with<F, Fut>(
+    module_paths: &[String],
+    mut options: SableOptions,
+    watched_paths: &[PathBuf],
+    mut run: F,
+) -> Result<(), AnyError>
+where
+    F: FnMut(String, SableOptions) -> Fut,
+    Fut: Future<Output = Result<(), AnyError>>,
+{
+    let mut watcher = ModuleWatcher::new(watched_paths)?;
+    // Local modules loaded by each test module
+    let mut module_graphs = vec![HashSet::new(); module_paths.len()];
+    // Indexes of test modules which have to be ran, in order
+    let mut queue: Vec<usize> = (0..module_paths.len()).collect();
+    let mut change = None;
+
+    loop {
+        clear_screen(change.as_ref());
+
+        let mut interrupted = None;
+        while let Some(&index) = queue.first() {
+            let (run_options, mut receiver) = next_run_options(&mut options);
+            let graph = &mut module_graphs[index];
+            // Module might not import the same modules anymore
+            graph.clear();
+
+            let run = run(module_paths[index].clone(), run_options);
+            interrupted = run_module(run, &mut receiver, &mut watcher, graph).await?;
+            if interrupted.is_some() {
+                break;
+            }
+            queue.remove(0);
+        }
+
+        if interrupted.is_none() {
+            print_waiting();
+        }
+
+        change = loop {
+            let next_change = match interrupted.take() {
+                Some(change) => change,
+                None => watcher.changed().await?,
+            };
+
+            for index in affected_tests(&module_graphs, &next_change) {
+                if !queue.contains(&index) {
+                    queue.push(index);
+                }
+            }
+            queue.sort_unstable();
+
+            if !queue.is_empty() {
+                break Some(next_change);
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, env, fs, rc::Rc, time::Duration};
+
+    use super::*;
+    use crate::test_options as options;
+
+    type RunFuture = std::pin::Pin<Box<dyn Future<Output = Result<(), AnyError>>>>;
+
+    /// Time after which a change made to the file system is surely reported
+    const CHANGE_DELAY: Duration = Duration::from_millis(300);
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("sable_{}_{}", name, std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            Self(path.canonicalize().unwrap())
+        }
+
+        fn file(&self, name: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, "").unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Records paths of ran modules, which report given imports to the loader
+    ///
+    /// First run of `hanging` never finishes
+    fn fake_runner(
+        imports: Vec<(PathBuf, Vec<PathBuf>)>,
+        hanging: Option<PathBuf>,
+    ) -> (
+        Rc<RefCell<Vec<PathBuf>>>,
+        impl FnMut(String, SableOptions) -> RunFuture,
+    ) {
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let recorded = runs.clone();
+
+        let run = move |path: String, options: SableOptions| {
+            let path = PathBuf::from(path);
+            let hangs = hanging.as_ref() == Some(&path) && !recorded.borrow().contains(&path);
+            recorded.borrow_mut().push(path.clone());
+
+            let sender = options.loaded_modules.unwrap();
+            sender.send(path.clone()).unwrap();
+            for (module, imports) in &imports {
+                if *module == path {
+                    for import in imports {
+                        sender.send(import.clone()).unwrap();
+                    }
+                }
+            }
+
+            Box::pin(async move {
+                if hangs {
+                    std::future::pending::<()>().await;
+                }
+                Ok(())
+            }) as RunFuture
+        };
+
+        (runs, run)
+    }
+
+    fn test_modules(paths: &[&PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn affected_tests_depend_on_changed_modules() {
+        let graphs = vec![
+            HashSet::from([PathBuf::from("/a.test.js"), PathBuf::from("/shared.js")]),
+            HashSet::from([PathBuf::from("/b.test.js")]),
+            HashSet::from([PathBuf::from("/c.test.js"), PathBuf::from("/shared.js")]),
+        ];
+
+        let change = Change::Modules(vec![PathBuf::from("/shared.js")]);
+        assert_eq!(affected_tests(&graphs, &change), vec![0, 2]);
+
+        let change = Change::Modules(vec![PathBuf::from("/unrelated.js")]);
+        assert!(affected_tests(&graphs, &change).is_empty());
+
+        let change = Change::WatchedPath(PathBuf::from("/assets/style.css"));
+        assert_eq!(affected_tests(&graphs, &change), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn only_loaded_modules_are_reported() {
+        let dir = TempDir::new("watch_modules");
+        let module = dir.file("module.js");
+        let other = dir.file("other.txt");
+
+        let mut watcher = ModuleWatcher::new(&[]).unwrap();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        sender.send(module.clone()).unwrap();
+        let modules = watcher.watch_loaded_modules(&mut receiver).unwrap();
+        assert_eq!(modules, HashSet::from([module.clone()]));
+
+        fs::write(&other, "changed").unwrap();
+        let change = tokio::time::timeout(CHANGE_DELAY, watcher.changed()).await;
+        assert!(change.is_err(), "Change of unloaded file got reported");
+
+        fs::write(&module, "changed").unwrap();
+        let change = watcher.changed().await.unwrap();
+        assert!(matches!(change, Change::Modules(paths) if paths == vec![module]));
+    }
+
+    #[tokio::test]
+    async fn hanging_run_gets_restarted() {
+        let dir = TempDir::new("watch_run");
+        let main = dir.file("main.js");
+        let (runs, run) = fake_runner(vec![], Some(main.clone()));
+
+        let path = main.to_string_lossy();
+        let watch = watch_run_with(&path, options(), &[], run);
+        let changes = async {
+            tokio::time::sleep(CHANGE_DELAY).await;
+            fs::write(&main, "changed").unwrap();
+            tokio::time::sleep(CHANGE_DELAY).await;
+        };
+
+        tokio::select! {
+            result = watch => panic!("Watching ended with {:?}", result.err()),
+            _ = changes => {}
+        }
+        assert_eq!(*runs.borrow(), vec![main.clone(), main]);
+    }
+
+    #[tokio::test]
+    async fn affected_tests_get_rerun() {
+        let dir = TempDir::new("watch_test");
+        let a = dir.file("a.test.js");
+        let b = dir.file("b.test.js");
+        let shared = dir.file("shared.js");
+        let (runs, run) = fake_runner(vec![(a.clone(), vec![shared.clone()])], Some(a.clone()));
+
+        let module_paths = test_modules(&[&a, &b]);
+        let watch = watch_test_with(&module_paths, options(), &[], run);
+        let changes = async {
+            // First run of a.test.js hangs, so b.test.js can only run after it gets restarted
+            tokio::time::sleep(CHANGE_DELAY).await;
+            assert_eq!(*runs.borrow(), vec![a.clone()]);
+            fs::write(&a, "changed").unwrap();
+            tokio::time::sleep(CHANGE_DELAY).await;
+
+            fs::write(&shared, "changed").unwrap();
+            tokio::time::sleep(CHANGE_DELAY).await;
+        };
+
+        tokio::select! {
+            result = watch => panic!("Watching ended with {:?}", result.err()),
+            _ = changes => {}
+        }
+        assert_eq!(*runs.borrow(), vec![a.clone(), a.clone(), b, a]);
+    }
+}
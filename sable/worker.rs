@@ -0,0 +1,120 @@
+use std::{pin::pin, rc::Rc, sync::Arc, thread};
+
+use deno_core::{error::AnyError, JsRuntime, RuntimeOptions, SharedArrayBufferStore};
+use sable_ext::extensions::{
+    errors::get_error_class_name,
+    permissions::PermissionsContainer,
+    sable, sable_cleanup, sable_worker,
+    web::{BlobStore, BroadcastHub},
+    worker::{CreateWorker, WorkerOptions},
+};
+
+use crate::{
+    loader::{ModuleSources, SableModuleLoader},
+    module_cache::ModuleCache,
+    SableOptions, RUNTIME_SNAPSHOT,
+};
+
+/// State shared by the main runtime and all of its workers
+#[derive(Clone)]
+pub struct SharedRuntimeState {
+    pub module_cache: Arc<ModuleCache>,
+    pub options: SableOptions,
+    /// Transferred ArrayBuffers and SharedArrayBuffers, which move between isolates through it
+    pub shared_array_buffer_store: SharedArrayBufferStore,
+    /// Lets workers read blobs and object URLs created by other runtimes
+    pub blob_store: BlobStore,
+    /// Lets `BroadcastChannel`s reach channels in other runtimes
+    pub broadcast_hub: BroadcastHub,
+}
+
+/// Callback which `op_worker_create` uses to start workers on their own threads
+pub fn create_worker_callback(shared: SharedRuntimeState) -> CreateWorker {
+    CreateWorker(Arc::new(move |options: WorkerOptions| {
+        let shared = shared.clone();
+        let name = match options.scope.name() {
+            "" => "sable-worker".to_owned(),
+            name => format!("sable-worker-{name}"),
+        };
+        thread::Builder::new()
+            .name(name)
+            .spawn(move || run_worker_thread(shared, options))?;
+        Ok(())
+    }))
+}
+
+fn run_worker_thread(shared: SharedRuntimeState, options: WorkerOptions) {
+    let scope = options.scope.clone();
+    let result = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(AnyError::from)
+        .and_then(|runtime| runtime.block_on(run_worker(shared, options)));
+
+    if let Err(error) = result {
+        scope.report_error(&error);
+    }
+}
+
+async fn run_worker(shared: SharedRuntimeState, options: WorkerOptions) -> Result<(), AnyError> {
+    let WorkerOptions {
+        main_module,
+        permissions,
+        scope,
+    } = options;
+    let termination = scope.termination().clone();
+    let permissions = PermissionsContainer::new(permissions);
+
+    let mut js_runtime = JsRuntime::new(RuntimeOptions {
+        startup_snapshot: Some(RUNTIME_SNAPSHOT),
+        module_loader: Some(Rc::new(SableModuleLoader::new(
+            shared.module_cache.clone(),
+            shared.options.clone(),
+            permissions.clone(),
+            ModuleSources::default(),
+        ))),
+        get_error_class_fn: Some(&get_error_class_name),
+        shared_array_buffer_store: Some(shared.shared_array_buffer_store.clone()),
+        extensions: vec![
+            sable::init_ops(),
+            sable_worker::init_ops_and_esm(scope.clone()),
+            sable_cleanup::init_ops_and_esm(),
+        ],
+        ..Default::default()
+    });
+
+    // Worker might have been terminated while its runtime was being created
+    if !termination.set_isolate(js_runtime.v8_isolate().thread_safe_handle()) {
+        return Ok(());
+    }
+
+    {
+        let state = js_runtime.op_state();
+        let mut state = state.borrow_mut();
+        state.put(permissions);
+        state.put(shared.blob_store.clone());
+        state.put(shared.broadcast_hub.clone());
+        state.put(create_worker_callback(shared));
+    }
+
+    let run = async {
+        let mod_id = js_runtime.load_main_es_module(&main_module).await?;
+        let evaluation = pin!(js_runtime.mod_evaluate(mod_id));
+        js_runtime
+            .with_event_loop_promise(evaluation, Default::default())
+            .await?;
+        scope.start_receiving();
+        js_runtime.run_event_loop(Default::default()).await
+    };
+
+    tokio::select! {
+        result = run => {
+            // Errors caused by terminating the isolate aren't errors of the worker
+            if termination.is_terminated() {
+                return Ok(());
+            }
+            result
+        }
+        _ = termination.terminated() => Ok(()),
+    }
+}
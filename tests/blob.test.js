@@ -0,0 +1,188 @@
+const { test } = Sable.testing;
+
+/** Served by fetch_server.py, which run.sh starts */
+const SERVER = "http://127.0.0.1:4545";
+
+await test("Blob concatenates parts and normalizes type", async (ctx) => {
+	const inner = new Blob(["b", new Uint8Array([99])]);
+	const blob = new Blob(["a", inner, new Uint16Array([0x6564]).buffer, "\r\nf"], {
+		type: "Text/Plain",
+	});
+
+	ctx.equals(blob.size, 8);
+	ctx.equals(blob.type, "text/plain");
+	ctx.equals(await blob.text(), "abcde\r\nf");
+	ctx.deepEquals(
+		await new Blob(["a"]).bytes(),
+		new Uint8Array([97]),
+	);
+	ctx.equals(new Blob([], { type: "café" }).type, "");
+	ctx.equals(
+		await new Blob(["a\r\nb\rc"], { endings: "native" }).text(),
+		"a\nb\nc",
+	);
+	ctx.throws(() => new Blob("abc"), TypeError);
+	ctx.throws(() => new Blob([], { endings: "unix" }), TypeError);
+});
+
+await test("Blob.slice shares parts and supports negative indices", async (ctx) => {
+	const blob = new Blob(["hello ", new Blob(["blob"]), " world"]);
+
+	ctx.equals(await blob.slice(3, 12).text(), "lo blob w");
+	ctx.equals(await blob.slice(-5).text(), "world");
+	ctx.equals(await blob.slice(6, -6).text(), "blob");
+	ctx.equals(blob.slice(10, 2).size, 0);
+
+	const typed = blob.slice(0, 5, "TEXT/HTML");
+	ctx.equals(typed.type, "text/html");
+	ctx.equals(await typed.slice(1, 3).text(), "el");
+	ctx.equals(
+		new TextDecoder().decode(await blob.slice(6, 10).arrayBuffer()),
+		"blob",
+	);
+});
+
+await test("Blob.stream reads in chunks", async (ctx) => {
+	const size = 200 * 1024;
+	const blob = new Blob([new Uint8Array(size).fill(1), "end"]);
+
+	let length = 0;
+	let chunks = 0;
+	for await (const chunk of blob.stream()) {
+		ctx.assert(chunk instanceof Uint8Array);
+		length += chunk.byteLength;
+		chunks++;
+	}
+	ctx.equals(length, size + 3);
+	ctx.assert(chunks > 1);
+
+	const reader = new Blob(["abc"]).stream().getReader({ mode: "byob" });
+	const { value } = await reader.read(new Uint8Array(2));
+	ctx.equals(new TextDecoder().decode(value), "ab");
+});
+
+await test("File has name and lastModified", async (ctx) => {
+	const file = new File(["content"], "notes.txt", {
+		type: "text/plain",
+		lastModified: 42,
+	});
+
+	ctx.assert(file instanceof Blob);
+	ctx.equals(file.name, "notes.txt");
+	ctx.equals(file.lastModified, 42);
+	ctx.equals(file.type, "text/plain");
+	ctx.equals(await file.text(), "content");
+	ctx.equals(Object.prototype.toString.call(file), "[object File]");
+	ctx.assert(Math.abs(new File([], "a").lastModified - Date.now()) < 1000);
+	ctx.throws(() => new File([]), TypeError);
+});
+
+await test("FormData keeps entries in order", (ctx) => {
+	const formData = new FormData();
+	formData.append("a", "1");
+	formData.append("b", new Blob(["x"], { type: "text/plain" }));
+	formData.append("a", "2");
+	formData.append("c", new File(["y"], "y.txt"), "renamed.txt");
+
+	ctx.equals(formData.get("a"), "1");
+	ctx.deepEquals(formData.getAll("a"), ["1", "2"]);
+	ctx.equals(formData.get("missing"), null);
+
+	const blob = formData.get("b");
+	ctx.assert(blob instanceof File);
+	ctx.equals(blob.name, "blob");
+	ctx.equals(blob.type, "text/plain");
+	ctx.equals(formData.get("c").name, "renamed.txt");
+
+	formData.set("a", "3");
+	ctx.deepEquals([...formData.keys()], ["a", "b", "c"]);
+	ctx.equals(formData.get("a"), "3");
+	formData.delete("b");
+	ctx.assert(!formData.has("b"));
+	ctx.throws(() => formData.append("a"), TypeError);
+	ctx.throws(() => new FormData({}), TypeError);
+});
+
+await test("FormData round trips through multipart bodies", async (ctx) => {
+	const formData = new FormData();
+	formData.append("text", "line 1\nline 2");
+	formData.append('quoted "name"', "value");
+	formData.append(
+		"file",
+		new File([new Uint8Array([0, 1, 2, 255])], "data.bin", {
+			type: "application/octet-stream",
+		}),
+	);
+
+	const response = new Response(formData);
+	const contentType = response.headers.get("content-type");
+	ctx.assert(contentType.startsWith("multipart/form-data; boundary="));
+
+	const parsed = await response.formData();
+	ctx.equals(parsed.get("text"), "line 1\r\nline 2");
+	ctx.equals(parsed.get('quoted "name"'), "value");
+	const file = parsed.get("file");
+	ctx.equals(file.name, "data.bin");
+	ctx.equals(file.type, "application/octet-stream");
+	ctx.deepEquals(await file.bytes(), new Uint8Array([0, 1, 2, 255]));
+});
+
+await test("Body mixin reads blobs and urlencoded form data", async (ctx) => {
+	const blob = await new Response("abc", {
+		headers: { "content-type": "text/css" },
+	}).blob();
+	ctx.equals(blob.type, "text/css");
+	ctx.equals(await blob.text(), "abc");
+
+	const request = new Request(SERVER, {
+		method: "POST",
+		body: new Blob(["{}"], { type: "application/json" }),
+	});
+	ctx.equals(request.headers.get("content-type"), "application/json");
+
+	const formData = await new Response(new URLSearchParams("a=1&b=2&a=3"))
+		.formData();
+	ctx.deepEquals(formData.getAll("a"), ["1", "3"]);
+	await ctx.rejects(new Response("a=1").formData());
+});
+
+await test("fetch sends blobs and form data", async (ctx) => {
+	let response = await fetch(`${SERVER}/echo`, {
+		method: "POST",
+		body: new Blob(["blob body"], { type: "text/x-custom" }),
+	});
+	let echo = await response.json();
+	ctx.equals(echo.body, "blob body");
+	ctx.equals(echo.headers["content-type"], "text/x-custom");
+
+	const formData = new FormData();
+	formData.append("field", "value");
+	formData.append("upload", new File(["file body"], "upload.txt"));
+	response = await fetch(`${SERVER}/echo`, { method: "POST", body: formData });
+	echo = await response.json();
+
+	const parsed = await new Response(echo.body, {
+		headers: { "content-type": echo.headers["content-type"] },
+	}).formData();
+	ctx.equals(parsed.get("field"), "value");
+	ctx.equals(parsed.get("upload").name, "upload.txt");
+	ctx.equals(await parsed.get("upload").text(), "file body");
+});
+
+await test("Object URLs can be fetched until they get revoked", async (ctx) => {
+	const blob = new Blob(["object ", "url"], { type: "text/plain" });
+	const url = URL.createObjectURL(blob.slice(7, blob.size, "text/plain"));
+	ctx.assert(url.startsWith("blob:"));
+
+	const response = await fetch(url);
+	ctx.equals(response.status, 200);
+	ctx.equals(response.headers.get("content-type"), "text/plain");
+	ctx.equals(response.headers.get("content-length"), "3");
+	ctx.equals(await response.text(), "url");
+	ctx.equals(await (await fetch(`${url}#fragment`)).text(), "url");
+
+	await ctx.rejects(fetch(url, { method: "POST", body: "a" }));
+	URL.revokeObjectURL(url);
+	await ctx.rejects(fetch(url));
+	ctx.throws(() => URL.createObjectURL("text"), TypeError);
+});
@@ -7,3 +7,21 @@ test("Encoding API", (ctx) => {
 
 	ctx.equals(text, decoder.decode(encoder.encode(text)));
 });
+
+test("atob() and btoa() convert between binary strings and base64", (ctx) => {
+	ctx.equals(btoa(""), "");
+	ctx.equals(btoa("f"), "Zg==");
+	ctx.equals(btoa("fo"), "Zm8=");
+	ctx.equals(btoa("foo"), "Zm9v");
+	ctx.equals(btoa("\xff\xfe\x00"), "//4A");
+	ctx.throws(() => btoa("€"), DOMException);
+
+	ctx.equals(atob("Zm9v"), "foo");
+	ctx.equals(atob("Zm8="), "fo");
+	ctx.equals(atob("Zg"), "f");
+	ctx.equals(atob(" Zm 9v\n"), "foo");
+	ctx.equals(atob("//4A"), "\xff\xfe\x00");
+	ctx.throws(() => atob("Zm9vY"), DOMException);
+	ctx.throws(() => atob("Zm9v!"), DOMException);
+	ctx.throws(() => atob("Zg==="), DOMException);
+});
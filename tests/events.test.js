@@ -0,0 +1,193 @@
+const { test } = Sable.testing;
+
+await test("Unhandled rejections can be prevented", async (ctx) => {
+	const reason = new Error("rejected");
+	const events = [];
+
+	/** @param {PromiseRejectionEvent} event */
+	const listener = (event) => {
+		events.push(event);
+		event.preventDefault();
+	};
+	addEventListener("unhandledrejection", listener);
+
+	Promise.reject(reason);
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	removeEventListener("unhandledrejection", listener);
+
+	ctx.equals(events.length, 1);
+	ctx.assert(events[0].isTrusted);
+	ctx.equals(events[0].reason, reason);
+});
+
+await test("Errors thrown by timers dispatch error event", async (ctx) => {
+	const error = new Error("thrown");
+	const events = [];
+
+	/** @param {ErrorEvent} event */
+	const listener = (event) => {
+		events.push(event);
+		event.preventDefault();
+	};
+	addEventListener("error", listener);
+
+	setTimeout(() => {
+		throw error;
+	}, 0);
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	removeEventListener("error", listener);
+
+	ctx.equals(events.length, 1);
+	ctx.equals(events[0].error, error);
+	ctx.equals(events[0].message, "thrown");
+	ctx.assert(events[0].filename.endsWith("events.test.js"));
+});
+
+test("reportError dispatches error event", (ctx) => {
+	const error = new TypeError("reported");
+	let reported;
+
+	/** @param {ErrorEvent} event */
+	const listener = (event) => {
+		reported = event.error;
+		event.preventDefault();
+	};
+	addEventListener("error", listener);
+	reportError(error);
+	removeEventListener("error", listener);
+
+	ctx.equals(reported, error);
+});
+
+await test("DOMException has names and legacy codes", (ctx) => {
+	const exception = new DOMException("aborted", "AbortError");
+	ctx.assert(exception instanceof Error);
+	ctx.equals(exception.name, "AbortError");
+	ctx.equals(exception.message, "aborted");
+	ctx.equals(exception.code, DOMException.ABORT_ERR);
+	ctx.equals(exception.code, 20);
+	ctx.assert(exception.stack.startsWith("AbortError: aborted"));
+	ctx.equals(new DOMException("custom", "CustomError").code, 0);
+	ctx.equals(new DOMException().name, "Error");
+
+	const event = new Event("test");
+	const target = new EventTarget();
+	target.addEventListener("test", () => {
+		try {
+			target.dispatchEvent(event);
+		} catch (error) {
+			ctx.equals(error.name, "InvalidStateError");
+			ctx.equals(error.code, DOMException.INVALID_STATE_ERR);
+		}
+	});
+	target.dispatchEvent(event);
+});
+
+await test("AbortController aborts its signal once", (ctx) => {
+	const controller = new AbortController();
+	const { signal } = controller;
+	ctx.throws(() => new AbortSignal(), TypeError);
+	ctx.assert(!signal.aborted);
+	signal.throwIfAborted();
+
+	const events = [];
+	signal.onabort = (event) => events.push(event.type);
+	signal.addEventListener("abort", () => events.push(signal.reason));
+
+	controller.abort("reason");
+	controller.abort("ignored");
+	ctx.assert(signal.aborted);
+	ctx.equals(signal.reason, "reason");
+	ctx.deepEquals(events, ["abort", "reason"]);
+	ctx.throws(() => signal.throwIfAborted());
+
+	const defaultReason = new AbortController();
+	defaultReason.abort();
+	ctx.assert(defaultReason.signal.reason instanceof DOMException);
+	ctx.equals(defaultReason.signal.reason.name, "AbortError");
+	ctx.equals(AbortSignal.abort().reason.name, "AbortError");
+
+	// Only a missing reason gets replaced
+	const nullReason = new AbortController();
+	nullReason.abort(null);
+	ctx.equals(nullReason.signal.reason, null);
+	ctx.equals(AbortSignal.abort(null).reason, null);
+});
+
+await test("AbortSignal removes event listeners", (ctx) => {
+	const controller = new AbortController();
+	const target = new EventTarget();
+	let calls = 0;
+	target.addEventListener("test", () => calls++, { signal: controller.signal });
+
+	target.dispatchEvent(new Event("test"));
+	controller.abort();
+	target.dispatchEvent(new Event("test"));
+	ctx.equals(calls, 1);
+});
+
+await test("AbortSignal.any() follows every signal", (ctx) => {
+	const first = new AbortController();
+	const second = new AbortController();
+	const any = AbortSignal.any([first.signal, second.signal]);
+	const nested = AbortSignal.any([any]);
+	ctx.assert(!any.aborted);
+
+	second.abort("second");
+	ctx.equals(any.reason, "second");
+	ctx.equals(nested.reason, "second");
+	first.abort("first");
+	ctx.equals(any.reason, "second");
+
+	const aborted = AbortSignal.any([new AbortController().signal, AbortSignal.abort("now")]);
+	ctx.equals(aborted.reason, "now");
+	ctx.throws(() => AbortSignal.any([{}]), TypeError);
+});
+
+await test("AbortSignal.timeout() aborts with TimeoutError", async (ctx) => {
+	const signal = AbortSignal.timeout(5);
+	ctx.assert(!signal.aborted);
+	// Timer of the signal doesn't keep the runtime alive on its own
+	const keepAlive = setTimeout(() => {}, 1000);
+	await new Promise((resolve) => signal.addEventListener("abort", resolve));
+	clearTimeout(keepAlive);
+	ctx.equals(signal.reason.name, "TimeoutError");
+	ctx.equals(signal.reason.code, DOMException.TIMEOUT_ERR);
+	ctx.throws(() => AbortSignal.timeout(-1), TypeError);
+});
+
+await test("Global scope is an EventTarget", (ctx) => {
+	ctx.assert(globalThis instanceof EventTarget);
+	ctx.equals(self, globalThis);
+
+	let calls = 0;
+	const listener = () => calls++;
+	addEventListener("test", listener);
+	globalThis.dispatchEvent(new Event("test"));
+	dispatchEvent(new Event("test"));
+	removeEventListener("test", listener);
+	dispatchEvent(new Event("test"));
+	ctx.equals(calls, 2);
+});
+
+await test("Errors thrown by microtasks dispatch error event", async (ctx) => {
+	const error = new Error("thrown");
+	const events = [];
+
+	/** @param {ErrorEvent} event */
+	const listener = (event) => {
+		events.push(event);
+		event.preventDefault();
+	};
+	addEventListener("error", listener);
+
+	queueMicrotask(() => {
+		throw error;
+	});
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	removeEventListener("error", listener);
+
+	ctx.equals(events.length, 1);
+	ctx.equals(events[0].error, error);
+	ctx.throws(() => queueMicrotask("not a function"), TypeError);
+});
@@ -0,0 +1,231 @@
+const { test } = Sable.testing;
+
+/** Served by fetch_server.py, which run.sh starts */
+const SERVER = "http://127.0.0.1:4545";
+
+await test("Headers combine, sort and validate values", (ctx) => {
+	const headers = new Headers({ "X-B": " 2 ", "x-a": "1" });
+	headers.append("X-B", "3");
+	headers.append("Set-Cookie", "a=1");
+	headers.append("Set-Cookie", "b=2");
+
+	ctx.equals(headers.get("x-b"), "2, 3");
+	ctx.deepEquals(headers.getSetCookie(), ["a=1", "b=2"]);
+	ctx.deepEquals([...headers], [
+		["set-cookie", "a=1"],
+		["set-cookie", "b=2"],
+		["x-a", "1"],
+		["x-b", "2, 3"],
+	]);
+
+	headers.set("x-b", "4");
+	ctx.equals(headers.get("X-B"), "4");
+	headers.delete("x-a");
+	ctx.assert(!headers.has("x-a"));
+
+	ctx.throws(() => headers.append("in valid", "1"), TypeError);
+	ctx.throws(() => headers.append("x-c", "a\nb"), TypeError);
+});
+
+await test("Request validates method and body", (ctx) => {
+	const request = new Request(`${SERVER}/echo`, { method: "post", body: "a" });
+	ctx.equals(request.method, "POST");
+	ctx.equals(request.url, `${SERVER}/echo`);
+	ctx.equals(request.headers.get("content-type"), "text/plain;charset=UTF-8");
+	ctx.equals(request.redirect, "follow");
+
+	ctx.throws(() => new Request(SERVER, { method: "TRACE" }), TypeError);
+	ctx.throws(() => new Request(SERVER, { body: "a" }), TypeError);
+	ctx.throws(() => new Request("/relative"), TypeError);
+	ctx.throws(
+		() => new Request(SERVER, { method: "POST", body: new ReadableStream() }),
+		TypeError,
+	);
+});
+
+await test("Request and Response bodies can be read once", async (ctx) => {
+	const request = new Request(SERVER, { method: "PUT", body: "text" });
+	const clone = request.clone();
+	ctx.equals(await request.text(), "text");
+	ctx.assert(request.bodyUsed);
+	await ctx.rejects(request.text());
+	ctx.equals(await clone.text(), "text");
+
+	const response = Response.json({ a: 1 }, { status: 201 });
+	ctx.equals(response.status, 201);
+	ctx.equals(response.headers.get("content-type"), "application/json");
+	ctx.deepEquals(await response.json(), { a: 1 });
+
+	const bytes = new Response(new Uint8Array([1, 2, 3]));
+	ctx.deepEquals(new Uint8Array(await bytes.arrayBuffer()), new Uint8Array([1, 2, 3]));
+	ctx.equals(await new Response().text(), "");
+});
+
+await test("Response validates status and guards headers", (ctx) => {
+	ctx.throws(() => new Response(null, { status: 199 }), RangeError);
+	ctx.throws(() => new Response("body", { status: 204 }), TypeError);
+
+	const error = Response.error();
+	ctx.equals(error.type, "error");
+	ctx.equals(error.status, 0);
+	ctx.throws(() => error.headers.set("a", "b"), TypeError);
+
+	const redirect = Response.redirect(`${SERVER}/a`, 307);
+	ctx.equals(redirect.status, 307);
+	ctx.equals(redirect.headers.get("location"), `${SERVER}/a`);
+	ctx.throws(() => Response.redirect(SERVER, 200), RangeError);
+});
+
+await test("fetch sends requests and reads responses", async (ctx) => {
+	const response = await fetch(`${SERVER}/echo`, {
+		method: "POST",
+		headers: { "X-Test": "value" },
+		body: "hello",
+	});
+	ctx.equals(response.status, 200);
+	ctx.assert(response.ok);
+	ctx.equals(response.type, "basic");
+	ctx.equals(response.url, `${SERVER}/echo`);
+	ctx.throws(() => response.headers.set("a", "b"), TypeError);
+
+	const echo = await response.json();
+	ctx.equals(echo.method, "POST");
+	ctx.equals(echo.headers["x-test"], "value");
+	ctx.equals(echo.headers["content-type"], "text/plain;charset=UTF-8");
+	ctx.equals(echo.body, "hello");
+
+	const notFound = await fetch(`${SERVER}/missing`);
+	ctx.equals(notFound.status, 404);
+	ctx.assert(!notFound.ok);
+	ctx.equals(await notFound.text(), "Not Found");
+
+	const head = await fetch(`${SERVER}/echo`, { method: "HEAD" });
+	ctx.equals(head.body, null);
+
+	const cookies = await fetch(`${SERVER}/cookies`);
+	ctx.deepEquals(cookies.headers.getSetCookie(), ["a=1", "b=2"]);
+	await cookies.body.cancel();
+});
+
+await test("fetch streams request and response bodies", async (ctx) => {
+	const encoder = new TextEncoder();
+	const body = ReadableStream.from(["a", "b", "c"]).pipeThrough(
+		new TransformStream({
+			transform(chunk, controller) {
+				controller.enqueue(encoder.encode(chunk));
+			},
+		}),
+	);
+
+	const response = await fetch(`${SERVER}/echo`, {
+		method: "POST",
+		body,
+		duplex: "half",
+	});
+	let json = "";
+	for await (const chunk of response.body.pipeThrough(new TextDecoderStream())) {
+		json += chunk;
+	}
+	ctx.equals(JSON.parse(json).body, "abc");
+});
+
+await test("fetch follows redirects depending on redirect mode", async (ctx) => {
+	const location = `${SERVER}/echo`;
+
+	const followed = await fetch(`${SERVER}/redirect/303?${location}`, {
+		method: "POST",
+		body: "dropped",
+	});
+	ctx.assert(followed.redirected);
+	ctx.equals(followed.url, `${SERVER}/echo`);
+	const echo = await followed.json();
+	ctx.equals(echo.method, "GET");
+	ctx.equals(echo.body, "");
+	ctx.equals(echo.headers["content-type"], undefined);
+
+	const kept = await fetch(`${SERVER}/redirect/307?${location}`, {
+		method: "PUT",
+		body: "kept",
+	});
+	ctx.equals((await kept.json()).body, "kept");
+
+	const manual = await fetch(`${SERVER}/redirect/302?${location}`, {
+		redirect: "manual",
+	});
+	ctx.equals(manual.status, 302);
+	ctx.assert(!manual.redirected);
+	await manual.body.cancel();
+
+	await ctx.rejects(
+		fetch(`${SERVER}/redirect/302?${location}`, { redirect: "error" }),
+	);
+});
+
+await test("fetch can be aborted", async (ctx) => {
+	const controller = new AbortController();
+	controller.abort("before");
+	await ctx.rejects(fetch(`${SERVER}/echo`, { signal: controller.signal }));
+
+	const request = new Request(`${SERVER}/echo`, { signal: controller.signal });
+	ctx.assert(request.signal.aborted);
+	ctx.assert(!new Request(SERVER).signal.aborted);
+
+	const slow = new AbortController();
+	const response = await fetch(`${SERVER}/slow`, { signal: slow.signal });
+	const text = response.text();
+	slow.abort("during");
+	try {
+		await text;
+		ctx.assert(false);
+	} catch (error) {
+		ctx.equals(error, "during");
+	}
+
+	try {
+		const signal = AbortSignal.timeout(10);
+		await (await fetch(`${SERVER}/slow`, { signal })).text();
+		ctx.assert(false);
+	} catch (error) {
+		ctx.equals(error.name, "TimeoutError");
+	}
+});
+
+await test("Finished response bodies stop listening to the request signal", async (ctx) => {
+	// Request signal follows the given one, so listeners get counted on every signal
+	const listeners = new Set();
+	const { addEventListener, removeEventListener } = AbortSignal.prototype;
+	AbortSignal.prototype.addEventListener = function (type, listener, options) {
+		if (type === "abort") listeners.add(listener);
+		return addEventListener.call(this, type, listener, options);
+	};
+	AbortSignal.prototype.removeEventListener = function (type, listener, options) {
+		if (type === "abort") listeners.delete(listener);
+		return removeEventListener.call(this, type, listener, options);
+	};
+
+	try {
+		const controller = new AbortController();
+		const signal = controller.signal;
+		await (await fetch(`${SERVER}/echo`, { signal })).text();
+		ctx.equals(listeners.size, 0);
+
+		const response = await fetch(`${SERVER}/slow`, { signal });
+		await response.body.cancel();
+		ctx.equals(listeners.size, 0);
+	} finally {
+		AbortSignal.prototype.addEventListener = addEventListener;
+		AbortSignal.prototype.removeEventListener = removeEventListener;
+	}
+});
+
+await test("fetch reads file URLs", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	await Sable.fs.writeTextFile(path, "from file");
+
+	const response = await fetch(`file://${path}`);
+	ctx.equals(await response.text(), "from file");
+	await ctx.rejects(fetch(`file://${path}`, { method: "POST", body: "a" }));
+	await ctx.rejects(fetch("ftp://127.0.0.1/"));
+
+	await Sable.fs.removeFile(path);
+});
@@ -0,0 +1,67 @@
+"""HTTP server used by fetch.test.js, started by run.sh"""
+
+import json
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+PORT = 4545
+
+
+class Handler(BaseHTTPRequestHandler):
+    protocol_version = "HTTP/1.1"
+
+    def log_message(self, *args):
+        pass
+
+    def send(self, status, body=b"", headers=()):
+        self.send_response(status)
+        for name, value in headers:
+            self.send_header(name, value)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        if self.command != "HEAD":
+            self.wfile.write(body)
+
+    def read_body(self):
+        if self.headers.get("Transfer-Encoding") == "chunked":
+            body = b""
+            while True:
+                size = int(self.rfile.readline().strip(), 16)
+                chunk = self.rfile.read(size + 2)[:-2]
+                if size == 0:
+                    return body
+                body += chunk
+        return self.rfile.read(int(self.headers.get("Content-Length", 0)))
+
+    def handle_request(self):
+        path, _, query = self.path.partition("?")
+        body = self.read_body()
+
+        if path == "/echo":
+            # Responds with the request it received
+            self.send(200, json.dumps({
+                "method": self.command,
+                "headers": {name.lower(): value for name, value in self.headers.items()},
+                "body": body.decode(),
+            }).encode(), [("Content-Type", "application/json")])
+        elif path.startswith("/redirect/"):
+            # /redirect/<status>?<location>
+            self.send(int(path[len("/redirect/"):]), headers=[("Location", query)])
+        elif path == "/cookies":
+            self.send(200, headers=[("Set-Cookie", "a=1"), ("Set-Cookie", "b=2")])
+        elif path == "/slow":
+            # Sends headers right away, but the body only after a while
+            self.send_response(200)
+            self.send_header("Content-Length", "4")
+            self.end_headers()
+            self.wfile.flush()
+            time.sleep(1)
+            self.wfile.write(b"slow")
+        else:
+            self.send(404, b"Not Found")
+
+    do_GET = do_HEAD = do_POST = do_PUT = do_DELETE = handle_request
+
+
+if __name__ == "__main__":
+    ThreadingHTTPServer(("127.0.0.1", PORT), Handler).serve_forever()
@@ -0,0 +1,193 @@
+const { test } = Sable.testing;
+
+await test("File system API", async (ctx) => {
+	const dir = await Sable.fs.makeTempDir({ prefix: "sable_test_" });
+
+	try {
+		await Sable.fs.mkdir(`${dir}/nested/deep`, { recursive: true });
+		await Sable.fs.writeTextFile(`${dir}/nested/file.txt`, "Hello, world!");
+		await Sable.fs.copyFile(`${dir}/nested/file.txt`, `${dir}/copy.txt`);
+		await Sable.fs.rename(`${dir}/copy.txt`, `${dir}/renamed.txt`);
+		await Sable.fs.truncate(`${dir}/renamed.txt`, 5);
+		ctx.equals(await Sable.fs.readTextFile(`${dir}/renamed.txt`), "Hello");
+
+		const info = await Sable.fs.stat(`${dir}/nested/file.txt`);
+		ctx.assert(info.isFile);
+		ctx.equals(info.size, 13);
+		ctx.assert(info.mtime instanceof Date);
+
+		const mtime = new Date(2020, 0, 1);
+		await Sable.fs.utime(`${dir}/renamed.txt`, mtime, mtime);
+		ctx.equals(
+			(await Sable.fs.stat(`${dir}/renamed.txt`)).mtime.getTime(),
+			mtime.getTime(),
+		);
+
+		await Sable.fs.chmod(`${dir}/renamed.txt`, 0o600);
+		ctx.equals((await Sable.fs.stat(`${dir}/renamed.txt`)).mode & 0o777, 0o600);
+
+		await Sable.fs.symlink(`${dir}/renamed.txt`, `${dir}/link`);
+		ctx.assert((await Sable.fs.lstat(`${dir}/link`)).isSymlink);
+		ctx.equals(await Sable.fs.readLink(`${dir}/link`), `${dir}/renamed.txt`);
+		ctx.equals(
+			await Sable.fs.realPath(`${dir}/link`),
+			await Sable.fs.realPath(`${dir}/renamed.txt`),
+		);
+
+		const names = [];
+		for await (const entry of Sable.fs.readDir(dir)) {
+			names.push(entry.name);
+		}
+		ctx.deepEquals(names.sort(), ["link", "nested", "renamed.txt"]);
+	} finally {
+		await Sable.fs.removeDirectory(dir, true);
+	}
+});
+
+await test("File system errors are typed", async (ctx) => {
+	let error;
+	try {
+		await Sable.fs.stat("./this/path/does/not/exist");
+	} catch (e) {
+		error = e;
+	}
+	ctx.assert(error instanceof Sable.errors.NotFound);
+	ctx.equals(error.code, "ENOENT");
+	ctx.equals(error.errno, 2);
+	ctx.equals(error.syscall, "stat");
+	ctx.equals(error.path, "./this/path/does/not/exist");
+
+	const dir = await Sable.fs.makeTempDir();
+	try {
+		await Sable.fs.mkdir(dir);
+	} catch (e) {
+		error = e;
+	} finally {
+		await Sable.fs.removeDirectory(dir, false);
+	}
+	ctx.assert(error instanceof Sable.errors.AlreadyExists);
+	ctx.equals(error.syscall, "mkdir");
+	ctx.equals(error.path, dir);
+});
+
+await test("Temporary entries can't leave their directory", async (ctx) => {
+	const dir = await Sable.fs.makeTempDir();
+	try {
+		for (const options of [{ prefix: "../escaped_" }, { suffix: "/../../escaped" }]) {
+			let error;
+			try {
+				await Sable.fs.makeTempFile({ dir, ...options });
+			} catch (e) {
+				error = e;
+			}
+			ctx.assert(error instanceof TypeError);
+
+			error = undefined;
+			try {
+				await Sable.fs.makeTempDir({ dir, ...options });
+			} catch (e) {
+				error = e;
+			}
+			ctx.assert(error instanceof TypeError);
+		}
+	} finally {
+		await Sable.fs.removeDirectory(dir, true);
+	}
+});
+
+await test("File handles", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+
+	try {
+		const file = await Sable.fs.open(path, { read: true, write: true });
+		const encoder = new TextEncoder();
+		ctx.equals(await file.write(encoder.encode("Hello, world!")), 13);
+
+		ctx.equals(await file.seek(7), 7);
+		const buffer = new Uint8Array(16);
+		const read = await file.read(buffer);
+		ctx.equals(new TextDecoder().decode(buffer.subarray(0, read)), "world!");
+		ctx.equals(await file.read(buffer), null);
+
+		await file.truncate(5);
+		await file.sync();
+		ctx.equals((await file.stat()).size, 5);
+		file.close();
+
+		ctx.equals(await Sable.fs.readTextFile(path), "Hello");
+	} finally {
+		await Sable.fs.removeFile(path);
+	}
+});
+
+await test("Written data can be read back after closing the file", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	const data = "x".repeat(1024 * 1024);
+
+	try {
+		const file = await Sable.fs.open(path, { write: true });
+		let written = 0;
+		while (written < data.length) {
+			written += await file.write(new TextEncoder().encode(data.slice(written)));
+		}
+		file.close();
+		ctx.equals(await Sable.fs.readTextFile(path), data);
+
+		const streamed = await Sable.fs.open(path, { write: true, truncate: true });
+		const writer = streamed.writable.getWriter();
+		for (let i = 0; i < 16; i++) {
+			await writer.write(new TextEncoder().encode(data.slice(0, 65536)));
+		}
+		await writer.close();
+		ctx.equals(await Sable.fs.readTextFile(path), data);
+	} finally {
+		await Sable.fs.removeFile(path);
+	}
+});
+
+await test("Watching a missing path fails", async (ctx) => {
+	const watcher = Sable.fs.watch("./this/path/does/not/exist");
+	let error;
+	try {
+		await watcher.ready;
+	} catch (e) {
+		error = e;
+	}
+	ctx.assert(error instanceof Sable.errors.NotFound);
+	ctx.equals(error.syscall, "watch");
+});
+
+await test("Closing a watcher of a missing path doesn't throw", async () => {
+	Sable.fs.watch("./this/path/does/not/exist").close();
+	// Rejection would be reported as unhandled once the op fails
+	await new Promise((resolve) => setTimeout(resolve, 50));
+});
+
+await test("Watching file system changes", async (ctx) => {
+	const dir = await Sable.fs.makeTempDir();
+	const watcher = Sable.fs.watch(dir);
+
+	try {
+		await watcher.ready;
+		await Sable.fs.writeTextFile(`${dir}/watched.txt`, "Hello");
+		await Sable.fs.rename(`${dir}/watched.txt`, `${dir}/renamed.txt`);
+
+		const events = [];
+		for await (const event of watcher) {
+			events.push(event);
+			if (event.kind === "rename") break;
+		}
+
+		ctx.deepEquals(events[0], {
+			kind: "create",
+			paths: [`${dir}/watched.txt`],
+		});
+		ctx.deepEquals(events.at(-1), {
+			kind: "rename",
+			paths: [`${dir}/watched.txt`, `${dir}/renamed.txt`],
+		});
+	} finally {
+		watcher.close();
+		await Sable.fs.removeDirectory(dir, true);
+	}
+});
@@ -0,0 +1,29 @@
+const { test } = Sable.testing;
+
+await test("Sable.io.read reads into given buffer", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	await Sable.fs.writeTextFile(path, "Hello");
+
+	const file = await Sable.fs.open(path);
+	try {
+		const buffer = new Uint8Array(8);
+		ctx.equals(await Sable.io.read(file.rid, buffer), 5);
+		ctx.equals(new TextDecoder().decode(buffer.subarray(0, 5)), "Hello");
+		ctx.equals(await Sable.io.read(file.rid, buffer), null);
+	} finally {
+		file.close();
+		await Sable.fs.removeFile(path);
+	}
+});
+
+await test("Stdio resources are registered", async (ctx) => {
+	const data = new TextEncoder().encode("");
+	ctx.equals(await Sable.io.stdout.write(data), 0);
+	ctx.equals(typeof Sable.io.stdin.isTerminal(), "boolean");
+	ctx.equals(typeof Sable.io.stdout.isTerminal(), "boolean");
+
+	if (Sable.io.stdout.isTerminal()) {
+		const { columns, rows } = Sable.io.consoleSize();
+		ctx.assert(columns > 0 && rows > 0);
+	}
+});
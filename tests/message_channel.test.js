@@ -0,0 +1,192 @@
+const { test } = Sable.testing;
+
+/**
+ * @param {EventTarget} target
+ * @returns {Promise<MessageEvent>}
+ */
+function nextMessage(target) {
+	return new Promise((resolve) =>
+		target.addEventListener("message", resolve, { once: true })
+	);
+}
+
+/**
+ * Closes the ports and channels and gives their pending receives a moment to get canceled
+ * @param {...{ close(): void }} closeables
+ */
+async function close(...closeables) {
+	for (const closeable of closeables) {
+		closeable.close();
+	}
+	await new Promise((resolve) => setTimeout(resolve, 10));
+}
+
+await test("MessageChannel passes messages between its ports", async (ctx) => {
+	const { port1, port2 } = new MessageChannel();
+	ctx.assert(port1 instanceof MessagePort);
+	ctx.throws(() => new MessagePort(), TypeError);
+
+	try {
+		const received = new Promise((resolve) => (port2.onmessage = resolve));
+		port1.postMessage({ value: [1, 2] });
+		const event = await received;
+		ctx.assert(event instanceof MessageEvent);
+		ctx.deepEquals(event.data, { value: [1, 2] });
+		ctx.deepEquals(event.ports, []);
+
+		// Messages wait until the port gets started
+		port2.postMessage("queued");
+		await new Promise((resolve) => setTimeout(resolve, 10));
+		const queued = nextMessage(port1);
+		port1.start();
+		ctx.equals((await queued).data, "queued");
+	} finally {
+		await close(port1, port2);
+	}
+});
+
+await test("MessagePort can be transferred", async (ctx) => {
+	const carrier = new MessageChannel();
+	const { port1, port2 } = new MessageChannel();
+
+	try {
+		const received = nextMessage(carrier.port2);
+		carrier.port2.start();
+		carrier.port1.postMessage({ port: port2 }, [port2]);
+		const event = await received;
+
+		const transferred = event.data.port;
+		ctx.assert(transferred instanceof MessagePort);
+		ctx.assert(transferred !== port2);
+		ctx.equals(event.ports[0], transferred);
+
+		const reply = nextMessage(transferred);
+		transferred.start();
+		port1.postMessage("through the transferred port");
+		ctx.equals((await reply).data, "through the transferred port");
+		await close(transferred);
+	} finally {
+		await close(carrier.port1, carrier.port2, port1);
+	}
+
+	const sending = new MessageChannel();
+	const { port1: other, port2: otherPartner } = new MessageChannel();
+	try {
+		const { port1: sender } = sending;
+		ctx.throws(() => sender.postMessage(null, [sender]), DOMException);
+		ctx.throws(() => sender.postMessage(other), DOMException);
+		ctx.throws(() => sender.postMessage(null, [other, other]), DOMException);
+		sender.postMessage(null, [other]);
+		ctx.throws(() => sender.postMessage(null, [other]), DOMException);
+	} finally {
+		await close(sending.port1, sending.port2, otherPartner);
+	}
+});
+
+await test("MessagePort refuses to post Blob and File", async (ctx) => {
+	const { port1, port2 } = new MessageChannel();
+	try {
+		for (
+			const value of [
+				new Blob(["blob"]),
+				{ nested: [new File(["file"], "file.txt")] },
+			]
+		) {
+			try {
+				port1.postMessage(value);
+				ctx.assert(false);
+			} catch (error) {
+				ctx.assert(error instanceof DOMException);
+				ctx.equals(error.name, "DataCloneError");
+			}
+		}
+
+		// Failed message doesn't affect the port
+		const received = nextMessage(port2);
+		port2.start();
+		port1.postMessage("after blob");
+		ctx.equals((await received).data, "after blob");
+	} finally {
+		await close(port1, port2);
+	}
+});
+
+await test("MessagePort can be transferred to a worker", async (ctx) => {
+	const worker = new Worker(new URL("./worker/port.js", import.meta.url), {
+		type: "module",
+	});
+	const { port1, port2 } = new MessageChannel();
+
+	try {
+		worker.postMessage(null, [port2]);
+		const reply = nextMessage(port1);
+		port1.start();
+		port1.postMessage(21);
+		ctx.equals((await reply).data, 42);
+	} finally {
+		worker.terminate();
+		await close(port1);
+	}
+});
+
+await test("BroadcastChannel reaches other channels with the same name", async (ctx) => {
+	const sender = new BroadcastChannel("channel");
+	const receiver = new BroadcastChannel("channel");
+	const other = new BroadcastChannel("other");
+	ctx.equals(receiver.name, "channel");
+
+	try {
+		let received = 0;
+		sender.onmessage = other.onmessage = () => received++;
+
+		const event = nextMessage(receiver);
+		sender.postMessage({ hello: "world" });
+		ctx.deepEquals((await event).data, { hello: "world" });
+		await new Promise((resolve) => setTimeout(resolve, 10));
+		ctx.equals(received, 0);
+	} finally {
+		await close(sender, receiver, other);
+	}
+
+	ctx.throws(() => sender.postMessage("closed"), DOMException);
+});
+
+await test("BroadcastChannel delivers every message of a burst", async (ctx) => {
+	const sender = new BroadcastChannel("burst");
+	const receiver = new BroadcastChannel("burst");
+
+	try {
+		const received = [];
+		const done = new Promise((resolve) => {
+			receiver.onmessage = (event) => {
+				received.push(event.data);
+				if (received.length === 1000) resolve();
+			};
+		});
+		// Receiver only gets to read once the whole burst has been sent
+		for (let i = 0; i < 1000; i++) {
+			sender.postMessage(i);
+		}
+		await done;
+		ctx.deepEquals(received, [...Array(1000).keys()]);
+	} finally {
+		await close(sender, receiver);
+	}
+});
+
+await test("BroadcastChannel reaches workers", async (ctx) => {
+	const worker = new Worker(new URL("./worker/broadcast.js", import.meta.url), {
+		type: "module",
+	});
+	const channel = new BroadcastChannel("worker");
+
+	try {
+		ctx.equals((await nextMessage(worker)).data, "ready");
+		const reply = nextMessage(channel);
+		channel.postMessage("ping");
+		ctx.equals((await reply).data, "worker got ping");
+	} finally {
+		worker.terminate();
+		await close(channel);
+	}
+});
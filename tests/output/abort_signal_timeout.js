@@ -0,0 +1,7 @@
+// Expects: aborted
+// Expects: timer fired
+
+// Runtime waits for the refed timer, but not for the signal which would time out in an hour
+AbortSignal.timeout(60 * 60 * 1000).addEventListener("abort", () => console.log("too late"));
+AbortSignal.timeout(5).addEventListener("abort", () => console.log("aborted"));
+setTimeout(() => console.log("timer fired"), 50);
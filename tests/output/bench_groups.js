@@ -0,0 +1,24 @@
+// Args: bench
+// Expects: map
+// Expects: for loop
+// Expects: mapping
+// Expects: Benchmark
+// Expects: Time/iter
+// Expects: baseline
+// Expects: for loop is
+// Expects: than map
+const { bench } = Sable.testing;
+
+const array = Array.from({ length: 100 }, (_, i) => i);
+
+bench("map", { group: "mapping", baseline: true }, () => {
+	return array.map((x) => x * 2);
+});
+
+bench("for loop", { group: "mapping" }, () => {
+	const result = new Array(array.length);
+	for (let i = 0; i < array.length; ++i) {
+		result[i] = array[i] * 2;
+	}
+	return result;
+});
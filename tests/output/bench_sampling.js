@@ -0,0 +1,42 @@
+// Args: bench
+// Expects: sync loop
+// Expects: /iter ±
+// Expects: samples,
+// Expects: outliers removed
+// Expects: sync loop result is valid
+// Expects: sleep (async)
+// Expects: sleep result is valid
+const { bench } = Sable.testing;
+
+/**
+ * @param {Awaited<ReturnType<typeof bench>>} result
+ * @param {boolean} async
+ * @param {number} minTime - time in milliseconds which a single call takes at least
+ */
+function checkResult(result, async, minTime) {
+	const valid = result.async === async &&
+		result.samples + result.outliers >= 10 &&
+		result.min >= minTime &&
+		result.min <= result.mean && result.mean <= result.max &&
+		result.p75 <= result.p99 && result.p99 <= result.max &&
+		Math.abs(result.iterPerSec * result.mean - 1000) < 1e-6;
+	console.log(`${result.name} result is ${valid ? "valid" : "invalid"}`);
+}
+
+checkResult(
+	await bench("sync loop", () => {
+		let sum = 0;
+		for (let i = 0; i < 100; ++i) sum += i;
+		return sum;
+	}),
+	false,
+	0,
+);
+
+checkResult(
+	await bench("sleep", async () => {
+		await new Promise((resolve) => setTimeout(resolve, 1));
+	}),
+	true,
+	1,
+);
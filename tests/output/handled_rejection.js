@@ -0,0 +1,8 @@
+// Expects: handled: canceled by listener
+
+addEventListener("unhandledrejection", (event) => {
+	event.preventDefault();
+	console.log(`handled: ${event.reason.message}`);
+});
+
+Promise.reject(new Error("canceled by listener"));
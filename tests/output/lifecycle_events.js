@@ -0,0 +1,36 @@
+// Expects: load trusted
+// Expects: beforeunload 1 trusted
+// Expects: more work
+// Expects: beforeunload 2 trusted
+// Expects: unload trusted
+
+// Runtime fires the events itself, overriding globals doesn't affect them
+globalThis.Event = class {
+	constructor() {
+		throw new Error("Event got constructed through the global");
+	}
+};
+globalThis.dispatchEvent = () => {
+	throw new Error("Event got dispatched through the global");
+};
+
+/**
+ * @param {{ type: string; isTrusted: boolean }} event
+ * @param {string} [label]
+ */
+function log(event, label = event.type) {
+	console.log(`${label} ${event.isTrusted ? "trusted" : "untrusted"}`);
+}
+
+let beforeunloads = 0;
+addEventListener("load", (event) => log(event));
+addEventListener("beforeunload", (event) => {
+	beforeunloads++;
+	log(event, `beforeunload ${beforeunloads}`);
+	if (beforeunloads === 1) {
+		// Canceling the event keeps the runtime alive for the scheduled work
+		event.preventDefault();
+		setTimeout(() => console.log("more work"), 10);
+	}
+});
+addEventListener("unload", (event) => log(event));
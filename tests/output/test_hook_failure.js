@@ -0,0 +1,30 @@
+// Args: test
+// Exit code: 1
+// Expects: parent
+// Expects: throwing beforeEach
+// Expects: beforeEach hook failed
+// Expects: caught: beforeEach hook failed for throwing beforeEach: broken
+// Expects: after the failure
+// Expects: ok
+// Expects: ok
+// Expects: 1 test(s) failed
+const { test } = Sable.testing;
+
+await test("parent", async (ctx) => {
+	ctx.beforeEach(async (testContext) => {
+		if (testContext.name === "throwing beforeEach") {
+			throw new Error("broken");
+		}
+	});
+
+	try {
+		await ctx.test("throwing beforeEach", (ctx) => {
+			ctx.assert(false);
+		});
+	} catch (error) {
+		console.log(`caught: ${error.message}`);
+	}
+
+	// Parent has been unlocked, so it can run more sub-tests
+	await ctx.test("after the failure", (ctx) => ctx.assert(true));
+});
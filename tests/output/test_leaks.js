@@ -0,0 +1,33 @@
+// Args: test
+// Expects: leaks an interval
+// Expects: leaks an interval is leaking timers!
+// Expects: Timers were created but never cleared:
+// Expects: (every 1000ms)
+// Expects: leaks a port
+// Expects: leaks a port is leaking resources!
+// Expects: Resources were opened but never closed:
+// Expects: messagePort (rid
+const { test } = Sable.testing;
+
+let interval;
+try {
+	await test("leaks an interval", () => {
+		interval = setInterval(() => {}, 1000);
+	});
+} catch (error) {
+	console.log(error.message);
+} finally {
+	clearInterval(interval);
+}
+
+let channel;
+try {
+	await test("leaks a port", () => {
+		channel = new MessageChannel();
+	});
+} catch (error) {
+	console.log(error.message);
+} finally {
+	channel.port1.close();
+	channel.port2.close();
+}
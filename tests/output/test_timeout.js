@@ -0,0 +1,16 @@
+// Args: test
+// Exit code: 1
+// Expects: hangs forever
+// Expects: timed out after 50ms
+// Expects: runs after the timed out test
+// Expects: ok
+// Expects: 1 test(s) failed
+const { test } = Sable.testing;
+
+await test("hangs forever", { timeout: 50 }, async () => {
+	await new Promise(() => {});
+});
+
+await test("runs after the timed out test", (ctx) => {
+	ctx.assert(true);
+});
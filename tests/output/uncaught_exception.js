@@ -0,0 +1,5 @@
+// Exit code: 1
+// Expects: error: Uncaught Error: Uncaught (in promise) lookalike
+
+// Message of a thrown error doesn't make it a rejection
+throw new Error("Uncaught (in promise) lookalike");
@@ -0,0 +1,4 @@
+// Exit code: 3
+// Expects: error: Uncaught (in promise) Error: nobody handled this
+
+Promise.reject(new Error("nobody handled this"));
@@ -0,0 +1,54 @@
+const { test } = Sable.testing;
+
+await test("Permissions API", async (ctx) => {
+	const revoked = await Sable.permissions.revoke({ name: "read" });
+	ctx.equals(revoked.state, "prompt");
+
+	const status = await Sable.permissions.query({ name: "read", path: "." });
+	ctx.equals(status.state, "prompt");
+
+	let error;
+	try {
+		await Sable.permissions.query({ name: "camera" });
+	} catch (e) {
+		error = e;
+	}
+	ctx.assert(error instanceof TypeError);
+});
+
+/**
+ * @param {() => unknown} callback
+ * @returns {Promise<unknown>} Error which the callback has thrown or rejected with
+ */
+async function thrownBy(callback) {
+	try {
+		await callback();
+	} catch (error) {
+		return error;
+	}
+}
+
+await test("Permissions API - denied ops throw PermissionDenied", async (ctx) => {
+	const { PermissionDenied } = Sable.errors;
+
+	await Sable.permissions.revoke({ name: "read" });
+	const readError = await thrownBy(() => Sable.fs.readTextFile("./tests/permissions.test.js"));
+	ctx.assert(readError instanceof PermissionDenied);
+	ctx.assert(readError.message.includes("--allow-read"));
+
+	await Sable.permissions.revoke({ name: "write" });
+	const writeError = await thrownBy(() => Sable.fs.makeTempFile());
+	ctx.assert(writeError instanceof PermissionDenied);
+
+	await Sable.permissions.revoke({ name: "net" });
+	const netError = await thrownBy(() => fetch("http://localhost:4545"));
+	ctx.assert(netError instanceof PermissionDenied);
+
+	await Sable.permissions.revoke({ name: "sys" });
+	const batteryError = await thrownBy(() => navigator.getBattery());
+	ctx.assert(batteryError instanceof PermissionDenied);
+	ctx.assert(batteryError.message.includes("--allow-sys"));
+
+	const mouseError = await thrownBy(() => addEventListener("mousemove", () => {}));
+	ctx.assert(mouseError instanceof PermissionDenied);
+});
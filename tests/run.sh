@@ -1,11 +1,53 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Server which fetch tests make requests to
+python3 ./tests/fetch_server.py &
+server_pid=$!
+trap "kill $server_pid" EXIT
+# Fetch tests fail if they start before the server listens
+for _ in $(seq 50); do
+    (exec 3<>/dev/tcp/127.0.0.1/4545) 2>/dev/null && break
+    sleep 0.1
+done
+
 # Loop over all files in tests that end with .test.js
 for file in ./tests/*.test.js; do
     if [ -f $file ]; then
-        # Run that test with `sable test`
-        target/debug/sable test $file;
+        # Run that test with `sable test`, fs tests need permissions
         # If exit code of that test wasn't 0, exit with 1
-        if [ $? != 0 ]; then
+        if ! target/debug/sable test --allow-all $file; then
             exit 1;
         fi;
     fi;
 done;
+
+# Files in tests/output check what sable prints and how it exits, which tests can't check from within
+#  - `// Args: ...` are arguments which sable gets ran with before the file path, `run` by default
+#  - `// Exit code: ...` is the expected exit code, 0 by default
+#  - `// Expects: ...` lines have to appear in the output in the same order, ANSI styles are stripped
+for file in ./tests/output/*.js; do
+    args=$(sed -n 's#^// Args: ##p' $file)
+    expected_code=$(sed -n 's#^// Exit code: ##p' $file)
+
+    # Expected failures mustn't stop the script
+    code=0
+    output=$(target/debug/sable ${args:-run} $file 2>&1 | sed 's/\x1b\[[0-9;]*m//g'; exit ${PIPESTATUS[0]}) || code=$?
+
+    if [ $code != ${expected_code:-0} ]; then
+        echo "$file exited with $code instead of ${expected_code:-0}:"
+        echo "$output"
+        exit 1;
+    fi;
+
+    if ! awk '
+        BEGIN { count = 0; found = 0 }
+        NR == FNR { if (sub(/^\/\/ Expects: /, "")) expected[count++] = $0; next }
+        found < count && index($0, expected[found]) { found++ }
+        END { if (found < count) { print "missing: " expected[found]; exit 1 } }
+    ' $file - <<< "$output"; then
+        echo "$file printed unexpected output:"
+        echo "$output"
+        exit 1;
+    fi;
+done;
@@ -0,0 +1,81 @@
+const { test } = Sable.testing;
+
+await test("structuredClone copies builtin types and cycles", (ctx) => {
+	const date = new Date(2020, 0, 1);
+	const original = {
+		map: new Map([["a", { b: 1 }]]),
+		set: new Set([1, "2", 3n]),
+		date,
+		regexp: /a+b/gi,
+		bytes: new Uint8Array([1, 2, 3]),
+		nested: [undefined, null, NaN, -0],
+	};
+	original.self = original;
+
+	const clone = structuredClone(original);
+	ctx.assert(clone !== original);
+	ctx.equals(clone.self, clone);
+	ctx.deepEquals(clone.map.get("a"), { b: 1 });
+	ctx.assert(clone.map.get("a") !== original.map.get("a"));
+	ctx.deepEquals([...clone.set], [1, "2", 3n]);
+	ctx.assert(clone.date instanceof Date);
+	ctx.equals(clone.date.getTime(), date.getTime());
+	ctx.equals(clone.regexp.source, "a+b");
+	ctx.equals(clone.regexp.flags, "gi");
+	ctx.deepEquals(clone.bytes, new Uint8Array([1, 2, 3]));
+	ctx.assert(clone.bytes.buffer !== original.bytes.buffer);
+	ctx.assert(Number.isNaN(clone.nested[2]));
+	ctx.assert(Object.is(clone.nested[3], -0));
+});
+
+await test("structuredClone copies errors", (ctx) => {
+	const error = new RangeError("out of range");
+	const clone = structuredClone(error);
+	ctx.assert(clone instanceof RangeError);
+	ctx.equals(clone.message, "out of range");
+	ctx.assert(clone !== error);
+});
+
+await test("structuredClone transfers ArrayBuffers", (ctx) => {
+	const buffer = new Uint8Array([1, 2, 3]).buffer;
+	const clone = structuredClone({ buffer }, { transfer: [buffer] });
+	ctx.assert(buffer.detached);
+	ctx.equals(buffer.byteLength, 0);
+	ctx.deepEquals(new Uint8Array(clone.buffer), new Uint8Array([1, 2, 3]));
+
+	const dataCloneError = (fn) => {
+		try {
+			fn();
+			ctx.assert(false);
+		} catch (error) {
+			ctx.assert(error instanceof DOMException);
+			ctx.equals(error.name, "DataCloneError");
+			ctx.equals(error.code, DOMException.DATA_CLONE_ERR);
+		}
+	};
+	dataCloneError(() => structuredClone(buffer, { transfer: [buffer] }));
+	const other = new ArrayBuffer(1);
+	dataCloneError(() => structuredClone(other, { transfer: [other, other] }));
+	dataCloneError(() => structuredClone(1, { transfer: [{}] }));
+});
+
+await test("structuredClone throws DataCloneError for functions, symbols and blobs", (ctx) => {
+	for (
+		const value of [
+			() => {},
+			{ method() {} },
+			Symbol("symbol"),
+			new Blob(["blob"]),
+			new File([], "file.txt"),
+		]
+	) {
+		try {
+			structuredClone(value);
+			ctx.assert(false);
+		} catch (error) {
+			ctx.assert(error instanceof DOMException);
+			ctx.equals(error.name, "DataCloneError");
+		}
+	}
+	ctx.throws(() => structuredClone(), TypeError);
+});
@@ -35,6 +35,15 @@ await test("Storage", (t) => {
 
 			storage.clear();
 			t.equals(storage.length, 0);
+
+			try {
+				storage.setItem("big", "a".repeat(5 * 1024 * 1024));
+				t.assert(false);
+			} catch (error) {
+				t.assert(error instanceof DOMException);
+				t.equals(error.name, "QuotaExceededError");
+				t.equals(error.code, DOMException.QUOTA_EXCEEDED_ERR);
+			}
 		});
 	}
 });
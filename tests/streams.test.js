@@ -0,0 +1,258 @@
+import { runWptTests } from "./wpt/testharness.js";
+
+const { test } = Sable.testing;
+
+/**
+ * @param {ReadableStream} stream
+ * @returns {Promise<any[]>}
+ */
+async function collect(stream) {
+	const chunks = [];
+	for await (const chunk of stream) {
+		chunks.push(chunk);
+	}
+	return chunks;
+}
+
+await test("ReadableStream delivers enqueued chunks", async (ctx) => {
+	const stream = new ReadableStream({
+		start(controller) {
+			controller.enqueue("a");
+			controller.enqueue("b");
+			controller.close();
+		},
+	});
+
+	const reader = stream.getReader();
+	ctx.deepEquals(await reader.read(), { value: "a", done: false });
+	ctx.deepEquals(await reader.read(), { value: "b", done: false });
+	ctx.deepEquals(await reader.read(), { value: undefined, done: true });
+	ctx.assert(stream.locked);
+	reader.releaseLock();
+	ctx.assert(!stream.locked);
+});
+
+await test("BYOB reader reads into given view", async (ctx) => {
+	const stream = new ReadableStream({
+		type: "bytes",
+		pull(controller) {
+			const view = controller.byobRequest.view;
+			view.set([1, 2, 3]);
+			controller.byobRequest.respond(3);
+			controller.close();
+		},
+	});
+
+	const reader = stream.getReader({ mode: "byob" });
+	const { value, done } = await reader.read(new Uint8Array(8));
+	ctx.assert(!done);
+	ctx.deepEquals(Array.from(value), [1, 2, 3]);
+	ctx.equals(value.buffer.byteLength, 8);
+	ctx.equals((await reader.read(new Uint8Array(8))).done, true);
+});
+
+await test("BYOB reader waits for min elements", async (ctx) => {
+	let next = 0;
+	const stream = new ReadableStream({
+		type: "bytes",
+		pull(controller) {
+			controller.enqueue(new Uint8Array([next++]));
+		},
+	});
+
+	const reader = stream.getReader({ mode: "byob" });
+	const { value } = await reader.read(new Uint8Array(4), { min: 4 });
+	ctx.deepEquals(Array.from(value), [0, 1, 2, 3]);
+	await reader.cancel();
+});
+
+await test("tee() delivers chunks to both branches", async (ctx) => {
+	const [left, right] = ReadableStream.from([1, 2, 3]).tee();
+	const [leftChunks, rightChunks] = await Promise.all([
+		collect(left),
+		collect(right),
+	]);
+	ctx.deepEquals(leftChunks, [1, 2, 3]);
+	ctx.deepEquals(rightChunks, [1, 2, 3]);
+});
+
+await test("pipeThrough() transforms chunks", async (ctx) => {
+	const upperCase = new TransformStream({
+		transform(chunk, controller) {
+			controller.enqueue(chunk.toUpperCase());
+		},
+		flush(controller) {
+			controller.enqueue("!");
+		},
+	});
+
+	const chunks = await collect(
+		ReadableStream.from(["a", "b"]).pipeThrough(upperCase),
+	);
+	ctx.deepEquals(chunks, ["A", "B", "!"]);
+});
+
+await test("pipeTo() writes chunks and closes the destination", async (ctx) => {
+	const written = [];
+	let closed = false;
+	const destination = new WritableStream({
+		write(chunk) {
+			written.push(chunk);
+		},
+		close() {
+			closed = true;
+		},
+	});
+
+	await ReadableStream.from(["x", "y"]).pipeTo(destination);
+	ctx.deepEquals(written, ["x", "y"]);
+	ctx.assert(closed);
+});
+
+await test("pipeTo() propagates source errors", async (ctx) => {
+	let abortReason;
+	const source = new ReadableStream({
+		start(controller) {
+			controller.error(new Error("failed"));
+		},
+	});
+	const destination = new WritableStream({
+		abort(reason) {
+			abortReason = reason;
+		},
+	});
+
+	await ctx.rejects(source.pipeTo(destination));
+	ctx.equals(abortReason.message, "failed");
+});
+
+await test("WritableStream applies backpressure", async (ctx) => {
+	const written = [];
+	const stream = new WritableStream({
+		async write(chunk) {
+			await new Promise((resolve) => setTimeout(resolve, 1));
+			written.push(chunk);
+		},
+	}, new CountQueuingStrategy({ highWaterMark: 2 }));
+
+	const writer = stream.getWriter();
+	ctx.equals(writer.desiredSize, 2);
+	writer.write(1);
+	writer.write(2);
+	ctx.equals(writer.desiredSize, 0);
+	await writer.ready;
+	await writer.close();
+	ctx.deepEquals(written, [1, 2]);
+});
+
+await test("ReadableStream.from() accepts async iterables", async (ctx) => {
+	async function* numbers() {
+		yield 1;
+		yield 2;
+	}
+	ctx.deepEquals(await collect(ReadableStream.from(numbers())), [1, 2]);
+});
+
+await test("Queuing strategies measure chunks", (ctx) => {
+	const bytes = new ByteLengthQueuingStrategy({ highWaterMark: 16 });
+	ctx.equals(bytes.highWaterMark, 16);
+	ctx.equals(bytes.size(new Uint8Array(5)), 5);
+
+	const count = new CountQueuingStrategy({ highWaterMark: 3 });
+	ctx.equals(count.highWaterMark, 3);
+	ctx.equals(count.size("anything"), 1);
+});
+
+await test("TextDecoderStream decodes characters split between chunks", async (ctx) => {
+	const bytes = new TextEncoder().encode("zażółć 🦀");
+	const chunks = [];
+	for (let i = 0; i < bytes.length; i += 3) {
+		chunks.push(bytes.subarray(i, i + 3));
+	}
+
+	const text = await collect(
+		ReadableStream.from(chunks).pipeThrough(new TextDecoderStream()),
+	);
+	ctx.equals(text.join(""), "zażółć 🦀");
+});
+
+await test("TextEncoderStream encodes surrogate pairs split between chunks", async (ctx) => {
+	const crab = "🦀";
+	const chunks = await collect(
+		ReadableStream.from([crab[0], crab[1]]).pipeThrough(
+			new TextEncoderStream(),
+		),
+	);
+	ctx.deepEquals(
+		chunks.flatMap((chunk) => Array.from(chunk)),
+		Array.from(new TextEncoder().encode(crab)),
+	);
+});
+
+await test("TextDecoder decodes streamed input", (ctx) => {
+	const decoder = new TextDecoder();
+	const bytes = new TextEncoder().encode("€");
+	ctx.equals(decoder.decode(bytes.subarray(0, 1), { stream: true }), "");
+	ctx.equals(decoder.decode(bytes.subarray(1, 2), { stream: true }), "");
+	ctx.equals(decoder.decode(bytes.subarray(2)), "€");
+	ctx.equals(decoder.decode(bytes.subarray(0, 1)), "\ufffd");
+});
+
+await test("FsFile streams read and write the file", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	try {
+		const output = await Sable.fs.open(path, { write: true });
+		await ReadableStream.from(["Hello, ", "streams"])
+			.pipeThrough(new TextEncoderStream())
+			.pipeTo(output.writable);
+
+		const input = await Sable.fs.open(path);
+		const text = await collect(
+			input.readable.pipeThrough(new TextDecoderStream()),
+		);
+		ctx.equals(text.join(""), "Hello, streams");
+	} finally {
+		await Sable.fs.removeFile(path);
+	}
+});
+
+/** Vendored WPT streams tests, see `wpt/streams/README.md` */
+const WPT_FILES = [
+	"readable-streams/general.any.js",
+	"readable-streams/cancel.any.js",
+	"readable-streams/tee.any.js",
+	"readable-streams/default-reader.any.js",
+	"readable-streams/async-iterator.any.js",
+	"readable-streams/from.any.js",
+	"readable-byte-streams/general.any.js",
+	"writable-streams/general.any.js",
+	"writable-streams/write.any.js",
+	"writable-streams/aborting.any.js",
+	"writable-streams/close.any.js",
+	"transform-streams/general.any.js",
+	"piping/general.any.js",
+	"queuing-strategies.any.js",
+];
+
+/** @type {Map<string, Set<string>>} Names of tests which are expected to fail, per file */
+const wptExpectedFailures = new Map(WPT_FILES.map((file) => [file, new Set()]));
+const expectedFailuresList = await Sable.fs.readTextFile(
+	new URL("./wpt/streams/expected_failures.txt", import.meta.url),
+);
+for (const line of expectedFailuresList.split("\n")) {
+	if (line === "" || line.startsWith("#")) continue;
+	const separator = line.indexOf(": ");
+	wptExpectedFailures.get(line.slice(0, separator))?.add(
+		line.slice(separator + 2),
+	);
+}
+
+for (const file of WPT_FILES) {
+	await test(`Streams pass WPT ${file}`, async (ctx) => {
+		const failures = await runWptTests(
+			new URL(`./wpt/streams/${file}`, import.meta.url),
+			wptExpectedFailures.get(file),
+		);
+		ctx.deepEquals(failures, []);
+	});
+}
@@ -0,0 +1,71 @@
+const { test, beforeAll, afterAll, beforeEach, afterEach } = Sable.testing;
+
+const hookCalls = [];
+beforeAll(() => hookCalls.push("beforeAll"));
+beforeEach((ctx) => hookCalls.push(`beforeEach ${ctx.name}`));
+afterEach(async (ctx) => {
+	await new Promise((resolve) => setTimeout(resolve, 1));
+	hookCalls.push(`afterEach ${ctx.name}`);
+});
+afterAll(() => {
+	if (hookCalls.at(-1) !== "afterEach Testing API - hooks order") {
+		throw new Error("afterAll hook didn't run last");
+	}
+});
+
+await test("Testing API - timeout option", { timeout: 1000 }, async (ctx) => {
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	ctx.assert(true);
+
+	await ctx.test("sub-test timeout option", { timeout: 1000 }, async (ctx) => {
+		await new Promise((resolve) => setTimeout(resolve, 10));
+		ctx.assert(true);
+	});
+});
+
+await test("Testing API - disabled timeout", { timeout: 0 }, async (ctx) => {
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	ctx.assert(true);
+});
+
+let leakedInterval;
+await test("Testing API - disabled sanitizers", {
+	sanitizeOps: false,
+	sanitizeResources: false,
+	sanitizeTimers: false,
+}, (ctx) => {
+	leakedInterval = setInterval(() => {}, 1000);
+	ctx.assert(true);
+});
+clearInterval(leakedInterval);
+
+await test("Testing API - cleared timers don't leak", async (ctx) => {
+	const id = setInterval(() => {}, 1000);
+	clearInterval(id);
+	await new Promise((resolve) => setTimeout(resolve, 10));
+	ctx.assert(true);
+});
+
+await test("Testing API - hooks order", async (ctx) => {
+	ctx.beforeAll(() => hookCalls.push("inner beforeAll"));
+	ctx.beforeEach((ctx) => hookCalls.push(`inner beforeEach ${ctx.name}`));
+	ctx.afterEach((ctx) => hookCalls.push(`inner afterEach ${ctx.name}`));
+	ctx.afterAll(() => hookCalls.push("inner afterAll"));
+
+	await ctx.test("a", () => hookCalls.push("a"));
+	await ctx.test("b", () => hookCalls.push("b"));
+
+	// File level hooks have to be registered before the first test for beforeAll to run
+	ctx.equals(hookCalls[0], "beforeAll");
+	const start = hookCalls.indexOf("beforeEach Testing API - hooks order");
+	ctx.deepEquals(hookCalls.slice(start), [
+		"beforeEach Testing API - hooks order",
+		"inner beforeAll",
+		"inner beforeEach a",
+		"a",
+		"inner afterEach a",
+		"inner beforeEach b",
+		"b",
+		"inner afterEach b",
+	]);
+});
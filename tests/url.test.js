@@ -0,0 +1,233 @@
+const { test } = Sable.testing;
+
+/**
+ * WPT url test data, same revision as rust-url which parses URLs
+ * @param {string} name
+ */
+async function readTestData(name) {
+	return await Sable.fs.readTextFile(
+		new URL(`./wpt/url/${name}`, import.meta.url),
+	);
+}
+
+/**
+ * Escapes the string like Rust's `str::escape_default`, which is how rust-url names its expected failures
+ * @param {string} string
+ * @returns {string}
+ */
+function escapeDefault(string) {
+	let escaped = "";
+	for (const char of string) {
+		const code = char.codePointAt(0);
+		if (char === "\t") escaped += "\\t";
+		else if (char === "\r") escaped += "\\r";
+		else if (char === "\n") escaped += "\\n";
+		else if (char === "\\" || char === "'" || char === '"') escaped += `\\${char}`;
+		else if (code >= 0x20 && code <= 0x7e) escaped += char;
+		else escaped += `\\u{${code.toString(16)}}`;
+	}
+	return escaped;
+}
+
+const expectedFailures = new Set(
+	(await readTestData("expected_failures.txt")).split("\n").filter(Boolean),
+);
+
+const COMPONENTS = [
+	"href",
+	"protocol",
+	"username",
+	"password",
+	"host",
+	"hostname",
+	"port",
+	"pathname",
+	"search",
+	"hash",
+];
+
+await test("URL parses WPT test data", async (ctx) => {
+	const cases = JSON.parse(await readTestData("urltestdata.json"));
+	const failures = [];
+
+	for (const data of cases) {
+		if (typeof data === "string") continue;
+
+		let name = `<${escapeDefault(data.input)}>`;
+		if (data.base !== null) {
+			name += ` against <${escapeDefault(data.base)}>`;
+		}
+		if (expectedFailures.has(name)) continue;
+
+		const base = data.base ?? undefined;
+		if (data.failure) {
+			if (URL.canParse(data.input, base)) {
+				failures.push(`${name}: expected failure`);
+			}
+			continue;
+		}
+
+		let url;
+		try {
+			url = new URL(data.input, base);
+		} catch (error) {
+			failures.push(`${name}: ${error}`);
+			continue;
+		}
+		for (const component of [...COMPONENTS, "origin"]) {
+			if (component in data && url[component] !== data[component]) {
+				failures.push(
+					`${name}: expected ${component} '${data[component]}', got '${url[component]}'`,
+				);
+			}
+		}
+	}
+
+	ctx.deepEquals(failures, []);
+});
+
+await test("URL setters follow WPT test data", async (ctx) => {
+	const { comment: _, ...setters } = JSON.parse(
+		await readTestData("setters_tests.json"),
+	);
+	const failures = [];
+
+	for (const [setter, cases] of Object.entries(setters)) {
+		for (const data of cases) {
+			const name = `<${escapeDefault(data.href)}> set ${setter} to <${
+				escapeDefault(data.new_value)
+			}>`;
+			if (expectedFailures.has(name)) continue;
+
+			const url = new URL(data.href);
+			try {
+				url[setter] = data.new_value;
+			} catch {
+				// Only the href setter throws, in which case the URL stays the same
+			}
+			for (const [component, expected] of Object.entries(data.expected)) {
+				if (url[component] !== expected) {
+					failures.push(
+						`${name}: expected ${component} '${expected}', got '${url[component]}'`,
+					);
+				}
+			}
+		}
+	}
+
+	ctx.deepEquals(failures, []);
+});
+
+await test("URL validates input", (ctx) => {
+	ctx.throws(() => new URL("relative"), TypeError);
+	ctx.throws(() => new URL("a", "relative"), TypeError);
+	ctx.equals(URL.parse("relative"), null);
+	ctx.equals(URL.parse("b", "https://example.com/a/").href, "https://example.com/a/b");
+	ctx.assert(URL.canParse("b", new URL("https://example.com")));
+
+	const url = new URL("https://example.com");
+	ctx.throws(() => {
+		url.href = "relative";
+	}, TypeError);
+	ctx.equals(JSON.stringify({ url }), '{"url":"https://example.com/"}');
+	ctx.equals(String(url), "https://example.com/");
+});
+
+await test("URLSearchParams stay in sync with URL", (ctx) => {
+	const url = new URL("https://example.com/?a=1&b=2&a=3");
+	const params = url.searchParams;
+	ctx.equals(params, url.searchParams);
+	ctx.equals(params.size, 3);
+	ctx.equals(params.get("a"), "1");
+	ctx.deepEquals(params.getAll("a"), ["1", "3"]);
+	ctx.assert(params.has("a", "3"));
+	ctx.assert(!params.has("a", "2"));
+
+	params.set("a", "x y");
+	ctx.equals(url.search, "?a=x+y&b=2");
+	params.append("c", "&=");
+	ctx.equals(url.href, "https://example.com/?a=x+y&b=2&c=%26%3D");
+	params.delete("a");
+	params.delete("c", "other");
+	ctx.deepEquals([...params.keys()], ["b", "c"]);
+
+	url.search = "?z=1&y=2";
+	params.sort();
+	ctx.deepEquals([...params], [["y", "2"], ["z", "1"]]);
+	ctx.equals(url.search, "?y=2&z=1");
+
+	params.delete("y");
+	params.delete("z");
+	ctx.equals(url.href, "https://example.com/");
+});
+
+await test("URLSearchParams accept every init", (ctx) => {
+	ctx.equals(new URLSearchParams("?a=b+c&d=%20").get("d"), " ");
+	ctx.equals(new URLSearchParams("?a=b+c").get("a"), "b c");
+	ctx.equals(new URLSearchParams([["a", "1"], ["a", "2"]]).toString(), "a=1&a=2");
+	ctx.equals(new URLSearchParams({ a: "1", b: "é" }).toString(), "a=1&b=%C3%A9");
+	ctx.equals(new URLSearchParams(new Map([["k", "v"]])).toString(), "k=v");
+	ctx.throws(() => new URLSearchParams([["a"]]), TypeError);
+
+	const original = new URLSearchParams("a=1");
+	const copy = new URLSearchParams(original);
+	copy.append("b", "2");
+	ctx.equals(original.toString(), "a=1");
+
+	const entries = [];
+	copy.forEach((value, name) => entries.push(`${name}=${value}`));
+	ctx.deepEquals(entries, ["a=1", "b=2"]);
+});
+
+await test("URLPattern matches URLs", (ctx) => {
+	const books = new URLPattern({ pathname: "/books/:id(\\d+)" });
+	ctx.equals(books.pathname, "/books/:id(\\d+)");
+	ctx.equals(books.protocol, "*");
+	ctx.assert(books.hasRegExpGroups);
+	ctx.assert(books.test("https://example.com/books/123"));
+	ctx.assert(!books.test("https://example.com/books/abc"));
+	ctx.equals(books.exec("https://example.com/books/abc"), null);
+
+	const match = books.exec({ pathname: "/books/42" });
+	ctx.equals(match.pathname.input, "/books/42");
+	ctx.deepEquals(match.pathname.groups, { id: "42" });
+	ctx.deepEquals(match.inputs, [{ pathname: "/books/42" }]);
+
+	const files = new URLPattern("/files/*", "https://example.com");
+	ctx.equals(files.protocol, "https");
+	ctx.equals(files.hostname, "example.com");
+	ctx.assert(!files.hasRegExpGroups);
+	ctx.deepEquals(
+		files.exec("https://example.com/files/a/b.txt").pathname.groups,
+		{ 0: "a/b.txt" },
+	);
+	ctx.assert(files.test("/files/c", "https://example.com"));
+	ctx.assert(!files.test("https://example.org/files/c"));
+
+	const hosts = new URLPattern("https://{:sub.}?example.com/:path*");
+	ctx.deepEquals(
+		hosts.exec("https://api.example.com/v1/users").hostname.groups,
+		{ sub: "api" },
+	);
+	ctx.deepEquals(
+		hosts.exec("https://example.com/v1").hostname.groups,
+		{ sub: undefined },
+	);
+	ctx.assert(!hosts.test("https://example.com/"));
+
+	const caseless = new URLPattern({ pathname: "/Foo" }, { ignoreCase: true });
+	ctx.assert(caseless.test({ pathname: "/foo" }));
+	ctx.assert(!new URLPattern({ pathname: "/Foo" }).test({ pathname: "/foo" }));
+
+	ctx.throws(() => new URLPattern({ pathname: "/:id(" }), TypeError);
+	ctx.throws(() => new URLPattern("/relative"), TypeError);
+});
+
+await test("File system APIs accept file URLs", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	const url = new URL(`file://${path}`);
+	await Sable.fs.writeTextFile(url, "from url");
+	ctx.equals(await Sable.fs.readTextFile(path), "from url");
+	ctx.assert((await Sable.fs.stat(url)).isFile);
+	await Sable.fs.removeFile(url);
+});
@@ -0,0 +1,163 @@
+const { test } = Sable.testing;
+
+/**
+ * @param {string} name
+ * @returns {URL}
+ */
+function fixture(name) {
+	return new URL(`./worker/${name}`, import.meta.url);
+}
+
+/**
+ * @param {Worker} worker
+ * @returns {Promise<any>}
+ */
+function nextMessage(worker) {
+	return new Promise((resolve) =>
+		worker.addEventListener("message", (event) => resolve(event.data), {
+			once: true,
+		})
+	);
+}
+
+/**
+ * Terminates the worker and gives its pending receive a moment to get canceled
+ * @param {Worker} worker
+ */
+async function terminate(worker) {
+	worker.terminate();
+	await new Promise((resolve) => setTimeout(resolve, 10));
+}
+
+await test("Worker exchanges messages with its parent", async (ctx) => {
+	const worker = new Worker(fixture("echo.js"), {
+		type: "module",
+		name: "echo",
+	});
+
+	try {
+		worker.postMessage({ nested: [1, "two", new Map([[3, 4]])] });
+		const reply = await nextMessage(worker);
+		ctx.equals(reply.name, "echo");
+		ctx.equals(reply.data.nested[1], "two");
+		ctx.equals(reply.data.nested[2].get(3), 4);
+
+		const received = new Promise((resolve) => (worker.onmessage = resolve));
+		worker.postMessage("handler");
+		const event = await received;
+		ctx.assert(event instanceof MessageEvent);
+		ctx.assert(event.isTrusted);
+		ctx.equals(event.data.data, "handler");
+	} finally {
+		await terminate(worker);
+	}
+});
+
+await test("Worker requires the module type", (ctx) => {
+	ctx.throws(() => new Worker(fixture("echo.js")), TypeError);
+	ctx.throws(
+		() => new Worker(fixture("echo.js"), { type: "classic" }),
+		TypeError,
+	);
+});
+
+await test("Worker moves transferred ArrayBuffers", async (ctx) => {
+	const worker = new Worker(fixture("echo.js"), { type: "module" });
+
+	try {
+		const buffer = new Uint8Array([1, 2, 3]).buffer;
+		worker.postMessage(buffer, [buffer]);
+		ctx.assert(buffer.detached);
+
+		const { data } = await nextMessage(worker);
+		ctx.assert(data instanceof ArrayBuffer);
+		ctx.deepEquals(new Uint8Array(data), new Uint8Array([1, 2, 3]));
+
+		const other = new ArrayBuffer(4);
+		worker.postMessage(other, { transfer: [other] });
+		ctx.equals((await nextMessage(worker)).data.byteLength, 4);
+		ctx.throws(() => worker.postMessage(1, [{}]), DOMException);
+	} finally {
+		await terminate(worker);
+	}
+});
+
+await test("Worker shares memory of SharedArrayBuffers", async (ctx) => {
+	const worker = new Worker(fixture("atomics.js"), { type: "module" });
+
+	try {
+		const shared = new SharedArrayBuffer(8);
+		const array = new Int32Array(shared);
+		worker.postMessage(shared);
+
+		Atomics.store(array, 0, 21);
+		Atomics.notify(array, 0);
+		ctx.equals(await nextMessage(worker), "done");
+		ctx.equals(Atomics.load(array, 1), 42);
+	} finally {
+		await terminate(worker);
+	}
+});
+
+await test("Worker reports uncaught errors to its parent", async (ctx) => {
+	const worker = new Worker(fixture("throws.js"), { type: "module" });
+
+	const event = await new Promise((resolve) => {
+		worker.onerror = (event) => {
+			event.preventDefault();
+			resolve(event);
+		};
+	});
+	ctx.assert(event instanceof ErrorEvent);
+	ctx.assert(event.message.includes("worker exploded"));
+	ctx.assert(event.filename.endsWith("/worker/throws.js"));
+	ctx.equals(event.lineno, 1);
+
+	await terminate(worker);
+});
+
+await test("Worker can close itself", async (ctx) => {
+	const worker = new Worker(fixture("echo.js"), { type: "module" });
+
+	worker.postMessage("close");
+	ctx.equals((await nextMessage(worker)).data, "close");
+	// Worker ends on its own, its resource gets closed once the parent notices
+	await new Promise((resolve) => setTimeout(resolve, 50));
+	worker.postMessage("ignored");
+	worker.terminate();
+});
+
+await test("Worker can be terminated while it's running", async (ctx) => {
+	const worker = new Worker(fixture("busy.js"), { type: "module" });
+
+	ctx.equals(await nextMessage(worker), "started");
+	await terminate(worker);
+	// Terminating twice does nothing
+	worker.terminate();
+});
+
+await test("Worker permissions can be narrowed down", async (ctx) => {
+	const path = await Sable.fs.makeTempFile();
+	const workers = [
+		new Worker(fixture("read_file.js"), { type: "module" }),
+		new Worker(fixture("read_file.js"), {
+			type: "module",
+			permissions: "none",
+		}),
+		new Worker(fixture("read_file.js"), {
+			type: "module",
+			permissions: { read: false },
+		}),
+	];
+
+	try {
+		const results = await Promise.all(workers.map((worker) => {
+			worker.postMessage(path);
+			return nextMessage(worker);
+		}));
+		ctx.deepEquals(results, ["granted", "PermissionDenied", "PermissionDenied"]);
+	} finally {
+		await Promise.all(workers.map(terminate));
+		await Sable.fs.removeFile(path);
+	}
+});
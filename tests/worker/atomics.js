@@ -0,0 +1,7 @@
+// Waits until the parent stores a number in the shared memory and doubles it
+onmessage = ({ data: shared }) => {
+	const array = new Int32Array(shared);
+	Atomics.wait(array, 0, 0);
+	Atomics.store(array, 1, Atomics.load(array, 0) * 2);
+	postMessage("done");
+};
@@ -0,0 +1,4 @@
+// Answers messages broadcast on the "worker" channel
+const channel = new BroadcastChannel("worker");
+channel.onmessage = ({ data }) => channel.postMessage(`worker got ${data}`);
+postMessage("ready");
@@ -0,0 +1,5 @@
+// Never yields, so it can only be stopped by terminating it
+postMessage("started");
+while (true) {
+	// Busy loop
+}
@@ -0,0 +1,5 @@
+// Sends received messages back, along with name of the worker
+onmessage = ({ data }) => {
+	postMessage({ name, data }, data instanceof ArrayBuffer ? [data] : []);
+	if (data === "close") close();
+};
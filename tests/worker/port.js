@@ -0,0 +1,4 @@
+// Doubles numbers received through the transferred port
+onmessage = ({ ports: [port] }) => {
+	port.onmessage = ({ data }) => port.postMessage(data * 2);
+};
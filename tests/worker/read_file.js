@@ -0,0 +1,9 @@
+// Reports whether the worker can read the file it receives path of
+onmessage = async ({ data: path }) => {
+	try {
+		await Sable.fs.readTextFile(path);
+		postMessage("granted");
+	} catch (error) {
+		postMessage(error.name);
+	}
+};
@@ -0,0 +1,366 @@
+// META: global=window,worker,shadowrealm
+// META: script=../resources/test-utils.js
+// META: script=../resources/recording-streams.js
+'use strict';
+
+const error1 = new Error('error1');
+error1.name = 'error1';
+
+test(() => {
+
+  const rs = new ReadableStream();
+  const ws = new WritableStream();
+
+  assert_false(rs.locked, 'sanity check: the ReadableStream must not start locked');
+  assert_false(ws.locked, 'sanity check: the WritableStream must not start locked');
+
+  rs.pipeTo(ws);
+
+  assert_true(rs.locked, 'the ReadableStream must become locked');
+  assert_true(ws.locked, 'the WritableStream must become locked');
+
+}, 'Piping must lock both the ReadableStream and WritableStream');
+
+promise_test(() => {
+
+  const rs = new ReadableStream({
+    start(controller) {
+      controller.close();
+    }
+  });
+  const ws = new WritableStream();
+
+  return rs.pipeTo(ws).then(() => {
+    assert_false(rs.locked, 'the ReadableStream must become unlocked');
+    assert_false(ws.locked, 'the WritableStream must become unlocked');
+  });
+
+}, 'Piping finishing must unlock both the ReadableStream and WritableStream');
+
+promise_test(t => {
+
+  const fakeRS = Object.create(ReadableStream.prototype);
+  const ws = new WritableStream();
+
+  return methodRejects(t, ReadableStream.prototype, 'pipeTo', fakeRS, [ws]);
+
+}, 'pipeTo must check the brand of its ReadableStream this value');
+
+promise_test(t => {
+
+  const rs = new ReadableStream();
+  const fakeWS = Object.create(WritableStream.prototype);
+
+  return methodRejects(t, ReadableStream.prototype, 'pipeTo', rs, [fakeWS]);
+
+}, 'pipeTo must check the brand of its WritableStream argument');
+
+promise_test(t => {
+
+  const rs = new ReadableStream();
+  const ws = new WritableStream();
+
+  rs.getReader();
+
+  assert_true(rs.locked, 'sanity check: the ReadableStream starts locked');
+  assert_false(ws.locked, 'sanity check: the WritableStream does not start locked');
+
+  return promise_rejects_js(t, TypeError, rs.pipeTo(ws)).then(() => {
+    assert_false(ws.locked, 'the WritableStream must still be unlocked');
+  });
+
+}, 'pipeTo must fail if the ReadableStream is locked, and not lock the WritableStream');
+
+promise_test(t => {
+
+  const rs = new ReadableStream({
+    start(controller) {
+      controller.close();
+    }
+  });
+  const ws = new WritableStream();
+
+  ws.getWriter();
+
+  assert_false(rs.locked, 'sanity check: the ReadableStream does not start locked');
+  assert_true(ws.locked, 'sanity check: the WritableStream starts locked');
+
+  return promise_rejects_js(t, TypeError, rs.pipeTo(ws)).then(() => {
+    assert_false(rs.locked, 'the ReadableStream must still be unlocked');
+  });
+
+}, 'pipeTo must fail if the WritableStream is locked, and not lock the ReadableStream');
+
+promise_test(() => {
+
+  const CHUNKS = 10;
+
+  const rs = new ReadableStream({
+    start(c) {
+      for (let i = 0; i < CHUNKS; ++i) {
+        c.enqueue(i);
+      }
+      c.close();
+    }
+  });
+
+  const written = [];
+  const ws = new WritableStream({
+    write(chunk) {
+      written.push(chunk);
+    },
+    close() {
+      written.push('closed');
+    }
+  }, new CountQueuingStrategy({ highWaterMark: CHUNKS }));
+
+  return rs.pipeTo(ws).then(() => {
+    const targetValues = [];
+    for (let i = 0; i < CHUNKS; ++i) {
+      targetValues.push(i);
+    }
+    targetValues.push('closed');
+
+    assert_array_equals(written, targetValues, 'the correct values must be written');
+
+    // Ensure both readable and writable are closed by the time the pipe finishes.
+    return Promise.all([
+      rs.getReader().closed,
+      ws.getWriter().closed
+    ]);
+  });
+
+  // NOTE: no requirement on *when* the pipe finishes; that is left to implementations.
+
+}, 'Piping from a ReadableStream from which lots of chunks are synchronously readable');
+
+promise_test(() => {
+
+  let controller;
+  const rs = recordingReadableStream({
+    start(c) {
+      controller = c;
+    }
+  });
+
+  const ws = recordingWritableStream();
+
+  const pipePromise = rs.pipeTo(ws).then(() => {
+    assert_array_equals(ws.events, ['write', 'Hello', 'close']);
+  });
+
+  step_timeout(() => {
+    controller.enqueue('Hello');
+    step_timeout(() => controller.close(), 10);
+  }, 10);
+
+  return pipePromise;
+
+}, 'Piping from a ReadableStream for which a chunk becomes asynchronously readable after the pipeTo');
+
+for (const preventAbort of [true, false]) {
+  promise_test(() => {
+
+    const rs = new ReadableStream({
+      pull() {
+        return Promise.reject(undefined);
+      }
+    });
+
+    return rs.pipeTo(new WritableStream(), { preventAbort }).then(
+        () => assert_unreached('pipeTo promise should be rejected'),
+        value => assert_equals(value, undefined, 'rejection value should be undefined'));
+
+  }, `an undefined rejection from pull should cause pipeTo() to reject when preventAbort is ${preventAbort}`);
+}
+
+for (const preventCancel of [true, false]) {
+  promise_test(() => {
+
+    const rs = new ReadableStream({
+      pull(controller) {
+        controller.enqueue(0);
+      }
+    });
+
+    const ws = new WritableStream({
+      write() {
+        return Promise.reject(undefined);
+      }
+    });
+
+    return rs.pipeTo(ws, { preventCancel }).then(
+        () => assert_unreached('pipeTo promise should be rejected'),
+        value => assert_equals(value, undefined, 'rejection value should be undefined'));
+
+  }, `an undefined rejection from write should cause pipeTo() to reject when preventCancel is ${preventCancel}`);
+}
+
+promise_test(t => {
+  const rs = new ReadableStream();
+  const ws = new WritableStream();
+  return promise_rejects_js(t, TypeError, rs.pipeTo(ws, {
+    get preventAbort() {
+      ws.getWriter();
+    }
+  }), 'pipeTo should reject');
+}, 'pipeTo() should reject if an option getter grabs a writer');
+
+promise_test(t => {
+  const rs = new ReadableStream({
+    start(controller) {
+      controller.close();
+    }
+  });
+  const ws = new WritableStream();
+
+  return rs.pipeTo(ws, null);
+}, 'pipeTo() promise should resolve if null is passed');
+
+promise_test(t => {
+  const rs = recordingReadableStream({
+    start(controller) {
+      controller.error(error1);
+    }
+  });
+  const ws = recordingWritableStream();
+
+  return promise_rejects_exactly(t, error1, rs.pipeTo(ws), 'pipeTo must reject with the same error').then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, []);
+    assert_array_equals(ws.events, ['abort', error1]);
+  });
+}, 'Errors must be propagated forward: starts errored; preventAbort = false; fulfilled abort promise');
+
+promise_test(t => {
+  const rs = recordingReadableStream({
+    start(controller) {
+      controller.error(error1);
+    }
+  });
+  const ws = recordingWritableStream();
+
+  return promise_rejects_exactly(t, error1, rs.pipeTo(ws, { preventAbort: true }),
+    'pipeTo must reject with the same error').then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, []);
+    assert_array_equals(ws.events, []);
+  });
+}, 'Errors must be propagated forward: starts errored; preventAbort = true');
+
+promise_test(t => {
+  const rs = recordingReadableStream();
+  const ws = recordingWritableStream({
+    start(controller) {
+      controller.error(error1);
+    }
+  });
+
+  return promise_rejects_exactly(t, error1, rs.pipeTo(ws), 'pipeTo must reject with the same error').then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, ['cancel', error1]);
+    assert_array_equals(ws.events, []);
+  });
+}, 'Errors must be propagated backward: starts errored; preventCancel = false; fulfilled cancel promise');
+
+promise_test(() => {
+  const rs = recordingReadableStream({
+    start(controller) {
+      controller.close();
+    }
+  });
+  const ws = recordingWritableStream();
+
+  return rs.pipeTo(ws, { preventClose: true }).then(() => {
+    assert_array_equals(ws.events, []);
+    assert_false(ws.locked, 'the WritableStream must become unlocked');
+    return ws.getWriter().close();
+  });
+}, 'Closing must be propagated forward: starts closed; preventClose = true');
+
+promise_test(t => {
+  const rs = recordingReadableStream();
+  const ws = recordingWritableStream();
+  const writer = ws.getWriter();
+  writer.close();
+  writer.releaseLock();
+
+  return promise_rejects_js(t, TypeError, rs.pipeTo(ws), 'pipeTo must reject with a TypeError').then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, ['cancel', rs.eventsWithoutPulls[1]]);
+    assert_equals(rs.eventsWithoutPulls[1].constructor, TypeError, 'the cancel reason should be a TypeError');
+  });
+}, 'Closing must be propagated backward: starts closed; preventCancel = false');
+
+promise_test(t => {
+  const controller = new AbortController();
+  controller.abort(error1);
+
+  const rs = recordingReadableStream();
+  const ws = recordingWritableStream();
+
+  return promise_rejects_exactly(t, error1, rs.pipeTo(ws, { signal: controller.signal }),
+    'pipeTo should reject with the abort reason').then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, ['cancel', error1], 'the source should be canceled');
+    assert_array_equals(ws.events, ['abort', error1], 'the destination should be aborted');
+  });
+}, 'an already aborted signal cancels the source and aborts the destination');
+
+promise_test(t => {
+  const controller = new AbortController();
+
+  const rs = recordingReadableStream();
+  const ws = recordingWritableStream();
+
+  const pipePromise = rs.pipeTo(ws, { signal: controller.signal, preventAbort: true, preventCancel: true });
+  return flushAsyncEvents().then(() => {
+    controller.abort(error1);
+    return promise_rejects_exactly(t, error1, pipePromise, 'pipeTo should reject with the abort reason');
+  }).then(() => {
+    assert_array_equals(rs.eventsWithoutPulls, [], 'the source should not be canceled');
+    assert_array_equals(ws.events, [], 'the destination should not be aborted');
+    assert_false(rs.locked, 'the ReadableStream must become unlocked');
+    assert_false(ws.locked, 'the WritableStream must become unlocked');
+  });
+}, 'aborting the signal with preventAbort and preventCancel only unlocks the streams');
+
+promise_test(() => {
+  const rs = new ReadableStream({
+    start(c) {
+      c.enqueue('a');
+      c.enqueue('b');
+      c.close();
+    }
+  });
+  const ts = new TransformStream({
+    transform(chunk, controller) {
+      controller.enqueue(chunk.toUpperCase());
+    }
+  });
+
+  const readable = rs.pipeThrough(ts);
+  assert_equals(readable, ts.readable, 'pipeThrough() should return the readable side');
+  assert_true(rs.locked, 'the source should be locked');
+  assert_true(ts.writable.locked, 'the writable side should be locked');
+
+  const chunks = [];
+  const reader = readable.getReader();
+  const pump = () => reader.read().then(({ value, done }) => {
+    if (done) {
+      return chunks;
+    }
+    chunks.push(value);
+    return pump();
+  });
+  return pump().then(result => {
+    assert_array_equals(result, ['A', 'B']);
+  });
+}, 'pipeThrough() returns the readable side and pipes into the writable side');
+
+test(() => {
+  const rs = new ReadableStream();
+  assert_throws_js(TypeError, () => rs.pipeThrough({ readable: new ReadableStream() }),
+    'pipeThrough() without a writable should throw');
+  assert_throws_js(TypeError, () => rs.pipeThrough({ writable: new WritableStream() }),
+    'pipeThrough() without a readable should throw');
+
+  rs.getReader();
+  assert_throws_js(TypeError, () => rs.pipeThrough(new TransformStream()),
+    'pipeThrough() on a locked stream should throw');
+}, 'pipeThrough() checks its arguments and the lock synchronously');
@@ -0,0 +1,468 @@
+// META: global=window,worker,shadowrealm
+// META: script=../resources/rs-utils.js
+// META: script=../resources/test-utils.js
+'use strict';
+
+const error1 = new Error('error1');
+error1.name = 'error1';
+
+test(() => {
+  assert_throws_js(TypeError, () => new ReadableStream().getReader({ mode: 'byob' }));
+}, 'getReader({mode: "byob"}) throws on non-bytes streams');
+
+test(() => {
+  // Constructing ReadableStream with an empty underlying byte source object as parameter shouldn't throw.
+  new ReadableStream({ type: 'bytes' }).getReader({ mode: 'byob' });
+  // Constructor must perform ToString(type).
+  new ReadableStream({ type: { toString() {return 'bytes';} } })
+    .getReader({ mode: 'byob' });
+  new ReadableStream({ type: { toString: null, valueOf() {return 'bytes';} } })
+    .getReader({ mode: 'byob' });
+}, 'ReadableStream with byte source can be constructed with no errors');
+
+test(() => {
+  const ReadableStreamBYOBReader = new ReadableStream({ type: 'bytes' }).getReader({ mode: 'byob' }).constructor;
+  const rs = new ReadableStream({ type: 'bytes' });
+
+  let reader = rs.getReader({ mode: { toString() { return 'byob'; } } });
+  assert_true(reader instanceof ReadableStreamBYOBReader, 'must give a BYOB reader');
+  reader.releaseLock();
+
+  reader = rs.getReader({ mode: { toString: null, valueOf() {return 'byob';} } });
+  assert_true(reader instanceof ReadableStreamBYOBReader, 'must give a BYOB reader');
+  reader.releaseLock();
+
+  reader = rs.getReader({ mode: 'byob', notmode: 'ignored' });
+  assert_true(reader instanceof ReadableStreamBYOBReader, 'must give a BYOB reader');
+}, 'getReader({mode}) must perform ToString()');
+
+promise_test(() => {
+  let startCalled = false;
+  let startCalledBeforePull = false;
+  let desiredSize;
+  let controller;
+
+  let resolveTestPromise;
+  const testPromise = new Promise(resolve => {
+    resolveTestPromise = resolve;
+  });
+
+  new ReadableStream({
+    start(c) {
+      controller = c;
+      startCalled = true;
+    },
+    pull() {
+      startCalledBeforePull = startCalled;
+      desiredSize = controller.desiredSize;
+      resolveTestPromise();
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 256
+  });
+
+  return testPromise.then(() => {
+    assert_true(startCalledBeforePull, 'start should be called before pull');
+    assert_equals(desiredSize, 256, 'desiredSize should equal highWaterMark');
+  });
+
+}, 'ReadableStream with byte source: Construct and expect start and pull being called');
+
+promise_test(() => {
+  let pullCount = 0;
+  let checkedNoPull = false;
+
+  let resolveTestPromise;
+  const testPromise = new Promise(resolve => {
+    resolveTestPromise = resolve;
+  });
+  let resolveStartPromise;
+
+  new ReadableStream({
+    start() {
+      return new Promise(resolve => {
+        resolveStartPromise = resolve;
+      });
+    },
+    pull() {
+      if (checkedNoPull) {
+        resolveTestPromise();
+      }
+
+      ++pullCount;
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 256
+  });
+
+  Promise.resolve().then(() => {
+    assert_equals(pullCount, 0);
+    checkedNoPull = true;
+    resolveStartPromise();
+  });
+
+  return testPromise;
+
+}, 'ReadableStream with byte source: No automatic pull call if start doesn\'t finish');
+
+promise_test(t => {
+  new ReadableStream({
+    pull: t.unreached_func('pull() should not be called'),
+    type: 'bytes'
+  }, {
+    highWaterMark: 0
+  });
+
+  return Promise.resolve();
+}, 'ReadableStream with byte source: Construct with highWaterMark of 0');
+
+test(() => {
+  new ReadableStream({
+    start(c) {
+      assert_equals(c.desiredSize, 10, 'desiredSize must start at the highWaterMark');
+      c.close();
+      assert_equals(c.desiredSize, 0, 'after closing, desiredSize must be 0');
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 10
+  });
+}, 'ReadableStream with byte source: desiredSize when closed');
+
+test(() => {
+  new ReadableStream({
+    start(c) {
+      assert_equals(c.desiredSize, 10, 'desiredSize must start at the highWaterMark');
+      c.error();
+      assert_equals(c.desiredSize, null, 'after erroring, desiredSize must be null');
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 10
+  });
+}, 'ReadableStream with byte source: desiredSize when errored');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader();
+  reader.releaseLock();
+
+  return promise_rejects_js(t, TypeError, reader.closed, 'closed must reject');
+}, 'ReadableStream with byte source: getReader(), then releaseLock()');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  reader.releaseLock();
+
+  return promise_rejects_js(t, TypeError, reader.closed, 'closed must reject');
+}, 'ReadableStream with byte source: getReader() with mode set to byob, then releaseLock()');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    start(c) {
+      c.close();
+    },
+    pull: t.unreached_func('pull() should not be called'),
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader();
+
+  return reader.closed.then(() => {
+    assert_throws_js(TypeError, () => stream.getReader(), 'getReader() must throw');
+  });
+}, 'ReadableStream with byte source: Test that closing a stream does not release a reader automatically');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    start(c) {
+      c.error(error1);
+    },
+    pull: t.unreached_func('pull() should not be called'),
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader();
+
+  return promise_rejects_exactly(t, error1, reader.closed, 'closed must reject').then(() => {
+    assert_throws_js(TypeError, () => stream.getReader(), 'getReader() must throw');
+  });
+}, 'ReadableStream with byte source: Test that erroring a stream does not release a reader automatically');
+
+promise_test(() => {
+  let pullCount = 0;
+
+  const stream = new ReadableStream({
+    start(c) {
+      c.enqueue(new Uint8Array(16));
+    },
+    pull() {
+      ++pullCount;
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 0
+  });
+
+  const reader = stream.getReader();
+  const promise = reader.read();
+
+  return promise.then(result => {
+    assert_false(result.done, 'result.done');
+
+    const view = result.value;
+    assert_equals(view.constructor, Uint8Array, 'view.constructor');
+    assert_equals(view.buffer.byteLength, 16, 'view.buffer');
+    assert_equals(view.byteOffset, 0, 'view.byteOffset');
+    assert_equals(view.byteLength, 16, 'view.byteLength');
+  });
+}, 'ReadableStream with byte source: enqueue(), getReader(), then read()');
+
+promise_test(() => {
+  let controller;
+
+  const stream = new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader();
+  const promise = reader.read().then(result => {
+    assert_false(result.done, 'result.done');
+    const view = result.value;
+    assert_equals(view.constructor, Uint8Array, 'view.constructor');
+    assert_equals(view.byteLength, 4, 'view.byteLength');
+    assert_array_equals([...view], [1, 2, 3, 4], 'view contents');
+  });
+
+  controller.enqueue(new Uint8Array([1, 2, 3, 4]));
+
+  return promise;
+}, 'ReadableStream with byte source: Push source that doesn\'t understand pull signal');
+
+test(() => {
+  let controller;
+
+  new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const view = new Uint8Array(1);
+  const buffer = view.buffer;
+  controller.enqueue(view);
+  assert_equals(buffer.byteLength, 0, 'enqueue() should transfer the buffer');
+
+  assert_throws_js(TypeError, () => controller.enqueue(view), 'enqueue() of a detached view should throw');
+  assert_throws_js(TypeError, () => controller.enqueue(new Uint8Array(0)), 'enqueue() of an empty view should throw');
+}, 'ReadableStream with byte source: enqueue() transfers the buffer and rejects detached or empty views');
+
+promise_test(() => {
+  let byobRequest;
+  let desiredSize;
+
+  const stream = new ReadableStream({
+    pull(c) {
+      byobRequest = c.byobRequest;
+      desiredSize = c.desiredSize;
+
+      const view = byobRequest.view;
+      view[0] = 0x01;
+      view[1] = 0x02;
+      byobRequest.respond(2);
+    },
+    type: 'bytes'
+  }, {
+    highWaterMark: 0
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  return reader.read(new Uint8Array(4)).then(result => {
+    assert_false(result.done, 'result.done');
+
+    const view = result.value;
+    assert_equals(view.constructor, Uint8Array, 'view.constructor');
+    assert_equals(view.buffer.byteLength, 4, 'view.buffer.byteLength');
+    assert_equals(view.byteOffset, 0, 'view.byteOffset');
+    assert_equals(view.byteLength, 2, 'view.byteLength');
+    assert_equals(view[0], 0x01, 'view[0]');
+    assert_equals(view[1], 0x02, 'view[1]');
+
+    assert_equals(desiredSize, 0, 'desiredSize should be zero');
+  });
+}, 'ReadableStream with byte source: read(view), then respond()');
+
+promise_test(() => {
+  const stream = new ReadableStream({
+    pull(c) {
+      const view = c.byobRequest.view;
+      view[0] = 0x01;
+      view[1] = 0x02;
+      c.byobRequest.respondWithNewView(new Uint8Array(view.buffer, view.byteOffset, 2));
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  return reader.read(new Uint8Array(4)).then(result => {
+    assert_false(result.done, 'result.done');
+    assert_equals(result.value.byteLength, 2, 'view.byteLength');
+    assert_array_equals([...result.value], [0x01, 0x02], 'view contents');
+  });
+}, 'ReadableStream with byte source: read(view), then respondWithNewView() with a view on the same buffer');
+
+promise_test(() => {
+  let controller;
+
+  const stream = new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  const read = reader.read(new Uint16Array(2));
+
+  controller.enqueue(new Uint8Array([1, 2, 3]));
+
+  return read.then(result => {
+    assert_false(result.done, 'result.done');
+    const view = result.value;
+    assert_equals(view.constructor, Uint16Array, 'view.constructor');
+    assert_equals(view.buffer.byteLength, 4, 'view.buffer.byteLength');
+    assert_equals(view.byteLength, 2, 'only whole elements should be filled');
+    return reader.read(new Uint8Array(2));
+  }).then(result => {
+    assert_false(result.done, 'result.done');
+    assert_equals(result.value.byteLength, 1, 'the remaining byte should be read');
+    assert_equals(result.value[0], 3, 'the remaining byte');
+  });
+}, 'ReadableStream with byte source: read(view) with Uint16Array on enqueue() of a 3-byte chunk');
+
+promise_test(t => {
+  let controller;
+
+  const stream = new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  const read = reader.read(new Uint16Array(1));
+
+  controller.enqueue(new Uint8Array([1]));
+  assert_throws_js(TypeError, () => controller.close(), 'close() with a partially filled element should throw');
+
+  return promise_rejects_js(t, TypeError, read, 'read(view) should reject');
+}, 'ReadableStream with byte source: A stream must be errored if close()-d before fulfilling read(view) with Uint16Array');
+
+promise_test(() => {
+  let controller;
+
+  const stream = new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  const read = reader.read(new Uint8Array(4));
+  controller.close();
+  controller.byobRequest.respond(0);
+
+  return read.then(result => {
+    assert_true(result.done, 'result.done');
+    const view = result.value;
+    assert_equals(view.constructor, Uint8Array, 'view.constructor');
+    assert_equals(view.byteLength, 0, 'view.byteLength');
+    assert_equals(view.buffer.byteLength, 4, 'view.buffer.byteLength');
+  });
+}, 'ReadableStream with byte source: read(view), then close() and respond(0) fulfill with an empty done view');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+
+  return Promise.all([
+    promise_rejects_js(t, TypeError, reader.read(new Uint8Array(0)), 'read() with an empty view must reject'),
+    promise_rejects_js(t, TypeError, reader.read(new Uint8Array(new ArrayBuffer(0))),
+      'read() with a view of an empty buffer must reject'),
+    promise_rejects_js(t, TypeError, reader.read({}), 'read() with a non-view must reject')
+  ]);
+}, 'ReadableStream with byte source: read(view) with invalid views rejects');
+
+promise_test(() => {
+  let controller;
+  const stream = new ReadableStream({
+    start(c) {
+      controller = c;
+    },
+    type: 'bytes'
+  });
+
+  const reader = stream.getReader({ mode: 'byob' });
+  const read = reader.read(new Uint8Array(4), { min: 3 });
+
+  controller.enqueue(new Uint8Array([1, 2]));
+  let fulfilled = false;
+  read.then(() => fulfilled = true);
+
+  return flushAsyncEvents().then(() => {
+    assert_false(fulfilled, 'read() should not fulfill before min bytes are available');
+    controller.enqueue(new Uint8Array([3, 4, 5]));
+    return read;
+  }).then(result => {
+    assert_false(result.done, 'result.done');
+    assert_array_equals([...result.value], [1, 2, 3, 4], 'view should be filled');
+  });
+}, 'ReadableStream with byte source: read(view, { min }) waits until min bytes are filled');
+
+promise_test(t => {
+  const stream = new ReadableStream({
+    type: 'bytes'
+  });
+  const reader = stream.getReader({ mode: 'byob' });
+
+  return Promise.all([
+    promise_rejects_js(t, TypeError, reader.read(new Uint8Array(1), { min: 0 }), 'min of 0 must reject'),
+    promise_rejects_js(t, RangeError, reader.read(new Uint8Array(1), { min: 2 }), 'min above the view length must reject')
+  ]);
+}, 'ReadableStream with byte source: read(view, { min }) rejects invalid min values');
+
+promise_test(() => {
+  const stream = new ReadableStream({
+    start(c) {
+      c.enqueue(new Uint8Array([1, 2]));
+      c.enqueue(new Uint8Array([3]));
+      c.close();
+    },
+    type: 'bytes'
+  });
+
+  const [branch1, branch2] = stream.tee();
+  return Promise.all([readableStreamToArray(branch1), readableStreamToArray(branch2)]).then(([chunks1, chunks2]) => {
+    assert_array_equals(chunks1.map(chunk => [...chunk].join()), ['1,2', '3'], 'branch1 chunks');
+    assert_array_equals(chunks2.map(chunk => [...chunk].join()), ['1,2', '3'], 'branch2 chunks');
+    assert_not_equals(chunks1[0].buffer, chunks2[0].buffer, 'branches should not share buffers');
+  });
+}, 'ReadableStream with byte source: tee() delivers copies of the chunks to both branches');
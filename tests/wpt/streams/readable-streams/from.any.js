@@ -0,0 +1,252 @@
+// META: global=window,worker,shadowrealm
+// META: script=../resources/test-utils.js
+'use strict';
+
+const iterableFactories = [
+  ['an array of values', () => {
+    return ['a', 'b'];
+  }],
+
+  ['an array of promises', () => {
+    return [
+      Promise.resolve('a'),
+      Promise.resolve('b')
+    ];
+  }],
+
+  ['an array iterator', () => {
+    return ['a', 'b'][Symbol.iterator]();
+  }],
+
+  ['a string', () => {
+    // This iterates over the code points of the string.
+    return 'ab';
+  }],
+
+  ['a Set', () => {
+    return new Set(['a', 'b']);
+  }],
+
+  ['a Set iterator', () => {
+    return new Set(['a', 'b'])[Symbol.iterator]();
+  }],
+
+  ['a sync generator', () => {
+    function* syncGenerator() {
+      yield 'a';
+      yield 'b';
+    }
+
+    return syncGenerator();
+  }],
+
+  ['an async generator', () => {
+    async function* asyncGenerator() {
+      yield 'a';
+      yield 'b';
+    }
+
+    return asyncGenerator();
+  }],
+
+  ['a sync iterable of values', () => {
+    const chunks = ['a', 'b'];
+    const it = {
+      next() {
+        return {
+          done: chunks.length === 0,
+          value: chunks.shift()
+        };
+      },
+      [Symbol.iterator]: () => it
+    };
+    return it;
+  }],
+
+  ['an async iterable', () => {
+    const chunks = ['a', 'b'];
+    const asyncIterator = {
+      next() {
+        return Promise.resolve({
+          done: chunks.length === 0,
+          value: chunks.shift()
+        })
+      },
+      [Symbol.asyncIterator]: () => asyncIterator
+    };
+    return asyncIterator;
+  }],
+
+  ['a ReadableStream', () => {
+    return new ReadableStream({
+      start(c) {
+        c.enqueue('a');
+        c.enqueue('b');
+        c.close();
+      }
+    });
+  }],
+];
+
+for (const [label, factory] of iterableFactories) {
+  promise_test(async () => {
+
+    const iterable = factory();
+    const rs = ReadableStream.from(iterable);
+    assert_equals(rs.constructor, ReadableStream, 'from() should return a ReadableStream');
+
+    const reader = rs.getReader();
+    assert_object_equals(await reader.read(), { value: 'a', done: false }, 'first read should be correct');
+    assert_object_equals(await reader.read(), { value: 'b', done: false }, 'second read should be correct');
+    assert_object_equals(await reader.read(), { value: undefined, done: true }, 'third read should be done');
+    await reader.closed;
+
+  }, `ReadableStream.from accepts ${label}`);
+}
+
+const badIterables = [
+  ['null', null],
+  ['undefined', undefined],
+  ['0', 0],
+  ['NaN', NaN],
+  ['true', true],
+  ['{}', {}],
+  ['Object.create(null)', Object.create(null)],
+  ['a function', () => 42],
+  ['a symbol', Symbol()],
+  ['an object with a non-callable @@iterator method', {
+    [Symbol.iterator]: 42
+  }],
+  ['an object with a non-callable @@asyncIterator method', {
+    [Symbol.asyncIterator]: 42
+  }],
+];
+
+for (const [label, iterable] of badIterables) {
+  test(() => {
+    assert_throws_js(TypeError, () => ReadableStream.from(iterable), 'from() should throw a TypeError')
+  }, `ReadableStream.from throws on invalid iterables; specifically ${label}`);
+}
+
+test(() => {
+  const theError = new Error('a unique string');
+  const iterable = {
+    [Symbol.iterator]() {
+      throw theError;
+    }
+  };
+
+  assert_throws_exactly(theError, () => ReadableStream.from(iterable), 'from() should re-throw the error');
+}, `ReadableStream.from re-throws errors from calling the @@iterator method`);
+
+test(() => {
+  const theError = new Error('a unique string');
+  const iterable = {
+    [Symbol.asyncIterator]() {
+      throw theError;
+    }
+  };
+
+  assert_throws_exactly(theError, () => ReadableStream.from(iterable), 'from() should re-throw the error');
+}, `ReadableStream.from re-throws errors from calling the @@asyncIterator method`);
+
+promise_test(async t => {
+  const theError = new Error('a unique string');
+  const iterable = {
+    async next() {
+      throw theError;
+    },
+    [Symbol.asyncIterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  const reader = rs.getReader();
+
+  await Promise.all([
+    promise_rejects_exactly(t, theError, reader.read()),
+    promise_rejects_exactly(t, theError, reader.closed)
+  ]);
+}, `ReadableStream.from: stream errors when next() rejects`);
+
+promise_test(async t => {
+  const iterable = {
+    next() {
+      return 42;
+    },
+    [Symbol.asyncIterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  const reader = rs.getReader();
+
+  await Promise.all([
+    promise_rejects_js(t, TypeError, reader.read()),
+    promise_rejects_js(t, TypeError, reader.closed)
+  ]);
+}, `ReadableStream.from: stream errors when next() doesn't fulfill with an object`);
+
+promise_test(async () => {
+  let nextCalls = 0;
+  const iterable = {
+    next() {
+      nextCalls += 1;
+      return { value: nextCalls, done: false };
+    },
+    [Symbol.iterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  await flushAsyncEvents();
+  assert_equals(nextCalls, 0, 'next() should not be called before the stream is read from');
+
+  const reader = rs.getReader();
+  assert_object_equals(await reader.read(), { value: 1, done: false }, 'first read should be correct');
+  await flushAsyncEvents();
+  assert_equals(nextCalls, 1, 'next() should only be called once per read');
+}, `ReadableStream.from: next() is only called when the stream is read from`);
+
+promise_test(async () => {
+  const reason = 'the reason';
+  let returnCalledWith;
+  const iterable = {
+    next() {
+      return { value: 'a', done: false };
+    },
+    return(value) {
+      returnCalledWith = value;
+      return { done: true };
+    },
+    [Symbol.iterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  await rs.cancel(reason);
+  assert_equals(returnCalledWith, reason, 'return() should be called with the cancel reason');
+}, `ReadableStream.from: cancelling the returned stream calls and awaits return()`);
+
+promise_test(async t => {
+  const iterable = {
+    next() {
+      return { value: 'a', done: false };
+    },
+    return() {
+      return 42;
+    },
+    [Symbol.asyncIterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  await promise_rejects_js(t, TypeError, rs.cancel(), 'cancel() should reject');
+}, `ReadableStream.from: return() is called when cancelling, and rejects when it doesn't fulfill with an object`);
+
+promise_test(async () => {
+  const iterable = {
+    next() {
+      return { value: 'a', done: false };
+    },
+    [Symbol.iterator]: () => iterable
+  };
+
+  const rs = ReadableStream.from(iterable);
+  await rs.cancel();
+}, `ReadableStream.from: cancel() resolves when return() method is missing`);
@@ -0,0 +1,112 @@
+'use strict';
+
+class SequentialPullSource {
+  constructor(limit, options) {
+    const async = options && options.async;
+
+    this.current = 0;
+    this.limit = limit;
+    this.opened = false;
+    this.closed = false;
+
+    this._exec = f => f();
+    if (async) {
+      this._exec = f => step_timeout(f, 0);
+    }
+  }
+
+  open(cb) {
+    this._exec(() => {
+      this.opened = true;
+      cb();
+    });
+  }
+
+  read(cb) {
+    this._exec(() => {
+      if (++this.current <= this.limit) {
+        cb(null, false, this.current);
+      } else {
+        cb(null, true, null);
+      }
+    });
+  }
+
+  close(cb) {
+    this._exec(() => {
+      this.closed = true;
+      cb();
+    });
+  }
+}
+
+function sequentialReadableStream(limit, options) {
+  const sequentialSource = new SequentialPullSource(limit, options);
+
+  const stream = new ReadableStream({
+    start() {
+      return new Promise((resolve, reject) => {
+        sequentialSource.open(err => {
+          if (err) {
+            reject(err);
+          }
+          resolve();
+        });
+      });
+    },
+
+    pull(c) {
+      return new Promise((resolve, reject) => {
+        sequentialSource.read((err, done, chunk) => {
+          if (err) {
+            reject(err);
+          } else if (done) {
+            sequentialSource.close(err2 => {
+              if (err2) {
+                reject(err2);
+              }
+              c.close();
+              resolve();
+            });
+          } else {
+            c.enqueue(chunk);
+            resolve();
+          }
+        });
+      });
+    }
+  });
+
+  stream.source = sequentialSource;
+
+  return stream;
+}
+
+function transferArrayBufferView(view) {
+  return structuredClone(view, { transfer: [view.buffer] });
+}
+
+self.SequentialPullSource = SequentialPullSource;
+self.sequentialReadableStream = sequentialReadableStream;
+self.transferArrayBufferView = transferArrayBufferView;
+
+self.readableStreamToArray = (readable, reader) => {
+  if (reader === undefined) {
+    reader = readable.getReader();
+  }
+
+  const chunks = [];
+
+  return pump();
+
+  function pump() {
+    return reader.read().then(result => {
+      if (result.done) {
+        return chunks;
+      }
+
+      chunks.push(result.value);
+      return pump();
+    });
+  }
+};
@@ -0,0 +1,216 @@
+// META: global=window,worker,shadowrealm
+// META: script=../resources/test-utils.js
+// META: script=../resources/recording-streams.js
+'use strict';
+
+const error1 = new Error('error1');
+error1.name = 'error1';
+
+const error2 = new Error('error2');
+error2.name = 'error2';
+
+promise_test(t => {
+  const ws = new WritableStream({
+    write: t.unreached_func('write() should not be called')
+  });
+
+  const writer = ws.getWriter();
+  const writePromise = writer.write('a');
+
+  const readyPromise = writer.ready;
+
+  writer.abort(error1);
+
+  assert_equals(writer.ready, readyPromise, 'the ready promise property should not change');
+
+  return Promise.all([
+    promise_rejects_exactly(t, error1, readyPromise, 'the ready promise should reject with error1'),
+    promise_rejects_exactly(t, error1, writePromise, 'the write() promise should reject with error1')
+  ]);
+}, 'Aborting a WritableStream before it starts should cause the writer\'s unsettled ready promise to reject');
+
+promise_test(t => {
+  const ws = new WritableStream();
+
+  const writer = ws.getWriter();
+  writer.write('a');
+
+  const readyPromise = writer.ready;
+
+  return readyPromise.then(() => {
+    writer.abort(error1);
+
+    assert_not_equals(writer.ready, readyPromise, 'the ready promise property should change');
+    return promise_rejects_exactly(t, error1, writer.ready, 'the ready promise should reject with error1');
+  });
+}, 'Aborting a WritableStream should cause the writer\'s fulfilled ready promise to reset to a rejected one');
+
+promise_test(t => {
+  const ws = new WritableStream();
+  const writer = ws.getWriter();
+
+  writer.releaseLock();
+
+  return promise_rejects_js(t, TypeError, writer.abort(), 'abort() should reject with a TypeError');
+}, 'abort() on a released writer rejects');
+
+promise_test(t => {
+  const ws = recordingWritableStream();
+
+  return delay(0)
+    .then(() => {
+      const writer = ws.getWriter();
+
+      const abortPromise = writer.abort(error1);
+
+      return Promise.all([
+        promise_rejects_exactly(t, error1, writer.write(1), 'write(1) must reject with error1'),
+        promise_rejects_exactly(t, error1, writer.write(2), 'write(2) must reject with error1'),
+        abortPromise
+      ]);
+    })
+    .then(() => {
+      assert_array_equals(ws.events, ['abort', error1]);
+    });
+}, 'Aborting a WritableStream immediately prevents future writes');
+
+promise_test(t => {
+  const ws = recordingWritableStream();
+  const results = [];
+
+  return delay(0)
+    .then(() => {
+      const writer = ws.getWriter();
+
+      results.push(
+        writer.write(1),
+        promise_rejects_exactly(t, error1, writer.write(2), 'write(2) must reject with error1'),
+        promise_rejects_exactly(t, error1, writer.write(3), 'write(3) must reject with error1')
+      );
+
+      const abortPromise = writer.abort(error1);
+
+      results.push(
+        promise_rejects_exactly(t, error1, writer.write(4), 'write(4) must reject with error1'),
+        promise_rejects_exactly(t, error1, writer.write(5), 'write(5) must reject with error1')
+      );
+
+      return abortPromise;
+    }).then(() => {
+      assert_array_equals(ws.events, ['write', 1, 'abort', error1]);
+
+      return Promise.all(results);
+    });
+}, 'Aborting a WritableStream prevents further writes after any that are in progress');
+
+promise_test(() => {
+  const ws = new WritableStream({
+    abort() {
+      return 'Hello';
+    }
+  });
+  const writer = ws.getWriter();
+
+  return writer.abort('a').then(value => {
+    assert_equals(value, undefined, 'fulfillment value must be undefined');
+  });
+}, 'Fulfillment value of writer.abort() call must be undefined even if the underlying sink returns a non-undefined ' +
+   'value');
+
+promise_test(t => {
+  const ws = new WritableStream({
+    abort() {
+      throw error1;
+    }
+  });
+  const writer = ws.getWriter();
+
+  return promise_rejects_exactly(t, error1, writer.abort(undefined),
+    'rejection reason of abortPromise must be the error thrown by abort');
+}, 'WritableStream if sink\'s abort throws, the promise returned by writer.abort() rejects');
+
+promise_test(t => {
+  const ws = new WritableStream({
+    abort() {
+      throw error1;
+    }
+  });
+
+  return promise_rejects_exactly(t, error1, ws.abort(undefined),
+    'rejection reason of abortPromise must be the error thrown by abort');
+}, 'WritableStream if sink\'s abort throws, the promise returned by ws.abort() rejects');
+
+promise_test(t => {
+  const ws = new WritableStream();
+  const writer = ws.getWriter();
+  return promise_rejects_js(t, TypeError, ws.abort.call(writer), 'abort() with a wrong receiver should reject');
+}, 'WritableStream.abort() should brand check its receiver');
+
+promise_test(t => {
+  let resolveWrite;
+  const ws = recordingWritableStream({
+    write() {
+      return new Promise(resolve => {
+        resolveWrite = resolve;
+      });
+    }
+  });
+  const writer = ws.getWriter();
+  return writer.ready.then(() => {
+    const writePromise = writer.write('a');
+    const abortPromise = writer.abort(error1);
+    let abortResolved = false;
+    abortPromise.then(() => {
+      abortResolved = true;
+    });
+    return flushAsyncEvents().then(() => {
+      assert_false(abortResolved, 'abort() should not resolve while the write is pending');
+      assert_array_equals(ws.events, ['write', 'a'], 'abort() should not be called on the sink yet');
+      resolveWrite();
+      return Promise.all([writePromise, abortPromise]);
+    }).then(() => {
+      assert_array_equals(ws.events, ['write', 'a', 'abort', error1], 'sink abort() should be called after the write');
+    });
+  });
+}, 'abort() waits for the pending write before calling the sink\'s abort()');
+
+promise_test(t => {
+  const ws = new WritableStream();
+  const writer = ws.getWriter();
+  return writer.close().then(() => {
+    return writer.abort(error1);
+  }).then(() => {
+    return writer.closed;
+  });
+}, 'abort() on a closed stream fulfills without erroring it');
+
+promise_test(t => {
+  const ws = recordingWritableStream();
+  const writer = ws.getWriter();
+  const abortPromise1 = writer.abort(error1);
+  const abortPromise2 = writer.abort(error2);
+  assert_equals(abortPromise1, abortPromise2, 'the promises must be the same');
+
+  return Promise.all([
+    abortPromise1,
+    promise_rejects_exactly(t, error1, writer.closed, 'closed should reject with the first abort reason')
+  ]).then(() => {
+    assert_array_equals(ws.events, ['abort', error1], 'sink abort() should be called only once');
+  });
+}, 'a second abort() returns the same promise as the first');
+
+promise_test(t => {
+  const controller = new AbortController();
+  let signal;
+  const ws = new WritableStream({
+    start(c) {
+      signal = c.signal;
+    }
+  });
+  const writer = ws.getWriter();
+  assert_false(signal.aborted, 'signal should not be aborted yet');
+  return writer.abort(error1).then(() => {
+    assert_true(signal.aborted, 'signal should be aborted');
+    assert_equals(signal.reason, error1, 'signal.reason should be the abort reason');
+  });
+}, 'WritableStreamDefaultController.signal is aborted with the abort reason');
@@ -0,0 +1,282 @@
+// Subset of WPT's testharness.js (https://web-platform-tests.org/writing-tests/testharness-api.html),
+// which is enough to run the vendored tests with `sable test`
+
+class AssertionError extends Error {
+	name = "AssertionError";
+}
+
+/**
+ * @param {boolean} condition
+ * @param {string} message
+ * @param {string} [description]
+ */
+function assert(condition, message, description = "") {
+	if (!condition) {
+		throw new AssertionError(description ? `${description}: ${message}` : message);
+	}
+}
+
+/**
+ * @param {any} value
+ * @returns {string}
+ */
+function format(value) {
+	if (typeof value === "string") return JSON.stringify(value);
+	if (Object.is(value, -0)) return "-0";
+	if (Array.isArray(value)) return `[${value.map(format).join(", ")}]`;
+	try {
+		return String(value);
+	} catch {
+		return Object.prototype.toString.call(value);
+	}
+}
+
+const asserts = {
+	assert_true(actual, description) {
+		assert(actual === true, `expected true, got ${format(actual)}`, description);
+	},
+	assert_false(actual, description) {
+		assert(actual === false, `expected false, got ${format(actual)}`, description);
+	},
+	assert_equals(actual, expected, description) {
+		assert(
+			Object.is(actual, expected),
+			`expected ${format(expected)}, got ${format(actual)}`,
+			description,
+		);
+	},
+	assert_not_equals(actual, expected, description) {
+		assert(
+			!Object.is(actual, expected),
+			`got disallowed value ${format(actual)}`,
+			description,
+		);
+	},
+	assert_in_array(actual, expected, description) {
+		assert(
+			expected.some((value) => Object.is(actual, value)),
+			`${format(actual)} isn't in ${format(expected)}`,
+			description,
+		);
+	},
+	assert_array_equals(actual, expected, description) {
+		assert(
+			actual.length === expected.length &&
+				expected.every((value, i) => Object.is(actual[i], value)),
+			`expected ${format(expected)}, got ${format(actual)}`,
+			description,
+		);
+	},
+	assert_object_equals(actual, expected, description) {
+		const keys = Object.keys(expected);
+		assert(
+			typeof actual === "object" && actual !== null &&
+				Object.keys(actual).length === keys.length &&
+				keys.every((key) => Object.is(actual[key], expected[key])),
+			`expected ${JSON.stringify(expected)}, got ${JSON.stringify(actual)}`,
+			description,
+		);
+	},
+	assert_class_string(object, expected, description) {
+		const actual = Object.prototype.toString.call(object);
+		assert(
+			actual === `[object ${expected}]`,
+			`expected [object ${expected}], got ${actual}`,
+			description,
+		);
+	},
+	assert_throws_js(constructor, fn, description) {
+		try {
+			fn();
+		} catch (error) {
+			assert(
+				error instanceof constructor,
+				`expected ${constructor.name}, got ${format(error)}`,
+				description,
+			);
+			return;
+		}
+		assert(false, `expected ${constructor.name} to be thrown`, description);
+	},
+	assert_throws_exactly(expected, fn, description) {
+		try {
+			fn();
+		} catch (error) {
+			assert(
+				Object.is(error, expected),
+				`expected ${format(expected)}, got ${format(error)}`,
+				description,
+			);
+			return;
+		}
+		assert(false, `expected ${format(expected)} to be thrown`, description);
+	},
+	assert_unreached(description) {
+		assert(false, "reached unreachable code", description);
+	},
+	async promise_rejects_js(_t, constructor, promise, description) {
+		try {
+			await promise;
+		} catch (error) {
+			asserts.assert_throws_js(constructor, () => {
+				throw error;
+			}, description);
+			return;
+		}
+		assert(false, `expected rejection with ${constructor.name}`, description);
+	},
+	async promise_rejects_exactly(_t, expected, promise, description) {
+		try {
+			await promise;
+		} catch (error) {
+			asserts.assert_throws_exactly(expected, () => {
+				throw error;
+			}, description);
+			return;
+		}
+		assert(false, `expected rejection with ${format(expected)}`, description);
+	},
+	step_timeout(fn, timeout, ...args) {
+		return setTimeout(fn, timeout, ...args);
+	},
+};
+
+/**
+ * Test passed to the test functions, errors of its steps fail it even when they happen asynchronously
+ */
+class Test {
+	/** @type {unknown[]} */
+	errors = [];
+	/** @type {(() => any)[]} */
+	cleanups = [];
+
+	/**
+	 * @param {Function} fn
+	 * @param {any} [thisArg]
+	 */
+	step(fn, thisArg = undefined, ...args) {
+		return this.step_func(fn, thisArg)(...args);
+	}
+
+	/**
+	 * @param {Function} fn
+	 * @param {any} [thisArg]
+	 */
+	step_func(fn, thisArg = undefined) {
+		return (...args) => {
+			try {
+				return fn.apply(thisArg, args);
+			} catch (error) {
+				this.errors.push(error);
+				throw error;
+			}
+		};
+	}
+
+	/**
+	 * @param {string} [description]
+	 */
+	unreached_func(description) {
+		return this.step_func(() => asserts.assert_unreached(description));
+	}
+
+	/**
+	 * @param {() => any} fn
+	 */
+	add_cleanup(fn) {
+		this.cleanups.push(fn);
+	}
+
+	/**
+	 * @param {Function} fn
+	 * @param {number} timeout
+	 */
+	step_timeout(fn, timeout, ...args) {
+		return setTimeout(this.step_func(fn), timeout, ...args);
+	}
+}
+
+/**
+ * @param {unknown} error
+ * @returns {string}
+ */
+function errorMessage(error) {
+	return error instanceof Error ? `${error.name}: ${error.message}` : format(error);
+}
+
+/**
+ * Runs test file and the scripts it includes through `// META: script=` comments
+ *
+ * Failures are reported as "<test name>: <error>", tests named in `expectedFailures` have to fail,
+ * they are reported when they pass
+ * @param {URL} url
+ * @param {Set<string>} expectedFailures
+ * @returns {Promise<string[]>}
+ */
+export async function runWptTests(url, expectedFailures) {
+	const source = await Sable.fs.readTextFile(url);
+	const scripts = [];
+	for (const [, script] of source.matchAll(/^\/\/ META: script=(.+)$/gm)) {
+		scripts.push(await Sable.fs.readTextFile(new URL(script, url)));
+	}
+
+	/** @type {[string, (t: Test) => any][]} */
+	const tests = [];
+	/** @type {[string, unknown[]][]} */
+	const results = [];
+
+	const runTest = async (name, fn) => {
+		const t = new Test();
+		try {
+			await fn(t);
+		} catch (error) {
+			// Errors of steps have already been recorded
+			if (!t.errors.includes(error)) t.errors.push(error);
+		}
+		for (const cleanup of t.cleanups) {
+			await cleanup();
+		}
+		results.push([name, t.errors]);
+	};
+
+	const harness = {
+		...asserts,
+		test(fn, name) {
+			tests.push([name, fn]);
+		},
+		promise_test(fn, name) {
+			tests.push([name, fn]);
+		},
+	};
+
+	// Scripts and the test file share their scope, like classic scripts on a web page
+	const globalsBefore = new Set(Reflect.ownKeys(globalThis));
+	try {
+		const run = new Function(
+			"self",
+			...Object.keys(harness),
+			[...scripts, source].join("\n;\n"),
+		);
+		run(globalThis, ...Object.values(harness));
+
+		for (const [name, fn] of tests) {
+			await runTest(name, fn);
+		}
+	} finally {
+		// Helpers get defined on `self`
+		for (const key of Reflect.ownKeys(globalThis)) {
+			if (!globalsBefore.has(key)) delete globalThis[key];
+		}
+	}
+
+	const failures = [];
+	for (const [name, errors] of results) {
+		const expected = expectedFailures.has(name);
+		if (errors.length > 0 && !expected) {
+			failures.push(`${name}: ${errors.map(errorMessage).join(", ")}`);
+		} else if (errors.length === 0 && expected) {
+			failures.push(`${name}: expected failure, but it passed`);
+		}
+	}
+	return failures;
+}